@@ -0,0 +1,96 @@
+//! Startup and theme benchmarks, run with `cargo bench -p hello-gp --features bench`.
+
+use std::{hint::black_box, path::PathBuf, rc::Rc, sync::mpsc};
+
+use criterion::{BenchmarkId, Criterion};
+use futures::{StreamExt as _, channel::mpsc::UnboundedSender};
+use gpui::{App, Application};
+use gpui_component::ThemeConfig;
+use hello_gp::bench;
+
+/// A themes dir to load on the main thread, and where to report it's loaded.
+type LoadRequest = (PathBuf, mpsc::Sender<()>);
+
+fn state_deserialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("state_deserialization");
+    for ext in ["toml", "json5", "json"] {
+        let text = bench::sample_state(ext);
+        group.bench_with_input(BenchmarkId::from_parameter(ext), &text, |b, text| {
+            b.iter(|| bench::parse_state(ext, black_box(text)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Runs off the main thread, which loads the themes as `requests` come in.
+fn theme_registry_load(c: &mut Criterion, requests: UnboundedSender<LoadRequest>) {
+    let mut group = c.benchmark_group("theme_registry_load");
+    group.sample_size(20);
+    for count in [1, 100, 1000] {
+        let dir = bench::write_theme_dir(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &dir, |b, dir| {
+            b.iter(|| {
+                let (tx, rx) = mpsc::channel();
+                requests
+                    .unbounded_send((black_box(dir.clone()), tx))
+                    .unwrap();
+                rx.recv().unwrap();
+            })
+        });
+        _ = std::fs::remove_dir_all(dir);
+    }
+    group.finish();
+}
+
+fn theme_apply(c: &mut Criterion, themes: &[Rc<ThemeConfig>], cx: &mut App) {
+    // Alternate between a light and a dark theme, so every apply changes something.
+    let mut ix = 0;
+    c.bench_function("theme_apply", |b| {
+        b.iter(|| {
+            ix = (ix + 1) % themes.len();
+            bench::apply_theme(black_box(&themes[ix]), cx);
+        })
+    });
+}
+
+fn main() {
+    let mut c = Criterion::default().configure_from_args();
+    state_deserialization(&mut c);
+
+    // Loading and applying themes needs the app, but no windows. The registry loads on the
+    // main thread, where the app runs, so the loads are measured from another one.
+    Application::headless().run(move |cx| {
+        gpui_component::init(cx);
+
+        let (requests_tx, mut requests) = futures::channel::mpsc::unbounded::<LoadRequest>();
+        let bench_thread = std::thread::spawn(move || {
+            theme_registry_load(&mut c, requests_tx);
+            c
+        });
+
+        cx.spawn(async move |cx| {
+            while let Some((dir, done)) = requests.next().await {
+                let Ok(loaded) = cx.update(|cx| bench::load_theme_dir(&dir, cx)) else {
+                    return;
+                };
+                _ = loaded.await;
+                _ = done.send(());
+            }
+            let mut c = bench_thread.join().expect("the benchmarks don't panic");
+
+            let dir = bench::write_theme_dir(2);
+            let Ok(loaded) = cx.update(|cx| bench::load_theme_dir(&dir, cx)) else {
+                return;
+            };
+            _ = loaded.await;
+            _ = cx.update(|cx| {
+                let themes = bench::themes(&["Bench 0", "Bench 1"], cx);
+                theme_apply(&mut c, &themes, cx);
+                c.final_summary();
+                cx.quit();
+            });
+            _ = std::fs::remove_dir_all(&dir);
+        })
+        .detach();
+    });
+}
@@ -0,0 +1,162 @@
+//! Collects the licenses of the crates linked into the app, embedded for the third-party
+//! licenses screen of the About dialog.
+
+use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    path::Path,
+    process::Command,
+};
+
+use serde_json::{Value, json};
+
+/// Files next to a crate's manifest that hold its license, matched by prefix.
+const LICENSE_FILES: [&str; 3] = ["LICENSE", "LICENCE", "COPYING"];
+
+fn metadata() -> Result<Value, String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|err| err.to_string())?;
+    let target = std::env::var("TARGET").map_err(|err| err.to_string())?;
+    let output = Command::new(cargo)
+        .args([
+            "metadata",
+            "--format-version",
+            "1",
+            "--filter-platform",
+            &target,
+        ])
+        .arg("--manifest-path")
+        .arg(Path::new(&manifest_dir).join("Cargo.toml"))
+        .output()
+        .map_err(|err| err.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|err| err.to_string())
+}
+
+/// The license texts shipped with a crate, as named by `license-file` or found by file name.
+fn license_text(package: &Value) -> Option<String> {
+    let manifest = Path::new(package["manifest_path"].as_str()?);
+    let dir = manifest.parent()?;
+    if let Some(file) = package["license_file"].as_str() {
+        return std::fs::read_to_string(dir.join(file)).ok();
+    }
+
+    let mut files = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_uppercase();
+            path.is_file() && LICENSE_FILES.iter().any(|prefix| name.starts_with(prefix))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    let texts = files
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect::<Vec<_>>();
+    (!texts.is_empty()).then(|| texts.join("\n\n---\n\n"))
+}
+
+/// Every crate the app links, following the normal dependencies from its own package, so
+/// build and dev dependencies, which aren't shipped, are left out.
+fn collect() -> Result<Vec<Value>, String> {
+    let metadata = metadata()?;
+    let packages = metadata["packages"]
+        .as_array()
+        .ok_or("no packages")?
+        .iter()
+        .filter_map(|package| Some((package["id"].as_str()?, package)))
+        .collect::<BTreeMap<_, _>>();
+    let nodes = metadata["resolve"]["nodes"]
+        .as_array()
+        .ok_or("no dependency graph")?
+        .iter()
+        .filter_map(|node| Some((node["id"].as_str()?, node)))
+        .collect::<BTreeMap<_, _>>();
+    let members = metadata["workspace_members"]
+        .as_array()
+        .ok_or("no workspace members")?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<HashSet<_>>();
+    // The resolve has no root in a virtual workspace, find the app among the members.
+    let name = std::env::var("CARGO_PKG_NAME").map_err(|err| err.to_string())?;
+    let root = members
+        .iter()
+        .copied()
+        .find(|id| {
+            packages
+                .get(id)
+                .is_some_and(|package| package["name"] == name.as_str())
+        })
+        .ok_or("the app isn't a workspace member")?;
+
+    let mut seen = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        let deps = nodes
+            .get(id)
+            .and_then(|node| node["deps"].as_array())
+            .into_iter()
+            .flatten();
+        for dep in deps {
+            let normal = dep["dep_kinds"]
+                .as_array()
+                .is_some_and(|kinds| kinds.iter().any(|kind| kind["kind"].is_null()));
+            if let Some(dep_id) = dep["pkg"].as_str()
+                && normal
+                && seen.insert(dep_id)
+            {
+                queue.push_back(dep_id);
+            }
+        }
+    }
+
+    let mut licenses = seen
+        .into_iter()
+        .filter(|id| !members.contains(id))
+        .filter_map(|id| packages.get(id))
+        .map(|package| {
+            json!({
+                "name": package["name"],
+                "version": package["version"],
+                "license": package["license"],
+                "repository": package["repository"],
+                "text": license_text(package),
+            })
+        })
+        .collect::<Vec<_>>();
+    licenses.sort_by_key(|license| {
+        (
+            license["name"].as_str().unwrap_or_default().to_lowercase(),
+            license["version"].as_str().unwrap_or_default().to_string(),
+        )
+    });
+    Ok(licenses)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=../../Cargo.lock");
+
+    // A build without the list is still a build, e.g. offline without the crate sources.
+    let licenses = collect().unwrap_or_else(|err| {
+        println!(
+            "cargo:warning=The third-party licenses are left out: {}",
+            err
+        );
+        vec![]
+    });
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    std::fs::write(
+        Path::new(&out_dir).join("licenses.json"),
+        Value::Array(licenses).to_string(),
+    )
+    .expect("failed to write the third-party licenses");
+}
@@ -0,0 +1,390 @@
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Window, actions, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputEvent, InputState},
+    v_flex,
+};
+use serde::Deserialize;
+
+use crate::{
+    About,
+    cursor::{CursorExt as _, CursorRole},
+    skeletons::{Loadable, SkeletonRows},
+    text_field::TextField,
+    tinted_icons::TintedIcon,
+    virtual_rows::{RowsDelegate, VirtualRows, VirtualRowsEvent},
+};
+
+actions!(help, [ShowLicenses]);
+
+const APP_NAME: &str = "GPUI Component";
+
+/// A crate linked into the app, as collected by the build script.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThirdPartyLicense {
+    pub name: String,
+    pub version: String,
+    /// The SPDX expression of the crate, e.g. `MIT OR Apache-2.0`.
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    /// The license files shipped with the crate.
+    pub text: Option<String>,
+}
+
+impl ThirdPartyLicense {
+    /// Every crate linked into the app, by name.
+    ///
+    /// Parsed on first use, which takes a moment, so views load them in the background.
+    pub fn all() -> &'static [ThirdPartyLicense] {
+        LICENSES.get_or_init(|| {
+            serde_json::from_str(include_str!(concat!(env!("OUT_DIR"), "/licenses.json")))
+                .unwrap_or_default()
+        })
+    }
+
+    /// The crates, if they're parsed already.
+    pub fn loaded() -> Option<&'static [ThirdPartyLicense]> {
+        LICENSES.get().map(Vec::as_slice)
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self
+                .license
+                .as_ref()
+                .is_some_and(|license| license.to_lowercase().contains(&query))
+    }
+}
+
+static LICENSES: OnceLock<Vec<ThirdPartyLicense>> = OnceLock::new();
+
+/// A row of [`LicensesView`], the crates are grouped by their license.
+enum LicenseRow {
+    Group(SharedString, usize),
+    Crate(&'static ThirdPartyLicense),
+}
+
+struct LicenseRows {
+    rows: Vec<LicenseRow>,
+}
+
+impl LicenseRows {
+    fn new(licenses: &'static [ThirdPartyLicense], query: &str) -> Self {
+        let mut groups = BTreeMap::<&str, Vec<&'static ThirdPartyLicense>>::new();
+        for license in licenses.iter().filter(|license| license.matches(query)) {
+            groups
+                .entry(license.license.as_deref().unwrap_or("Unknown"))
+                .or_default()
+                .push(license);
+        }
+
+        let mut rows = vec![];
+        for (name, crates) in groups {
+            rows.push(LicenseRow::Group(name.to_string().into(), crates.len()));
+            rows.extend(crates.into_iter().map(LicenseRow::Crate));
+        }
+        Self { rows }
+    }
+
+    fn crate_at(&self, ix: usize) -> Option<&'static ThirdPartyLicense> {
+        match self.rows.get(ix)? {
+            LicenseRow::Crate(license) => Some(license),
+            LicenseRow::Group(..) => None,
+        }
+    }
+
+    fn crates_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| matches!(row, LicenseRow::Crate(_)))
+            .count()
+    }
+}
+
+impl RowsDelegate for LicenseRows {
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn is_header(&self, ix: usize, _: &App) -> bool {
+        matches!(self.rows.get(ix), Some(LicenseRow::Group(..)))
+    }
+
+    fn render_row(&self, ix: usize, selected: bool, _: &mut Window, cx: &mut App) -> AnyElement {
+        match &self.rows[ix] {
+            LicenseRow::Group(name, count) => h_flex()
+                .size_full()
+                .px_2()
+                .gap_2()
+                .text_xs()
+                .font_semibold()
+                .text_color(cx.theme().muted_foreground)
+                .child(name.clone())
+                .child(count.to_string())
+                .into_any_element(),
+            LicenseRow::Crate(license) => h_flex()
+                .size_full()
+                .px_2()
+                .rounded(cx.theme().radius)
+                .cursor_role(CursorRole::Pointer, cx)
+                .when(selected, |this| this.bg(cx.theme().accent))
+                .child(format!("{} {}", license.name, license.version))
+                .into_any_element(),
+        }
+    }
+}
+
+/// The crates the app is built from and their licenses, searchable by name and license.
+pub struct LicensesView {
+    focus_handle: FocusHandle,
+    search: Entity<InputState>,
+    licenses: Loadable<&'static [ThirdPartyLicense]>,
+    rows: Entity<VirtualRows<LicenseRows>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LicensesView {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search = cx.new(|cx| InputState::new(window, cx).placeholder("Search crates..."));
+        let rows = cx.new(|cx| VirtualRows::new(LicenseRows { rows: vec![] }, window, cx));
+        let _subscriptions = vec![
+            cx.subscribe(&search, |this, _, event, cx| {
+                if let InputEvent::Change = event {
+                    this.update_rows(cx);
+                }
+            }),
+            cx.subscribe(&rows, |_, rows, event, cx| match event {
+                VirtualRowsEvent::Select(_) => cx.notify(),
+                VirtualRowsEvent::Confirm(ix) => {
+                    if let Some(url) = rows
+                        .read(cx)
+                        .delegate()
+                        .crate_at(*ix)
+                        .and_then(|license| license.repository.as_ref())
+                    {
+                        cx.open_url(url);
+                    }
+                }
+            }),
+        ];
+
+        let licenses = match ThirdPartyLicense::loaded() {
+            Some(licenses) => Loadable::Loaded(licenses),
+            None => {
+                cx.spawn(async move |this, cx| {
+                    let licenses = cx
+                        .background_spawn(async { ThirdPartyLicense::all() })
+                        .await;
+                    this.update(cx, |this, cx| {
+                        this.licenses = Loadable::Loaded(licenses);
+                        this.update_rows(cx);
+                    })
+                })
+                .detach();
+                Loadable::Loading
+            }
+        };
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            search,
+            licenses,
+            rows,
+            _subscriptions,
+        };
+        this.update_rows(cx);
+        this
+    }
+
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        let licenses = self.licenses.loaded().copied().unwrap_or_default();
+        let query = self.search.read(cx).value().trim().to_string();
+        self.rows.update(cx, |rows, cx| {
+            *rows.delegate_mut() = LicenseRows::new(licenses, &query);
+            rows.set_selected_index(None, cx);
+            rows.refresh(cx);
+        });
+        cx.notify();
+    }
+
+    fn selected(&self, cx: &App) -> Option<&'static ThirdPartyLicense> {
+        let rows = self.rows.read(cx);
+        rows.delegate().crate_at(rows.selected_index()?)
+    }
+
+    fn render_details(license: &'static ThirdPartyLicense, cx: &App) -> impl IntoElement {
+        v_flex()
+            .id("license-details")
+            .h(px(160.))
+            .gap_2()
+            .p_2()
+            .overflow_y_scroll()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().font_semibold().child(license.name.clone()))
+                    .when_some(license.repository.clone(), |this, url| {
+                        this.child(
+                            Button::new("license-repository")
+                                .xsmall()
+                                .link()
+                                .label(url.clone())
+                                .on_click(move |_, _, cx| cx.open_url(&url)),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(
+                        license.text.clone().unwrap_or_else(|| {
+                            "The crate doesn't ship a license file.".to_string()
+                        }),
+                    ),
+            )
+    }
+}
+
+impl Focusable for LicensesView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LicensesView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let all = self.licenses.loaded().copied().unwrap_or_default();
+        let shown = self.rows.read(cx).delegate().crates_count();
+
+        v_flex()
+            .gap_2()
+            .child(TextField::new("license-search", &self.search).cleanable())
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(if self.licenses.is_loading() {
+                        "Loading crates...".to_string()
+                    } else {
+                        format!("{} of {} crates", shown, all.len())
+                    }),
+            )
+            .child(v_flex().h(px(420.)).map(|this| {
+                if self.licenses.is_loading() {
+                    this.py_1().child(SkeletonRows::new("licenses-loading", 12))
+                } else if all.is_empty() {
+                    this.child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("This build doesn't include the third-party licenses."),
+                    )
+                } else {
+                    this.child(div().flex_1().overflow_hidden().child(self.rows.clone()))
+                        .when_some(self.selected(cx), |this, license| {
+                            this.child(Self::render_details(license, cx))
+                        })
+                }
+            }))
+    }
+}
+
+fn open_licenses(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        let view = LicensesView::view(window, cx);
+        window.open_modal(cx, move |modal, _, _| {
+            modal
+                .title("Third-Party Licenses")
+                .width(px(640.))
+                .child(view.clone())
+        });
+    });
+}
+
+fn open_about(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        window.open_modal(cx, |modal, _, cx| {
+            modal.title(SharedString::from(APP_NAME)).child(
+                v_flex()
+                    .gap_3()
+                    .child(
+                        h_flex()
+                            .gap_3()
+                            .child(TintedIcon::new("app-mark").size_10())
+                            .child(format!("Version {}", env!("CARGO_PKG_VERSION"))),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(match ThirdPartyLicense::loaded() {
+                                Some(licenses) => format!(
+                                    "Licensed under {}, built with {} open source crates.",
+                                    env!("CARGO_PKG_LICENSE"),
+                                    licenses.len()
+                                ),
+                                None => format!(
+                                    "Licensed under {}, built with open source crates.",
+                                    env!("CARGO_PKG_LICENSE")
+                                ),
+                            }),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .justify_end()
+                            .child(
+                                Button::new("show-licenses")
+                                    .small()
+                                    .outline()
+                                    .label("Third-Party Licenses...")
+                                    .on_click(|_, _, cx| open_licenses(cx)),
+                            )
+                            .child(
+                                Button::new("close-about")
+                                    .small()
+                                    .primary()
+                                    .label("Close")
+                                    .on_click(|_, window, cx| window.close_modal(cx)),
+                            ),
+                    ),
+            )
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    // Parse the licenses ahead of the views that show them.
+    cx.background_spawn(async {
+        ThirdPartyLicense::all();
+    })
+    .detach();
+
+    cx.on_action(|_: &About, cx| open_about(cx));
+    cx.on_action(|_: &ShowLicenses, cx| open_licenses(cx));
+}
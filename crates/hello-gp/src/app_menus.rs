@@ -1,29 +1,82 @@
-use gpui::{App, Menu, MenuItem, SharedString};
+use gpui::{App, Global, Menu, MenuItem, SharedString};
 use gpui_component::{ThemeMode, ThemeRegistry};
 
 use crate::{
-    About, CloseWindow, Open, Quit, SelectLocale, ToggleSearch,
-    themes::{SwitchTheme, SwitchThemeMode},
+    About, CloseWindow, Open, Quit, SelectLocale, ShowTelemetry, ToggleSearch,
+    appearance::{ChooseAccentColor, ChooseCursorColors, FollowSystemMode},
+    diagnostics::{ReportIssue, ShowGpuDiagnostics},
+    encryption::ToggleConfigEncryption,
+    extension_manager::ShowExtensions,
+    extensions::{ExtensionRegistry, RunExtensionCommand},
+    factory_reset::ResetAllSettings,
+    icons::{Icons, SelectIconPack},
+    kiosk,
+    layouts::{ApplyLayoutPreset, LayoutPresets, SaveLayoutPreset},
+    lock::{LockApp, SetLockPassphrase},
+    macos,
+    net_settings::ShowNetworkSettings,
+    pointer::ShowPointerDiagnostics,
+    profiles::{NewProfile, SwitchProfile},
+    session::Session,
+    settings::OpenSettings,
+    settings_editor::EditRawSettings,
+    settings_window::ShowSettings,
+    shortcuts::ShowShortcuts,
+    snapshots::RestoreConfiguration,
+    sounds::{SelectSoundPack, Sounds, ToggleMute},
+    split::{CloseSplit, FocusNextSplit, FocusPreviousSplit, SplitDown, SplitRight},
+    style_guide::ShowStyleGuide,
+    theme_diff::CompareThemes,
+    themes::{ExportTheme, ImportTheme, SwitchTheme, SwitchThemeMode, ToggleThemeMode},
+    whats_new::ShowWhatsNew,
 };
 
+/// The app menu title, kept to rebuild the menus when their items change.
+struct MenuTitle(SharedString);
+
+impl Global for MenuTitle {}
+
 pub fn init(title: impl Into<SharedString>, cx: &mut App) {
+    let title = title.into();
+    cx.set_global(MenuTitle(title.clone()));
+    // Nothing to reach the restricted actions through.
+    if kiosk::enabled() {
+        cx.set_menus(vec![]);
+        return;
+    }
     cx.set_menus(vec![
         Menu {
-            name: title.into(),
+            name: title,
             items: vec![
                 MenuItem::action("About", About),
                 MenuItem::Separator,
+                MenuItem::action("Settings...", ShowSettings),
+                MenuItem::action("Open Settings File", OpenSettings),
+                MenuItem::action("Advanced Settings (Raw JSON)...", EditRawSettings),
+                MenuItem::action("Network Settings...", ShowNetworkSettings),
                 MenuItem::action("Open...", Open),
                 MenuItem::Separator,
                 MenuItem::Submenu(Menu {
                     name: "Appearance".into(),
                     items: vec![
+                        MenuItem::action("System", FollowSystemMode),
                         MenuItem::action("Light", SwitchThemeMode(ThemeMode::Light)),
                         MenuItem::action("Dark", SwitchThemeMode(ThemeMode::Dark)),
+                        MenuItem::action("Toggle Dark Mode", ToggleThemeMode),
+                        MenuItem::separator(),
+                        MenuItem::action("Accent Color...", ChooseAccentColor),
+                        MenuItem::action("Cursor and Selection...", ChooseCursorColors),
                     ],
                 }),
                 theme_menu(cx),
+                icon_pack_menu(cx),
+                sounds_menu(cx),
                 language_menu(cx),
+                extensions_menu(cx),
+                MenuItem::Separator,
+                MenuItem::action("Lock", LockApp),
+                MenuItem::action("Set Lock Passphrase...", SetLockPassphrase),
+                MenuItem::action("Encrypt Config Files", ToggleConfigEncryption),
                 MenuItem::Separator,
                 MenuItem::action("Quit", Quit),
             ],
@@ -55,17 +108,118 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
         },
         Menu {
             name: "Window".into(),
-            items: vec![
-                MenuItem::action("Close Window", CloseWindow),
-                MenuItem::separator(),
-                MenuItem::action("Toggle Search", ToggleSearch),
-            ],
+            items: macos::window_menu_items()
+                .into_iter()
+                .chain([
+                    MenuItem::separator(),
+                    MenuItem::action("Close Window", CloseWindow),
+                    MenuItem::separator(),
+                    MenuItem::action("Split Right", SplitRight),
+                    MenuItem::action("Split Down", SplitDown),
+                    MenuItem::action("Close Split", CloseSplit),
+                    MenuItem::action("Focus Next Split", FocusNextSplit),
+                    MenuItem::action("Focus Previous Split", FocusPreviousSplit),
+                    layout_menu(cx),
+                    profile_menu(cx),
+                    MenuItem::separator(),
+                    MenuItem::action("Toggle Search", ToggleSearch),
+                ])
+                .collect(),
         },
         Menu {
             name: "Help".into(),
-            items: vec![MenuItem::action("Open Website", Open)],
+            items: vec![
+                MenuItem::action("Open Website", Open),
+                MenuItem::action("What's New", ShowWhatsNew),
+                MenuItem::action("Keyboard Shortcuts", ShowShortcuts),
+                MenuItem::separator(),
+                MenuItem::action("Usage Data...", ShowTelemetry),
+                MenuItem::action("Restore Previous Configuration...", RestoreConfiguration),
+                MenuItem::action("Reset All Settings...", ResetAllSettings),
+                MenuItem::separator(),
+                MenuItem::action("GPU Diagnostics...", ShowGpuDiagnostics),
+                MenuItem::action("Pointer Diagnostics...", ShowPointerDiagnostics),
+                MenuItem::action("Report an Issue...", ReportIssue),
+            ],
         },
     ]);
+    cx.set_dock_menu(dock_menu(cx));
+}
+
+/// The quick actions of the macOS dock menu and the Windows jump list.
+///
+/// Jump list entries launch a second instance with `--dock-action <index>`, which hands
+/// the index over to the running one, so keep the order stable within a session.
+fn dock_menu(cx: &App) -> Vec<MenuItem> {
+    let recent = LayoutPresets::global(cx)
+        .recent(cx)
+        .map(|preset| {
+            MenuItem::action(
+                format!("Layout: {}", preset.name),
+                ApplyLayoutPreset(preset.name.clone().into()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut items = vec![
+        MenuItem::action("Toggle Dark Mode", ToggleThemeMode),
+        MenuItem::action("Open Settings", ShowSettings),
+    ];
+    if !recent.is_empty() {
+        items.push(MenuItem::separator());
+        items.extend(recent);
+    }
+    items
+}
+
+/// Rebuild the menus, e.g. after a layout preset was saved.
+pub fn refresh(cx: &mut App) {
+    if let Some(MenuTitle(title)) = cx.try_global::<MenuTitle>() {
+        init(title.clone(), cx);
+    }
+}
+
+fn layout_menu(cx: &App) -> MenuItem {
+    MenuItem::Submenu(Menu {
+        name: "Layout".into(),
+        items: LayoutPresets::global(cx)
+            .presets
+            .iter()
+            .map(|preset| {
+                MenuItem::action(
+                    preset.name.clone(),
+                    ApplyLayoutPreset(preset.name.clone().into()),
+                )
+            })
+            .chain([
+                MenuItem::separator(),
+                MenuItem::action("Save Current Layout...", SaveLayoutPreset),
+            ])
+            .collect(),
+    })
+}
+
+fn profile_menu(cx: &App) -> MenuItem {
+    let session = Session::global(cx);
+    MenuItem::Submenu(Menu {
+        name: "Profile".into(),
+        items: session
+            .profiles
+            .keys()
+            .map(|name| {
+                let label = if *name == session.profile {
+                    format!("{} (Active)", name)
+                } else {
+                    name.clone()
+                };
+                MenuItem::action(label, SwitchProfile(name.clone().into()))
+            })
+            .chain([
+                MenuItem::separator(),
+                MenuItem::action("New Profile...", NewProfile),
+            ])
+            .collect(),
+    })
 }
 
 fn language_menu(_cx: &App) -> MenuItem {
@@ -82,9 +236,70 @@ fn theme_menu(cx: &App) -> MenuItem {
     let themes = ThemeRegistry::global(cx).sorted_themes();
     MenuItem::Submenu(Menu {
         name: "Theme".into(),
-        items: themes
-            .iter()
-            .map(|theme| MenuItem::action(theme.name.clone(), SwitchTheme(theme.name.clone())))
+        items: [
+            MenuItem::action("Compare Themes...", CompareThemes),
+            MenuItem::action("Style Guide...", ShowStyleGuide),
+            MenuItem::separator(),
+            MenuItem::action("Import Theme...", ImportTheme),
+            MenuItem::action("Export Theme...", ExportTheme),
+            MenuItem::separator(),
+        ]
+        .into_iter()
+        .chain(
+            themes
+                .iter()
+                .map(|theme| MenuItem::action(theme.name.clone(), SwitchTheme(theme.name.clone()))),
+        )
+        .collect(),
+    })
+}
+
+fn icon_pack_menu(cx: &App) -> MenuItem {
+    MenuItem::Submenu(Menu {
+        name: "Icon Theme".into(),
+        items: [MenuItem::action("Default", SelectIconPack(None))]
+            .into_iter()
+            .chain(Icons::global(cx).packs().iter().map(|pack| {
+                MenuItem::action(pack.name.clone(), SelectIconPack(Some(pack.id.clone())))
+            }))
             .collect(),
     })
 }
+
+fn sounds_menu(cx: &App) -> MenuItem {
+    MenuItem::Submenu(Menu {
+        name: "Sounds".into(),
+        items: [
+            MenuItem::action("Mute", ToggleMute),
+            MenuItem::separator(),
+            MenuItem::action("Default", SelectSoundPack(None)),
+        ]
+        .into_iter()
+        .chain(Sounds::global(cx).packs().iter().map(|pack| {
+            MenuItem::action(pack.name.clone(), SelectSoundPack(Some(pack.id.clone())))
+        }))
+        .collect(),
+    })
+}
+
+fn extensions_menu(cx: &App) -> MenuItem {
+    let registry = ExtensionRegistry::global(cx);
+    MenuItem::Submenu(Menu {
+        name: "Extensions".into(),
+        items: [
+            MenuItem::action("Manage Extensions...", ShowExtensions),
+            MenuItem::separator(),
+        ]
+        .into_iter()
+        .chain(registry.read(cx).commands().map(|(ext, command)| {
+            MenuItem::action(
+                format!("{}: {}", ext.name, command.title),
+                RunExtensionCommand {
+                    extension: ext.id.clone(),
+                    command: command.id.clone(),
+                },
+            )
+        }))
+        .collect(),
+    })
+}
@@ -0,0 +1,106 @@
+//! A thin bar across the top of the window while the [`TaskScheduler`] runs tasks, like a
+//! download or an install, so they show without opening the task list.
+
+use std::time::Duration;
+
+use gpui::{
+    Animation, AnimationExt as _, App, IntoElement, ParentElement as _, RenderOnce, Styled as _,
+    Window, div, prelude::FluentBuilder as _, px, relative,
+};
+use gpui_component::{ActiveTheme as _, Colorize as _};
+
+use crate::{appearance, power, tasks::TaskScheduler};
+
+/// How long the segment of an indeterminate bar takes to cross the window.
+const SWEEP_DURATION: Duration = Duration::from_millis(1600);
+/// The width of the segment of an indeterminate bar.
+const SWEEP_WIDTH: f32 = 0.3;
+
+/// How far the running tasks are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppProgressMode {
+    /// The average of the tasks in `0.0..=1.0`, when every one reports its progress.
+    Determinate(f32),
+    /// A task doesn't say how far it is.
+    Indeterminate,
+}
+
+impl AppProgressMode {
+    /// The progress of the running tasks, `None` when there are none.
+    pub fn of_running_tasks(cx: &App) -> Option<Self> {
+        let scheduler = TaskScheduler::global(cx);
+        let progress = scheduler
+            .read(cx)
+            .running_tasks()
+            .map(|task| task.progress)
+            .collect::<Vec<_>>();
+        if progress.is_empty() {
+            return None;
+        }
+        let known = progress.iter().flatten().copied().collect::<Vec<_>>();
+        Some(if known.len() == progress.len() {
+            Self::Determinate(known.iter().sum::<f32>() / known.len() as f32)
+        } else {
+            Self::Indeterminate
+        })
+    }
+}
+
+/// The bar, laid over the top of what's below it so nothing moves when it shows.
+///
+/// The owner re-renders it by observing [`TaskScheduler::global`].
+#[derive(IntoElement, Default)]
+pub struct AppProgress;
+
+impl AppProgress {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl RenderOnce for AppProgress {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let mode = AppProgressMode::of_running_tasks(cx);
+        let animated = !appearance::reduce_motion(cx) && power::animations_enabled(window, cx);
+        let color = cx.theme().primary;
+
+        div()
+            .relative()
+            .w_full()
+            .h_0()
+            .when_some(mode, |this, mode| {
+                let track = div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .w_full()
+                    .h(px(2.))
+                    .overflow_hidden()
+                    .bg(color.opacity(0.2));
+                this.child(match mode {
+                    AppProgressMode::Determinate(progress) => {
+                        track.child(div().h_full().w(relative(progress.clamp(0., 1.))).bg(color))
+                    }
+                    AppProgressMode::Indeterminate if animated => track.child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .h_full()
+                            .w(relative(SWEEP_WIDTH))
+                            .bg(color)
+                            .with_animation(
+                                "app-progress",
+                                Animation::new(SWEEP_DURATION).repeat(),
+                                |this, delta| {
+                                    this.left(relative(delta * (1. + SWEEP_WIDTH) - SWEEP_WIDTH))
+                                },
+                            ),
+                    ),
+                    // Still, without the sweep, a dimmer full bar.
+                    AppProgressMode::Indeterminate => {
+                        track.child(div().size_full().bg(color.opacity(0.6)))
+                    }
+                })
+            })
+    }
+}
@@ -0,0 +1,336 @@
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{
+    App, AppContext as _, Font, FontFallbacks, Global, Hsla, ParentElement as _, Styled as _,
+    WindowAppearance, actions, div, font,
+};
+use gpui_component::{
+    ActiveTheme as _, Colorize as _, ContextModal as _, Sizable as _, Theme, ThemeMode,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    color_input::{ColorInput, ColorInputEvent},
+    events::{self, ThemeChanged},
+    renderer,
+    settings::Settings,
+};
+
+actions!(
+    appearance,
+    [ChooseAccentColor, ChooseCursorColors, FollowSystemMode]
+);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AppearanceSettings {
+    /// Overrides the `primary` color of every theme, as `#rrggbb`.
+    pub accent_color: Option<String>,
+    /// Overrides the text cursor (caret) color of every theme, as `#rrggbb`.
+    pub cursor_color: Option<String>,
+    /// Overrides the text selection color of every theme, as `#rrggbb` or `#rrggbbaa`.
+    pub selection_color: Option<String>,
+    /// The color of the squiggles under misspelled words, as `#rrggbb`, the `danger` color of
+    /// the theme when unset.
+    pub squiggle_color: Option<String>,
+    /// Id of the icon pack in the `icons` data dir, the built-in icons when empty.
+    pub icon_pack: Option<String>,
+    /// Switch between light and dark with the system. Picking a mode turns it off.
+    pub follow_system: bool,
+    /// Leave out decorative animations, like confetti.
+    pub reduce_motion: bool,
+    /// The font of emoji and other color glyphs the UI font lacks, the one of the platform
+    /// when unset, e.g. `Noto Color Emoji`.
+    pub emoji_font: Option<String>,
+}
+
+/// A theme color that can be overridden in [`AppearanceSettings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorOverride {
+    Accent,
+    Cursor,
+    Selection,
+}
+
+impl ColorOverride {
+    const ALL: [Self; 3] = [Self::Accent, Self::Cursor, Self::Selection];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Accent => "Accent",
+            Self::Cursor => "Cursor",
+            Self::Selection => "Selection",
+        }
+    }
+
+    fn setting(self, settings: &mut AppearanceSettings) -> &mut Option<String> {
+        match self {
+            Self::Accent => &mut settings.accent_color,
+            Self::Cursor => &mut settings.cursor_color,
+            Self::Selection => &mut settings.selection_color,
+        }
+    }
+
+    fn value(self, cx: &App) -> Option<Hsla> {
+        let mut settings = Settings::global(cx).appearance.clone();
+        Hsla::parse_hex(self.setting(&mut settings).as_deref()?).ok()
+    }
+
+    fn theme_color(self, theme: &Theme) -> Hsla {
+        match self {
+            Self::Accent => theme.primary,
+            Self::Cursor => theme.caret,
+            Self::Selection => theme.selection,
+        }
+    }
+
+    fn set_theme_color(self, theme: &mut Theme, value: Hsla) {
+        match self {
+            Self::Accent => theme.primary = value,
+            Self::Cursor => theme.caret = value,
+            Self::Selection => theme.selection = value,
+        }
+    }
+}
+
+fn apply_overrides(cx: &mut App) {
+    for color in ColorOverride::ALL {
+        let Some(value) = color.value(cx) else {
+            continue;
+        };
+        // Setting the theme notifies its observers again, so only write when it differs.
+        if color.theme_color(cx.theme()) != value {
+            color.set_theme_color(Theme::global_mut(cx), value);
+            cx.refresh_windows();
+        }
+    }
+}
+
+fn set_override(color: ColorOverride, value: Option<Hsla>, cx: &mut App) {
+    Settings::update(cx, |settings, _| {
+        *color.setting(&mut settings.appearance) = value.map(|value| value.to_hex());
+    });
+    apply_overrides(cx);
+}
+
+fn open_color_dialog(title: &'static str, colors: &'static [ColorOverride], cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        let inputs = colors
+            .iter()
+            .map(|&color| {
+                let value = color
+                    .value(cx)
+                    .unwrap_or_else(|| color.theme_color(cx.theme()));
+                let input = cx.new(|cx| ColorInput::new(value, window, cx));
+                cx.subscribe(&input, move |_, event, cx| match event {
+                    ColorInputEvent::Change(value) => set_override(color, Some(*value), cx),
+                })
+                .detach();
+                (color, input)
+            })
+            .collect::<Vec<_>>();
+
+        window.open_modal(cx, move |modal, _, _| {
+            modal.title(title).child(
+                v_flex()
+                    .gap_3()
+                    .children(inputs.iter().map(|(color, input)| {
+                        v_flex()
+                            .gap_1()
+                            .child(div().text_sm().child(color.label()))
+                            .child(input.clone())
+                    }))
+                    .child(
+                        h_flex().justify_end().child(
+                            Button::new("reset-colors")
+                                .small()
+                                .ghost()
+                                .label("Use Theme Colors")
+                                .on_click(move |_, window, cx| {
+                                    for color in colors {
+                                        set_override(*color, None, cx);
+                                    }
+                                    // The theme's own colors come back on the next reload.
+                                    crate::themes::reload_state(cx);
+                                    window.close_modal(cx);
+                                }),
+                        ),
+                    ),
+            )
+        });
+    });
+}
+
+/// The light or dark preference of the desktop portal, `None` without one.
+#[derive(Default)]
+struct PortalColorScheme(Option<ThemeMode>);
+
+impl Global for PortalColorScheme {}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use futures::channel::mpsc::UnboundedSender;
+    use gpui_component::ThemeMode;
+    use zbus::{
+        blocking::{Connection, Proxy},
+        zvariant::{OwnedValue, Value},
+    };
+
+    const NAMESPACE: &str = "org.freedesktop.appearance";
+    const KEY: &str = "color-scheme";
+
+    /// The `color-scheme` is 1 for dark, 2 for light and 0 without a preference.
+    fn mode(value: &Value) -> Option<ThemeMode> {
+        match value {
+            // `Read` wraps the value once more than `ReadOne`.
+            Value::Value(value) => mode(value),
+            Value::U32(1) => Some(ThemeMode::Dark),
+            Value::U32(2) => Some(ThemeMode::Light),
+            _ => None,
+        }
+    }
+
+    fn watch(tx: &UnboundedSender<Option<ThemeMode>>) -> zbus::Result<()> {
+        let connection = Connection::session()?;
+        let proxy = Proxy::new(
+            &connection,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+        )?;
+        // Subscribe first, so a change right after the read isn't lost.
+        let changes = proxy.receive_signal("SettingChanged")?;
+        let value: OwnedValue = proxy
+            .call("ReadOne", &(NAMESPACE, KEY))
+            .or_else(|_| proxy.call("Read", &(NAMESPACE, KEY)))?;
+        _ = tx.unbounded_send(mode(&value));
+
+        for message in changes {
+            let (namespace, key, value) = message
+                .body()
+                .deserialize::<(String, String, OwnedValue)>()?;
+            if namespace == NAMESPACE && key == KEY && tx.unbounded_send(mode(&value)).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Watch the color scheme of the desktop portal, implemented for the desktops of
+    /// `XDG_CURRENT_DESKTOP` by their portal backend (GNOME, KDE and others).
+    pub(super) fn watch_color_scheme(tx: UnboundedSender<Option<ThemeMode>>) {
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let result = std::thread::Builder::new()
+            .name("color-scheme".into())
+            .spawn(move || {
+                if let Err(err) = watch(&tx) {
+                    tracing::info!("No color scheme from the portal of {:?}: {}", desktop, err);
+                }
+            });
+        if let Err(err) = result {
+            tracing::error!("Failed to watch the color scheme: {}", err);
+        }
+    }
+}
+
+/// The window appearance tells the mode of the system elsewhere.
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use futures::channel::mpsc::UnboundedSender;
+    use gpui_component::ThemeMode;
+
+    pub(super) fn watch_color_scheme(_: UnboundedSender<Option<ThemeMode>>) {}
+}
+
+/// The light or dark preference of the system.
+fn system_mode(cx: &App) -> ThemeMode {
+    if let Some(mode) = cx.global::<PortalColorScheme>().0 {
+        return mode;
+    }
+    match cx.window_appearance() {
+        WindowAppearance::Dark | WindowAppearance::VibrantDark => ThemeMode::Dark,
+        WindowAppearance::Light | WindowAppearance::VibrantLight => ThemeMode::Light,
+    }
+}
+
+/// The emoji font of the platform.
+#[cfg(target_os = "macos")]
+const DEFAULT_EMOJI_FONT: &str = "Apple Color Emoji";
+#[cfg(target_os = "windows")]
+const DEFAULT_EMOJI_FONT: &str = "Segoe UI Emoji";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_EMOJI_FONT: &str = "Noto Color Emoji";
+
+/// The UI font of the theme, falling back to the emoji font for the glyphs it lacks, so
+/// emoji in theme names and user text show in color rather than as boxes.
+pub fn ui_font(cx: &App) -> Font {
+    let emoji_font = Settings::global(cx)
+        .appearance
+        .emoji_font
+        .clone()
+        .filter(|font| !font.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EMOJI_FONT.to_string());
+    let mut ui_font = font(cx.theme().font_family.clone());
+    ui_font.fallbacks = Some(FontFallbacks::from_fonts(vec![emoji_font]));
+    ui_font
+}
+
+/// Whether to leave out decorative animations, also with software rendering.
+pub fn reduce_motion(cx: &App) -> bool {
+    Settings::global(cx).appearance.reduce_motion || renderer::reduced_effects()
+}
+
+/// Switch to the mode of the system when following it, e.g. after the system switched.
+pub(crate) fn sync_system_mode(cx: &mut App) {
+    if !Settings::global(cx).appearance.follow_system {
+        return;
+    }
+    let mode = system_mode(cx);
+    if cx.theme().mode != mode {
+        Theme::change(mode, None, cx);
+        cx.refresh_windows();
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(PortalColorScheme::default());
+    let (tx, mut rx) = mpsc::unbounded();
+    platform::watch_color_scheme(tx);
+    cx.spawn(async move |cx| {
+        while let Some(mode) = rx.next().await {
+            let result = cx.update(|cx| {
+                cx.set_global(PortalColorScheme(mode));
+                sync_system_mode(cx);
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+    sync_system_mode(cx);
+    cx.observe_global::<Settings>(sync_system_mode).detach();
+    cx.on_action(|_: &FollowSystemMode, cx| {
+        Settings::update(cx, |settings, _| settings.appearance.follow_system = true);
+        sync_system_mode(cx);
+    });
+
+    apply_overrides(cx);
+    events::subscribe::<ThemeChanged>(cx, |_, cx| apply_overrides(cx)).detach();
+    cx.on_action(|_: &ChooseAccentColor, cx| {
+        open_color_dialog("Accent Color", &[ColorOverride::Accent], cx)
+    });
+    cx.on_action(|_: &ChooseCursorColors, cx| {
+        open_color_dialog(
+            "Cursor and Selection",
+            &[ColorOverride::Cursor, ColorOverride::Selection],
+            cx,
+        )
+    });
+}
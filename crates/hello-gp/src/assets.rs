@@ -3,6 +3,8 @@ use gpui::{AssetSource, Result, SharedString};
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
 
+use crate::icons::{ICON_PACK_ASSET_PREFIX, Icons};
+
 #[derive(RustEmbed)]
 #[folder = "$CARGO_MANIFEST_DIR/assets"]
 #[include = "icons/**/*.svg"]
@@ -14,6 +16,13 @@ impl AssetSource for Assets {
             return Ok(None);
         }
 
+        if let Some(path) = path.strip_prefix(ICON_PACK_ASSET_PREFIX) {
+            let path = Icons::dir().join(path);
+            return std::fs::read(&path)
+                .map(|data| Some(Cow::Owned(data)))
+                .map_err(|err| anyhow!("could not read icon at \"{}\": {}", path.display(), err));
+        }
+
         Self::get(path)
             .map(|f| Some(f.data))
             .ok_or_else(|| anyhow!("could not find asset at path \"{path}\""))
@@ -0,0 +1,105 @@
+//! Entry points for the benchmarks in `benches/`, built with the `bench` feature only.
+
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use futures::channel::oneshot;
+use gpui::App;
+use gpui_component::{Theme, ThemeConfig, ThemeRegistry};
+
+use crate::{config::ConfigFormat, themes::State};
+
+/// The default state, written in the format with the extension `ext` (`toml`, `json5`, `json`).
+pub fn sample_state(ext: &str) -> String {
+    let format =
+        ConfigFormat::from_path(Path::new(&format!("state.{}", ext))).expect("a config format");
+    format
+        .serialize(&State::default())
+        .expect("the state serializes")
+}
+
+/// Parse `text` as the state, like on startup.
+pub fn parse_state(ext: &str, text: &str) -> anyhow::Result<()> {
+    let format = ConfigFormat::from_path(Path::new(&format!("state.{}", ext)))
+        .ok_or_else(|| anyhow::anyhow!("Unknown config format {}", ext))?;
+    format.parse::<State>(text).map(|_| ())
+}
+
+/// A theme file with the theme `name`, with every color set like the bundled themes.
+fn theme_file(name: &str, dark: bool) -> String {
+    let (background, foreground) = if dark {
+        ("#1e1e1e", "#e4e4e4")
+    } else {
+        ("#ffffff", "#1e1e1e")
+    };
+    serde_json::json!({
+        "themes": [{
+            "name": name,
+            "mode": if dark { "dark" } else { "light" },
+            "colors": {
+                "background": background,
+                "foreground": foreground,
+                "border": "#d4d4d4",
+                "primary.background": "#2563eb",
+                "primary.foreground": "#ffffff",
+                "secondary.background": "#f4f4f5",
+                "accent.background": "#e4e4e7",
+                "muted.background": "#f4f4f5",
+                "muted.foreground": "#71717a",
+                "danger.background": "#ef4444",
+                "success.background": "#22c55e",
+                "warning.background": "#f59e0b",
+            },
+        }],
+    })
+    .to_string()
+}
+
+/// A fresh themes dir in the temp dir with `count` theme files.
+pub fn write_theme_dir(count: usize) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("hello-gp-bench-themes-{}", count));
+    _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("a temp dir");
+    for ix in 0..count {
+        let name = format!("Bench {}", ix);
+        std::fs::write(
+            dir.join(format!("{}.json", ix)),
+            theme_file(&name, ix % 2 == 1),
+        )
+        .expect("a theme file");
+    }
+    dir
+}
+
+/// Load the themes in `dir` into the theme registry, the way it's done on startup, resolving
+/// once they're loaded. Each call also starts watching `dir`, like on startup.
+pub fn load_theme_dir(dir: &Path, cx: &mut App) -> oneshot::Receiver<()> {
+    let (tx, rx) = oneshot::channel();
+    // Called again on each change of the dir, only the first load is waited for.
+    let tx = Cell::new(Some(tx));
+    if let Err(err) = ThemeRegistry::watch_dir(dir.to_path_buf(), cx, move |_| {
+        if let Some(tx) = tx.take() {
+            _ = tx.send(());
+        }
+    }) {
+        tracing::error!("Failed to load the themes of {}: {}", dir.display(), err);
+    }
+    rx
+}
+
+/// The themes of the registry with these `names`, e.g. those written by [`write_theme_dir`].
+pub fn themes(names: &[&str], cx: &App) -> Vec<Rc<ThemeConfig>> {
+    let themes = ThemeRegistry::global(cx).themes();
+    names
+        .iter()
+        .filter_map(|name| themes.get(*name).cloned())
+        .collect()
+}
+
+/// Make `theme` the active theme.
+pub fn apply_theme(theme: &Rc<ThemeConfig>, cx: &mut App) {
+    Theme::global_mut(cx).apply_config(theme);
+}
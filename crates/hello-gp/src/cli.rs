@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    deep_links,
+    renderer::{DisplayServer, WindowDecorations},
+    themes::THEME_FILE_EXTENSION,
+};
+
+/// Command line arguments of the gallery.
+///
+/// ```sh
+/// cargo run -- [story_name] [--software-render] [--dump-config-schema[=settings|state]]
+///     [--dock-action <index>] [--kiosk] [--safe-mode] [--reset-state] [--unregister] [--x11 | --wayland]
+///     [--scale-factor <factor>] [--decorations=client|server]
+/// cargo run -- hello-gp://settings/appearance
+/// cargo run -- path/to/theme.hgptheme
+/// ```
+///
+/// A `hello-gp://` link is what the OS passes when the app is opened through its URL
+/// scheme, see [`crate::DeepLinks`] for the paths.
+#[derive(Debug, Clone, Default)]
+pub struct Cli {
+    /// The story to open, e.g. `button`.
+    pub story: Option<String>,
+    /// Print the JSON Schema of the config files and exit, optionally only for one of them.
+    pub dump_config_schema: Option<Option<String>>,
+    /// Render on the CPU with reduced effects, for machines where GPU setup fails.
+    pub software_render: bool,
+    /// Run an entry of the dock menu in the running instance, passed by the Windows jump list.
+    pub dock_action: Option<usize>,
+    /// A deep link to open, e.g. `hello-gp://themes/gallery`.
+    pub url: Option<String>,
+    /// Run fullscreen and read-only with a restricted set of actions, see [`crate::KioskSettings`].
+    pub kiosk: bool,
+    /// Start without the user themes, extensions and scripts, also done after the last starts
+    /// crashed.
+    pub safe_mode: bool,
+    /// Move the config dir to a backup and start with the default settings.
+    pub reset_state: bool,
+    /// A theme file to install, passed by the OS when it's opened from the file manager.
+    pub file: Option<PathBuf>,
+    /// Remove the URL scheme, file associations and shortcuts, and exit. Run by the
+    /// uninstaller.
+    pub unregister: bool,
+    /// The display server on Linux, see [`crate::RenderSettings`].
+    pub display_server: Option<DisplayServer>,
+    /// A fixed scale factor for X11.
+    pub scale_factor: Option<f32>,
+    /// Who draws the window decorations on Linux.
+    pub decorations: Option<WindowDecorations>,
+}
+
+impl Cli {
+    pub fn parse() -> Self {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Self {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+
+            match flag {
+                "--dump-config-schema" => cli.dump_config_schema = Some(value),
+                "--software-render" => cli.software_render = true,
+                "--kiosk" => cli.kiosk = true,
+                "--safe-mode" => cli.safe_mode = true,
+                "--reset-state" => cli.reset_state = true,
+                "--unregister" => cli.unregister = true,
+                "--x11" => cli.display_server = Some(DisplayServer::X11),
+                "--wayland" => cli.display_server = Some(DisplayServer::Wayland),
+                "--scale-factor" => {
+                    cli.scale_factor = value.or_else(|| args.next()).and_then(|v| v.parse().ok())
+                }
+                "--decorations" => {
+                    cli.decorations = value.as_deref().and_then(WindowDecorations::parse);
+                    if cli.decorations.is_none() {
+                        eprintln!("Unknown decorations: {}, expected client or server", arg);
+                    }
+                }
+                // The jump list passes the index as a separate argument.
+                "--dock-action" => {
+                    cli.dock_action = value.or_else(|| args.next()).and_then(|v| v.parse().ok())
+                }
+                _ if flag.starts_with("--") => eprintln!("Unknown argument: {}", arg),
+                _ if arg.starts_with(&format!("{}://", deep_links::SCHEME)) => cli.url = Some(arg),
+                _ if Path::new(&arg)
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(THEME_FILE_EXTENSION)) =>
+                {
+                    // Absolute, since it may be handed to an instance with another cwd.
+                    cli.file = std::path::absolute(&arg).ok()
+                }
+                _ if cli.story.is_none() => cli.story = Some(arg),
+                _ => {}
+            }
+        }
+        cli
+    }
+}
@@ -0,0 +1,246 @@
+use std::process::Command;
+
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, IntoElement,
+    ParentElement as _, Render, SharedString, Styled as _, Subscription, Window, div, hsla,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Colorize as _, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    color_picker::{ColorPicker, ColorPickerEvent, ColorPickerState},
+    h_flex,
+    input::InputState,
+};
+
+use crate::forms::{self, FormField, FormFieldEvent};
+
+/// Screen color pickers that print the picked color as `#rrggbb`, tried in order.
+const EYEDROPPERS: [(&str, &[&str]); 2] = [("hyprpicker", &[]), ("xcolor", &[])];
+
+#[derive(Debug, Clone, Copy)]
+pub enum ColorInputEvent {
+    Change(Hsla),
+}
+
+/// A color field with a picker, hex and HSL inputs, and an eyedropper when the OS has one.
+///
+/// ```ignore
+/// let accent = cx.new(|cx| ColorInput::new(cx.theme().primary, window, cx));
+/// cx.subscribe(&accent, |_, _, ColorInputEvent::Change(color), _| println!("{}", color.to_hex()));
+/// ```
+pub struct ColorInput {
+    value: Hsla,
+    picker: Entity<ColorPickerState>,
+    hex: Entity<FormField>,
+    hue: Entity<FormField>,
+    saturation: Entity<FormField>,
+    lightness: Entity<FormField>,
+    picking: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ColorInput {
+    pub fn new(value: Hsla, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let picker = cx.new(|cx| ColorPickerState::new(window, cx).default_value(value));
+        let mut field = |placeholder: &'static str, max: Option<f64>| {
+            cx.new(|cx| {
+                let input = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+                let field = FormField::new(input, cx);
+                match max {
+                    Some(max) => field.validate(forms::number_in(0., max)),
+                    None => field.validate(forms::hex_color),
+                }
+            })
+        };
+        let hex = field("#rrggbb", None);
+        let hue = field("H", Some(360.));
+        let saturation = field("S", Some(100.));
+        let lightness = field("L", Some(100.));
+
+        let mut _subscriptions = vec![
+            cx.subscribe_in(&picker, window, |this, _, event, window, cx| match event {
+                ColorPickerEvent::Change(Some(color)) => this.apply(*color, window, cx),
+                ColorPickerEvent::Change(None) => {}
+            }),
+            cx.subscribe_in(&hex, window, |this, _, event, window, cx| {
+                let FormFieldEvent::Change(value) = event;
+                if let Ok(color) = Hsla::parse_hex(value) {
+                    this.apply(color, window, cx);
+                }
+            }),
+        ];
+        for input in [&hue, &saturation, &lightness] {
+            _subscriptions.push(cx.subscribe_in(
+                input,
+                window,
+                |this, _, _: &FormFieldEvent, window, cx| {
+                    if let Some(color) = this.parse_hsl(cx) {
+                        this.apply(color, window, cx);
+                    }
+                },
+            ));
+        }
+
+        let mut this = Self {
+            value,
+            picker,
+            hex,
+            hue,
+            saturation,
+            lightness,
+            picking: false,
+            _subscriptions,
+        };
+        this.sync_inputs(window, cx);
+        this
+    }
+
+    pub fn value(&self) -> Hsla {
+        self.value
+    }
+
+    /// Set the color without emitting [`ColorInputEvent::Change`].
+    pub fn set_value(&mut self, value: Hsla, window: &mut Window, cx: &mut Context<Self>) {
+        self.value = value;
+        self.sync_inputs(window, cx);
+        cx.notify();
+    }
+
+    fn apply(&mut self, value: Hsla, window: &mut Window, cx: &mut Context<Self>) {
+        if value == self.value {
+            return;
+        }
+        self.set_value(value, window, cx);
+        cx.emit(ColorInputEvent::Change(value));
+    }
+
+    /// The color of the HSL fields, when they're all valid.
+    fn parse_hsl(&self, cx: &App) -> Option<Hsla> {
+        let read = |field: &Entity<FormField>| {
+            let field = field.read(cx);
+            if !field.is_valid() {
+                return None;
+            }
+            field.value(cx).parse::<f32>().ok()
+        };
+        let (h, s, l) = (
+            read(&self.hue)?,
+            read(&self.saturation)?,
+            read(&self.lightness)?,
+        );
+
+        Some(hsla(h / 360., s / 100., l / 100., self.value.a))
+    }
+
+    fn sync_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let value = self.value;
+        self.picker
+            .update(cx, |picker, cx| picker.set_value(value, window, cx));
+        self.hex
+            .update(cx, |field, cx| field.set_value(value.to_hex(), window, cx));
+        for (input, component) in [
+            (&self.hue, value.h * 360.),
+            (&self.saturation, value.s * 100.),
+            (&self.lightness, value.l * 100.),
+        ] {
+            input.update(cx, |field, cx| {
+                field.set_value(format!("{:.0}", component), window, cx)
+            });
+        }
+    }
+
+    /// The first eyedropper found in `PATH`, only Linux desktops ship one we can drive.
+    fn eyedropper() -> Option<(&'static str, &'static [&'static str])> {
+        let path = std::env::var_os("PATH")?;
+        EYEDROPPERS.into_iter().find(|(program, _)| {
+            std::env::split_paths(&path).any(|dir| dir.join(program).is_file())
+        })
+    }
+
+    fn pick_from_screen(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((program, args)) = Self::eyedropper() else {
+            return;
+        };
+
+        self.picking = true;
+        cx.notify();
+        let output = cx
+            .background_executor()
+            .spawn(async move { Command::new(program).args(args).output() });
+        cx.spawn_in(window, async move |this, window| {
+            let output = output.await;
+            _ = this.update_in(window, |this, window, cx| {
+                this.picking = false;
+                cx.notify();
+
+                let picked = match output {
+                    Ok(output) if output.status.success() => {
+                        let text = String::from_utf8_lossy(&output.stdout);
+                        Hsla::parse_hex(text.trim()).ok()
+                    }
+                    Ok(_) => None,
+                    Err(err) => {
+                        tracing::error!("Failed to run {}: {}", program, err);
+                        None
+                    }
+                };
+                if let Some(color) = picked {
+                    this.apply(color, window, cx);
+                }
+            });
+        })
+        .detach();
+    }
+}
+
+impl EventEmitter<ColorInputEvent> for ColorInput {}
+
+impl Focusable for ColorInput {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.hex.read(cx).focus_handle(cx)
+    }
+}
+
+impl Render for ColorInput {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_eyedropper = Self::eyedropper().is_some();
+        let hsl_input = |field: &Entity<FormField>, label: &'static str| {
+            h_flex()
+                .items_start()
+                .gap_1()
+                .w(px(72.))
+                .child(
+                    div()
+                        .pt_1()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(SharedString::from(label)),
+                )
+                .child(field.clone())
+        };
+
+        // Top aligned, as a field grows by its error.
+        h_flex()
+            .items_start()
+            .gap_2()
+            .child(ColorPicker::new(&self.picker).small())
+            .child(div().w(px(96.)).child(self.hex.clone()))
+            .child(hsl_input(&self.hue, "H"))
+            .child(hsl_input(&self.saturation, "S"))
+            .child(hsl_input(&self.lightness, "L"))
+            .when(has_eyedropper, |this| {
+                this.child(
+                    Button::new("eyedropper")
+                        .small()
+                        .ghost()
+                        .icon(IconName::Eye)
+                        .tooltip("Pick a color from the screen")
+                        .loading(self.picking)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.pick_from_screen(window, cx);
+                        })),
+                )
+            })
+    }
+}
@@ -0,0 +1,445 @@
+//! A select of many options, filtered as they're typed, with the options loaded in the
+//! background the first time it opens.
+//!
+//! ```ignore
+//! let fonts = cx.new(|_| {
+//!     Combobox::new("font", |cx| {
+//!         let names = cx.text_system().all_font_names();
+//!         Task::ready(Ok(names.into_iter().map(ComboboxItem::from).collect()))
+//!     })
+//!     .placeholder("Font")
+//! });
+//! cx.subscribe(&fonts, |_, _, ComboboxEvent::Change(item), _| println!("{}", item.value));
+//! ```
+
+use std::rc::Rc;
+
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Corner, DismissEvent, ElementId, Entity,
+    EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding,
+    ParentElement as _, Render, SharedString, Styled as _, Subscription, Task, Window, actions,
+    div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputEvent, InputState},
+    popover::Popover,
+    v_flex,
+};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    dismiss::{DismissLayer, DismissStack},
+    skeletons::{Loadable, SkeletonRows},
+    text_field::TextField,
+    virtual_rows::{RowsDelegate, VirtualRows, VirtualRowsEvent},
+};
+
+actions!(combobox, [SelectPrev, SelectNext]);
+
+const CONTEXT: &str = "Combobox";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+    ]);
+}
+
+/// An option of a [`Combobox`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboboxItem {
+    pub value: SharedString,
+    pub label: SharedString,
+    /// Shown after the label, dimmed, and searched too.
+    pub detail: Option<SharedString>,
+}
+
+impl ComboboxItem {
+    pub fn new(value: impl Into<SharedString>, label: impl Into<SharedString>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+            detail: None,
+        }
+    }
+
+    pub fn detail(mut self, detail: impl Into<SharedString>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Whether every word of the lowercase `query` is in the label or the detail.
+    fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {}",
+            self.label.to_lowercase(),
+            self.detail.as_deref().unwrap_or_default().to_lowercase()
+        );
+        query.split_whitespace().all(|word| haystack.contains(word))
+    }
+}
+
+/// An item labeled by its value.
+impl From<SharedString> for ComboboxItem {
+    fn from(value: SharedString) -> Self {
+        Self::new(value.clone(), value)
+    }
+}
+
+impl From<String> for ComboboxItem {
+    fn from(value: String) -> Self {
+        SharedString::from(value).into()
+    }
+}
+
+/// Loads the options of a [`Combobox`].
+pub type OptionsLoader = Rc<dyn Fn(&mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>>>;
+
+#[derive(Debug, Clone)]
+pub enum ComboboxEvent {
+    /// An option was picked, by a click or Enter.
+    Change(ComboboxItem),
+}
+
+struct ItemRows {
+    items: Vec<ComboboxItem>,
+    selected: Option<SharedString>,
+}
+
+impl RowsDelegate for ItemRows {
+    fn rows_count(&self, _: &App) -> usize {
+        self.items.len()
+    }
+
+    fn render_row(&self, ix: usize, selected: bool, _: &mut Window, cx: &mut App) -> AnyElement {
+        let item = &self.items[ix];
+        let checked = self.selected.as_ref() == Some(&item.value);
+        h_flex()
+            .size_full()
+            .gap_2()
+            .px_2()
+            .rounded(cx.theme().radius)
+            .text_sm()
+            .cursor_role(CursorRole::Pointer, cx)
+            .when(selected, |this| this.bg(cx.theme().accent))
+            .child(div().w_4().when(checked, |this| {
+                this.child(Icon::new(IconName::Check).small())
+            }))
+            .child(div().flex_1().truncate().child(item.label.clone()))
+            .when_some(item.detail.clone(), |this, detail| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(detail),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+/// The popover of a [`Combobox`], a query and the options matching it.
+struct ComboboxMenu {
+    query: Entity<InputState>,
+    rows: Entity<VirtualRows<ItemRows>>,
+    loader: OptionsLoader,
+    /// `None` until first opened.
+    options: Option<Loadable<Vec<ComboboxItem>>>,
+    /// Set while the selection moves by the keyboard or the query, which doesn't pick.
+    navigating: bool,
+    _subscriptions: Vec<Subscription>,
+    _dismiss: Subscription,
+}
+
+impl ComboboxMenu {
+    fn new(loader: OptionsLoader, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let query = cx.new(|cx| InputState::new(window, cx).placeholder("Search..."));
+        let rows = cx.new(|cx| {
+            VirtualRows::new(
+                ItemRows {
+                    items: vec![],
+                    selected: None,
+                },
+                window,
+                cx,
+            )
+        });
+        let _subscriptions = vec![
+            cx.subscribe(&query, |this, _, event: &InputEvent, cx| match event {
+                InputEvent::Change => this.update_rows(cx),
+                InputEvent::PressEnter { .. } => {
+                    if let Some(ix) = this.rows.read(cx).selected_index() {
+                        this.pick(ix, cx);
+                    }
+                }
+                _ => {}
+            }),
+            cx.subscribe(
+                &rows,
+                |this, _, event: &VirtualRowsEvent, cx| match *event {
+                    VirtualRowsEvent::Select(ix) if !this.navigating => this.pick(ix, cx),
+                    VirtualRowsEvent::Confirm(ix) => this.pick(ix, cx),
+                    VirtualRowsEvent::Select(_) => {}
+                },
+            ),
+        ];
+        // Closed by escape while the query has the focus, the popover is open then.
+        let view = cx.weak_entity();
+        let _dismiss = DismissStack::register(DismissLayer::Menu, window, cx, move |window, cx| {
+            view.update(cx, |this, cx| {
+                let open = this.query.focus_handle(cx).is_focused(window);
+                if open {
+                    cx.emit(DismissEvent);
+                }
+                open
+            })
+            .unwrap_or(false)
+        });
+
+        Self {
+            query,
+            rows,
+            loader,
+            options: None,
+            navigating: false,
+            _subscriptions,
+            _dismiss,
+        }
+    }
+
+    /// Clear the query, and load the options the first time.
+    fn open(
+        &mut self,
+        selected: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.query
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.rows
+            .update(cx, |rows, _| rows.delegate_mut().selected = selected);
+        if self.options.is_none() {
+            self.reload(cx);
+        } else {
+            self.update_rows(cx);
+        }
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.options = Some(Loadable::Loading);
+        let load = (self.loader)(cx);
+        cx.spawn(async move |this, cx| {
+            let options = load.await;
+            this.update(cx, |this, cx| {
+                this.options = Some(options.into());
+                this.update_rows(cx);
+            })
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        let query = self.query.read(cx).value().trim().to_lowercase();
+        let mut items = self
+            .options
+            .as_ref()
+            .and_then(Loadable::loaded)
+            .into_iter()
+            .flatten()
+            .filter(|item| item.matches(&query))
+            .cloned()
+            .collect::<Vec<_>>();
+        // The options starting with what's typed first, so typing a name goes to it.
+        if !query.is_empty() {
+            items.sort_by_key(|item| !item.label.to_lowercase().starts_with(&query));
+        }
+
+        self.navigating = true;
+        self.rows.update(cx, |rows, cx| {
+            let selected = rows.delegate().selected.clone();
+            let ix = if query.is_empty() {
+                items
+                    .iter()
+                    .position(|item| Some(&item.value) == selected.as_ref())
+            } else {
+                (!items.is_empty()).then_some(0)
+            };
+            rows.delegate_mut().items = items;
+            rows.refresh(cx);
+            rows.set_selected_index(ix, cx);
+        });
+        self.navigating = false;
+        cx.notify();
+    }
+
+    fn select_step(&mut self, delta: isize, cx: &mut Context<Self>) {
+        self.navigating = true;
+        self.rows.update(cx, |rows, cx| {
+            let count = rows.delegate().items.len();
+            if count == 0 {
+                return;
+            }
+            let ix = match rows.selected_index() {
+                Some(ix) => (ix as isize + delta).clamp(0, count as isize - 1) as usize,
+                None if delta < 0 => count - 1,
+                None => 0,
+            };
+            rows.set_selected_index(Some(ix), cx);
+        });
+        self.navigating = false;
+    }
+
+    fn pick(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(item) = self.rows.read(cx).delegate().items.get(ix).cloned() else {
+            return;
+        };
+        cx.emit(ComboboxEvent::Change(item));
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<ComboboxEvent> for ComboboxMenu {}
+impl EventEmitter<DismissEvent> for ComboboxMenu {}
+
+impl Focusable for ComboboxMenu {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query.focus_handle(cx)
+    }
+}
+
+impl Render for ComboboxMenu {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context(CONTEXT)
+            .on_action(cx.listener(|this, _: &SelectPrev, _, cx| this.select_step(-1, cx)))
+            .on_action(cx.listener(|this, _: &SelectNext, _, cx| this.select_step(1, cx)))
+            .w(px(280.))
+            .gap_2()
+            .child(TextField::new("combobox-query", &self.query).cleanable())
+            .child(match &self.options {
+                None | Some(Loadable::Loading) => {
+                    SkeletonRows::new("combobox-options", 4).into_any_element()
+                }
+                Some(Loadable::Failed(err)) => div()
+                    .text_sm()
+                    .text_color(cx.theme().danger)
+                    .child(err.clone())
+                    .into_any_element(),
+                Some(Loadable::Loaded(_)) if self.rows.read(cx).delegate().items.is_empty() => {
+                    div()
+                        .py_2()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("No matches")
+                        .into_any_element()
+                }
+                Some(Loadable::Loaded(_)) => div()
+                    .h(px(240.))
+                    .child(self.rows.clone())
+                    .into_any_element(),
+            })
+    }
+}
+
+/// A button showing the picked option, opening a searchable list of the options.
+///
+/// Unlike a popup menu, it takes thousands of options: the list only renders the rows in view.
+pub struct Combobox {
+    id: ElementId,
+    menu: Option<Entity<ComboboxMenu>>,
+    loader: OptionsLoader,
+    selected: Option<ComboboxItem>,
+    placeholder: SharedString,
+    disabled: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Combobox {
+    pub fn new(
+        id: impl Into<ElementId>,
+        loader: impl Fn(&mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>> + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            menu: None,
+            loader: Rc::new(loader),
+            selected: None,
+            placeholder: "Select...".into(),
+            disabled: false,
+            _subscriptions: vec![],
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn selected(&self) -> Option<&ComboboxItem> {
+        self.selected.as_ref()
+    }
+
+    /// Pick an option without emitting [`ComboboxEvent::Change`].
+    pub fn set_selected(&mut self, item: Option<ComboboxItem>, cx: &mut Context<Self>) {
+        self.selected = item;
+        cx.notify();
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool, cx: &mut Context<Self>) {
+        self.disabled = disabled;
+        cx.notify();
+    }
+
+    /// The menu, made the first time it opens.
+    fn menu(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<ComboboxMenu> {
+        if let Some(menu) = &self.menu {
+            return menu.clone();
+        }
+        let loader = self.loader.clone();
+        let menu = cx.new(|cx| ComboboxMenu::new(loader, window, cx));
+        self._subscriptions
+            .push(cx.subscribe(&menu, |this, _, event: &ComboboxEvent, cx| {
+                let ComboboxEvent::Change(item) = event;
+                this.selected = Some(item.clone());
+                cx.emit(ComboboxEvent::Change(item.clone()));
+                cx.notify();
+            }));
+        self.menu = Some(menu.clone());
+        menu
+    }
+}
+
+impl EventEmitter<ComboboxEvent> for Combobox {}
+
+impl Render for Combobox {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let label = self
+            .selected
+            .as_ref()
+            .map_or_else(|| self.placeholder.clone(), |item| item.label.clone());
+        let this = cx.entity();
+
+        Popover::new(self.id.clone())
+            .anchor(Corner::TopLeft)
+            .trigger(
+                Button::new("trigger")
+                    .small()
+                    .outline()
+                    .disabled(self.disabled)
+                    .label(label)
+                    .icon(IconName::ChevronsUpDown),
+            )
+            .content(move |window, cx| {
+                this.update(cx, |this, cx| {
+                    let menu = this.menu(window, cx);
+                    let selected = this.selected.as_ref().map(|item| item.value.clone());
+                    menu.update(cx, |menu, cx| menu.open(selected, window, cx));
+                    menu
+                })
+            })
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use gpui::{
+    AnyElement, App, Bounds, Global, Hsla, IntoElement, ParentElement as _, Pixels, RenderOnce,
+    Styled as _, Window, WindowId, canvas, div, fill, point, px, size,
+};
+use gpui_component::ActiveTheme as _;
+use rand::{Rng as _, SeedableRng as _, rngs::StdRng};
+
+use crate::appearance;
+
+/// How long a burst lasts, the pieces fade out over the last part of it.
+const DURATION: Duration = Duration::from_millis(2800);
+const FADE_OUT: f32 = 0.25;
+const PIECES: usize = 120;
+/// In pixels per second squared.
+const GRAVITY: f32 = 900.;
+
+/// A piece of confetti, at the top of its parent when the burst starts.
+struct Piece {
+    /// Where it starts, as a share of the width.
+    x: f32,
+    /// In pixels per second, up is negative.
+    velocity: (f32, f32),
+    /// How far and how fast it sways from side to side.
+    sway: (f32, f32),
+    /// How fast it flips, which narrows it.
+    spin: f32,
+    size: (f32, f32),
+    color: usize,
+}
+
+impl Piece {
+    fn random(rng: &mut StdRng, colors: usize) -> Self {
+        Self {
+            x: rng.gen_range(0.05..0.95),
+            velocity: (rng.gen_range(-80.0..80.0), rng.gen_range(-420.0..-120.0)),
+            sway: (rng.gen_range(6.0..24.0), rng.gen_range(2.0..6.0)),
+            spin: rng.gen_range(4.0..12.0),
+            size: (rng.gen_range(5.0..9.0), rng.gen_range(8.0..14.0)),
+            color: rng.gen_range(0..colors),
+        }
+    }
+
+    fn bounds(&self, parent: Bounds<Pixels>, t: f32) -> Bounds<Pixels> {
+        let x = self.x * parent.size.width.as_f32()
+            + self.velocity.0 * t
+            + self.sway.0 * (self.sway.1 * t).sin();
+        let y = self.velocity.1 * t + 0.5 * GRAVITY * t * t;
+        let width = self.size.0 * (self.spin * t).cos().abs().max(0.2);
+        Bounds::new(
+            parent.origin + point(px(x), px(y)),
+            size(px(width), px(self.size.1)),
+        )
+    }
+}
+
+/// A burst of confetti over its parent, from `started` on, e.g. when something finished.
+///
+/// Place it last in a `relative` parent. It draws nothing when motion is reduced.
+#[derive(IntoElement)]
+pub struct Confetti {
+    seed: u64,
+    started: Instant,
+}
+
+impl Confetti {
+    /// The same `seed` scatters the pieces the same way on every frame.
+    pub fn new(seed: u64, started: Instant) -> Self {
+        Self { seed, started }
+    }
+
+    fn paint(&self, bounds: Bounds<Pixels>, colors: &[Hsla], window: &mut Window) {
+        let elapsed = self.started.elapsed();
+        if elapsed >= DURATION {
+            return;
+        }
+        let t = elapsed.as_secs_f32();
+        let progress = t / DURATION.as_secs_f32();
+        let opacity = ((1. - progress) / FADE_OUT).min(1.);
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        for _ in 0..PIECES {
+            let piece = Piece::random(&mut rng, colors.len());
+            let color = colors[piece.color].opacity(opacity);
+            window.paint_quad(fill(piece.bounds(bounds, t), color).corner_radii(px(1.)));
+        }
+        window.request_animation_frame();
+    }
+}
+
+impl RenderOnce for Confetti {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        if appearance::reduce_motion(cx) || self.started.elapsed() >= DURATION {
+            return div().into_any_element();
+        }
+        let theme = cx.theme();
+        let colors = [
+            theme.primary,
+            theme.success,
+            theme.warning,
+            theme.danger,
+            theme.info,
+        ];
+
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .overflow_hidden()
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, _| self.paint(bounds, &colors, window),
+                )
+                .size_full(),
+            )
+            .into_any_element()
+    }
+}
+
+/// The burst shown over a window by [`celebrate`].
+struct Celebration {
+    window: WindowId,
+    seed: u64,
+    started: Instant,
+}
+
+impl Global for Celebration {}
+
+/// Burst confetti over the active window.
+pub fn celebrate(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    cx.set_global(Celebration {
+        window: window.window_id(),
+        seed: rand::random(),
+        started: Instant::now(),
+    });
+    cx.refresh_windows();
+}
+
+/// The burst of [`celebrate`] over the content of `window`, while it lasts.
+pub(crate) fn render_layer(window: &Window, cx: &App) -> Option<AnyElement> {
+    let celebration = cx.try_global::<Celebration>()?;
+    if celebration.window != window.window_handle().window_id()
+        || celebration.started.elapsed() >= DURATION
+    {
+        return None;
+    }
+    Some(Confetti::new(celebration.seed, celebration.started).into_any_element())
+}
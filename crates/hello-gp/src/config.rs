@@ -0,0 +1,557 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use anyhow::Context as _;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    encryption, kiosk,
+    settings::{SETTINGS_NAME, Settings},
+    themes::{STATE_NAME, State, get_data_dir},
+};
+
+/// The formats a config file can be written in, detected by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfigFormat {
+    Toml,
+    Json5,
+    /// The legacy format, still read so existing installs keep their config.
+    Json,
+}
+
+impl ConfigFormat {
+    /// In order of preference, when there is more than one file for the same config.
+    pub(crate) const ALL: [Self; 3] = [Self::Toml, Self::Json5, Self::Json];
+
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Json5 => "json5",
+            Self::Json => "json",
+        }
+    }
+
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(ext))
+    }
+
+    pub(crate) fn parse<T: DeserializeOwned>(self, text: &str) -> anyhow::Result<T> {
+        Ok(match self {
+            Self::Toml => toml::from_str(text)?,
+            Self::Json5 => {
+                check_json5_depth(text)?;
+                json5::from_str(text)?
+            }
+            Self::Json => serde_json::from_str(text)?,
+        })
+    }
+
+    pub(crate) fn serialize<T: Serialize>(self, value: &T) -> anyhow::Result<String> {
+        Ok(match self {
+            Self::Toml => {
+                let mut value = serde_json::to_value(value)?;
+                strip_nulls(&mut value);
+                toml::to_string_pretty(&value)?
+            }
+            // Plain JSON is valid JSON5.
+            Self::Json5 | Self::Json => serde_json::to_string_pretty(value)?,
+        })
+    }
+}
+
+/// Drop the nulls TOML has no way to write, e.g. in the free-form values of extension
+/// settings, instead of failing the whole file on them. They read back as missing.
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(values) => {
+            values.retain(|value| !value.is_null());
+            values.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+/// How deep arrays and objects may nest in a JSON5 config, far more than any config needs.
+const MAX_DEPTH: usize = 64;
+/// Bigger than any config the app writes, so a stray huge file isn't read into memory.
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Reject JSON5 nested deeper than [`MAX_DEPTH`] before parsing it.
+///
+/// `serde_json` and `toml` stop at their own recursion limits, the JSON5 parser recurses
+/// until the stack overflows, which would take the app down on a malformed file.
+fn check_json5_depth(text: &str) -> anyhow::Result<()> {
+    let mut depth = 0usize;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => _ = chars.next(),
+                        next if next == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.find(|&next| next == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut star = false;
+                for next in chars.by_ref() {
+                    if star && next == '/' {
+                        break;
+                    }
+                    star = next == '*';
+                }
+            }
+            '[' | '{' => {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    anyhow::bail!("nested deeper than {} levels", MAX_DEPTH);
+                }
+            }
+            ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Decrypt (if need be) and parse the text of a config file, `None` if it's empty.
+pub(crate) fn parse_file<T: DeserializeOwned>(
+    text: &str,
+    format: ConfigFormat,
+) -> anyhow::Result<Option<T>> {
+    let text = if encryption::is_encrypted(text) {
+        encryption::decrypt(text).context("failed to decrypt")?
+    } else {
+        text.to_string()
+    };
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    format.parse(&text).map(Some)
+}
+
+/// All the file names a config named `name` may be stored as, e.g. `settings.toml`.
+pub(crate) fn file_names(name: &str) -> impl Iterator<Item = String> + '_ {
+    ConfigFormat::ALL
+        .into_iter()
+        .map(move |format| format!("{}.{}", name, format.extension()))
+}
+
+/// The existing file for the config `name` in `dir`, if any.
+pub(crate) fn find(dir: &Path, name: &str) -> Option<PathBuf> {
+    file_names(name)
+        .map(|file_name| dir.join(file_name))
+        .find(|path| path.exists())
+}
+
+/// The file the config `name` is read from, or written to for a new install.
+pub(crate) fn path(dir: &Path, name: &str) -> PathBuf {
+    find(dir, name)
+        .unwrap_or_else(|| dir.join(format!("{}.{}", name, ConfigFormat::Toml.extension())))
+}
+
+/// Whether each dir was writable when first probed, so the probe file isn't created and
+/// removed (and the watchers of the dir woken) on every load and save.
+static WRITABLE: LazyLock<Mutex<HashMap<PathBuf, bool>>> = LazyLock::new(Default::default);
+
+/// Whether files can be created in `dir`, which is created if it doesn't exist yet. Probed
+/// once per run.
+fn is_writable(dir: &Path) -> bool {
+    let mut writable = WRITABLE.lock().unwrap_or_else(|err| err.into_inner());
+    *writable.entry(dir.to_path_buf()).or_insert_with(|| {
+        let probe = dir.join(".write-probe");
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(&probe, ""))
+            .and_then(|_| std::fs::remove_file(&probe))
+            .is_ok()
+    })
+}
+
+/// Where the user's changes to the configs in `dir` go when `dir` can't be written, e.g. a
+/// config dir shared by everyone on the machine (`HELLO_GP_CONFIG=/etc/hello-gp`).
+///
+/// The files in `dir` are then the read-only base, and the overlay only holds the values
+/// the user changed, merged over the base on load.
+fn overlay_dir(dir: &Path) -> Option<PathBuf> {
+    if is_writable(dir) {
+        return None;
+    }
+    let overlay = get_data_dir().join("config");
+    (overlay != dir).then_some(overlay)
+}
+
+fn overlay_path(overlay: &Path, name: &str) -> PathBuf {
+    overlay.join(format!("{}.{}", name, ConfigFormat::Json5.extension()))
+}
+
+/// The file the user's changes to the config `name` are written to, the overlay file
+/// when `dir` isn't writable.
+pub(crate) fn user_path(dir: &Path, name: &str) -> PathBuf {
+    match overlay_dir(dir) {
+        Some(overlay) => overlay_path(&overlay, name),
+        None => path(dir, name),
+    }
+}
+
+/// Read the config file `name` in `dir`, `None` if it's missing or empty.
+fn load_file<T: DeserializeOwned>(dir: &Path, name: &str) -> anyhow::Result<Option<T>> {
+    let Some(path) = find(dir, name) else {
+        return Ok(None);
+    };
+    let size = std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    if size > MAX_FILE_SIZE {
+        anyhow::bail!("{} is too large ({} bytes)", path.display(), size);
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let format = ConfigFormat::from_path(&path).unwrap_or(ConfigFormat::Json);
+    parse_file(&text, format)
+}
+
+/// Merge `overlay` into `base`, tables key by key and everything else replaced. A `null`
+/// over a key of `base` removes it, it was removed from the config (see [`delta`]).
+fn merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(_) if value.is_null() => _ = base.remove(&key),
+                    Some(base) => merge(base, value),
+                    None => _ = base.insert(key, value),
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// The values of `value` that differ from `base`, the inverse of [`merge`]. The keys of
+/// `base` missing from `value`, e.g. a removed entry of a map, are `null`.
+///
+/// A `null` value over a key of `base` reads back as missing, which the configs (all
+/// `#[serde(default)]` with `None` defaults) deserialize the same.
+fn delta(value: serde_json::Value, base: &serde_json::Value) -> Option<serde_json::Value> {
+    match (value, base) {
+        (serde_json::Value::Object(value), serde_json::Value::Object(base)) => {
+            let removed = base
+                .keys()
+                .filter(|key| !value.contains_key(*key))
+                .map(|key| (key.clone(), serde_json::Value::Null))
+                .collect::<Vec<_>>();
+            let changed = value
+                .into_iter()
+                .filter_map(|(key, value)| match base.get(&key) {
+                    Some(base) => delta(value, base).map(|value| (key, value)),
+                    None => Some((key, value)),
+                })
+                .chain(removed)
+                .collect::<serde_json::Map<_, _>>();
+            (!changed.is_empty()).then_some(serde_json::Value::Object(changed))
+        }
+        (value, base) => (value != *base).then_some(value),
+    }
+}
+
+/// Read the config `name` from `dir`, a missing or empty file gives the defaults.
+///
+/// When `dir` isn't writable, the user's overlay is merged over it.
+pub(crate) fn load<T: DeserializeOwned + Default>(dir: &Path, name: &str) -> anyhow::Result<T> {
+    let overlay = match overlay_dir(dir) {
+        Some(overlay) => load_file::<serde_json::Value>(&overlay, name)?,
+        None => None,
+    };
+    let Some(overlay) = overlay else {
+        return Ok(load_file(dir, name)?.unwrap_or_default());
+    };
+
+    let mut value = load_file::<serde_json::Value>(dir, name)?
+        .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+    merge(&mut value, overlay);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Write the config `name` to `dir`, keeping the format of the existing file.
+///
+/// A legacy JSON file is migrated to TOML. With config encryption on, the file is
+/// encrypted but keeps its extension, so it's still found under the same name.
+///
+/// When `dir` isn't writable, only the values that differ from its files are written to
+/// the user's overlay, as JSON5 since TOML has no `null`.
+///
+/// Does nothing in kiosk mode.
+pub(crate) fn save<T: Serialize + DeserializeOwned + Default>(
+    dir: &Path,
+    name: &str,
+    value: &T,
+) -> anyhow::Result<()> {
+    save_as(dir, name, value, ConfigFormat::Toml)
+}
+
+/// Write the config `name` to `dir` like [`save`], in `format` when there's no file yet, for
+/// configs the app writes more than the user, e.g. recorded macros as JSON.
+pub(crate) fn save_as<T: Serialize + DeserializeOwned + Default>(
+    dir: &Path,
+    name: &str,
+    value: &T,
+    format: ConfigFormat,
+) -> anyhow::Result<()> {
+    // Every run of a kiosk starts from the same config.
+    if kiosk::enabled() {
+        return Ok(());
+    }
+    if let Some(overlay) = overlay_dir(dir) {
+        return save_overlay(dir, &overlay, name, value);
+    }
+
+    let existing = find(dir, name);
+    let format = existing
+        .as_deref()
+        .and_then(ConfigFormat::from_path)
+        .filter(|format| *format != ConfigFormat::Json)
+        .unwrap_or(format);
+    let path = dir.join(format!("{}.{}", name, format.extension()));
+    write(&path, format, value)?;
+
+    if let Some(legacy) = existing.filter(|existing| *existing != path) {
+        tracing::info!(
+            "Migrated {} to {}",
+            legacy.display(),
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        std::fs::remove_file(legacy)?;
+    }
+    Ok(())
+}
+
+fn save_overlay<T: Serialize + DeserializeOwned + Default>(
+    dir: &Path,
+    overlay: &Path,
+    name: &str,
+    value: &T,
+) -> anyhow::Result<()> {
+    // Compared with the base as loaded, so values left at their defaults don't end up in the
+    // overlay and pinned there when the base changes.
+    let base = serde_json::to_value(load_file::<T>(dir, name)?.unwrap_or_default())?;
+    let changed = delta(serde_json::to_value(value)?, &base)
+        .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+
+    write(&overlay_path(overlay, name), ConfigFormat::Json5, &changed)?;
+    tracing::debug!(
+        "{} isn't writable, saved {} to {}",
+        dir.display(),
+        name,
+        overlay.display()
+    );
+    Ok(())
+}
+
+fn write<T: Serialize>(path: &Path, format: ConfigFormat, value: &T) -> anyhow::Result<()> {
+    let mut text = format.serialize(value)?;
+    if encryption::enabled() {
+        text = encryption::encrypt(&text)?;
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The JSON Schema of the config `name`, or of all configs keyed by name.
+///
+/// Point the editor at it for completions, e.g. the `$schema` key in `settings.json5`
+/// or `json.schemas` in VS Code.
+pub fn schema(name: Option<&str>) -> anyhow::Result<serde_json::Value> {
+    let schemas = [
+        (SETTINGS_NAME, schemars::schema_for!(Settings)),
+        (STATE_NAME, schemars::schema_for!(State)),
+    ];
+
+    match name {
+        Some(name) => schemas
+            .into_iter()
+            .find(|(config, _)| *config == name)
+            .map(|(_, schema)| schema.to_value())
+            .ok_or_else(|| anyhow::anyhow!("unknown config: {}", name)),
+        None => Ok(serde_json::Value::Object(
+            schemas
+                .into_iter()
+                .map(|(config, schema)| (config.to_string(), schema.to_value()))
+                .collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        config::{ConfigFormat, MAX_DEPTH, check_json5_depth, delta, merge, parse_file},
+        settings::Settings,
+    };
+
+    #[test]
+    fn test_format_round_trips() {
+        let mut settings = Settings::default();
+        settings.telemetry.enabled = true;
+        settings.telemetry.endpoint = Some("https://example.com/events".into());
+        let expected = serde_json::to_value(&settings).unwrap();
+
+        for format in ConfigFormat::ALL {
+            let text = format.serialize(&settings).unwrap();
+            let parsed: Settings = format.parse(&text).unwrap();
+            assert_eq!(
+                serde_json::to_value(&parsed).unwrap(),
+                expected,
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_empty_file() {
+        for format in ConfigFormat::ALL {
+            assert!(parse_file::<Settings>(" \n", format).unwrap().is_none());
+        }
+        assert!(parse_file::<Settings>("[telemetry", ConfigFormat::Toml).is_err());
+    }
+
+    #[test]
+    fn test_json5_depth() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(check_json5_depth(&nested(MAX_DEPTH)).is_ok());
+        assert!(check_json5_depth(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(check_json5_depth(&"[".repeat(100_000)).is_err());
+
+        // Brackets in strings and comments don't nest.
+        let quoted = format!("{{ a: \"{}\", b: '{}' }}", "[".repeat(100), "{".repeat(100));
+        assert!(check_json5_depth(&quoted).is_ok());
+        let escaped = format!("{{ a: \"\\\"{}\" }}", "[".repeat(100));
+        assert!(check_json5_depth(&escaped).is_ok());
+        let comments = format!(
+            "{{\n  // {}\n  /* {} */ a: 1\n}}",
+            "[".repeat(100),
+            "{".repeat(100)
+        );
+        assert!(check_json5_depth(&comments).is_ok());
+
+        // A deep file is rejected before the parser recurses into it.
+        assert!(
+            ConfigFormat::Json5
+                .parse::<Settings>(&nested(MAX_DEPTH + 1))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_toml_round_trip_with_null_extension_value() {
+        let mut settings = Settings::default();
+        let values = json!({ "greeting": "hi", "unset": null, "nested": { "gone": null } });
+        let serde_json::Value::Object(values) = values else {
+            unreachable!()
+        };
+        settings.extensions.insert("hello".into(), values);
+
+        let text = ConfigFormat::Toml.serialize(&settings).unwrap();
+        let settings: Settings = ConfigFormat::Toml.parse(&text).unwrap();
+        assert_eq!(
+            serde_json::Value::Object(settings.extensions["hello"].clone()),
+            json!({ "greeting": "hi", "nested": {} })
+        );
+    }
+
+    #[test]
+    fn test_merge_overlay() {
+        let mut base = json!({
+            "theme": { "mode": "light", "font_size": 14 },
+            "recent": ["a", "b"],
+            "sounds": true,
+        });
+        merge(
+            &mut base,
+            json!({ "theme": { "mode": "dark" }, "recent": ["c"], "extra": 1 }),
+        );
+        assert_eq!(
+            base,
+            json!({
+                "theme": { "mode": "dark", "font_size": 14 },
+                "recent": ["c"],
+                "sounds": true,
+                "extra": 1,
+            })
+        );
+
+        // A table over a value, or a value over a table, replaces it.
+        let mut base = json!({ "a": 1, "b": { "c": 2 } });
+        merge(&mut base, json!({ "a": { "x": 1 }, "b": 3 }));
+        assert_eq!(base, json!({ "a": { "x": 1 }, "b": 3 }));
+    }
+
+    #[test]
+    fn test_delta_from_base() {
+        let base = json!({
+            "theme": { "mode": "light", "font_size": 14 },
+            "recent": ["a", "b"],
+            "sounds": true,
+        });
+        assert_eq!(delta(base.clone(), &base), None);
+
+        let value = json!({
+            "theme": { "mode": "dark", "font_size": 14 },
+            "recent": ["a"],
+            "sounds": true,
+            "extra": null,
+        });
+        let changed = delta(value.clone(), &base).unwrap();
+        assert_eq!(
+            changed,
+            json!({ "theme": { "mode": "dark" }, "recent": ["a"], "extra": null })
+        );
+
+        // The overlay merged over the base gives back what was saved.
+        let mut merged = base.clone();
+        merge(&mut merged, changed);
+        assert_eq!(merged, value);
+    }
+
+    #[test]
+    fn test_delta_removed_keys() {
+        let base = json!({
+            "extensions": { "a": { "x": 1 }, "b": { "y": 2, "z": 3 } },
+            "sounds": true,
+        });
+        let value = json!({
+            "extensions": { "b": { "y": 2 } },
+            "sounds": true,
+        });
+        let changed = delta(value.clone(), &base).unwrap();
+        assert_eq!(
+            changed,
+            json!({ "extensions": { "a": null, "b": { "z": null } } })
+        );
+
+        // The tombstones remove the keys from the base again.
+        let mut merged = base.clone();
+        merge(&mut merged, changed);
+        assert_eq!(merged, value);
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use gpui::{App, CursorStyle, Global, SharedString, Styled};
+use gpui_component::ActiveTheme as _;
+use serde::Deserialize;
+
+use crate::{
+    events::{self, ThemeChanged},
+    themes::get_data_dir,
+};
+
+/// What the cursor over an element is for, resolved to a cursor by the active theme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorRole {
+    Default,
+    /// Something clickable, like a link or a row.
+    Pointer,
+    Text,
+    /// Something that can be dragged, and while it's dragged.
+    Grab,
+    Grabbing,
+    /// The handle between panels side by side, and between stacked ones.
+    ResizeColumn,
+    ResizeRow,
+    Crosshair,
+    NotAllowed,
+}
+
+impl CursorRole {
+    fn default_cursor(self) -> CursorShape {
+        match self {
+            Self::Default => CursorShape::Arrow,
+            Self::Pointer => CursorShape::PointingHand,
+            Self::Text => CursorShape::IBeam,
+            Self::Grab => CursorShape::OpenHand,
+            Self::Grabbing => CursorShape::ClosedHand,
+            Self::ResizeColumn => CursorShape::ResizeColumn,
+            Self::ResizeRow => CursorShape::ResizeRow,
+            Self::Crosshair => CursorShape::Crosshair,
+            Self::NotAllowed => CursorShape::NotAllowed,
+        }
+    }
+}
+
+/// The cursors a theme can pick, as named in theme files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorShape {
+    Arrow,
+    PointingHand,
+    IBeam,
+    Crosshair,
+    OpenHand,
+    ClosedHand,
+    ResizeLeftRight,
+    ResizeUpDown,
+    ResizeColumn,
+    ResizeRow,
+    NotAllowed,
+    ContextMenu,
+    DragLink,
+    DragCopy,
+    Hidden,
+}
+
+impl CursorShape {
+    /// The closest cursor the platform has, e.g. Windows has no grabbing hand.
+    fn style(self) -> CursorStyle {
+        match self {
+            Self::OpenHand | Self::ClosedHand if cfg!(target_os = "windows") => {
+                CursorStyle::PointingHand
+            }
+            Self::ResizeColumn if cfg!(target_os = "windows") => CursorStyle::ResizeLeftRight,
+            Self::ResizeRow if cfg!(target_os = "windows") => CursorStyle::ResizeUpDown,
+            // Often missing from the cursor themes of Linux desktops.
+            Self::ContextMenu | Self::DragLink | Self::DragCopy if !cfg!(target_os = "macos") => {
+                CursorStyle::Arrow
+            }
+            Self::Arrow => CursorStyle::Arrow,
+            Self::PointingHand => CursorStyle::PointingHand,
+            Self::IBeam => CursorStyle::IBeam,
+            Self::Crosshair => CursorStyle::Crosshair,
+            Self::OpenHand => CursorStyle::OpenHand,
+            Self::ClosedHand => CursorStyle::ClosedHand,
+            Self::ResizeLeftRight => CursorStyle::ResizeLeftRight,
+            Self::ResizeUpDown => CursorStyle::ResizeUpDown,
+            Self::ResizeColumn => CursorStyle::ResizeColumn,
+            Self::ResizeRow => CursorStyle::ResizeRow,
+            Self::NotAllowed => CursorStyle::OperationNotAllowed,
+            Self::ContextMenu => CursorStyle::ContextualMenu,
+            Self::DragLink => CursorStyle::DragLink,
+            Self::DragCopy => CursorStyle::DragCopy,
+            Self::Hidden => CursorStyle::None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemeFileCursors {
+    themes: Vec<ThemeCursors>,
+}
+
+#[derive(Deserialize)]
+struct ThemeCursors {
+    name: SharedString,
+    #[serde(default)]
+    cursors: BTreeMap<CursorRole, CursorShape>,
+}
+
+/// The cursors of the active theme, declared next to its colors:
+///
+/// ```json
+/// { "name": "Retro", "mode": "light", "cursors": { "pointer": "arrow", "text": "crosshair" } }
+/// ```
+///
+/// The roles a theme leaves out get the usual cursor.
+#[derive(Default)]
+pub struct Cursors {
+    overrides: BTreeMap<CursorRole, CursorShape>,
+}
+
+impl Global for Cursors {}
+
+impl Cursors {
+    /// The cursor for `role` in the active theme.
+    pub fn style(role: CursorRole, cx: &App) -> CursorStyle {
+        cx.try_global::<Self>()
+            .and_then(|this| this.overrides.get(&role).copied())
+            .unwrap_or_else(|| role.default_cursor())
+            .style()
+    }
+
+    fn find(theme: &SharedString) -> BTreeMap<CursorRole, CursorShape> {
+        let Ok(entries) = std::fs::read_dir(get_data_dir().join("themes")) else {
+            return BTreeMap::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .find_map(|path| {
+                let json = std::fs::read_to_string(path).ok()?;
+                let file = serde_json::from_str::<ThemeFileCursors>(&json).ok()?;
+                file.themes
+                    .into_iter()
+                    .find(|entry| &entry.name == theme)
+                    .map(|entry| entry.cursors)
+            })
+            .unwrap_or_default()
+    }
+
+    fn update_theme(theme: &SharedString, cx: &mut App) {
+        let overrides = Self::find(theme);
+        if overrides != cx.global::<Self>().overrides {
+            cx.global_mut::<Self>().overrides = overrides;
+            cx.refresh_windows();
+        }
+    }
+}
+
+/// Set the cursor of an element by what it's for, so themes can change it.
+pub trait CursorExt: Styled + Sized {
+    fn cursor_role(self, role: CursorRole, cx: &App) -> Self {
+        self.cursor(Cursors::style(role, cx))
+    }
+}
+
+impl<E: Styled> CursorExt for E {}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Cursors::default());
+    Cursors::update_theme(&cx.theme().theme_name().clone(), cx);
+
+    events::subscribe::<ThemeChanged>(cx, |event, cx| Cursors::update_theme(&event.name, cx))
+        .detach();
+}
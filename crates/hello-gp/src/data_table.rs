@@ -0,0 +1,259 @@
+//! A table of rows of one type, with sortable and resizable columns that are remembered per
+//! table id across restarts.
+
+use std::{cmp::Ordering, collections::BTreeMap, rc::Rc};
+
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, IntoElement, Pixels, SharedString, Window,
+};
+use gpui_component::{
+    popup_menu::PopupMenu,
+    table::{Column, ColumnSort, Table, TableDelegate, TableEvent},
+};
+
+use crate::session::Session;
+
+type Compare<T> = Rc<dyn Fn(&T, &T) -> Ordering>;
+type RenderCell<T> = Rc<dyn Fn(&T, &mut Window, &mut App) -> AnyElement>;
+type ContextMenu<T> = Rc<dyn Fn(&T, PopupMenu, &Window, &App) -> PopupMenu>;
+
+/// A column of a [`DataTable`], rendering a cell of each row.
+pub struct DataColumn<T> {
+    column: Column,
+    compare: Option<Compare<T>>,
+    render: RenderCell<T>,
+}
+
+impl<T> DataColumn<T> {
+    /// `key` identifies the column in the saved state of the table, keep it stable.
+    pub fn new(
+        key: impl Into<SharedString>,
+        name: impl Into<SharedString>,
+        render: impl Fn(&T, &mut Window, &mut App) -> AnyElement + 'static,
+    ) -> Self {
+        Self {
+            column: Column::new(key, name),
+            compare: None,
+            render: Rc::new(render),
+        }
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.column = self.column.width(width);
+        self
+    }
+
+    /// Make the column sortable, by `compare` in ascending order.
+    pub fn sort_by(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> Self {
+        self.column = self.column.sortable();
+        self.compare = Some(Rc::new(compare));
+        self
+    }
+
+    pub fn sort_by_key<K: Ord>(self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.sort_by(move |a, b| key(a).cmp(&key(b)))
+    }
+}
+
+/// The rows and columns of a [`DataTable`].
+pub struct DataTableDelegate<T> {
+    id: SharedString,
+    columns: Vec<DataColumn<T>>,
+    rows: Vec<T>,
+    /// The key of the column the rows are sorted by.
+    sort: Option<(SharedString, ColumnSort)>,
+    context_menu: Option<ContextMenu<T>>,
+}
+
+impl<T: 'static> DataTableDelegate<T> {
+    pub fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    pub fn row(&self, ix: usize) -> Option<&T> {
+        self.rows.get(ix)
+    }
+
+    /// Replace the rows, keeping them in the order the table is sorted by.
+    pub fn set_rows(&mut self, rows: Vec<T>) {
+        self.rows = rows;
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        let Some((key, sort)) = &self.sort else {
+            return;
+        };
+        let Some(compare) = self
+            .columns
+            .iter()
+            .find(|col| &col.column.key == key)
+            .and_then(|col| col.compare.clone())
+        else {
+            return;
+        };
+        match sort {
+            ColumnSort::Ascending => self.rows.sort_by(|a, b| compare(a, b)),
+            ColumnSort::Descending => self.rows.sort_by(|a, b| compare(b, a)),
+            ColumnSort::Default => {}
+        }
+    }
+
+    /// Restore the widths and the sort the table was left with.
+    fn restore(&mut self, cx: &App) {
+        let Some(state) = Session::global(cx).tables.get(self.id.as_ref()) else {
+            return;
+        };
+        for col in &mut self.columns {
+            if let Some(width) = state.widths.get(col.column.key.as_ref()) {
+                col.column = col.column.clone().width(*width);
+            }
+        }
+        if let Some((key, descending)) = &state.sort
+            && let Some(col_ix) = self
+                .columns
+                .iter()
+                .position(|col| col.compare.is_some() && col.column.key.as_ref() == key)
+        {
+            let sort = if *descending {
+                ColumnSort::Descending
+            } else {
+                ColumnSort::Ascending
+            };
+            let col = &mut self.columns[col_ix];
+            col.column = match sort {
+                ColumnSort::Descending => col.column.clone().descending(),
+                _ => col.column.clone().ascending(),
+            };
+            self.sort = Some((col.column.key.clone(), sort));
+        }
+    }
+
+    fn widths_by_key(&self, widths: &[Pixels]) -> BTreeMap<String, f32> {
+        self.columns
+            .iter()
+            .zip(widths)
+            .map(|(col, width)| (col.column.key.to_string(), width.as_f32()))
+            .collect()
+    }
+}
+
+impl<T: 'static> TableDelegate for DataTableDelegate<T> {
+    fn columns_count(&self, _: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _: &App) -> &Column {
+        &self.columns[col_ix].column
+    }
+
+    fn render_td(
+        &self,
+        row_ix: usize,
+        col_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) -> impl IntoElement {
+        (self.columns[col_ix].render)(&self.rows[row_ix], window, cx)
+    }
+
+    fn context_menu(&self, row_ix: usize, menu: PopupMenu, window: &Window, cx: &App) -> PopupMenu {
+        match (&self.context_menu, self.rows.get(row_ix)) {
+            (Some(context_menu), Some(row)) => context_menu(row, menu, window, cx),
+            _ => menu,
+        }
+    }
+
+    fn move_column(
+        &mut self,
+        col_ix: usize,
+        to_ix: usize,
+        _: &mut Window,
+        _: &mut Context<Table<Self>>,
+    ) {
+        let col = self.columns.remove(col_ix);
+        self.columns.insert(to_ix, col);
+    }
+
+    fn perform_sort(
+        &mut self,
+        col_ix: usize,
+        sort: ColumnSort,
+        _: &mut Window,
+        cx: &mut Context<Table<Self>>,
+    ) {
+        let Some(col) = self.columns.get(col_ix) else {
+            return;
+        };
+        self.sort = (sort != ColumnSort::Default).then(|| (col.column.key.clone(), sort));
+        self.sort_rows();
+
+        let id = self.id.to_string();
+        let saved = self
+            .sort
+            .as_ref()
+            .map(|(key, sort)| (key.to_string(), *sort == ColumnSort::Descending));
+        Session::update(cx, |session| {
+            session.tables.entry(id).or_default().sort = saved
+        });
+    }
+}
+
+/// Builds a [`Table`] of rows of `T`, which keeps its column widths and sort per table id.
+///
+/// Subscribe to the [`TableEvent`]s of the table for the selection.
+pub struct DataTable<T> {
+    delegate: DataTableDelegate<T>,
+}
+
+impl<T: 'static> DataTable<T> {
+    /// `id` identifies the table in the session, keep it stable.
+    pub fn new(id: impl Into<SharedString>, columns: Vec<DataColumn<T>>) -> Self {
+        Self {
+            delegate: DataTableDelegate {
+                id: id.into(),
+                columns,
+                rows: vec![],
+                sort: None,
+                context_menu: None,
+            },
+        }
+    }
+
+    /// Add items of the context menu of a row, e.g. actions on it.
+    pub fn context_menu(
+        mut self,
+        context_menu: impl Fn(&T, PopupMenu, &Window, &App) -> PopupMenu + 'static,
+    ) -> Self {
+        self.delegate.context_menu = Some(Rc::new(context_menu));
+        self
+    }
+
+    pub fn build(
+        self,
+        rows: Vec<T>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Table<DataTableDelegate<T>>> {
+        let mut delegate = self.delegate;
+        delegate.restore(cx);
+        delegate.set_rows(rows);
+
+        let table = cx.new(|cx| Table::new(delegate, window, cx));
+        cx.subscribe(&table, |table, event: &TableEvent, cx| {
+            if let TableEvent::ColumnWidthsChanged(widths) = event {
+                let delegate = table.read(cx).delegate();
+                let (id, widths) = (delegate.id.to_string(), delegate.widths_by_key(widths));
+                Session::update(cx, |session| {
+                    session.tables.entry(id).or_default().widths = widths
+                });
+            }
+        })
+        .detach();
+        table
+    }
+}
@@ -9,7 +9,10 @@ use gpui_component::{
     v_flex,
 };
 
-use crate::section;
+use crate::{
+    date_time_picker::{DateTimePicker, DateTimePickerEvent},
+    format, section,
+};
 
 pub struct DatePickerStory {
     date_picker: Entity<DatePickerState>,
@@ -20,6 +23,7 @@ pub struct DatePickerStory {
     date_range_picker: Entity<DatePickerState>,
     default_range_mode_picker: Entity<DatePickerState>,
     without_appearance_picker: Entity<DatePickerState>,
+    date_time_picker: Entity<DateTimePicker>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -90,6 +94,8 @@ impl DatePickerStory {
 
         let without_appearance_picker = cx.new(|cx| DatePickerState::new(window, cx));
 
+        let date_time_picker = cx.new(|cx| DateTimePicker::now(window, cx));
+
         let _subscriptions = vec![
             cx.subscribe(&date_picker, |this, _, ev, _| match ev {
                 DatePickerEvent::Change(date) => {
@@ -106,6 +112,9 @@ impl DatePickerStory {
                     this.date_picker_value = date.format("%Y-%m-%d").map(|s| s.to_string());
                 }
             }),
+            cx.subscribe(&date_time_picker, |_, _, _: &DateTimePickerEvent, cx| {
+                cx.notify()
+            }),
         ];
 
         Self {
@@ -116,6 +125,7 @@ impl DatePickerStory {
             date_range_picker,
             default_range_mode_picker,
             without_appearance_picker,
+            date_time_picker,
             date_picker_value: None,
             _subscriptions,
         }
@@ -212,6 +222,20 @@ impl Render for DatePickerStory {
                     format!("Date picker value: {:?}", self.date_picker_value).into_element(),
                 ),
             )
+            .child({
+                let value = self.date_time_picker.read(cx).value();
+                let value = value.and_local_timezone(chrono::Local).single();
+                section("Date and Time")
+                    .max_w_128()
+                    .child(self.date_time_picker.clone())
+                    .children(value.map(|value| {
+                        format!(
+                            "{} ({})",
+                            format::date_time(&value),
+                            format::relative(&value)
+                        )
+                    }))
+            })
             .child(
                 section("Without Appearance").max_w_128().child(
                     div().w_full().bg(cx.theme().secondary).child(
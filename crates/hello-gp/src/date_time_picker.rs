@@ -0,0 +1,163 @@
+//! Pickers of a time of day, and of a date and a time, e.g. for when something is scheduled.
+
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike as _};
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    ParentElement as _, Render, Styled as _, Subscription, Window, div,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _, calendar,
+    date_picker::{DatePicker, DatePickerEvent, DatePickerState},
+    h_flex,
+};
+
+use crate::number_field::{NumberField, NumberFieldEvent};
+
+#[derive(Debug, Clone, Copy)]
+pub enum TimePickerEvent {
+    Change(NaiveTime),
+}
+
+/// A time of day as hours and minutes, each stepped or typed on its own.
+pub struct TimePicker {
+    hour: Entity<NumberField>,
+    minute: Entity<NumberField>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TimePicker {
+    pub fn new(time: NaiveTime, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let hour = cx.new(|cx| NumberField::new(time.hour() as f64, window, cx).range(0., 23.));
+        let minute = cx.new(|cx| NumberField::new(time.minute() as f64, window, cx).range(0., 59.));
+        let _subscriptions = [&hour, &minute]
+            .into_iter()
+            .map(|field| {
+                cx.subscribe(field, |this, _, _: &NumberFieldEvent, cx| {
+                    cx.emit(TimePickerEvent::Change(this.time(cx)));
+                })
+            })
+            .collect();
+
+        Self {
+            hour,
+            minute,
+            _subscriptions,
+        }
+    }
+
+    pub fn time(&self, cx: &App) -> NaiveTime {
+        let hour = self.hour.read(cx).value() as u32;
+        let minute = self.minute.read(cx).value() as u32;
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default()
+    }
+
+    pub fn set_time(&mut self, time: NaiveTime, window: &mut Window, cx: &mut Context<Self>) {
+        self.hour.update(cx, |field, cx| {
+            field.set_value(time.hour() as f64, window, cx)
+        });
+        self.minute.update(cx, |field, cx| {
+            field.set_value(time.minute() as f64, window, cx)
+        });
+    }
+}
+
+impl EventEmitter<TimePickerEvent> for TimePicker {}
+
+impl Focusable for TimePicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.hour.focus_handle(cx)
+    }
+}
+
+impl Render for TimePicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_1()
+            .child(self.hour.clone())
+            .child(div().text_color(cx.theme().muted_foreground).child(":"))
+            .child(self.minute.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DateTimePickerEvent {
+    Change(NaiveDateTime),
+}
+
+/// A date from a calendar and a time of day, in local time.
+pub struct DateTimePicker {
+    date: Entity<DatePickerState>,
+    time: Entity<TimePicker>,
+    value: NaiveDateTime,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DateTimePicker {
+    pub fn new(value: NaiveDateTime, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let date = cx.new(|cx| {
+            let mut picker = DatePickerState::new(window, cx);
+            picker.set_date(value.date(), window, cx);
+            picker
+        });
+        let time = cx.new(|cx| TimePicker::new(value.time(), window, cx));
+        let _subscriptions = vec![
+            cx.subscribe(&date, |this, _, event: &DatePickerEvent, cx| {
+                let DatePickerEvent::Change(date) = event;
+                if let calendar::Date::Single(Some(date)) = date {
+                    this.set(*date, this.value.time(), cx);
+                }
+            }),
+            cx.subscribe(&time, |this, _, event: &TimePickerEvent, cx| {
+                let TimePickerEvent::Change(time) = *event;
+                this.set(this.value.date(), time, cx);
+            }),
+        ];
+
+        Self {
+            date,
+            time,
+            value,
+            _subscriptions,
+        }
+    }
+
+    /// Now, to the minute.
+    pub fn now(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let now = Local::now().naive_local();
+        let now = now
+            .with_second(0)
+            .and_then(|now| now.with_nanosecond(0))
+            .unwrap_or(now);
+        Self::new(now, window, cx)
+    }
+
+    pub fn value(&self) -> NaiveDateTime {
+        self.value
+    }
+
+    fn set(&mut self, date: NaiveDate, time: NaiveTime, cx: &mut Context<Self>) {
+        let value = date.and_time(time);
+        if value != self.value {
+            self.value = value;
+            cx.emit(DateTimePickerEvent::Change(value));
+            cx.notify();
+        }
+    }
+}
+
+impl EventEmitter<DateTimePickerEvent> for DateTimePicker {}
+
+impl Focusable for DateTimePicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.date.focus_handle(cx)
+    }
+}
+
+impl Render for DateTimePicker {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_2()
+            .child(div().w_40().child(DatePicker::new(&self.date).small()))
+            .child(self.time.clone())
+    }
+}
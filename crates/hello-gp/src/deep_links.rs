@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use gpui::{Action, App, Global, SharedString};
+use gpui_component::ThemeMode;
+
+use crate::{
+    appearance::ChooseCursorColors,
+    diagnostics::ShowGpuDiagnostics,
+    extension_manager::ShowExtensions,
+    kiosk,
+    layouts::ApplyLayoutPreset,
+    net_settings::ShowNetworkSettings,
+    settings_editor::EditRawSettings,
+    settings_window::{RevealSetting, ShowSettings},
+    telemetry::ShowTelemetry,
+    theme_diff::CompareThemes,
+    themes::{SwitchTheme, SwitchThemeMode},
+};
+
+/// The URL scheme the app is registered for.
+pub const SCHEME: &str = "hello-gp";
+
+/// Show a story of the gallery, by name.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = story, no_json)]
+pub struct OpenStory(pub SharedString);
+
+/// The `:name` segments of a route, as matched in a link.
+pub type RouteParams = HashMap<&'static str, String>;
+
+type RouteHandler = Box<dyn Fn(&RouteParams) -> Option<Box<dyn Action>>>;
+
+struct Route {
+    segments: Vec<&'static str>,
+    handler: RouteHandler,
+}
+
+impl Route {
+    fn matches(&self, path: &[&str]) -> Option<RouteParams> {
+        if self.segments.len() != path.len() {
+            return None;
+        }
+
+        let mut params = RouteParams::new();
+        for (segment, part) in self.segments.iter().zip(path) {
+            match segment.strip_prefix(':') {
+                Some(name) => _ = params.insert(name, percent_decode(part)),
+                None if segment.eq_ignore_ascii_case(part) => {}
+                None => return None,
+            }
+        }
+        Some(params)
+    }
+}
+
+/// Decode `%20` and the like, so that `themes/One%20Dark` matches the theme `One Dark`.
+pub(crate) fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Maps the paths of `hello-gp://` links to the actions they run.
+///
+/// Routes are tried in the order they were added, so add the literal ones
+/// (`themes/gallery`) before those with a parameter in the same place (`themes/:name`).
+pub struct DeepLinks {
+    routes: Vec<Route>,
+}
+
+impl Global for DeepLinks {}
+
+impl DeepLinks {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// Run the action the handler returns for links matching `pattern`, e.g. `layouts/:name`.
+    pub fn route(
+        cx: &mut App,
+        pattern: &'static str,
+        handler: impl Fn(&RouteParams) -> Option<Box<dyn Action>> + 'static,
+    ) {
+        cx.global_mut::<Self>().routes.push(Route {
+            segments: pattern.split('/').filter(|s| !s.is_empty()).collect(),
+            handler: Box::new(handler),
+        });
+    }
+
+    /// The action for `url`, `None` if it isn't a link of the app or no route matches.
+    pub fn resolve(&self, url: &str) -> Option<Box<dyn Action>> {
+        let path = url
+            .strip_prefix(SCHEME)?
+            .strip_prefix("://")?
+            .split(['?', '#'])
+            .next()?;
+        let path = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        self.routes
+            .iter()
+            .find_map(|route| (route.handler)(&route.matches(&path)?))
+    }
+}
+
+fn action(action: impl Action) -> Option<Box<dyn Action>> {
+    Some(Box::new(action))
+}
+
+/// Open the screen `url` points to, bringing the app to the front.
+pub fn open(url: &str, cx: &mut App) {
+    let Some(action) = DeepLinks::global(cx).resolve(url) else {
+        tracing::warn!("No route for {}", url);
+        return;
+    };
+    if !kiosk::allows(action.as_ref(), cx) {
+        tracing::warn!("{} isn't allowed in kiosk mode", url);
+        return;
+    }
+
+    cx.activate(true);
+    dispatch(action, cx);
+}
+
+/// Dispatch `action` in the active window, so the views in it can handle it too.
+pub(crate) fn dispatch(action: Box<dyn Action>, cx: &mut App) {
+    if let Some(window) = cx
+        .active_window()
+        .or_else(|| cx.windows().into_iter().next())
+    {
+        _ = window.update(cx, |_, window, cx| {
+            window.activate_window();
+            window.dispatch_action(action, cx);
+        });
+    } else {
+        cx.dispatch_action(action.as_ref());
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(DeepLinks { routes: vec![] });
+
+    DeepLinks::route(cx, "settings", |_| action(ShowSettings));
+    DeepLinks::route(cx, "settings/advanced", |_| action(EditRawSettings));
+    DeepLinks::route(cx, "settings/appearance", |_| {
+        action(RevealSetting("Appearance".into()))
+    });
+    DeepLinks::route(cx, "settings/cursor", |_| action(ChooseCursorColors));
+    DeepLinks::route(cx, "settings/network", |_| action(ShowNetworkSettings));
+    DeepLinks::route(cx, "settings/telemetry", |_| action(ShowTelemetry));
+    DeepLinks::route(cx, "settings/:key", |params| {
+        action(RevealSetting(params["key"].clone().into()))
+    });
+    DeepLinks::route(cx, "themes/gallery", |_| action(CompareThemes));
+    DeepLinks::route(cx, "themes/mode/:mode", |params| {
+        match params["mode"].to_lowercase().as_str() {
+            "light" => action(SwitchThemeMode(ThemeMode::Light)),
+            "dark" => action(SwitchThemeMode(ThemeMode::Dark)),
+            _ => None,
+        }
+    });
+    DeepLinks::route(cx, "themes/:name", |params| {
+        action(SwitchTheme(params["name"].clone().into()))
+    });
+    DeepLinks::route(cx, "extensions", |_| action(ShowExtensions));
+    DeepLinks::route(cx, "diagnostics/gpu", |_| action(ShowGpuDiagnostics));
+    DeepLinks::route(cx, "layouts/:name", |params| {
+        action(ApplyLayoutPreset(params["name"].clone().into()))
+    });
+    DeepLinks::route(cx, "stories/:name", |params| {
+        action(OpenStory(params["name"].clone().into()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::Action;
+
+    use crate::{
+        deep_links::{DeepLinks, Route, RouteParams, action, percent_decode},
+        theme_diff::CompareThemes,
+        themes::SwitchTheme,
+    };
+
+    fn router(
+        routes: Vec<(&'static str, fn(&RouteParams) -> Option<Box<dyn Action>>)>,
+    ) -> DeepLinks {
+        DeepLinks {
+            routes: routes
+                .into_iter()
+                .map(|(pattern, handler)| Route {
+                    segments: pattern.split('/').filter(|s| !s.is_empty()).collect(),
+                    handler: Box::new(handler),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let links = router(vec![
+            ("themes/gallery", |_| action(CompareThemes)),
+            ("themes/:name", |params| {
+                action(SwitchTheme(params["name"].clone().into()))
+            }),
+        ]);
+        let resolves_to = |url: &str, expected: &dyn Action| {
+            links
+                .resolve(url)
+                .is_some_and(|action| action.partial_eq(expected))
+        };
+
+        // The literal route is added first, and wins over the parameter.
+        assert!(resolves_to("hello-gp://themes/gallery", &CompareThemes));
+        assert!(resolves_to("hello-gp://Themes/Gallery/", &CompareThemes));
+        assert!(resolves_to(
+            "hello-gp://themes/One%20Dark?from=docs#top",
+            &SwitchTheme("One Dark".into())
+        ));
+
+        assert!(links.resolve("hello-gp://themes").is_none());
+        assert!(links.resolve("hello-gp://themes/a/b").is_none());
+        assert!(links.resolve("other://themes/gallery").is_none());
+        assert!(links.resolve("themes/gallery").is_none());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("One%20Dark"), "One Dark");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+        // Not an escape, kept as is.
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+}
@@ -0,0 +1,411 @@
+use std::fmt::Write as _;
+
+use gpui::{
+    App, AppContext as _, ClipboardItem, Context, Decorations, Entity, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Window, actions, div,
+    prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputState, TextInput},
+    notification::Notification,
+    switch::Switch,
+    text::TextView,
+    v_flex,
+};
+
+use crate::{
+    logs,
+    memory::{Memory, format_size},
+    policy, renderer,
+    settings::Settings,
+    text_field::TextField,
+    watchdog,
+};
+
+actions!(diagnostics, [ShowGpuDiagnostics, ReportIssue]);
+
+/// Where issues are filed, the title and body are passed as query parameters.
+const ISSUE_TRACKER_URL: &str = "https://github.com/jonirrings/hello-gp/issues/new";
+/// Browsers and GitHub give up on longer URLs, the body is cut to fit.
+const MAX_ISSUE_BODY: usize = 6000;
+
+/// The graphics API GPUI renders with on this platform.
+fn graphics_backend() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Metal"
+    } else if cfg!(target_os = "windows") {
+        "DirectX 11"
+    } else {
+        "Vulkan (blade)"
+    }
+}
+
+/// Renderer details for bug reports, e.g. when the app renders black on someone's machine.
+pub struct GpuDiagnostics {
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GpuDiagnostics {
+    pub fn view(_: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            // Redraw with every memory sample.
+            _subscriptions: vec![cx.observe_global::<Memory>(|_, cx| cx.notify())],
+        })
+    }
+
+    /// Resident memory over the last few minutes, as bars.
+    fn render_sparkline(cx: &App) -> impl IntoElement {
+        const HEIGHT: f32 = 40.;
+        let values = Memory::global(cx)
+            .samples()
+            .map(|sample| sample.resident.unwrap_or(0))
+            .collect::<Vec<_>>();
+        let max = values.iter().copied().max().unwrap_or(0).max(1);
+
+        h_flex()
+            .h(px(HEIGHT))
+            .items_end()
+            .gap_px()
+            .children(values.into_iter().map(|value| {
+                div()
+                    .w(px(4.))
+                    .h(px(HEIGHT * value as f32 / max as f32))
+                    .bg(cx.theme().primary)
+            }))
+    }
+
+    /// The report as Markdown, so it can be pasted into an issue as is.
+    pub fn report(window: &Window, cx: &App) -> String {
+        let mut report = String::new();
+        _ = writeln!(report, "## GPU Diagnostics\n");
+        _ = writeln!(
+            report,
+            "- App: {} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        _ = writeln!(
+            report,
+            "- OS: {} ({})",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        _ = writeln!(report, "- Backend: {}", graphics_backend());
+        _ = writeln!(
+            report,
+            "- Software rendering: {}",
+            renderer::reduced_effects()
+        );
+
+        match window.gpu_specs() {
+            Some(specs) => {
+                _ = writeln!(report, "- Adapter: {}", specs.device_name);
+                _ = writeln!(
+                    report,
+                    "- Driver: {} {}",
+                    specs.driver_name, specs.driver_info
+                );
+                _ = writeln!(
+                    report,
+                    "- Software emulated: {}",
+                    specs.is_software_emulated
+                );
+            }
+            None => _ = writeln!(report, "- Adapter: not reported on this platform"),
+        }
+        // GPUI presents in sync with the display refresh on every platform, it isn't configurable.
+        _ = writeln!(report, "- VSync: on (platform managed)");
+        _ = writeln!(report, "- Window scale factor: {}", window.scale_factor());
+
+        if let Some(display) = renderer::display_info() {
+            _ = writeln!(report, "\n### Display Server\n");
+            _ = writeln!(
+                report,
+                "- Session: {}",
+                display.session_type.as_deref().unwrap_or("unknown")
+            );
+            _ = writeln!(
+                report,
+                "- Server: {}{}",
+                display.server,
+                if display.forced { " (forced)" } else { "" }
+            );
+            _ = writeln!(
+                report,
+                "- Fractional scaling: {}",
+                window.scale_factor().fract() != 0.
+            );
+            match display.scale_factor {
+                Some(scale_factor) => {
+                    _ = writeln!(report, "- Scale factor override: {}", scale_factor)
+                }
+                None => _ = writeln!(report, "- Scale factor override: none"),
+            }
+            let decorations = match window.window_decorations() {
+                Decorations::Server => "server",
+                Decorations::Client { .. } => "client",
+            };
+            _ = writeln!(
+                report,
+                "- Decorations: {:?} requested, {} in use",
+                display.decorations, decorations
+            );
+        }
+
+        _ = writeln!(report, "\n### Responsiveness\n");
+        _ = writeln!(
+            report,
+            "- UI stalls: {} (longest {:.1}s)",
+            watchdog::stalls(),
+            watchdog::longest_stall().as_secs_f64()
+        );
+
+        _ = writeln!(report, "\n### Memory\n");
+        let memory = Memory::global(cx);
+        if let Some(sample) = memory.latest() {
+            match sample.resident {
+                Some(resident) => _ = writeln!(report, "- Resident: {}", format_size(resident)),
+                None => _ = writeln!(report, "- Resident: not reported on this platform"),
+            }
+            _ = writeln!(report, "- Window views alive: {}", sample.views);
+        }
+        if memory.leak_canary() {
+            _ = writeln!(report, "- Leaked views: {}", memory.leaks().len());
+            for leak in memory.leaks() {
+                _ = writeln!(report, "  - {}", leak);
+            }
+        }
+
+        _ = writeln!(report, "\n### Displays\n");
+        let primary = cx.primary_display().map(|display| display.id());
+        for display in cx.displays() {
+            let bounds = display.bounds();
+            _ = writeln!(
+                report,
+                "- {:?}{}: {}x{} at ({}, {})",
+                display.id(),
+                if Some(display.id()) == primary {
+                    " (primary)"
+                } else {
+                    ""
+                },
+                bounds.size.width,
+                bounds.size.height,
+                bounds.origin.x,
+                bounds.origin.y,
+            );
+        }
+        report
+    }
+}
+
+impl Focusable for GpuDiagnostics {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for GpuDiagnostics {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let report = SharedString::from(Self::report(window, cx));
+        let locked = policy::is_locked("render.software");
+
+        v_flex()
+            .id("gpu-diagnostics")
+            .size_full()
+            .p_4()
+            .gap_3()
+            .overflow_y_scroll()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Switch::new("software-render")
+                            .checked(Settings::global(cx).render.software)
+                            .label("Software rendering (applies on restart)")
+                            .disabled(locked)
+                            .when(locked, |this| this.tooltip(policy::LOCKED_TOOLTIP))
+                            .on_click(|checked: &bool, _, cx| {
+                                let checked = *checked;
+                                Settings::update(cx, |settings, _| {
+                                    settings.render.software = checked
+                                });
+                                cx.refresh_windows();
+                            }),
+                    )
+                    .child(
+                        Button::new("copy-gpu-report")
+                            .small()
+                            .outline()
+                            .label("Copy Report")
+                            .on_click({
+                                let report = report.clone();
+                                move |_, window, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        report.to_string(),
+                                    ));
+                                    window.push_notification(
+                                        Notification::success(
+                                            "Copied the report to the clipboard.",
+                                        ),
+                                        cx,
+                                    );
+                                }
+                            }),
+                    ),
+            )
+            .child(Self::render_sparkline(cx))
+            .child(TextView::markdown("gpu-report", report, window, cx))
+    }
+}
+
+/// The body of a new issue: a section to describe the problem, and what the app knows about
+/// the machine, the theme and the last errors.
+pub fn issue_body(window: &Window, cx: &App) -> String {
+    let mut body = String::new();
+    _ = writeln!(body, "## What happened?\n");
+    _ = writeln!(
+        body,
+        "<!-- What you did, what you expected and what happened instead. -->\n"
+    );
+    _ = writeln!(body, "## Environment\n");
+    _ = writeln!(
+        body,
+        "- App: {} {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    _ = writeln!(
+        body,
+        "- OS: {} ({})",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    match window.gpu_specs() {
+        Some(specs) => {
+            _ = writeln!(
+                body,
+                "- GPU: {} ({} {})",
+                specs.device_name, specs.driver_name, specs.driver_info
+            )
+        }
+        None => _ = writeln!(body, "- GPU: not reported on this platform"),
+    }
+    _ = writeln!(body, "- Backend: {}", graphics_backend());
+    _ = writeln!(
+        body,
+        "- Theme: {} ({})",
+        cx.theme().theme_name(),
+        cx.theme().mode.name()
+    );
+
+    let errors = logs::recent_errors();
+    _ = writeln!(body, "\n## Recent errors\n");
+    if errors.is_empty() {
+        _ = writeln!(body, "None");
+    } else {
+        _ = writeln!(body, "```\n{}\n```", errors.join("\n"));
+    }
+    body
+}
+
+/// `text` as a URL query value.
+fn encode_query(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn issue_url(title: &str, body: &str) -> String {
+    let mut end = body.len().min(MAX_ISSUE_BODY);
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!(
+        "{}?title={}&body={}",
+        ISSUE_TRACKER_URL,
+        encode_query(title),
+        encode_query(&body[..end])
+    )
+}
+
+/// Show the report to edit before it leaves the machine, then open the tracker with it.
+fn open_report_dialog(window: &mut Window, cx: &mut App) {
+    let title = cx.new(|cx| InputState::new(window, cx).placeholder("A short summary"));
+    let body = issue_body(window, cx);
+    let body = cx.new(|cx| InputState::new(window, cx).multi_line().default_value(body));
+
+    window.open_modal(cx, move |modal, _, cx| {
+        let (title, body) = (title.clone(), body.clone());
+        modal.title("Report an Issue").width(px(640.)).child(
+            v_flex()
+                .gap_3()
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(
+                            "Nothing is sent from the app, the report opens in the browser. \
+                             Remove anything you'd rather not share first.",
+                        ),
+                )
+                .child(TextField::new("issue-title", &title))
+                .child(TextInput::new(&body).h(px(320.)))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .justify_end()
+                        .child(
+                            Button::new("cancel-report")
+                                .small()
+                                .ghost()
+                                .label("Cancel")
+                                .on_click(|_, window, cx| window.close_modal(cx)),
+                        )
+                        .child(
+                            Button::new("open-report")
+                                .small()
+                                .primary()
+                                .label("Open in Browser")
+                                .on_click(move |_, window, cx| {
+                                    let url = issue_url(
+                                        title.read(cx).value().trim(),
+                                        body.read(cx).value(),
+                                    );
+                                    cx.open_url(&url);
+                                    window.close_modal(cx);
+                                }),
+                        ),
+                ),
+        )
+    });
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "GPU Diagnostics",
+        Some(size(px(640.), px(560.))),
+        |window, cx| GpuDiagnostics::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ShowGpuDiagnostics, cx| open_window(cx));
+    cx.on_action(|_: &ReportIssue, cx| {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_, window, cx| open_report_dialog(window, cx));
+        }
+    });
+}
@@ -0,0 +1,199 @@
+//! File dialogs for imports and exports: the native ones of the platform, or the built-in
+//! [`FilePicker`](crate::FilePicker) where there are none, e.g. without a desktop portal.
+//!
+//! Each dialog is opened for a purpose, like `"themes"`, and starts in the folder the last
+//! dialog of that purpose was left in.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use futures::{FutureExt as _, future::LocalBoxFuture};
+use gpui::{App, PathPromptOptions, SharedString, Task, Window};
+
+use crate::{
+    file_picker::{self, PickerMode, PickerOptions},
+    session::Session,
+};
+
+#[derive(Debug, Clone)]
+pub struct DialogOptions {
+    purpose: &'static str,
+    title: SharedString,
+    extensions: Vec<SharedString>,
+    directory: Option<PathBuf>,
+}
+
+impl DialogOptions {
+    /// `purpose` keys the last folder in the session, keep it stable.
+    pub fn new(purpose: &'static str, title: impl Into<SharedString>) -> Self {
+        Self {
+            purpose,
+            title: title.into(),
+            extensions: vec![],
+            directory: None,
+        }
+    }
+
+    /// The extensions of the files to pick, without the dot. The first is added to a saved
+    /// file name that has none.
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions = extensions
+            .iter()
+            .map(|ext| ext.to_string().into())
+            .collect();
+        self
+    }
+
+    /// The folder to start in the first time, before there's a last folder.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    fn start_dir(&self, cx: &App) -> Option<PathBuf> {
+        Session::global(cx)
+            .dialog_dirs
+            .get(self.purpose)
+            .cloned()
+            .or_else(|| self.directory.clone())
+            .filter(|dir| dir.is_dir())
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        self.extensions.is_empty()
+            || path.extension().is_some_and(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|allowed| ext.eq_ignore_ascii_case(allowed.as_ref()))
+            })
+    }
+}
+
+/// Pick a file to open, `None` when the dialog was dismissed.
+pub fn open_file(
+    options: DialogOptions,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<anyhow::Result<Option<PathBuf>>> {
+    let native = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some(options.title.clone()),
+    });
+    prompt(
+        options,
+        PickerMode::OpenFile,
+        async move { Ok(native.await??.and_then(|paths| paths.into_iter().next())) }.boxed_local(),
+        window,
+        cx,
+    )
+}
+
+/// Pick a path to save a file to, starting with the `file_name`.
+pub fn save_file(
+    options: DialogOptions,
+    file_name: impl Into<SharedString>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<anyhow::Result<Option<PathBuf>>> {
+    let file_name = file_name.into();
+    let directory = options
+        .start_dir(cx)
+        .or_else(|| directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()))
+        .unwrap_or_default();
+    let native = cx.prompt_for_new_path(&directory, Some(&file_name));
+    prompt(
+        options,
+        PickerMode::SaveFile(file_name),
+        async move { Ok(native.await??) }.boxed_local(),
+        window,
+        cx,
+    )
+}
+
+/// Pick a folder.
+pub fn pick_folder(
+    options: DialogOptions,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<anyhow::Result<Option<PathBuf>>> {
+    let native = cx.prompt_for_paths(PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: Some(options.title.clone()),
+    });
+    prompt(
+        options,
+        PickerMode::Folder,
+        async move { Ok(native.await??.and_then(|paths| paths.into_iter().next())) }.boxed_local(),
+        window,
+        cx,
+    )
+}
+
+fn prompt(
+    options: DialogOptions,
+    mode: PickerMode,
+    native: LocalBoxFuture<'static, anyhow::Result<Option<PathBuf>>>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<anyhow::Result<Option<PathBuf>>> {
+    let window = window.window_handle();
+    let mut picker_options = PickerOptions::new(options.title.clone(), mode.clone());
+    picker_options.extensions = options.extensions.clone();
+    picker_options.directory = options.start_dir(cx);
+
+    cx.spawn(async move |cx| {
+        let path = match native.await {
+            Ok(path) => path,
+            Err(err) => {
+                tracing::warn!("No native file dialog, using the built-in one: {}", err);
+                let picked = window.update(cx, |_, window, cx| {
+                    file_picker::pick(picker_options, window, cx)
+                })?;
+                picked.await.unwrap_or_default()
+            }
+        };
+        let Some(mut path) = path else {
+            return Ok(None);
+        };
+
+        // The native dialogs don't filter by extension everywhere.
+        match mode {
+            PickerMode::OpenFile if !options.matches(&path) => {
+                return Err(anyhow!(
+                    "Expected a .{} file, got {}",
+                    options
+                        .extensions
+                        .iter()
+                        .map(|ext| ext.as_ref())
+                        .collect::<Vec<_>>()
+                        .join(" or ."),
+                    path.display()
+                ));
+            }
+            PickerMode::SaveFile(_) if path.extension().is_none() => {
+                if let Some(ext) = options.extensions.first() {
+                    path.set_extension(ext.as_ref());
+                }
+            }
+            _ => {}
+        }
+
+        let dir = match mode {
+            PickerMode::Folder => Some(path.clone()),
+            _ => path.parent().map(Path::to_path_buf),
+        };
+        if let Some(dir) = dir {
+            let purpose = options.purpose.to_string();
+            cx.update(|cx| {
+                Session::update(cx, |session| {
+                    session.dialog_dirs.insert(purpose, dir);
+                })
+            })?;
+        }
+        Ok(Some(path))
+    })
+}
@@ -0,0 +1,134 @@
+//! What escape closes: the topmost of the transient surfaces of the window, a tooltip before a
+//! menu, before the command palette, before a dialog, and a selection last.
+//!
+//! Surfaces register while they are up and are dropped from the stack with the returned
+//! [`Subscription`]:
+//!
+//! ```ignore
+//! self._dismiss = DismissStack::register(DismissLayer::Menu, window, cx, move |window, cx| {
+//!     menu.update(cx, |menu, cx| menu.close(window, cx));
+//!     true
+//! });
+//! ```
+//!
+//! Escape first goes to what's focused, so an input or a menu of the components that handles it,
+//! or a focused [`WithTooltip`](crate::tooltips::WithTooltip), takes it before the stack does.
+
+use std::{cell::RefCell, rc::Rc};
+
+use gpui::{App, Global, KeyBinding, Subscription, Window, WindowId, actions};
+use gpui_component::ContextModal as _;
+
+actions!(dismiss, [Dismiss]);
+
+/// The kinds of transient surfaces, the topmost first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DismissLayer {
+    Tooltip,
+    Menu,
+    Palette,
+    Dialog,
+    /// Selected items, cleared when nothing is above them.
+    Selection,
+}
+
+type DismissHandler = Rc<dyn Fn(&mut Window, &mut App) -> bool>;
+
+struct Entry {
+    id: usize,
+    window: WindowId,
+    layer: DismissLayer,
+    dismiss: DismissHandler,
+}
+
+#[derive(Default)]
+struct Entries {
+    next_id: usize,
+    entries: Vec<Entry>,
+}
+
+/// The registered surfaces of all the windows.
+#[derive(Default)]
+pub struct DismissStack(Rc<RefCell<Entries>>);
+
+impl Global for DismissStack {}
+
+impl DismissStack {
+    /// Register a surface of `window` until the returned subscription is dropped.
+    ///
+    /// `dismiss` closes it, or returns `false` when there's nothing to close (e.g. no selection),
+    /// and escape goes on to the next one.
+    pub fn register(
+        layer: DismissLayer,
+        window: &Window,
+        cx: &mut App,
+        dismiss: impl Fn(&mut Window, &mut App) -> bool + 'static,
+    ) -> Subscription {
+        let entries = cx.default_global::<Self>().0.clone();
+        let id = {
+            let mut entries = entries.borrow_mut();
+            let id = entries.next_id;
+            entries.next_id += 1;
+            entries.entries.push(Entry {
+                id,
+                window: window.window_handle().window_id(),
+                layer,
+                dismiss: Rc::new(dismiss),
+            });
+            id
+        };
+        Subscription::new(move || entries.borrow_mut().entries.retain(|entry| entry.id != id))
+    }
+
+    /// The handlers of `window`, the topmost layer first and the latest first within a layer.
+    fn handlers(window: &Window, cx: &App) -> Vec<(DismissLayer, DismissHandler)> {
+        let Some(stack) = cx.try_global::<Self>() else {
+            return vec![];
+        };
+        let window_id = window.window_handle().window_id();
+        let entries = stack.0.borrow();
+        let mut entries = entries
+            .entries
+            .iter()
+            .filter(|entry| entry.window == window_id)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| (entry.layer, std::cmp::Reverse(entry.id)));
+        entries
+            .into_iter()
+            .map(|entry| (entry.layer, entry.dismiss.clone()))
+            .collect()
+    }
+}
+
+/// Close the topmost surface of the window, whether something was closed.
+///
+/// A dialog that didn't register is closed after the registered dialogs.
+pub fn dismiss(window: &mut Window, cx: &mut App) -> bool {
+    let mut modal_checked = false;
+    for (layer, dismiss) in DismissStack::handlers(window, cx) {
+        if layer > DismissLayer::Dialog && !modal_checked {
+            modal_checked = true;
+            if close_modal(window, cx) {
+                return true;
+            }
+        }
+        // Handlers are called outside of the borrow, they may register or drop others.
+        if dismiss(window, cx) {
+            return true;
+        }
+    }
+    !modal_checked && close_modal(window, cx)
+}
+
+fn close_modal(window: &mut Window, cx: &mut App) -> bool {
+    if !window.has_active_modal(cx) {
+        return false;
+    }
+    window.close_modal(cx);
+    true
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(DismissStack::default());
+    cx.bind_keys([KeyBinding::new("escape", Dismiss, None)]);
+}
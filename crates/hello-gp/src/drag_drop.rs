@@ -0,0 +1,147 @@
+//! Dragging things within the app: what's dragged is a payload of its own type, dropped on the
+//! targets that take that type, with a preview under the pointer.
+//!
+//! ```ignore
+//! div()
+//!     .id(("item", ix))
+//!     .draggable(DraggedItem(ix), item.name.clone(), cx)
+//!     .drop_target(cx, move |DraggedItem(from), _, cx| {
+//!         view.update(cx, |this, _| drag_drop::move_item(&mut this.items, *from, ix))
+//!     })
+//! ```
+
+use gpui::{
+    App, AppContext as _, Context, Div, DragMoveEvent, InteractiveElement as _, IntoElement,
+    ParentElement as _, Pixels, Point, Render, ScrollHandle, SharedString, Stateful,
+    StatefulInteractiveElement as _, Styled as _, Window, div, point, px,
+};
+use gpui_component::{ActiveTheme as _, Colorize as _, h_flex};
+
+use crate::cursor::{CursorExt as _, CursorRole};
+
+/// How close to the edge of a list the pointer scrolls it while dragging.
+const AUTO_SCROLL_EDGE: Pixels = px(32.);
+/// How far a list scrolls per move of the pointer at its very edge.
+const AUTO_SCROLL_STEP: f32 = 12.;
+
+/// What follows the pointer while dragging, a label of what's dragged.
+pub struct DragPreview {
+    label: SharedString,
+    offset: Point<Pixels>,
+}
+
+impl DragPreview {
+    pub fn new(label: impl Into<SharedString>, offset: Point<Pixels>) -> Self {
+        Self {
+            label: label.into(),
+            offset,
+        }
+    }
+}
+
+impl Render for DragPreview {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Beside the pointer rather than under it, so the target under it shows.
+        div()
+            .pl(self.offset.x + px(12.))
+            .pt(self.offset.y + px(12.))
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .px_2()
+                    .py_1()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().ring)
+                    .bg(cx.theme().popover.opacity(0.9))
+                    .text_color(cx.theme().popover_foreground)
+                    .text_sm()
+                    .shadow_md()
+                    .child(self.label.clone()),
+            )
+    }
+}
+
+/// Dragging and dropping on elements with an id.
+pub trait DragDropExt: Sized {
+    /// Drag `payload` off the element, showing `label` under the pointer.
+    fn draggable<T: Clone + 'static>(
+        self,
+        payload: T,
+        label: impl Into<SharedString>,
+        cx: &App,
+    ) -> Self;
+
+    /// Take payloads of type `T` dropped on the element, marked while one is dragged over it.
+    fn drop_target<T: 'static>(
+        self,
+        cx: &App,
+        on_drop: impl Fn(&T, &mut Window, &mut App) + 'static,
+    ) -> Self;
+
+    /// Scroll the element while a payload of type `T` is dragged near its top or bottom, to
+    /// reach the rows out of view.
+    fn auto_scroll<T: 'static>(self, scroll_handle: &ScrollHandle) -> Self;
+}
+
+impl DragDropExt for Stateful<Div> {
+    fn draggable<T: Clone + 'static>(
+        self,
+        payload: T,
+        label: impl Into<SharedString>,
+        cx: &App,
+    ) -> Self {
+        let label = label.into();
+        self.cursor_role(CursorRole::Grab, cx)
+            .on_drag(payload, move |_, offset, _, cx| {
+                cx.new(|_| DragPreview::new(label.clone(), offset))
+            })
+    }
+
+    fn drop_target<T: 'static>(
+        self,
+        cx: &App,
+        on_drop: impl Fn(&T, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        let (background, border) = (cx.theme().drop_target, cx.theme().drag_border);
+        self.drag_over::<T>(move |style, _, _, _| style.bg(background).border_color(border))
+            .on_drop(on_drop)
+    }
+
+    fn auto_scroll<T: 'static>(self, scroll_handle: &ScrollHandle) -> Self {
+        let scroll_handle = scroll_handle.clone();
+        self.on_drag_move::<T>(move |event: &DragMoveEvent<T>, window, _| {
+            let bounds = event.bounds;
+            let y = event.event.position.y;
+            if !bounds.contains(&event.event.position) {
+                return;
+            }
+            let top = y - bounds.top();
+            let bottom = bounds.bottom() - y;
+            // Faster the closer to the edge.
+            let delta = if top < AUTO_SCROLL_EDGE {
+                AUTO_SCROLL_STEP * (1. - top.as_f32() / AUTO_SCROLL_EDGE.as_f32())
+            } else if bottom < AUTO_SCROLL_EDGE {
+                -AUTO_SCROLL_STEP * (1. - bottom.as_f32() / AUTO_SCROLL_EDGE.as_f32())
+            } else {
+                return;
+            };
+            let offset = scroll_handle.offset();
+            let max = scroll_handle.max_offset().height;
+            let y = (offset.y.as_f32() + delta).clamp(-max.as_f32(), 0.);
+            if y != offset.y.as_f32() {
+                scroll_handle.set_offset(point(offset.x, px(y)));
+                window.refresh();
+            }
+        })
+    }
+}
+
+/// Move the item at `from` to `to`, the items between shifting over.
+pub fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) {
+    if from >= items.len() || from == to {
+        return;
+    }
+    let item = items.remove(from);
+    items.insert(to.min(items.len()), item);
+}
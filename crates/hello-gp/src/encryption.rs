@@ -0,0 +1,216 @@
+use std::sync::{
+    RwLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{Context as _, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chacha20poly1305::{
+    KeyInit as _, XChaCha20Poly1305, XNonce,
+    aead::{Aead as _, generic_array::GenericArray},
+};
+use gpui::{App, actions};
+use gpui_component::{ContextModal as _, notification::Notification};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    layouts::{LAYOUTS_NAME, LayoutPresets},
+    secrets::Secrets,
+    session::{SESSION_NAME, Session},
+    settings::{SETTINGS_NAME, Settings},
+    themes::{STATE_NAME, State, get_config_dir},
+};
+
+actions!(encryption, [ToggleConfigEncryption]);
+
+/// The first line of an encrypted config file, followed by the base64 of nonce and ciphertext.
+const MAGIC: &str = "hello-gp-encrypted:v1";
+/// The name of the key in [`Secrets`].
+const KEY_NAME: &str = "config-encryption-key";
+const NONCE_LEN: usize = 24;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct EncryptionSettings {
+    /// Encrypt the settings, state, session and layout files with a key kept in the OS
+    /// keychain, e.g. to sync the config dir through storage you don't trust.
+    ///
+    /// Files are decrypted on load either way, turning this off writes them in plain again.
+    pub enabled: bool,
+}
+
+/// Config files are read and written outside of the app context (and before it has
+/// the settings), so the key and the switch live here rather than in a global.
+static CIPHER: RwLock<Option<XChaCha20Poly1305>> = RwLock::new(None);
+static ENCRYPT: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn is_encrypted(text: &str) -> bool {
+    text.starts_with(MAGIC)
+}
+
+/// Whether [`config::save`] should write `encrypt`ed files.
+pub(crate) fn enabled() -> bool {
+    ENCRYPT.load(Ordering::Relaxed)
+}
+
+pub(crate) fn encrypt(text: &str) -> anyhow::Result<String> {
+    let cipher = CIPHER.read().map_err(|_| anyhow!("poisoned key"))?;
+    let cipher = cipher.as_ref().context("no config encryption key")?;
+
+    let nonce = rand::random::<[u8; NONCE_LEN]>();
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), text.as_bytes())
+        .map_err(|_| anyhow!("failed to encrypt"))?;
+    let payload = [nonce.as_slice(), &ciphertext].concat();
+    Ok(format!("{}\n{}\n", MAGIC, BASE64.encode(payload)))
+}
+
+pub(crate) fn decrypt(text: &str) -> anyhow::Result<String> {
+    let payload = text
+        .strip_prefix(MAGIC)
+        .context("not an encrypted config")?
+        .trim();
+    let payload = BASE64.decode(payload).context("invalid encrypted config")?;
+    if payload.len() < NONCE_LEN {
+        return Err(anyhow!("truncated encrypted config"));
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+
+    let cipher = CIPHER.read().map_err(|_| anyhow!("poisoned key"))?;
+    let cipher = cipher
+        .as_ref()
+        .context("the config is encrypted, but the key isn't in the keychain")?;
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("the config is encrypted with a different key"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+fn set_key(key: &[u8]) {
+    if let Ok(mut cipher) = CIPHER.write() {
+        *cipher = Some(XChaCha20Poly1305::new(GenericArray::from_slice(key)));
+    }
+}
+
+/// Use the key from the keychain, if there is one. Runs before the configs are loaded.
+pub(crate) fn load_key(cx: &App) {
+    let key = match Secrets::get(KEY_NAME, cx) {
+        Ok(Some(key)) => key,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::error!("Failed to read the config encryption key: {}", err);
+            return;
+        }
+    };
+    match BASE64.decode(key) {
+        Ok(key) if key.len() == 32 => set_key(&key),
+        _ => tracing::error!("Invalid config encryption key in the keychain"),
+    }
+}
+
+/// Create the key on first use, it must survive restarts or the files can't be read again.
+fn ensure_key(cx: &mut App) -> anyhow::Result<()> {
+    if CIPHER.read().is_ok_and(|cipher| cipher.is_some()) {
+        return Ok(());
+    }
+    if !Secrets::is_persistent(cx) {
+        return Err(anyhow!("there is no OS keychain to keep the key in"));
+    }
+
+    let key = rand::random::<[u8; 32]>();
+    Secrets::set(KEY_NAME, &BASE64.encode(key), cx)?;
+    set_key(&key);
+    Ok(())
+}
+
+/// Follow the `encryption.enabled` setting, after the settings were (re)loaded.
+pub(crate) fn sync(cx: &mut App) {
+    let enabled = Settings::global(cx).encryption.enabled;
+    if enabled && let Err(err) = ensure_key(cx) {
+        tracing::error!("Config files are written unencrypted: {}", err);
+        ENCRYPT.store(false, Ordering::Relaxed);
+        return;
+    }
+    ENCRYPT.store(enabled, Ordering::Relaxed);
+}
+
+/// Write the loaded configs again, so they are all encrypted (or decrypted) right away.
+fn rewrite(cx: &App) -> anyhow::Result<()> {
+    let dir = get_config_dir();
+    config::save(&dir, SETTINGS_NAME, Settings::global(cx))?;
+    // The theme state isn't kept in memory, it's written when the theme changes.
+    if config::find(&dir, STATE_NAME).is_some() {
+        let state = config::load::<State>(&dir, STATE_NAME)?;
+        config::save(&dir, STATE_NAME, &state)?;
+    }
+    config::save(&dir, SESSION_NAME, Session::global(cx))?;
+    config::save(&dir, LAYOUTS_NAME, LayoutPresets::global(cx))?;
+    Ok(())
+}
+
+fn toggle(cx: &mut App) {
+    let enable = !Settings::global(cx).encryption.enabled;
+    let result = (|| {
+        if enable {
+            ensure_key(cx)?;
+        }
+        ENCRYPT.store(enable, Ordering::Relaxed);
+        Settings::update(cx, |settings, _| settings.encryption.enabled = enable);
+        rewrite(cx)
+    })();
+
+    let note = match result {
+        Ok(()) if enable => Notification::success("Config files are now encrypted."),
+        Ok(()) => Notification::success("Config files are now stored unencrypted."),
+        Err(err) => {
+            tracing::error!("Failed to toggle config encryption: {:#}", err);
+            Notification::error(format!("Failed to toggle config encryption: {}", err))
+        }
+    };
+    if let Some(window) = cx.active_window() {
+        _ = window.update(cx, |_, window, cx| window.push_notification(note, cx));
+    }
+}
+
+pub fn init(cx: &mut App) {
+    sync(cx);
+    cx.on_action(|_: &ToggleConfigEncryption, cx| toggle(cx));
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+
+    use crate::encryption::{BASE64, MAGIC, NONCE_LEN, decrypt, encrypt, is_encrypted, set_key};
+
+    // The key is global to the process, so the cases run in order in a single test.
+    #[test]
+    fn test_encrypt_decrypt() {
+        let text = "[theme]\nmode = \"dark\"\n";
+        set_key(&[1; 32]);
+        let encrypted = encrypt(text).unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("dark"));
+        assert_eq!(decrypt(&encrypted).unwrap(), text);
+        // A fresh nonce every time.
+        assert_ne!(encrypt(text).unwrap(), encrypted);
+
+        let payload = BASE64.decode(encrypted.lines().nth(1).unwrap()).unwrap();
+        let with_payload = |payload: &[u8]| format!("{}\n{}\n", MAGIC, BASE64.encode(payload));
+        // Shorter than the nonce.
+        assert!(decrypt(&with_payload(&payload[..NONCE_LEN - 1])).is_err());
+        // Cut in the ciphertext.
+        assert!(decrypt(&with_payload(&payload[..payload.len() - 1])).is_err());
+        // Tampered with.
+        let mut tampered = payload.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&with_payload(&tampered)).is_err());
+        assert!(decrypt(&format!("{}\nnot base64!\n", MAGIC)).is_err());
+        assert!(decrypt(text).is_err());
+
+        set_key(&[2; 32]);
+        assert!(decrypt(&encrypted).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use gpui::{App, Global, SharedString};
+use gpui_component::ThemeMode;
+
+type Handler = dyn Fn(&dyn Any, &mut App);
+
+/// A typed publish/subscribe bus so modules can react to each other without direct coupling.
+///
+/// The bus only keeps weak references to the handlers, a handler lives as long as the
+/// [`EventSubscription`] returned by [`subscribe`].
+#[derive(Default)]
+pub struct EventBus {
+    handlers: HashMap<TypeId, Vec<Weak<Handler>>>,
+}
+
+impl Global for EventBus {}
+
+/// Unsubscribes when dropped, unless detached.
+#[must_use = "the handler is removed when the subscription is dropped"]
+pub struct EventSubscription {
+    _handler: Rc<Handler>,
+}
+
+impl EventSubscription {
+    /// Keep the handler alive for the lifetime of the app.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+
+/// Subscribe to events of type `E`.
+pub fn subscribe<E: 'static>(
+    cx: &mut App,
+    f: impl Fn(&E, &mut App) + 'static,
+) -> EventSubscription {
+    let handler: Rc<Handler> = Rc::new(move |event, cx| {
+        if let Some(event) = event.downcast_ref::<E>() {
+            f(event, cx);
+        }
+    });
+
+    let bus = cx.default_global::<EventBus>();
+    let handlers = bus.handlers.entry(TypeId::of::<E>()).or_default();
+    handlers.retain(|handler| handler.strong_count() > 0);
+    handlers.push(Rc::downgrade(&handler));
+
+    EventSubscription { _handler: handler }
+}
+
+/// Publish an event to all live subscribers of its type.
+pub fn publish<E: 'static>(event: E, cx: &mut App) {
+    let Some(bus) = cx.try_global::<EventBus>() else {
+        return;
+    };
+    let handlers = bus
+        .handlers
+        .get(&TypeId::of::<E>())
+        .map(|handlers| {
+            handlers
+                .iter()
+                .filter_map(|handler| handler.upgrade())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for handler in handlers {
+        handler(&event, cx);
+    }
+}
+
+/// The theme (or one of its options, like font size or scrollbar) has changed.
+#[derive(Debug, Clone)]
+pub struct ThemeChanged {
+    pub name: SharedString,
+    pub mode: ThemeMode,
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(EventBus::default());
+}
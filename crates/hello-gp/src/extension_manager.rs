@@ -0,0 +1,373 @@
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, FocusHandle, Focusable, InteractiveElement as _,
+    IntoElement, ParentElement as _, Render, SharedString, Styled as _, Subscription, Window,
+    actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariant, ButtonVariants as _},
+    h_flex,
+    modal::ModalButtonProps,
+    notification::NotificationType,
+    switch::Switch,
+    table::{Table, TableEvent},
+    tag::Tag,
+    v_flex,
+};
+
+use crate::{
+    data_table::{DataColumn, DataTable, DataTableDelegate},
+    extensions::{Extension, ExtensionManifest, ExtensionRegistry, FeedEntry},
+    markdown::Markdown,
+};
+
+actions!(extensions, [ShowExtensions]);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = extensions, no_json)]
+struct ToggleExtension(SharedString);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = extensions, no_json)]
+struct UninstallExtension(SharedString);
+
+/// A row of the extensions table, as the extension was when the table was updated.
+struct ExtensionRow {
+    manifest: ExtensionManifest,
+    update: Option<FeedEntry>,
+    enabled: bool,
+}
+
+impl ExtensionRow {
+    fn all(registry: &ExtensionRegistry) -> Vec<Self> {
+        registry
+            .extensions()
+            .iter()
+            .map(|ext| Self {
+                manifest: ext.manifest.clone(),
+                update: ext.update.clone(),
+                enabled: ext.is_enabled(),
+            })
+            .collect()
+    }
+}
+
+fn set_enabled(id: &SharedString, enabled: bool, window: &mut Window, cx: &mut App) {
+    let result = ExtensionRegistry::global(cx)
+        .update(cx, |registry, cx| registry.set_enabled(id, enabled, cx));
+    if let Err(err) = result {
+        window.push_notification(
+            (NotificationType::Error, SharedString::from(err.to_string())),
+            cx,
+        );
+    }
+}
+
+fn columns() -> Vec<DataColumn<ExtensionRow>> {
+    vec![
+        DataColumn::new("name", "Name", |row: &ExtensionRow, _, _| {
+            row.manifest.name.clone().into_any_element()
+        })
+        .width(200.)
+        .sort_by_key(|row| row.manifest.name.to_lowercase()),
+        DataColumn::new("version", "Version", |row: &ExtensionRow, _, cx| {
+            h_flex()
+                .gap_2()
+                .child(format!("v{}", row.manifest.version))
+                .when_some(row.update.as_ref(), |this, update| {
+                    this.child(
+                        Tag::primary()
+                            .small()
+                            .child(format!("v{} available", update.version)),
+                    )
+                })
+                .text_color(cx.theme().foreground)
+                .into_any_element()
+        })
+        .width(180.)
+        .sort_by_key(|row| row.update.is_some()),
+        DataColumn::new("authors", "Authors", |row: &ExtensionRow, _, cx| {
+            div()
+                .text_color(cx.theme().muted_foreground)
+                .child(row.manifest.authors.join(", "))
+                .into_any_element()
+        })
+        .width(220.),
+        DataColumn::new("enabled", "Enabled", |row: &ExtensionRow, _, _| {
+            let id = row.manifest.id.clone();
+            Switch::new(SharedString::from(format!("toggle-extension-{}", id)))
+                .checked(row.enabled)
+                .on_click(move |checked: &bool, window, cx| set_enabled(&id, *checked, window, cx))
+                .into_any_element()
+        })
+        .width(80.)
+        .sort_by_key(|row| row.enabled),
+    ]
+}
+
+/// Lists the installed extensions, with enable/disable, update check and uninstall.
+pub struct ExtensionManager {
+    focus_handle: FocusHandle,
+    registry: Entity<ExtensionRegistry>,
+    table: Entity<Table<DataTableDelegate<ExtensionRow>>>,
+    selected: Option<SharedString>,
+    checking: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ExtensionManager {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let registry = ExtensionRegistry::global(cx);
+        let table = DataTable::new("extensions", columns())
+            .context_menu(|row, menu, _, _| {
+                let id = row.manifest.id.clone();
+                menu.menu(
+                    if row.enabled { "Disable" } else { "Enable" },
+                    Box::new(ToggleExtension(id.clone())),
+                )
+                .separator()
+                .menu("Uninstall...", Box::new(UninstallExtension(id)))
+            })
+            .build(ExtensionRow::all(registry.read(cx)), window, cx);
+
+        let _subscriptions = vec![
+            cx.observe(&registry, |this, registry, cx| {
+                let rows = ExtensionRow::all(registry.read(cx));
+                this.table.update(cx, |table, cx| {
+                    table.delegate_mut().set_rows(rows);
+                    cx.notify();
+                });
+                cx.notify();
+            }),
+            cx.subscribe(&table, |this, table, event: &TableEvent, cx| {
+                if let TableEvent::SelectRow(ix) = event {
+                    this.selected = table
+                        .read(cx)
+                        .delegate()
+                        .row(*ix)
+                        .map(|row| row.manifest.id.clone());
+                    cx.notify();
+                }
+            }),
+        ];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            registry,
+            table,
+            selected: None,
+            checking: false,
+            _subscriptions,
+        }
+    }
+    fn check_for_updates(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let task = self
+            .registry
+            .update(cx, |registry, cx| registry.check_for_updates(cx));
+        self.checking = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, window| {
+            let result = task.await;
+            _ = this.update_in(window, |this, window, cx| {
+                this.checking = false;
+                let note = match result {
+                    Ok(0) => (
+                        NotificationType::Info,
+                        "All extensions are up to date.".into(),
+                    ),
+                    Ok(count) => (
+                        NotificationType::Info,
+                        format!("{} extension updates available.", count).into(),
+                    ),
+                    Err(err) => (
+                        NotificationType::Error,
+                        SharedString::from(format!("Failed to check for updates: {}", err)),
+                    ),
+                };
+                window.push_notification(note, cx);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn confirm_uninstall(&mut self, id: SharedString, window: &mut Window, cx: &mut Context<Self>) {
+        let registry = self.registry.clone();
+        window.open_modal(cx, move |modal, _, _| {
+            let registry = registry.clone();
+            let id = id.clone();
+            modal
+                .confirm()
+                .child(format!(
+                    "Uninstall {}? Its files, data and settings will be removed.",
+                    id
+                ))
+                .button_props(
+                    ModalButtonProps::default()
+                        .ok_text("Uninstall")
+                        .ok_variant(ButtonVariant::Danger),
+                )
+                .on_ok(move |_, window, cx| {
+                    let result = registry.update(cx, |registry, cx| registry.uninstall(&id, cx));
+                    if let Err(err) = result {
+                        window.push_notification(
+                            (
+                                NotificationType::Error,
+                                SharedString::from(format!("Failed to uninstall: {}", err)),
+                            ),
+                            cx,
+                        );
+                    }
+                    true
+                })
+        });
+    }
+
+    fn toggle_extension(
+        &mut self,
+        action: &ToggleExtension,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let enabled = self
+            .registry
+            .read(cx)
+            .extension(&action.0)
+            .is_some_and(|ext| ext.is_enabled());
+        set_enabled(&action.0, !enabled, window, cx);
+    }
+
+    fn uninstall_extension(
+        &mut self,
+        action: &UninstallExtension,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.confirm_uninstall(action.0.clone(), window, cx);
+    }
+
+    /// The description of the selected extension, and what can be done with it.
+    fn render_details(&self, ext: &Extension, cx: &Context<Self>) -> impl IntoElement {
+        let manifest = &ext.manifest;
+        let id = manifest.id.clone();
+
+        h_flex()
+            .gap_4()
+            .p_3()
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .gap_1()
+                    .child(div().font_semibold().child(manifest.name.clone()))
+                    .child(
+                        Markdown::new("extension-description", manifest.description.clone())
+                            .muted(),
+                    ),
+            )
+            .when_some(
+                ext.update.as_ref().and_then(|update| update.url.clone()),
+                |this, url| {
+                    this.child(
+                        Button::new("update-extension")
+                            .small()
+                            .outline()
+                            .label("Get Update")
+                            .on_click(move |_, _, cx| cx.open_url(&url)),
+                    )
+                },
+            )
+            .child(
+                Button::new("uninstall-extension")
+                    .small()
+                    .ghost()
+                    .label("Uninstall")
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.confirm_uninstall(id.clone(), window, cx);
+                    })),
+            )
+    }
+}
+
+impl Focusable for ExtensionManager {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ExtensionManager {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let registry = self.registry.clone();
+        let registry = registry.read(cx);
+        let empty = registry.extensions().is_empty();
+        let details = self
+            .selected
+            .as_ref()
+            .and_then(|id| registry.extension(id))
+            .map(|ext| self.render_details(ext, cx));
+
+        v_flex()
+            .id("extension-manager")
+            .size_full()
+            .p_4()
+            .gap_3()
+            .on_action(cx.listener(Self::toggle_extension))
+            .on_action(cx.listener(Self::uninstall_extension))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!(
+                                "Extensions are loaded from {}",
+                                ExtensionRegistry::dir().display()
+                            )),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("reload-extensions")
+                                    .small()
+                                    .outline()
+                                    .label("Reload")
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.registry
+                                            .update(cx, |registry, cx| registry.reload(cx));
+                                    })),
+                            )
+                            .child(
+                                Button::new("check-extension-updates")
+                                    .small()
+                                    .outline()
+                                    .label("Check for Updates")
+                                    .loading(self.checking)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.check_for_updates(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+            .map(|this| {
+                if empty {
+                    this.child(
+                        div()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("No extensions installed."),
+                    )
+                } else {
+                    this.child(div().flex_1().child(self.table.clone()))
+                }
+            })
+            .children(details)
+    }
+}
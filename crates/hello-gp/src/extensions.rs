@@ -0,0 +1,610 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, anyhow};
+use gpui::{Action, App, AppContext as _, Context, Entity, Global, SharedString, Task};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{net::Net, safe_mode, settings::Settings, themes::get_data_dir};
+
+const MANIFEST_FILE: &str = "extension.json";
+
+/// Run a command contributed by an extension, e.g. from a menu or a status bar item.
+#[derive(Action, Clone, PartialEq, Eq, Deserialize)]
+#[action(namespace = extensions, no_json)]
+pub struct RunExtensionCommand {
+    pub extension: SharedString,
+    pub command: SharedString,
+}
+
+/// The `extension.json` at the root of each extension directory.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtensionManifest {
+    pub id: SharedString,
+    pub name: SharedString,
+    pub version: SharedString,
+    #[serde(default)]
+    pub description: SharedString,
+    #[serde(default)]
+    pub authors: Vec<SharedString>,
+    /// Which [`ExtensionLoader`] loads this extension, `declarative` by default.
+    #[serde(default = "default_kind")]
+    pub kind: SharedString,
+    /// Entry point relative to the extension dir, e.g. the library of a `dylib` extension.
+    #[serde(default)]
+    pub entry: Option<PathBuf>,
+    #[serde(default)]
+    pub contributes: Contributions,
+}
+
+fn default_kind() -> SharedString {
+    "declarative".into()
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Contributions {
+    pub commands: Vec<CommandContribution>,
+    pub status_bar: Vec<StatusBarContribution>,
+    pub settings: Vec<SettingContribution>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandContribution {
+    pub id: SharedString,
+    pub title: SharedString,
+    /// For declarative extensions: an app action to dispatch, e.g. `themes::SwitchThemeMode`.
+    #[serde(default)]
+    pub action: Option<SharedString>,
+    #[serde(default)]
+    pub action_data: Option<serde_json::Value>,
+    /// For declarative extensions: a URL to open.
+    #[serde(default)]
+    pub url: Option<SharedString>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatusBarContribution {
+    pub id: SharedString,
+    pub text: SharedString,
+    #[serde(default)]
+    pub tooltip: Option<SharedString>,
+    /// The command to run when clicked.
+    #[serde(default)]
+    pub command: Option<SharedString>,
+}
+
+/// A settings entry, the value is stored in `Settings::extensions[<extension id>][<key>]`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SettingContribution {
+    pub key: SharedString,
+    pub title: SharedString,
+    #[serde(default)]
+    pub description: SharedString,
+    /// Stored when the extension is enabled and the setting has no value yet.
+    #[serde(default)]
+    pub default: serde_json::Value,
+}
+
+/// The runtime side of a loaded extension.
+///
+/// Implemented by each loader, so the host doesn't care whether the extension
+/// is declarative or a dynamic library. Other kinds plug in with
+/// [`ExtensionRegistry::register_loader`].
+pub trait ExtensionRuntime {
+    fn activate(&mut self, _cx: &mut App) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn deactivate(&mut self, _cx: &mut App) {}
+
+    fn run_command(&mut self, command: &CommandContribution, cx: &mut App) -> anyhow::Result<()>;
+}
+
+/// Creates the runtime for an extension of a given `kind`.
+pub type ExtensionLoader =
+    fn(&ExtensionManifest, &Path) -> anyhow::Result<Box<dyn ExtensionRuntime>>;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ExtensionsSettings {
+    /// Ids of the installed extensions that are disabled.
+    pub disabled: BTreeSet<String>,
+    /// URL of a JSON feed with the latest version of each extension, see [`FeedEntry`].
+    pub feed_url: Option<String>,
+}
+
+/// An entry of the extensions update feed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedEntry {
+    pub id: SharedString,
+    pub version: SharedString,
+    #[serde(default)]
+    pub url: Option<SharedString>,
+}
+
+pub struct Extension {
+    pub manifest: ExtensionManifest,
+    pub path: PathBuf,
+    /// The newer version available in the update feed.
+    pub update: Option<FeedEntry>,
+    /// `None` while the extension is disabled.
+    runtime: Option<Box<dyn ExtensionRuntime>>,
+}
+
+impl Extension {
+    pub fn is_enabled(&self) -> bool {
+        self.runtime.is_some()
+    }
+
+    /// Where the extension may keep its own data, removed on uninstall.
+    pub fn data_dir(&self) -> PathBuf {
+        get_data_dir()
+            .join("extensions-data")
+            .join(self.manifest.id.as_ref())
+    }
+}
+
+/// Whether `id` is a plain name, `[A-Za-z0-9._-]+` and not `.` or `..`, safe to make a path of.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Discovers and loads extensions from `get_data_dir()/extensions`.
+pub struct ExtensionRegistry {
+    loaders: HashMap<SharedString, ExtensionLoader>,
+    extensions: Vec<Extension>,
+}
+
+struct GlobalExtensionRegistry(Entity<ExtensionRegistry>);
+
+impl Global for GlobalExtensionRegistry {}
+
+impl ExtensionRegistry {
+    fn new() -> Self {
+        let mut this = Self {
+            loaders: HashMap::new(),
+            extensions: vec![],
+        };
+        this.register_loader("declarative", load_declarative);
+        #[cfg(feature = "dylib-extensions")]
+        this.register_loader("dylib", dylib::load);
+        this
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalExtensionRegistry>().0.clone()
+    }
+
+    pub fn dir() -> PathBuf {
+        get_data_dir().join("extensions")
+    }
+
+    pub fn register_loader(&mut self, kind: impl Into<SharedString>, loader: ExtensionLoader) {
+        self.loaders.insert(kind.into(), loader);
+    }
+
+    pub fn extensions(&self) -> &[Extension] {
+        &self.extensions
+    }
+
+    pub fn extension(&self, id: &str) -> Option<&Extension> {
+        self.extensions
+            .iter()
+            .find(|ext| ext.manifest.id.as_ref() == id)
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = &Extension> {
+        self.extensions.iter().filter(|ext| ext.is_enabled())
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = (&ExtensionManifest, &CommandContribution)> {
+        self.enabled().flat_map(|ext| {
+            ext.manifest
+                .contributes
+                .commands
+                .iter()
+                .map(move |command| (&ext.manifest, command))
+        })
+    }
+
+    pub fn status_bar_items(
+        &self,
+    ) -> impl Iterator<Item = (&ExtensionManifest, &StatusBarContribution)> {
+        self.enabled().flat_map(|ext| {
+            ext.manifest
+                .contributes
+                .status_bar
+                .iter()
+                .map(move |item| (&ext.manifest, item))
+        })
+    }
+
+    /// The settings contributed by the enabled extensions.
+    pub fn settings(&self) -> impl Iterator<Item = (&ExtensionManifest, &SettingContribution)> {
+        self.enabled().flat_map(|ext| {
+            ext.manifest
+                .contributes
+                .settings
+                .iter()
+                .map(move |setting| (&ext.manifest, setting))
+        })
+    }
+
+    /// Store the `default` of each contributed setting that has no value yet, so the extension
+    /// and the settings window read it like any other value.
+    fn seed_setting_defaults(&self, cx: &mut App) {
+        let values = &Settings::global(cx).extensions;
+        let missing = self
+            .settings()
+            .filter(|(manifest, setting)| {
+                !setting.default.is_null()
+                    && !values
+                        .get(manifest.id.as_ref())
+                        .is_some_and(|values| values.contains_key(setting.key.as_ref()))
+            })
+            .map(|(manifest, setting)| {
+                (
+                    manifest.id.to_string(),
+                    setting.key.to_string(),
+                    setting.default.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return;
+        }
+
+        Settings::update(cx, |settings, _| {
+            for (id, key, value) in missing {
+                settings
+                    .extensions
+                    .entry(id)
+                    .or_default()
+                    .insert(key, value);
+            }
+        });
+    }
+
+    /// Scan the extensions dir and (re)load every extension found.
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        for mut ext in self.extensions.drain(..) {
+            if let Some(runtime) = ext.runtime.as_mut() {
+                runtime.deactivate(cx);
+            }
+        }
+
+        if safe_mode::enabled() {
+            tracing::info!("Safe mode, the extensions are not loaded");
+            return;
+        }
+
+        let dir = Self::dir();
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            tracing::debug!("No extensions dir at {}", dir.display());
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.join(MANIFEST_FILE).is_file() {
+                continue;
+            }
+
+            match self.load(&path, cx) {
+                Ok(ext) => {
+                    tracing::info!(
+                        "Loaded extension {} v{}",
+                        ext.manifest.id,
+                        ext.manifest.version
+                    );
+                    self.extensions.push(ext);
+                }
+                Err(err) => {
+                    tracing::error!("Failed to load extension {}: {:?}", path.display(), err)
+                }
+            }
+        }
+        self.extensions
+            .sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+        self.seed_setting_defaults(cx);
+        cx.notify();
+    }
+
+    fn load(&self, path: &Path, cx: &mut App) -> anyhow::Result<Extension> {
+        let json = std::fs::read_to_string(path.join(MANIFEST_FILE))?;
+        let manifest: ExtensionManifest =
+            serde_json::from_str(&json).context("invalid extension.json")?;
+        // The id names the data dir of the extension, removed on uninstall.
+        if !is_valid_id(&manifest.id) {
+            return Err(anyhow!("invalid extension id {:?}", manifest.id));
+        }
+        if self
+            .extensions
+            .iter()
+            .any(|ext| ext.manifest.id == manifest.id)
+        {
+            return Err(anyhow!("duplicate extension id {}", manifest.id));
+        }
+
+        let loader = self
+            .loaders
+            .get(&manifest.kind)
+            .ok_or_else(|| anyhow!("unsupported extension kind: {}", manifest.kind))?;
+        let disabled = Settings::global(cx)
+            .extension_manager
+            .disabled
+            .contains(manifest.id.as_ref());
+        let runtime = if disabled {
+            None
+        } else {
+            let mut runtime = loader(&manifest, path)?;
+            runtime.activate(cx)?;
+            Some(runtime)
+        };
+
+        Ok(Extension {
+            manifest,
+            path: path.to_path_buf(),
+            update: None,
+            runtime,
+        })
+    }
+
+    pub fn set_enabled(
+        &mut self,
+        id: &str,
+        enabled: bool,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let ix = self
+            .extensions
+            .iter()
+            .position(|ext| ext.manifest.id.as_ref() == id)
+            .ok_or_else(|| anyhow!("extension {} is not installed", id))?;
+
+        let ext = &self.extensions[ix];
+        if enabled && !ext.is_enabled() {
+            let loader = self
+                .loaders
+                .get(&ext.manifest.kind)
+                .ok_or_else(|| anyhow!("unsupported extension kind: {}", ext.manifest.kind))?;
+            let mut runtime = loader(&ext.manifest, &ext.path)?;
+            runtime.activate(cx)?;
+            self.extensions[ix].runtime = Some(runtime);
+            self.seed_setting_defaults(cx);
+        } else if !enabled && let Some(mut runtime) = self.extensions[ix].runtime.take() {
+            runtime.deactivate(cx);
+        }
+
+        let id = id.to_string();
+        Settings::update(cx, |settings, _| {
+            let disabled = &mut settings.extension_manager.disabled;
+            if enabled {
+                disabled.remove(&id);
+            } else {
+                disabled.insert(id);
+            }
+        });
+        cx.notify();
+        Ok(())
+    }
+
+    /// Remove the extension from disk, along with its data and settings.
+    pub fn uninstall(&mut self, id: &str, cx: &mut Context<Self>) -> anyhow::Result<()> {
+        let ix = self
+            .extensions
+            .iter()
+            .position(|ext| ext.manifest.id.as_ref() == id)
+            .ok_or_else(|| anyhow!("extension {} is not installed", id))?;
+
+        let mut ext = self.extensions.remove(ix);
+        if let Some(runtime) = ext.runtime.as_mut() {
+            runtime.deactivate(cx);
+        }
+        // Drop the runtime before removing files, a loaded library may keep them busy.
+        drop(ext.runtime.take());
+
+        std::fs::remove_dir_all(&ext.path)
+            .with_context(|| format!("failed to remove {}", ext.path.display()))?;
+        let data_dir = ext.data_dir();
+        if data_dir.exists() {
+            std::fs::remove_dir_all(&data_dir)?;
+        }
+
+        let id = id.to_string();
+        Settings::update(cx, |settings, _| {
+            settings.extensions.remove(&id);
+            settings.extension_manager.disabled.remove(&id);
+        });
+        tracing::info!("Uninstalled extension {}", id);
+        cx.notify();
+        Ok(())
+    }
+
+    /// Fetch the update feed and mark the extensions that have a newer version.
+    pub fn check_for_updates(&mut self, cx: &mut Context<Self>) -> Task<anyhow::Result<usize>> {
+        let Some(feed_url) = Settings::global(cx).extension_manager.feed_url.clone() else {
+            return Task::ready(Err(anyhow!("no extension feed configured")));
+        };
+
+        let request = Net::get(feed_url, cx);
+        cx.spawn(async move |this, cx| {
+            let body = request.await?;
+            let feed: Vec<FeedEntry> =
+                serde_json::from_slice(&body).context("invalid extension feed")?;
+
+            this.update(cx, |this, cx| {
+                let mut count = 0;
+                for ext in this.extensions.iter_mut() {
+                    ext.update = feed
+                        .iter()
+                        .find(|entry| {
+                            entry.id == ext.manifest.id
+                                && is_newer_version(&entry.version, &ext.manifest.version)
+                        })
+                        .cloned();
+                    count += ext.update.is_some() as usize;
+                }
+                cx.notify();
+                count
+            })
+        })
+    }
+
+    pub fn run_command(
+        &mut self,
+        extension: &str,
+        command: &str,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<()> {
+        let ext = self
+            .extensions
+            .iter_mut()
+            .find(|ext| ext.manifest.id.as_ref() == extension)
+            .ok_or_else(|| anyhow!("extension {} is not loaded", extension))?;
+        let command = ext
+            .manifest
+            .contributes
+            .commands
+            .iter()
+            .find(|c| c.id.as_ref() == command)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown command {}::{}", extension, command))?;
+
+        ext.runtime
+            .as_mut()
+            .ok_or_else(|| anyhow!("extension {} is disabled", extension))?
+            .run_command(&command, cx)
+    }
+}
+
+/// Compare dotted versions numerically, e.g. `1.10.0` is newer than `1.9.2`.
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    let parse = |version: &str| {
+        let version = version.trim_start_matches('v');
+        let release = version.split(['-', '+']).next().unwrap_or(version);
+        release
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    parse(latest) > parse(current)
+}
+
+/// Extensions that only contribute actions, URLs and settings via their manifest.
+struct DeclarativeRuntime;
+
+impl ExtensionRuntime for DeclarativeRuntime {
+    fn run_command(&mut self, command: &CommandContribution, cx: &mut App) -> anyhow::Result<()> {
+        if let Some(url) = &command.url {
+            cx.open_url(url);
+        }
+        if let Some(action) = &command.action {
+            let action = cx.build_action(action, command.action_data.clone())?;
+            cx.defer(move |cx| cx.dispatch_action(action.as_ref()));
+        }
+        Ok(())
+    }
+}
+
+fn load_declarative(_: &ExtensionManifest, _: &Path) -> anyhow::Result<Box<dyn ExtensionRuntime>> {
+    Ok(Box::new(DeclarativeRuntime))
+}
+
+/// Native extensions, loaded from a dynamic library exporting a C ABI:
+///
+/// ```c
+/// int hello_gp_extension_run_command(const char *command);
+/// ```
+#[cfg(feature = "dylib-extensions")]
+mod dylib {
+    use std::{ffi::CString, os::raw::c_char, path::Path};
+
+    use anyhow::{Context as _, anyhow};
+    use gpui::App;
+
+    use super::{CommandContribution, ExtensionManifest, ExtensionRuntime};
+
+    type RunCommandFn = unsafe extern "C" fn(*const c_char) -> i32;
+
+    struct DylibRuntime {
+        library: libloading::Library,
+    }
+
+    impl ExtensionRuntime for DylibRuntime {
+        fn run_command(
+            &mut self,
+            command: &CommandContribution,
+            _: &mut App,
+        ) -> anyhow::Result<()> {
+            let command = CString::new(command.id.as_ref())?;
+            // SAFETY: the symbol signature is part of the extension ABI documented above.
+            let code = unsafe {
+                let run: libloading::Symbol<RunCommandFn> =
+                    self.library.get(b"hello_gp_extension_run_command")?;
+                run(command.as_ptr())
+            };
+            if code != 0 {
+                return Err(anyhow!("command exited with {}", code));
+            }
+            Ok(())
+        }
+    }
+
+    pub(super) fn load(
+        manifest: &ExtensionManifest,
+        path: &Path,
+    ) -> anyhow::Result<Box<dyn ExtensionRuntime>> {
+        let entry = manifest
+            .entry
+            .as_ref()
+            .context("dylib extensions require an `entry`")?;
+        // SAFETY: loading a library runs its initializers, extensions are trusted by the user.
+        let library = unsafe { libloading::Library::new(path.join(entry))? };
+        Ok(Box::new(DylibRuntime { library }))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let registry = cx.new(|cx| {
+        let mut registry = ExtensionRegistry::new();
+        registry.reload(cx);
+        registry
+    });
+    cx.set_global(GlobalExtensionRegistry(registry));
+
+    cx.on_action(|action: &RunExtensionCommand, cx| {
+        let result = ExtensionRegistry::global(cx).update(cx, |registry, cx| {
+            registry.run_command(&action.extension, &action.command, cx)
+        });
+        if let Err(err) = result {
+            tracing::error!("Failed to run extension command: {:?}", err);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extensions::is_newer_version;
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("1.10.0", "1.9.2"));
+        assert!(is_newer_version("2.0.0", "1.99.99"));
+        assert!(is_newer_version("v0.3.1", "0.3.0"));
+        assert!(!is_newer_version("0.3.0", "0.3.0"));
+        assert!(!is_newer_version("1.9.2", "1.10.0"));
+        // Pre-release and build suffixes are ignored.
+        assert!(!is_newer_version("0.3.0-beta.1", "0.3.0"));
+        assert!(is_newer_version("0.4.0+build.7", "0.3.9"));
+    }
+}
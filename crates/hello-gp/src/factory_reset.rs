@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use gpui::{App, ParentElement as _, Styled as _, actions, div};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::{
+    config,
+    settings::{SETTINGS_NAME, Settings},
+    themes::{get_config_dir, get_data_dir},
+};
+
+actions!(settings, [ResetAllSettings]);
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// Left by the reset action, the reset itself happens on the next start before anything is
+/// loaded, so nothing written while quitting ends up in the fresh config dir.
+const PENDING_FILE: &str = "reset-pending";
+
+fn backups_dir() -> PathBuf {
+    get_data_dir().join("backups")
+}
+
+fn pending_path() -> PathBuf {
+    get_data_dir().join(PENDING_FILE)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move the config dir into `get_data_dir()/backups/<timestamp>` and start over with the
+/// default settings. Returns the backup, `None` when there was nothing to reset.
+pub fn reset() -> anyhow::Result<Option<PathBuf>> {
+    let config_dir = get_config_dir();
+    if !config_dir.exists() {
+        return Ok(None);
+    }
+
+    let backup = backups_dir().join(Local::now().format(TIMESTAMP_FORMAT).to_string());
+    std::fs::create_dir_all(backups_dir())?;
+    // Renaming fails when the data dir is on another file system.
+    if std::fs::rename(&config_dir, &backup).is_err() {
+        copy_dir(&config_dir, &backup)?;
+        std::fs::remove_dir_all(&config_dir)?;
+    }
+
+    std::fs::create_dir_all(&config_dir)?;
+    config::save(&config_dir, SETTINGS_NAME, &Settings::default())?;
+    tracing::info!(
+        "Reset all settings, the old ones are in {}",
+        backup.display()
+    );
+    Ok(Some(backup))
+}
+
+/// Reset when `requested` (`--reset-state`) or the reset action restarted the app for it.
+/// Must run before the app is initialized.
+pub fn apply(requested: bool) {
+    let pending = pending_path();
+    if !requested && !pending.exists() {
+        return;
+    }
+    _ = std::fs::remove_file(&pending);
+    match reset() {
+        Ok(Some(backup)) => eprintln!(
+            "Reset all settings, the old ones were moved to {}",
+            backup.display()
+        ),
+        Ok(None) => {}
+        Err(err) => eprintln!("Failed to reset the settings: {}", err),
+    }
+}
+
+/// Reset on the next start, and restart right away.
+fn reset_and_restart(cx: &mut App) {
+    let pending = pending_path();
+    if let Some(dir) = pending.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(err) = std::fs::write(&pending, "") {
+        tracing::error!("Failed to schedule the reset: {}", err);
+        return;
+    }
+    cx.restart(None);
+}
+
+fn open_confirm_dialog(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        window.open_modal(cx, |modal, _, cx| {
+            modal.title("Reset All Settings?").child(
+                v_flex()
+                    .gap_3()
+                    .child(
+                        "The settings, the session, the layouts and the scripts are moved to a \
+                         backup, and the app restarts with the defaults.",
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("Backups are kept in {}", backups_dir().display())),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .justify_end()
+                            .child(
+                                Button::new("cancel-reset")
+                                    .small()
+                                    .ghost()
+                                    .label("Cancel")
+                                    .on_click(|_, window, cx| window.close_modal(cx)),
+                            )
+                            .child(
+                                Button::new("confirm-reset")
+                                    .small()
+                                    .danger()
+                                    .label("Reset and Restart")
+                                    .on_click(|_, _, cx| reset_and_restart(cx)),
+                            ),
+                    ),
+            )
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ResetAllSettings, cx| open_confirm_dialog(cx));
+}
@@ -0,0 +1,456 @@
+//! An in-app file browser, for where the native dialogs are missing or awkward, e.g. in a
+//! sandbox without a portal.
+
+use std::path::{Path, PathBuf};
+
+use futures::channel::oneshot;
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, FocusHandle, Focusable, IntoElement,
+    ParentElement as _, Render, SharedString, Styled as _, Subscription, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::{InputEvent, InputState},
+    v_flex,
+};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    skeletons::{Loadable, SkeletonRows},
+    text_field::TextField,
+    virtual_rows::{RowsDelegate, VirtualRows, VirtualRowsEvent},
+};
+
+/// What the picker picks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickerMode {
+    OpenFile,
+    /// A path to save to, starting with a suggested file name.
+    SaveFile(SharedString),
+    Folder,
+}
+
+#[derive(Debug, Clone)]
+pub struct PickerOptions {
+    pub title: SharedString,
+    pub mode: PickerMode,
+    /// The extensions of the files shown, without the dot, all files when empty.
+    pub extensions: Vec<SharedString>,
+    /// Where to start, the home dir when unset.
+    pub directory: Option<PathBuf>,
+}
+
+impl PickerOptions {
+    pub fn new(title: impl Into<SharedString>, mode: PickerMode) -> Self {
+        Self {
+            title: title.into(),
+            mode,
+            extensions: vec![],
+            directory: None,
+        }
+    }
+
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions = extensions
+            .iter()
+            .map(|ext| ext.to_string().into())
+            .collect();
+        self
+    }
+
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DirEntry {
+    name: SharedString,
+    path: PathBuf,
+    is_dir: bool,
+}
+
+impl DirEntry {
+    fn is_hidden(&self) -> bool {
+        self.name.starts_with('.')
+    }
+}
+
+/// The entries of `dir`, folders first, by name.
+fn read_dir(dir: &Path) -> anyhow::Result<Vec<DirEntry>> {
+    let mut entries = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            DirEntry {
+                name: entry.file_name().to_string_lossy().to_string().into(),
+                is_dir: path.is_dir(),
+                path,
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
+
+struct EntryRows {
+    entries: Vec<DirEntry>,
+}
+
+impl RowsDelegate for EntryRows {
+    fn rows_count(&self, _: &App) -> usize {
+        self.entries.len()
+    }
+
+    fn render_row(&self, ix: usize, selected: bool, _: &mut Window, cx: &mut App) -> AnyElement {
+        let entry = &self.entries[ix];
+        h_flex()
+            .size_full()
+            .gap_2()
+            .px_2()
+            .rounded(cx.theme().radius)
+            .text_sm()
+            .cursor_role(CursorRole::Pointer, cx)
+            .when(selected, |this| this.bg(cx.theme().accent))
+            .child(
+                Icon::new(if entry.is_dir {
+                    IconName::Folder
+                } else {
+                    IconName::File
+                })
+                .small()
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(entry.name.clone())
+            .into_any_element()
+    }
+}
+
+/// Browses the file system for a file or folder, by breadcrumbs or double clicks, with the
+/// entries filtered by name.
+pub struct FilePicker {
+    focus_handle: FocusHandle,
+    options: PickerOptions,
+    dir: PathBuf,
+    entries: Loadable<Vec<DirEntry>>,
+    show_hidden: bool,
+    filter: Entity<InputState>,
+    /// The file name, when saving.
+    file_name: Option<Entity<InputState>>,
+    rows: Entity<VirtualRows<EntryRows>>,
+    done: Option<oneshot::Sender<Option<PathBuf>>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FilePicker {
+    fn new(
+        options: PickerOptions,
+        done: oneshot::Sender<Option<PathBuf>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let filter = cx.new(|cx| InputState::new(window, cx).placeholder("Filter..."));
+        let file_name = match &options.mode {
+            PickerMode::SaveFile(name) => {
+                let name = name.clone();
+                Some(cx.new(|cx| InputState::new(window, cx).default_value(name)))
+            }
+            _ => None,
+        };
+        let rows = cx.new(|cx| VirtualRows::new(EntryRows { entries: vec![] }, window, cx));
+        let _subscriptions = vec![
+            cx.subscribe(&filter, |this, _, event, cx| {
+                if let InputEvent::Change = event {
+                    this.update_rows(cx);
+                }
+            }),
+            cx.subscribe_in(&rows, window, |this, rows, event, window, cx| {
+                let Some(entry) = (match event {
+                    VirtualRowsEvent::Select(ix) | VirtualRowsEvent::Confirm(ix) => {
+                        rows.read(cx).delegate().entries.get(*ix).cloned()
+                    }
+                }) else {
+                    return;
+                };
+                match event {
+                    VirtualRowsEvent::Select(_) => {
+                        if !entry.is_dir
+                            && let Some(file_name) = &this.file_name
+                        {
+                            file_name.update(cx, |input, cx| {
+                                input.set_value(entry.name.clone(), window, cx)
+                            });
+                        }
+                    }
+                    VirtualRowsEvent::Confirm(_) if entry.is_dir => {
+                        this.navigate(entry.path, window, cx)
+                    }
+                    VirtualRowsEvent::Confirm(_) => this.choose(Some(entry.path), window, cx),
+                }
+            }),
+        ];
+
+        let dir = options
+            .directory
+            .clone()
+            .filter(|dir| dir.is_dir())
+            .or_else(|| directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("/"));
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            options,
+            dir: dir.clone(),
+            entries: Loadable::Loading,
+            show_hidden: false,
+            filter,
+            file_name,
+            rows,
+            done: Some(done),
+            _subscriptions,
+        };
+        this.navigate(dir, window, cx);
+        this
+    }
+
+    fn navigate(&mut self, dir: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        self.dir = dir.clone();
+        self.entries = Loadable::Loading;
+        self.filter
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        cx.spawn(async move |this, cx| {
+            let entries = cx
+                .background_spawn({
+                    let dir = dir.clone();
+                    async move { read_dir(&dir) }
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                // Skip a listing that finished after moving on.
+                if this.dir == dir {
+                    this.entries = entries.into();
+                    this.update_rows(cx);
+                }
+            })
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn shown(&self, entry: &DirEntry) -> bool {
+        (self.show_hidden || !entry.is_hidden())
+            && match self.options.mode {
+                PickerMode::Folder => entry.is_dir,
+                _ => {
+                    entry.is_dir
+                        || self.options.extensions.is_empty()
+                        || entry.path.extension().is_some_and(|ext| {
+                            self.options
+                                .extensions
+                                .iter()
+                                .any(|allowed| ext.eq_ignore_ascii_case(allowed.as_ref()))
+                        })
+                }
+            }
+    }
+
+    fn update_rows(&mut self, cx: &mut Context<Self>) {
+        let query = self.filter.read(cx).value().trim().to_lowercase();
+        let entries = self
+            .entries
+            .loaded()
+            .into_iter()
+            .flatten()
+            .filter(|entry| self.shown(entry))
+            .filter(|entry| entry.name.to_lowercase().contains(&query))
+            .cloned()
+            .collect();
+        self.rows.update(cx, |rows, cx| {
+            *rows.delegate_mut() = EntryRows { entries };
+            rows.set_selected_index(None, cx);
+            rows.refresh(cx);
+        });
+        cx.notify();
+    }
+
+    fn selected_entry(&self, cx: &App) -> Option<DirEntry> {
+        let rows = self.rows.read(cx);
+        rows.delegate().entries.get(rows.selected_index()?).cloned()
+    }
+
+    /// The path the primary button picks right now.
+    fn target(&self, cx: &App) -> Option<PathBuf> {
+        match &self.options.mode {
+            PickerMode::OpenFile => self
+                .selected_entry(cx)
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| entry.path),
+            PickerMode::Folder => Some(
+                self.selected_entry(cx)
+                    .map_or_else(|| self.dir.clone(), |entry| entry.path),
+            ),
+            PickerMode::SaveFile(_) => {
+                let name = self.file_name.as_ref()?.read(cx).value().trim().to_string();
+                if name.is_empty() {
+                    return None;
+                }
+                let mut path = self.dir.join(name);
+                if path.extension().is_none()
+                    && let Some(ext) = self.options.extensions.first()
+                {
+                    path.set_extension(ext.as_ref());
+                }
+                Some(path)
+            }
+        }
+    }
+
+    fn choose(&mut self, path: Option<PathBuf>, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(done) = self.done.take() {
+            _ = done.send(path);
+        }
+        window.close_modal(cx);
+    }
+
+    fn render_breadcrumbs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ancestors = self
+            .dir
+            .ancestors()
+            .map(Path::to_path_buf)
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .gap_0p5()
+            .flex_wrap()
+            .children(ancestors.into_iter().rev().enumerate().map(|(ix, path)| {
+                let label = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                h_flex()
+                    .when(ix > 0, |this| {
+                        this.child(
+                            Icon::new(IconName::ChevronRight)
+                                .xsmall()
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .child(
+                        Button::new(("breadcrumb", ix))
+                            .xsmall()
+                            .ghost()
+                            .label(label)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.navigate(path.clone(), window, cx)
+                            })),
+                    )
+            }))
+    }
+}
+
+impl Focusable for FilePicker {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for FilePicker {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let target = self.target(cx);
+        let confirm_label = match self.options.mode {
+            PickerMode::OpenFile => "Open",
+            PickerMode::SaveFile(_) => "Save",
+            PickerMode::Folder => "Choose Folder",
+        };
+
+        v_flex()
+            .gap_2()
+            .child(self.render_breadcrumbs(cx))
+            .child(
+                h_flex()
+                    .gap_3()
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(TextField::new("file-picker-filter", &self.filter).cleanable()),
+                    )
+                    .child(
+                        Checkbox::new("show-hidden-files")
+                            .label("Hidden Files")
+                            .checked(self.show_hidden)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.show_hidden = *checked;
+                                this.update_rows(cx);
+                            })),
+                    ),
+            )
+            .child(div().h(px(320.)).map(|this| {
+                match &self.entries {
+                    Loadable::Loading => this
+                        .py_1()
+                        .child(SkeletonRows::new("file-picker-loading", 10)),
+                    Loadable::Failed(err) => this.child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().danger)
+                            .child(err.clone()),
+                    ),
+                    Loadable::Loaded(_) => this.child(self.rows.clone()),
+                }
+            }))
+            .when_some(self.file_name.clone(), |this, file_name| {
+                this.child(TextField::new("file-picker-name", &file_name))
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_end()
+                    .child(
+                        Button::new("file-picker-cancel")
+                            .small()
+                            .outline()
+                            .label("Cancel")
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.choose(None, window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("file-picker-confirm")
+                            .small()
+                            .primary()
+                            .label(confirm_label)
+                            .disabled(target.is_none())
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.choose(target.clone(), window, cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+/// Show the picker over `window`, the path picked or `None` when it was dismissed.
+pub fn pick(
+    options: PickerOptions,
+    window: &mut Window,
+    cx: &mut App,
+) -> oneshot::Receiver<Option<PathBuf>> {
+    let (tx, rx) = oneshot::channel();
+    let title = options.title.clone();
+    let picker = cx.new(|cx| FilePicker::new(options, tx, window, cx));
+    window.open_modal(cx, move |modal, _, _| {
+        modal
+            .title(title.clone())
+            .width(px(640.))
+            .child(picker.clone())
+    });
+    rx
+}
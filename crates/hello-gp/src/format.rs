@@ -0,0 +1,169 @@
+//! Dates and times the way the user's locale writes them, and how long ago something was.
+//!
+//! The locale is the `format.locale` setting, or else the one of `LC_ALL`, `LC_TIME` or
+//! `LANG`, e.g. `de_DE.UTF-8`, and `en_US` without one, like when the app isn't started from a
+//! shell on macOS and Windows.
+
+use std::sync::{LazyLock, RwLock};
+
+use chrono::{DateTime, Local, Locale, TimeDelta};
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FormatSettings {
+    /// The locale dates and times are written in, e.g. `de_DE`, rather than the system's.
+    pub locale: Option<String>,
+}
+
+/// Locales offered when the system doesn't list its own.
+const COMMON_LOCALES: &[&str] = &[
+    "ar_EG", "cs_CZ", "da_DK", "de_AT", "de_CH", "de_DE", "el_GR", "en_AU", "en_CA", "en_GB",
+    "en_IE", "en_IN", "en_NZ", "en_US", "es_ES", "es_MX", "fi_FI", "fr_CA", "fr_CH", "fr_FR",
+    "he_IL", "hi_IN", "hu_HU", "id_ID", "it_IT", "ja_JP", "ko_KR", "nb_NO", "nl_BE", "nl_NL",
+    "pl_PL", "pt_BR", "pt_PT", "ro_RO", "ru_RU", "sk_SK", "sv_SE", "th_TH", "tr_TR", "uk_UA",
+    "vi_VN", "zh_CN", "zh_HK", "zh_TW",
+];
+
+/// The locale picked in the settings, over the system's.
+static OVERRIDE: RwLock<Option<Locale>> = RwLock::new(None);
+
+static SYSTEM_LOCALE: LazyLock<Locale> = LazyLock::new(|| {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| parse_locale(&value))
+        .unwrap_or(Locale::en_US)
+});
+
+/// The locale of a POSIX locale name, e.g. `de_DE.UTF-8` or `sr_RS@latin`.
+fn parse_locale(name: &str) -> Option<Locale> {
+    let name = name.split(['.', '@']).next()?;
+    match name {
+        "C" | "POSIX" => Some(Locale::POSIX),
+        name => Locale::try_from(name).ok(),
+    }
+}
+
+/// The locale dates and times are written in.
+pub fn locale() -> Locale {
+    OVERRIDE
+        .read()
+        .ok()
+        .and_then(|locale| *locale)
+        .unwrap_or(*SYSTEM_LOCALE)
+}
+
+/// The locales the system has, as `language_TERRITORY`, or common ones if it doesn't say.
+///
+/// Runs `locale -a`, so it's for a background thread.
+pub fn available_locales() -> Vec<String> {
+    let listed = std::process::Command::new("locale")
+        .arg("-a")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    let mut locales = listed
+        .lines()
+        .filter_map(|line| line.split(['.', '@']).next())
+        .filter(|name| name.contains('_') && Locale::try_from(*name).is_ok())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if locales.is_empty() {
+        locales = COMMON_LOCALES.iter().map(|name| name.to_string()).collect();
+    }
+    locales.sort();
+    locales.dedup();
+    locales
+}
+
+/// The date, e.g. `03/14/2025` in `en_US` and `14.03.2025` in `de_DE`.
+pub fn date(time: &DateTime<Local>) -> String {
+    time.format_localized("%x", locale()).to_string()
+}
+
+/// The time of day, e.g. `02:30:00 PM` in `en_US` and `14:30:00` in `de_DE`.
+pub fn time(time: &DateTime<Local>) -> String {
+    time.format_localized("%X", locale()).to_string()
+}
+
+/// The date and the time of day.
+pub fn date_time(time: &DateTime<Local>) -> String {
+    format!("{} {}", date(time), self::time(time))
+}
+
+/// A time in seconds since the Unix epoch, in the local time zone.
+pub fn from_timestamp(seconds: u64) -> Option<DateTime<Local>> {
+    DateTime::from_timestamp(seconds as i64, 0).map(|time| time.with_timezone(&Local))
+}
+
+/// How long ago `time` was, or how long until it is, e.g. `5 minutes ago`, `yesterday` or
+/// `in 2 hours`, and the date after a week.
+pub fn relative(time: &DateTime<Local>) -> String {
+    let now = Local::now();
+    let delta = now - *time;
+    let past = delta >= TimeDelta::zero();
+    let abs = delta.abs();
+    let ago = |count: i64, unit: &str| {
+        let unit = if count == 1 {
+            unit.to_string()
+        } else {
+            format!("{}s", unit)
+        };
+        if past {
+            format!("{} {} ago", count, unit)
+        } else {
+            format!("in {} {}", count, unit)
+        }
+    };
+
+    if abs < TimeDelta::seconds(45) {
+        return "just now".to_string();
+    }
+    if abs < TimeDelta::minutes(45) {
+        return ago(abs.num_minutes().max(1), "minute");
+    }
+    let days = (now.date_naive() - time.date_naive()).num_days();
+    if abs < TimeDelta::hours(22) || days == 0 {
+        return ago(((abs.num_minutes() as f64) / 60.).round() as i64, "hour");
+    }
+    match days {
+        1 => "yesterday".to_string(),
+        -1 => "tomorrow".to_string(),
+        2..7 => ago(days, "day"),
+        -6..=-2 => ago(-days, "day"),
+        _ => date(time),
+    }
+}
+
+fn sync_locale(cx: &mut App) {
+    let locale = Settings::global(cx)
+        .format
+        .locale
+        .as_deref()
+        .and_then(|name| {
+            let locale = parse_locale(name);
+            if locale.is_none() {
+                tracing::warn!("Unknown locale {}, using the system's", name);
+            }
+            locale
+        });
+    if let Ok(mut current) = OVERRIDE.write()
+        && *current != locale
+    {
+        *current = locale;
+        cx.refresh_windows();
+    }
+}
+
+pub fn init(cx: &mut App) {
+    sync_locale(cx);
+    cx.observe_global::<Settings>(sync_locale).detach();
+}
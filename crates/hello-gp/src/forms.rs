@@ -0,0 +1,215 @@
+//! Text fields checked as they're typed in, showing what's wrong below the field rather than
+//! fixing the value up behind the user's back.
+//!
+//! ```ignore
+//! let speed = cx.new(|cx| {
+//!     let input = cx.new(|cx| InputState::new(window, cx).default_value("1.0"));
+//!     FormField::new(input, cx).validate(forms::number_in(0.25, 5.))
+//! });
+//! cx.subscribe(&speed, |_, _, FormFieldEvent::Change(value), _| println!("{}", value));
+//! ```
+
+use std::rc::Rc;
+
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, IntoElement,
+    ParentElement as _, Render, SharedString, Styled as _, Subscription, Window, div,
+    prelude::FluentBuilder as _,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    input::{InputEvent, InputState, TextInput},
+    v_flex,
+};
+
+/// Checks a value, returning what's wrong with it.
+pub type Validator = Rc<dyn Fn(&str) -> Result<(), SharedString>>;
+
+/// A `#rrggbb` or `#rrggbbaa` color.
+pub fn hex_color(value: &str) -> Result<(), SharedString> {
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("Use #rrggbb or #rrggbbaa".into());
+    }
+    Hsla::parse_hex(value)
+        .map(|_| ())
+        .map_err(|_| "Not a color".into())
+}
+
+/// A number from `min` to `max`.
+pub fn number_in(min: f64, max: f64) -> impl Fn(&str) -> Result<(), SharedString> {
+    move |value| match value.parse::<f64>() {
+        Ok(number) if (min..=max).contains(&number) => Ok(()),
+        Ok(_) => Err(format!("Must be from {} to {}", min, max).into()),
+        Err(_) => Err("Not a number".into()),
+    }
+}
+
+/// A number without a fraction.
+pub fn whole_number(value: &str) -> Result<(), SharedString> {
+    match value.parse::<f64>() {
+        Ok(number) if number.fract() == 0. => Ok(()),
+        Ok(_) => Err("Must be a whole number".into()),
+        Err(_) => Err("Not a number".into()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FormFieldEvent {
+    /// The user typed a value that passes every validator, trimmed.
+    Change(SharedString),
+}
+
+/// A text input with validators and the first error they found.
+///
+/// The field is dirty while its value differs from the one it was set to or last saved at,
+/// which the owner marks with [`FormField::mark_saved`].
+pub struct FormField {
+    input: Entity<InputState>,
+    validators: Vec<Validator>,
+    saved: SharedString,
+    error: Option<SharedString>,
+    /// Set while the value is set from code, so it isn't emitted as typed.
+    syncing: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FormField {
+    /// A field for `input`, its current value saved.
+    pub fn new(input: Entity<InputState>, cx: &mut Context<Self>) -> Self {
+        let saved = input.read(cx).value().trim().to_string().into();
+        let _subscriptions = vec![cx.subscribe(&input, |this, _, event, cx| {
+            if matches!(event, InputEvent::Change) && !this.syncing {
+                this.check(cx);
+            }
+        })];
+
+        Self {
+            input,
+            validators: vec![],
+            saved,
+            error: None,
+            syncing: false,
+            _subscriptions,
+        }
+    }
+
+    pub fn validate(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), SharedString> + 'static,
+    ) -> Self {
+        self.validators.push(Rc::new(validator));
+        self
+    }
+
+    /// The value, trimmed.
+    pub fn value(&self, cx: &App) -> SharedString {
+        self.input.read(cx).value().trim().to_string().into()
+    }
+
+    pub fn error(&self) -> Option<&SharedString> {
+        self.error.as_ref()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn is_dirty(&self, cx: &App) -> bool {
+        self.value(cx) != self.saved
+    }
+
+    /// Set the value from code, clearing the error and the dirty state.
+    pub fn set_value(
+        &mut self,
+        value: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let value = value.into();
+        self.syncing = true;
+        self.input
+            .update(cx, |input, cx| input.set_value(value.clone(), window, cx));
+        self.syncing = false;
+        self.saved = value;
+        self.error = None;
+        cx.notify();
+    }
+
+    /// The current value was saved, it's no longer dirty.
+    pub fn mark_saved(&mut self, cx: &mut Context<Self>) {
+        self.saved = self.value(cx);
+    }
+
+    fn check(&mut self, cx: &mut Context<Self>) {
+        let value = self.value(cx);
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(&value).err());
+        if self.error.is_none() {
+            cx.emit(FormFieldEvent::Change(value));
+        }
+        cx.notify();
+    }
+}
+
+impl EventEmitter<FormFieldEvent> for FormField {}
+
+impl Focusable for FormField {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.input.read(cx).focus_handle(cx)
+    }
+}
+
+impl Render for FormField {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(
+                TextInput::new(&self.input)
+                    .small()
+                    .when(self.error.is_some(), |this| {
+                        this.border_color(cx.theme().danger)
+                    }),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_xs().text_color(cx.theme().danger).child(error))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::forms::{hex_color, number_in, whole_number};
+
+    #[test]
+    fn test_hex_color() {
+        assert!(hex_color("#1a2b3c").is_ok());
+        assert!(hex_color("#1A2B3C80").is_ok());
+        assert!(hex_color("#1a2b3").is_err());
+        assert!(hex_color("#1a2b3c8").is_err());
+        assert!(hex_color("#1a2b3g").is_err());
+        assert!(hex_color("").is_err());
+    }
+
+    #[test]
+    fn test_number_in() {
+        let speed = number_in(0.25, 5.);
+        assert!(speed("0.25").is_ok());
+        assert!(speed("1").is_ok());
+        assert!(speed("5").is_ok());
+        assert_eq!(speed("5.5").unwrap_err(), "Must be from 0.25 to 5");
+        assert_eq!(speed("0").unwrap_err(), "Must be from 0.25 to 5");
+        assert_eq!(speed("fast").unwrap_err(), "Not a number");
+    }
+
+    #[test]
+    fn test_whole_number() {
+        assert!(whole_number("12").is_ok());
+        assert!(whole_number("-3").is_ok());
+        assert!(whole_number("12.0").is_ok());
+        assert_eq!(whole_number("12.5").unwrap_err(), "Must be a whole number");
+        assert_eq!(whole_number("twelve").unwrap_err(), "Not a number");
+    }
+}
@@ -0,0 +1,69 @@
+//! Entry points for the fuzz targets in `fuzz/`, built with the `fuzz` feature only.
+//!
+//! Each takes whatever bytes the fuzzer comes up with, like a config file mangled on disk,
+//! and must never panic.
+
+use gpui::{DummyKeyboardMapper, NoAction};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::{self, ConfigFormat},
+    scopes::KeymapSettings,
+    settings::Settings,
+    themes::{State, parse_theme_file},
+};
+
+/// Parse `data` as the config `T` in every format, the way it's loaded on startup.
+fn parse_config<T: DeserializeOwned>(data: &[u8]) -> Vec<T> {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return vec![];
+    };
+    ConfigFormat::ALL
+        .into_iter()
+        .filter_map(|format| config::parse_file::<T>(text, format).ok().flatten())
+        .collect()
+}
+
+pub fn state(data: &[u8]) {
+    for state in parse_config::<State>(data) {
+        // What was read must be written back, e.g. when the theme is switched.
+        for format in ConfigFormat::ALL {
+            _ = format.serialize(&state);
+        }
+    }
+}
+
+pub fn settings(data: &[u8]) {
+    for settings in parse_config::<Settings>(data) {
+        for format in ConfigFormat::ALL {
+            _ = format.serialize(&settings);
+        }
+        // The settings window reads and writes single values by key.
+        for key in ["appearance.accent_color", "telemetry.enabled", "extensions"] {
+            if let Some(value) = settings.value(key) {
+                _ = settings.with_value(key, value);
+            }
+        }
+    }
+}
+
+pub fn keymap(data: &[u8]) {
+    for keymap in parse_config::<KeymapSettings>(data) {
+        // The keys and the context are what the user wrote, the action doesn't matter.
+        for binding in &keymap.bindings {
+            _ = binding.load(Box::new(NoAction), &DummyKeyboardMapper);
+        }
+    }
+}
+
+pub fn theme(data: &[u8]) {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(file) = parse_theme_file(json) {
+        _ = file.defaults.get(std::env::consts::OS);
+        for theme in &file.themes {
+            _ = serde_json::to_string(theme);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use gpui::{
+    App, DispatchPhase, Global, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, Pixels, PlatformInput, Point, ScrollDelta, ScrollWheelEvent, Styled as _, Timer,
+    TouchPhase, Window, canvas, px,
+};
+use gpui_component::Theme;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input_settings::InputSettings,
+    split::{GoBack, GoForward},
+};
+
+/// How long a press is held still before it's a long press.
+const LONG_PRESS: Duration = Duration::from_millis(500);
+/// How far a press moves before it's a drag rather than a long press.
+const LONG_PRESS_SLOP: f32 = 8.;
+/// How far two fingers move sideways before the swipe navigates.
+const SWIPE_DISTANCE: f32 = 120.;
+/// How far a pinch goes per step of the font size.
+const PINCH_STEP: f32 = 40.;
+/// The font sizes a pinch zooms between.
+const FONT_SIZES: (f32, f32) = (10., 24.);
+
+/// The touch gestures of the shell, each of them can be turned off.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GestureSettings {
+    /// Pinch to zoom the UI, also ctrl and the scroll wheel as touchpads report a pinch on
+    /// Windows and Linux.
+    pub pinch_to_zoom: bool,
+    /// Swipe sideways with two fingers to go back and forward in a split pane.
+    pub swipe_to_navigate: bool,
+    /// Press and hold to open the context menu, like a right click.
+    ///
+    /// Off by default, a press of the mouse and of a finger can't be told apart.
+    pub long_press: bool,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            pinch_to_zoom: true,
+            swipe_to_navigate: true,
+            long_press: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Gestures {
+    /// How far the pinch went since the last step of the font size.
+    pinch: f32,
+    /// How far the two fingers moved since the swipe started, none once it navigated.
+    swipe: Option<(f32, f32)>,
+    /// Bumped on every press, so only the timer of the last one opens a menu.
+    presses: usize,
+    /// Where the left button went down, while it could still be a long press.
+    press: Option<Point<Pixels>>,
+    /// A long press opened a menu, so its release isn't a click.
+    pressed_long: bool,
+}
+
+impl Global for Gestures {}
+
+fn settings(cx: &App) -> &GestureSettings {
+    &InputSettings::global(cx).gestures
+}
+
+fn scroll_wheel(event: &ScrollWheelEvent, window: &mut Window, cx: &mut App) {
+    let delta = event.delta.pixel_delta(window.line_height());
+
+    if event.modifiers.control && settings(cx).pinch_to_zoom {
+        let this = cx.global_mut::<Gestures>();
+        this.pinch += delta.y.as_f32();
+        let steps = (this.pinch / PINCH_STEP).trunc();
+        if steps != 0. {
+            this.pinch -= steps * PINCH_STEP;
+            let theme = Theme::global_mut(cx);
+            let font_size = (theme.font_size.as_f32() + steps).clamp(FONT_SIZES.0, FONT_SIZES.1);
+            theme.font_size = px(font_size);
+            cx.refresh_windows();
+        }
+        // A pinch zooms instead of scrolling what's under it.
+        cx.stop_propagation();
+        return;
+    }
+
+    // Only the two fingers of a touchpad, the notches of a wheel are no swipe.
+    if !matches!(event.delta, ScrollDelta::Pixels(_)) || !settings(cx).swipe_to_navigate {
+        return;
+    }
+    let this = cx.global_mut::<Gestures>();
+    match event.touch_phase {
+        TouchPhase::Started => this.swipe = Some((0., 0.)),
+        TouchPhase::Moved => {
+            if let Some((x, y)) = this.swipe.as_mut() {
+                *x += delta.x.as_f32();
+                *y += delta.y.as_f32();
+            }
+        }
+        TouchPhase::Ended => this.swipe = None,
+    }
+    // Mostly sideways, so scrolling down a page doesn't navigate.
+    let Some((x, y)) = this.swipe else {
+        return;
+    };
+    if x.abs() < SWIPE_DISTANCE || x.abs() < y.abs() * 2. {
+        return;
+    }
+    this.swipe = None;
+    if x > 0. {
+        window.dispatch_action(Box::new(GoBack), cx);
+    } else {
+        window.dispatch_action(Box::new(GoForward), cx);
+    }
+}
+
+fn mouse_down(event: &MouseDownEvent, window: &mut Window, cx: &mut App) {
+    let this = cx.global_mut::<Gestures>();
+    this.presses += 1;
+    this.pressed_long = false;
+    this.press = None;
+    if event.button != MouseButton::Left || !settings(cx).long_press {
+        return;
+    }
+
+    let this = cx.global_mut::<Gestures>();
+    this.press = Some(event.position);
+    let (presses, position, modifiers) = (this.presses, event.position, event.modifiers);
+    let handle = window.window_handle();
+    cx.spawn(async move |cx| {
+        Timer::after(LONG_PRESS).await;
+        _ = cx.update_window(handle, |_, window, cx| {
+            let this = cx.global_mut::<Gestures>();
+            if this.presses != presses || this.press.take().is_none() {
+                return;
+            }
+            this.pressed_long = true;
+            for event in [
+                PlatformInput::MouseDown(MouseDownEvent {
+                    button: MouseButton::Right,
+                    position,
+                    modifiers,
+                    click_count: 1,
+                    first_mouse: false,
+                }),
+                PlatformInput::MouseUp(MouseUpEvent {
+                    button: MouseButton::Right,
+                    position,
+                    modifiers,
+                    click_count: 1,
+                }),
+            ] {
+                window.dispatch_event(event, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+fn mouse_move(event: &MouseMoveEvent, cx: &mut App) {
+    let this = cx.global_mut::<Gestures>();
+    if let Some(press) = this.press {
+        let distance = event.position - press;
+        if distance.x.abs().max(distance.y.abs()) > px(LONG_PRESS_SLOP) {
+            this.press = None;
+        }
+    }
+}
+
+fn mouse_up(event: &MouseUpEvent, cx: &mut App) {
+    if event.button != MouseButton::Left {
+        return;
+    }
+    let this = cx.global_mut::<Gestures>();
+    this.press = None;
+    if std::mem::take(&mut this.pressed_long) {
+        cx.stop_propagation();
+    }
+}
+
+/// Listens for the gestures in the window it's rendered in, before the elements under the
+/// pointer see the events.
+pub(crate) fn listener() -> impl IntoElement {
+    canvas(
+        |_, _, _| {},
+        |_, _, window, _| {
+            window.on_mouse_event(|event: &ScrollWheelEvent, phase, window, cx| {
+                if phase == DispatchPhase::Capture {
+                    scroll_wheel(event, window, cx)
+                }
+            });
+            window.on_mouse_event(|event: &MouseDownEvent, phase, window, cx| {
+                if phase == DispatchPhase::Capture {
+                    mouse_down(event, window, cx)
+                }
+            });
+            window.on_mouse_event(|event: &MouseMoveEvent, phase, _, cx| {
+                if phase == DispatchPhase::Capture {
+                    mouse_move(event, cx)
+                }
+            });
+            window.on_mouse_event(|event: &MouseUpEvent, phase, _, cx| {
+                if phase == DispatchPhase::Capture {
+                    mouse_up(event, cx)
+                }
+            });
+        },
+    )
+    .absolute()
+    .size_0()
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Gestures::default());
+}
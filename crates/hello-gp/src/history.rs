@@ -0,0 +1,357 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gpui::{App, Global, SharedString, actions};
+use gpui_component::ThemeMode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    settings::Settings,
+    themes::{self, get_data_dir},
+};
+
+actions!(settings, [UndoSettingsChange]);
+
+const HISTORY_FILE: &str = "settings-history.jsonl";
+/// How many changes are kept, older ones roll off the file.
+const MAX_CHANGES: usize = 1000;
+/// How many batches of changes can be undone.
+const MAX_UNDO: usize = 100;
+/// Written instead of the values of secrets, e.g. the lock passphrase.
+const REDACTED: &str = "<redacted>";
+
+/// Where a change came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSource {
+    /// The settings window, a dialog or a menu.
+    #[default]
+    Ui,
+    /// An edit of the config files on disk.
+    File,
+    /// Another instance of the app, or a link opened by the OS.
+    Ipc,
+}
+
+impl ChangeSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ui => "App",
+            Self::File => "File",
+            Self::Ipc => "IPC",
+        }
+    }
+}
+
+/// A setting (or the theme) that changed, as recorded in the audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The path of the setting, e.g. `telemetry.enabled`, or `theme.name` and `theme.mode`.
+    pub key: String,
+    pub old: Value,
+    pub new: Value,
+    pub source: ChangeSource,
+}
+
+impl Change {
+    /// Whether the old value is known, secrets are only logged as changed.
+    pub fn is_revertible(&self) -> bool {
+        self.old != Value::String(REDACTED.into())
+    }
+}
+
+/// The audit log of the settings and the theme, in `get_data_dir()/settings-history.jsonl`.
+///
+/// Every change is recorded with where it came from. The changes made in the app can be
+/// undone, batch by batch, with [`UndoSettingsChange`].
+#[derive(Default)]
+pub struct History {
+    changes: Vec<Change>,
+    /// Where the changes being made come from, see [`with_source`].
+    source: ChangeSource,
+    /// The changes made in the app, as they were made together.
+    undo_stack: Vec<Vec<Change>>,
+    undoing: bool,
+}
+
+impl Global for History {}
+
+impl History {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// The recorded changes, oldest first.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Where the changes being made right now come from.
+    pub fn source(cx: &App) -> ChangeSource {
+        cx.try_global::<Self>()
+            .map(|history| history.source)
+            .unwrap_or_default()
+    }
+
+    fn path() -> PathBuf {
+        get_data_dir().join(HISTORY_FILE)
+    }
+
+    fn load() -> Vec<Change> {
+        std::fs::read_to_string(Self::path())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    fn save(&self) {
+        let lines = self
+            .changes
+            .iter()
+            .filter_map(|change| serde_json::to_string(change).ok())
+            .collect::<Vec<_>>();
+        let path = Self::path();
+        let result = std::fs::create_dir_all(get_data_dir())
+            .and_then(|_| std::fs::write(&path, lines.join("\n")));
+        if let Err(err) = result {
+            tracing::warn!("Failed to write {}: {}", path.display(), err);
+        }
+    }
+
+    /// Record the changed values, `(key, old, new)`, as made together.
+    pub fn record(
+        changes: impl IntoIterator<Item = (String, Value, Value)>,
+        source: ChangeSource,
+        cx: &mut App,
+    ) {
+        let Some(history) = cx.try_global_mut::<Self>() else {
+            return;
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let batch = changes
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(key, old, new)| {
+                let (old, new) = if is_secret(&key) {
+                    (REDACTED.into(), REDACTED.into())
+                } else {
+                    (old, new)
+                };
+                Change {
+                    timestamp,
+                    key,
+                    old,
+                    new,
+                    source,
+                }
+            })
+            .collect::<Vec<_>>();
+        if batch.is_empty() {
+            return;
+        }
+
+        for change in &batch {
+            tracing::debug!(
+                "{} changed from {} to {} ({:?})",
+                change.key,
+                change.old,
+                change.new,
+                change.source
+            );
+        }
+        history.changes.extend(batch.iter().cloned());
+        if history.changes.len() > MAX_CHANGES {
+            let excess = history.changes.len() - MAX_CHANGES;
+            history.changes.drain(..excess);
+        }
+        if source == ChangeSource::Ui && !history.undoing {
+            history.undo_stack.push(batch);
+            if history.undo_stack.len() > MAX_UNDO {
+                history.undo_stack.remove(0);
+            }
+        }
+        history.save();
+    }
+
+    /// Undo the last changes made in the app.
+    pub fn undo(cx: &mut App) {
+        let Some(batch) = cx.global_mut::<Self>().undo_stack.pop() else {
+            return;
+        };
+        cx.global_mut::<Self>().undoing = true;
+        revert(&batch, cx);
+        cx.global_mut::<Self>().undoing = false;
+    }
+}
+
+/// Settings whose values don't belong in a log.
+fn is_secret(key: &str) -> bool {
+    ["passphrase", "password", "secret", "token"]
+        .iter()
+        .any(|word| key.contains(word))
+}
+
+/// The values of the JSON, keyed by their dotted path. Arrays count as one value.
+fn flatten(value: &Value, prefix: &str, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(value, &key, out);
+            }
+        }
+        value => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// Record the differences between two versions of the settings.
+pub fn record_settings(old: &Settings, new: &Settings, source: ChangeSource, cx: &mut App) {
+    let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return;
+    };
+    let (mut old_values, mut new_values) = (BTreeMap::new(), BTreeMap::new());
+    flatten(&old, "", &mut old_values);
+    flatten(&new, "", &mut new_values);
+
+    let keys = old_values
+        .keys()
+        .chain(new_values.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let changes = keys.into_iter().map(|key| {
+        let old = old_values.remove(&key).unwrap_or(Value::Null);
+        let new = new_values.remove(&key).unwrap_or(Value::Null);
+        (key, old, new)
+    });
+    History::record(changes, source, cx);
+}
+
+/// Record a switch of the theme or of its mode.
+pub(crate) fn record_theme(
+    old: (&SharedString, ThemeMode),
+    new: (&SharedString, ThemeMode),
+    source: ChangeSource,
+    cx: &mut App,
+) {
+    History::record(
+        [
+            (
+                "theme.name".into(),
+                old.0.as_ref().into(),
+                new.0.as_ref().into(),
+            ),
+            (
+                "theme.mode".into(),
+                old.1.name().into(),
+                new.1.name().into(),
+            ),
+        ],
+        source,
+        cx,
+    );
+}
+
+/// Put the changed values back to what they were before `changes`.
+pub fn revert(changes: &[Change], cx: &mut App) {
+    let mut settings = Settings::global(cx).clone();
+    for change in changes.iter().rev().filter(|change| change.is_revertible()) {
+        match change.key.as_str() {
+            "theme.name" => {
+                if let Some(name) = change.old.as_str() {
+                    themes::switch_theme(name.to_string().into(), cx);
+                }
+            }
+            "theme.mode" => {
+                let mode = match change.old.as_str() {
+                    Some("dark") => ThemeMode::Dark,
+                    _ => ThemeMode::Light,
+                };
+                themes::switch_mode(mode, cx);
+            }
+            key => match settings.with_value(key, change.old.clone()) {
+                Ok(reverted) => settings = reverted,
+                Err(err) => tracing::error!("Failed to revert {}: {}", key, err),
+            },
+        }
+    }
+    Settings::update(cx, |current, _| *current = settings);
+    cx.refresh_windows();
+}
+
+/// Run `f` with the changes it makes recorded as coming from `source`.
+pub fn with_source<R>(source: ChangeSource, cx: &mut App, f: impl FnOnce(&mut App) -> R) -> R {
+    let previous = cx
+        .try_global_mut::<History>()
+        .map(|history| std::mem::replace(&mut history.source, source));
+    let result = f(cx);
+    if let Some(previous) = previous {
+        cx.global_mut::<History>().source = previous;
+    }
+    result
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(History {
+        changes: History::load(),
+        ..Default::default()
+    });
+    cx.on_action(|_: &UndoSettingsChange, cx| History::undo(cx));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use crate::history::flatten;
+
+    #[test]
+    fn test_flatten() {
+        let mut out = BTreeMap::new();
+        flatten(
+            &json!({
+                "theme": "Default Light",
+                "font": { "size": 14, "family": { "ui": "Inter" } },
+                "recent": ["a", "b"],
+                "proxy": null,
+            }),
+            "",
+            &mut out,
+        );
+        assert_eq!(
+            out,
+            BTreeMap::from([
+                ("font.family.ui".to_string(), json!("Inter")),
+                ("font.size".to_string(), json!(14)),
+                ("proxy".to_string(), json!(null)),
+                ("recent".to_string(), json!(["a", "b"])),
+                ("theme".to_string(), json!("Default Light")),
+            ])
+        );
+
+        let mut out = BTreeMap::new();
+        flatten(&json!({ "size": 14 }), "font", &mut out);
+        assert_eq!(out, BTreeMap::from([("font.size".to_string(), json!(14))]));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use gpui::{Action, App, Global, SharedString};
+use gpui_component::{Icon, IconName, IconNamed as _};
+use serde::Deserialize;
+
+use crate::{settings::Settings, themes::get_data_dir};
+
+/// Asset paths with this prefix are loaded from [`Icons::dir`] instead of the embedded assets.
+pub(crate) const ICON_PACK_ASSET_PREFIX: &str = "icon-packs/";
+
+/// Select the icon pack by id, `None` for the built-in icons.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = appearance, no_json)]
+pub struct SelectIconPack(pub Option<SharedString>);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IconPackManifest {
+    name: Option<String>,
+}
+
+/// A directory of `<icon-name>.svg` files under `get_data_dir()/icons/<id>`,
+/// with an optional `pack.json` to give it a display name.
+#[derive(Debug, Clone)]
+pub struct IconPack {
+    pub id: SharedString,
+    pub name: SharedString,
+    icons: BTreeSet<String>,
+}
+
+/// Resolves logical icon names to the active icon pack, falling back to the built-in [`IconName`]s.
+#[derive(Default)]
+pub struct Icons {
+    packs: Vec<IconPack>,
+}
+
+impl Global for Icons {}
+
+impl Icons {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn dir() -> PathBuf {
+        get_data_dir().join("icons")
+    }
+
+    pub fn packs(&self) -> &[IconPack] {
+        &self.packs
+    }
+
+    fn load_packs() -> Vec<IconPack> {
+        let Ok(entries) = std::fs::read_dir(Self::dir()) else {
+            return vec![];
+        };
+
+        let mut packs = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let id = entry.file_name().to_str()?.to_string();
+                let manifest = std::fs::read_to_string(path.join("pack.json"))
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<IconPackManifest>(&json).ok())
+                    .unwrap_or_default();
+                let icons = std::fs::read_dir(&path)
+                    .ok()?
+                    .flatten()
+                    .filter_map(|entry| {
+                        let path = entry.path();
+                        (path.extension()? == "svg")
+                            .then(|| path.file_stem()?.to_str().map(str::to_string))?
+                    })
+                    .collect();
+
+                Some(IconPack {
+                    name: manifest.name.unwrap_or_else(|| id.clone()).into(),
+                    id: id.into(),
+                    icons,
+                })
+            })
+            .collect::<Vec<_>>();
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        packs
+    }
+
+    pub fn reload(cx: &mut App) {
+        let packs = Self::load_packs();
+        tracing::info!("Loaded {} icon packs", packs.len());
+        cx.set_global(Self { packs });
+        cx.refresh_windows();
+    }
+
+    fn active_pack<'a>(&'a self, cx: &App) -> Option<&'a IconPack> {
+        let id = Settings::global(cx).appearance.icon_pack.as_deref()?;
+        self.packs.iter().find(|pack| pack.id == id)
+    }
+
+    /// The icon for `name` from the active pack, e.g. `"bell"` for `bell.svg`,
+    /// or `fallback` when the pack doesn't have it.
+    pub fn named(name: &str, fallback: IconName, cx: &App) -> Icon {
+        let Some(pack) = Self::global(cx).active_pack(cx) else {
+            return Icon::new(fallback);
+        };
+        if !pack.icons.contains(name) {
+            return Icon::new(fallback);
+        }
+
+        Icon::new(fallback).path(format!(
+            "{}{}/{}.svg",
+            ICON_PACK_ASSET_PREFIX, pack.id, name
+        ))
+    }
+
+    /// The asset path of the icon for `name`, from the active pack when it has it, or else
+    /// from the built-in icons.
+    pub fn asset_path(name: &str, cx: &App) -> SharedString {
+        match Self::global(cx).active_pack(cx) {
+            Some(pack) if pack.icons.contains(name) => {
+                format!("{}{}/{}.svg", ICON_PACK_ASSET_PREFIX, pack.id, name).into()
+            }
+            _ => format!("icons/{}.svg", name).into(),
+        }
+    }
+
+    /// The icon for a built-in [`IconName`], using the same file name as the built-in set.
+    pub fn get(icon: IconName, cx: &App) -> Icon {
+        let path = icon.clone().path();
+        let name = path
+            .rsplit('/')
+            .next()
+            .and_then(|file| file.strip_suffix(".svg"))
+            .unwrap_or_default()
+            .to_string();
+        Self::named(&name, icon, cx)
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Icons {
+        packs: Icons::load_packs(),
+    });
+
+    cx.on_action(|select: &SelectIconPack, cx| {
+        let pack = select.0.as_ref().map(|id| id.to_string());
+        Settings::update(cx, |settings, _| settings.appearance.icon_pack = pack);
+        cx.refresh_windows();
+    });
+}
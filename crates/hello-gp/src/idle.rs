@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Task, Timer};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct IdleSettings {
+    pub enabled: bool,
+    /// Minutes without any input before the app is considered idle.
+    pub threshold_minutes: u64,
+    /// Dim the window content while idle.
+    pub dim: bool,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_minutes: 5,
+            dim: true,
+        }
+    }
+}
+
+impl IdleSettings {
+    fn threshold(&self) -> Duration {
+        Duration::from_secs(self.threshold_minutes.max(1) * 60)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleEvent {
+    Idle,
+    Active,
+}
+
+/// Tracks user input and emits [`IdleEvent`]s when the app goes idle or becomes active again.
+///
+/// Modules that do expensive background work should subscribe to the global monitor
+/// (or check [`IdleMonitor::is_idle`]) and pause while idle, like the telemetry uploads, the
+/// memory sampler and the visibility probe. Work that can wait goes to
+/// [`IdleMonitor::when_active`].
+pub struct IdleMonitor {
+    last_activity: Instant,
+    idle: bool,
+    /// Work deferred until the user is back, keyed so repeated requests run once.
+    pending: HashMap<&'static str, Box<dyn FnOnce(&mut App)>>,
+    _task: Task<()>,
+}
+
+struct GlobalIdleMonitor(Entity<IdleMonitor>);
+
+impl Global for GlobalIdleMonitor {}
+
+impl EventEmitter<IdleEvent> for IdleMonitor {}
+
+impl IdleMonitor {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let _task = cx.spawn(async move |this, cx| {
+            loop {
+                Timer::after(CHECK_INTERVAL).await;
+                if this.update(cx, |this, cx| this.check(cx)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            last_activity: Instant::now(),
+            idle: false,
+            pending: HashMap::new(),
+            _task,
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalIdleMonitor>().0.clone()
+    }
+
+    pub fn is_idle(cx: &App) -> bool {
+        Self::global(cx).read(cx).idle
+    }
+
+    /// Run `f` now, or once the user is back if the app is idle.
+    pub fn when_active(key: &'static str, f: impl FnOnce(&mut App) + 'static, cx: &mut App) {
+        let monitor = Self::global(cx);
+        if !monitor.read(cx).idle {
+            f(cx);
+            return;
+        }
+
+        monitor.update(cx, |this, _| {
+            this.pending.insert(key, Box::new(f));
+        });
+    }
+
+    /// Record user activity, leaving the idle state if needed.
+    pub fn touch(cx: &mut App) {
+        Self::global(cx).update(cx, |this, cx| {
+            this.last_activity = Instant::now();
+            if this.idle {
+                this.idle = false;
+                let pending = std::mem::take(&mut this.pending);
+                cx.emit(IdleEvent::Active);
+                cx.notify();
+                cx.defer(move |cx| {
+                    for (_, f) in pending {
+                        f(cx);
+                    }
+                });
+            }
+        });
+    }
+
+    fn check(&mut self, cx: &mut Context<Self>) {
+        let settings = Settings::global(cx).idle.clone();
+        if !settings.enabled || self.idle {
+            return;
+        }
+
+        if self.last_activity.elapsed() >= settings.threshold() {
+            tracing::info!(
+                "No input for {} minutes, going idle",
+                settings.threshold_minutes
+            );
+            self.idle = true;
+            cx.emit(IdleEvent::Idle);
+            cx.notify();
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let monitor = cx.new(IdleMonitor::new);
+    cx.set_global(GlobalIdleMonitor(monitor));
+
+    cx.observe_keystrokes(|_, _, cx| IdleMonitor::touch(cx))
+        .detach();
+}
@@ -1,12 +1,22 @@
-use crate::section;
+use crate::{
+    dialogs::{self, DialogOptions},
+    image_viewer::{ImageViewer, ZoomMode},
+    section,
+};
 use gpui::{
     App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement as _,
-    Render, Styled, Window, img,
+    Render, Styled, Window, div, img, px,
+};
+use gpui_component::{
+    Sizable as _,
+    button::{Button, ButtonVariants as _},
+    dock::PanelControl,
+    h_flex, v_flex,
 };
-use gpui_component::{dock::PanelControl, v_flex};
 
 pub struct ImageStory {
     focus_handle: gpui::FocusHandle,
+    viewer: Entity<ImageViewer>,
 }
 
 impl super::Story for ImageStory {
@@ -28,12 +38,26 @@ impl super::Story for ImageStory {
 }
 
 impl ImageStory {
-    pub fn new(_: &mut Window, cx: &mut App) -> Self {
+    pub fn new(window: &mut Window, cx: &mut App) -> Self {
         Self {
             focus_handle: cx.focus_handle(),
+            viewer: cx.new(|cx| ImageViewer::new(window, cx)),
         }
     }
 
+    fn open_image(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let options = DialogOptions::new("images", "Open Image")
+            .extensions(&["png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff"]);
+        let picked = dialogs::open_file(options, window, cx);
+        let viewer = self.viewer.clone();
+        cx.spawn(async move |_, cx| match picked.await {
+            Ok(Some(path)) => _ = viewer.update(cx, |viewer, cx| viewer.load_path(path, cx)),
+            Ok(None) => {}
+            Err(err) => tracing::error!("Failed to open an image: {}", err),
+        })
+        .detach();
+    }
+
     pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| Self::new(window, cx))
     }
@@ -46,7 +70,16 @@ impl Focusable for ImageStory {
 }
 
 impl Render for ImageStory {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mode_button = |id: &'static str, label: &'static str, mode: ZoomMode| {
+            let viewer = self.viewer.clone();
+            Button::new(id)
+                .small()
+                .outline()
+                .label(label)
+                .on_click(move |_, _, cx| viewer.update(cx, |viewer, cx| viewer.set_mode(mode, cx)))
+        };
+
         // The svg file are from Assets
         // See: crates/story/src/assets.rs#L21
         v_flex()
@@ -62,5 +95,33 @@ impl Render for ImageStory {
                     img("https://pub.lbkrs.com/files/202503/vEnnmgUM6bo362ya/sdk.svg").h_24(),
                 ),
             )
+            .child(
+                section("Image Viewer").child(
+                    v_flex()
+                        .w_full()
+                        .gap_2()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Button::new("open-image")
+                                        .small()
+                                        .primary()
+                                        .label("Open Image...")
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.open_image(window, cx)
+                                        })),
+                                )
+                                .child(mode_button("fit-image", "Fit", ZoomMode::Fit))
+                                .child(mode_button("fill-image", "Fill", ZoomMode::Fill))
+                                .child(mode_button(
+                                    "actual-size-image",
+                                    "Actual Size",
+                                    ZoomMode::ActualSize,
+                                )),
+                        )
+                        .child(div().w_full().h(px(360.)).child(self.viewer.clone())),
+                ),
+            )
     }
 }
@@ -0,0 +1,324 @@
+//! A surface to look at an image: fit to the view, fill it, or at its actual size, zoomed with
+//! the wheel and panned by dragging.
+
+use std::{path::Path, sync::Arc};
+
+use gpui::{
+    App, AppContext as _, Bounds, Context, FocusHandle, Focusable, InteractiveElement as _,
+    IntoElement, KeyBinding, MouseButton, MouseDownEvent, MouseMoveEvent, ParentElement as _,
+    Pixels, Point, Render, RenderImage, ScrollWheelEvent, Size, Styled as _, Task, Window, actions,
+    canvas, div, img, point, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{ActiveTheme as _, v_flex};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    images::{self, ImageRequest, Images},
+    skeletons::{Loadable, Shimmer},
+};
+
+actions!(
+    image_viewer,
+    [ZoomIn, ZoomOut, FitToView, FillView, ActualSize]
+);
+
+const CONTEXT: &str = "ImageViewer";
+/// How much a step of the zoom scales the image.
+const ZOOM_STEP: f32 = 1.25;
+/// The zoom is kept between these, relative to the actual size.
+const ZOOM_RANGE: (f32, f32) = (0.05, 32.);
+
+/// How the image is scaled, until it's zoomed by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ZoomMode {
+    /// All of the image in view.
+    #[default]
+    Fit,
+    /// The view covered by the image, cropping it.
+    Fill,
+    /// One image pixel per logical pixel.
+    ActualSize,
+}
+
+/// Shows an image, decoded off the main thread and turned upright by its EXIF orientation.
+pub struct ImageViewer {
+    focus_handle: FocusHandle,
+    /// `None` until an image is loaded.
+    image: Option<Loadable<Arc<RenderImage>>>,
+    /// Follows the view as it's resized, `None` once zoomed by hand.
+    mode: Option<ZoomMode>,
+    zoom: f32,
+    /// Of the center of the image from the center of the view.
+    offset: Point<Pixels>,
+    viewport: Bounds<Pixels>,
+    /// Where the pointer was while dragging.
+    drag: Option<Point<Pixels>>,
+    _load_task: Option<Task<()>>,
+}
+
+impl ImageViewer {
+    pub fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            image: None,
+            mode: Some(ZoomMode::Fit),
+            zoom: 1.,
+            offset: Point::default(),
+            viewport: Bounds::default(),
+            drag: None,
+            _load_task: None,
+        }
+    }
+
+    /// Load the image file at `path`, or an `http(s)` URL, through the [`Images`] cache.
+    pub fn load_path(&mut self, path: impl AsRef<Path>, cx: &mut Context<Self>) {
+        let source = path.as_ref().to_string_lossy().to_string();
+        let image = Images::load(ImageRequest::new(source), cx);
+        self.set_loading(image, cx);
+    }
+
+    /// Load an image from its encoded bytes, e.g. downloaded.
+    pub fn load(&mut self, bytes: Task<anyhow::Result<Vec<u8>>>, cx: &mut Context<Self>) {
+        let image = cx.spawn(async move |_, cx| {
+            let bytes = bytes.await?;
+            cx.background_spawn(async move { images::decode(&bytes, None, 0.) })
+                .await
+        });
+        self.set_loading(image, cx);
+    }
+
+    fn set_loading(
+        &mut self,
+        image: Task<anyhow::Result<Arc<RenderImage>>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.image = Some(Loadable::Loading);
+        cx.notify();
+        self._load_task = Some(cx.spawn(async move |this, cx| {
+            let image = image.await;
+            _ = this.update(cx, |this, cx| {
+                if let Err(err) = &image {
+                    tracing::error!("Failed to load the image: {}", err);
+                }
+                this.set_loaded(image.into(), cx);
+            });
+        }));
+    }
+
+    /// Show an image that's already decoded.
+    pub fn set_image(&mut self, image: Arc<RenderImage>, cx: &mut Context<Self>) {
+        self._load_task = None;
+        self.set_loaded(Loadable::Loaded(image), cx);
+    }
+
+    fn set_loaded(&mut self, image: Loadable<Arc<RenderImage>>, cx: &mut Context<Self>) {
+        self.image = Some(image);
+        self.set_mode(self.mode.unwrap_or_default(), cx);
+    }
+
+    pub fn set_mode(&mut self, mode: ZoomMode, cx: &mut Context<Self>) {
+        self.mode = Some(mode);
+        self.offset = Point::default();
+        self.zoom = self.zoom_for(mode);
+        cx.notify();
+    }
+
+    /// The size of the image at its actual size, zero while there's none.
+    fn image_size(&self) -> Size<Pixels> {
+        match &self.image {
+            Some(Loadable::Loaded(image)) => {
+                let pixels = image.size(0);
+                size(px(pixels.width.0 as f32), px(pixels.height.0 as f32))
+            }
+            _ => Size::default(),
+        }
+    }
+
+    fn zoom_for(&self, mode: ZoomMode) -> f32 {
+        let (image, view) = (self.image_size(), self.viewport.size);
+        if image.width <= px(0.) || image.height <= px(0.) || view.width <= px(0.) {
+            return 1.;
+        }
+        let scale_x = view.width.as_f32() / image.width.as_f32();
+        let scale_y = view.height.as_f32() / image.height.as_f32();
+        match mode {
+            ZoomMode::Fit => scale_x.min(scale_y),
+            ZoomMode::Fill => scale_x.max(scale_y),
+            ZoomMode::ActualSize => 1.,
+        }
+    }
+
+    /// Zoom by `factor`, keeping the point of the image under `anchor` (in window
+    /// coordinates) in place, the center of the view when `None`.
+    fn zoom_by(&mut self, factor: f32, anchor: Option<Point<Pixels>>, cx: &mut Context<Self>) {
+        let zoom = (self.zoom * factor).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+        let factor = zoom / self.zoom;
+        let anchor = anchor.unwrap_or(self.viewport.center()) - self.viewport.center();
+        self.offset = anchor - (anchor - self.offset) * factor;
+        self.zoom = zoom;
+        self.mode = None;
+        cx.notify();
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_by(ZOOM_STEP, None, cx);
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom_by(1. / ZOOM_STEP, None, cx);
+    }
+
+    fn fit_to_view(&mut self, _: &FitToView, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_mode(ZoomMode::Fit, cx);
+    }
+
+    fn fill_view(&mut self, _: &FillView, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_mode(ZoomMode::Fill, cx);
+    }
+
+    fn actual_size(&mut self, _: &ActualSize, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_mode(ZoomMode::ActualSize, cx);
+    }
+
+    fn scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let delta = event.delta.pixel_delta(window.line_height()).y.as_f32();
+        if delta != 0. {
+            // A notch of a wheel is a step, a touchpad scrolls in smaller ones.
+            let factor = ZOOM_STEP.powf((delta / 40.).clamp(-1., 1.));
+            self.zoom_by(factor, Some(event.position), cx);
+        }
+    }
+
+    fn mouse_down(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        window.focus(&self.focus_handle);
+        self.drag = Some(event.position);
+        cx.notify();
+    }
+
+    fn mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(last) = self.drag else {
+            return;
+        };
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.drag = None;
+            return;
+        }
+        self.offset = self.offset + (event.position - last);
+        self.drag = Some(event.position);
+        cx.notify();
+    }
+
+    fn set_viewport(&mut self, viewport: Bounds<Pixels>, cx: &mut Context<Self>) {
+        if viewport.size != self.viewport.size {
+            self.viewport = viewport;
+            if let Some(mode) = self.mode {
+                self.zoom = self.zoom_for(mode);
+            }
+            cx.notify();
+        } else {
+            self.viewport = viewport;
+        }
+    }
+}
+
+impl Focusable for ImageViewer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ImageViewer {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entity = cx.entity();
+        let image_size = self.image_size();
+        let image_size = size(image_size.width * self.zoom, image_size.height * self.zoom);
+        let origin = point(
+            (self.viewport.size.width - image_size.width) / 2. + self.offset.x,
+            (self.viewport.size.height - image_size.height) / 2. + self.offset.y,
+        );
+
+        div()
+            .id("image-viewer")
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::fit_to_view))
+            .on_action(cx.listener(Self::fill_view))
+            .on_action(cx.listener(Self::actual_size))
+            .on_scroll_wheel(cx.listener(Self::scroll_wheel))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::mouse_down))
+            .on_mouse_move(cx.listener(Self::mouse_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| {
+                    this.drag = None;
+                    cx.notify();
+                }),
+            )
+            .relative()
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().secondary)
+            .cursor_role(
+                if self.drag.is_some() {
+                    CursorRole::Grabbing
+                } else {
+                    CursorRole::Grab
+                },
+                cx,
+            )
+            .child(
+                canvas(
+                    move |bounds, _, cx| {
+                        entity.update(cx, |this, cx| this.set_viewport(bounds, cx))
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .map(|this| match &self.image {
+                None => this,
+                Some(Loadable::Loading) => this.child(
+                    v_flex()
+                        .size_full()
+                        .p_4()
+                        .child(Shimmer::new("image-viewer-loading").size_full()),
+                ),
+                Some(Loadable::Failed(err)) => this.child(
+                    v_flex()
+                        .size_full()
+                        .items_center()
+                        .justify_center()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(err.clone()),
+                ),
+                Some(Loadable::Loaded(image)) => this.child(
+                    img(image.clone())
+                        .absolute()
+                        .left(origin.x)
+                        .top(origin.y)
+                        .w(image_size.width)
+                        .h(image_size.height),
+                ),
+            })
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("=", ZoomIn, Some(CONTEXT)),
+        KeyBinding::new("+", ZoomIn, Some(CONTEXT)),
+        KeyBinding::new("-", ZoomOut, Some(CONTEXT)),
+        KeyBinding::new("0", FitToView, Some(CONTEXT)),
+        KeyBinding::new("9", FillView, Some(CONTEXT)),
+        KeyBinding::new("1", ActualSize, Some(CONTEXT)),
+    ]);
+}
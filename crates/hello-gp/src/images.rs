@@ -0,0 +1,353 @@
+//! Images loaded from files or URLs off the main thread, decoded at the size they're shown at,
+//! and kept in a cache of a fixed budget, the least recently used ones dropped first.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{
+    App, DefiniteLength, Global, IntoElement, Length, ObjectFit, ParentElement as _, Pixels,
+    Refineable as _, RenderImage, RenderOnce, SharedString, Size, StyleRefinement, Styled,
+    StyledImage as _, Task, Window, div, img, size,
+};
+use image::{DynamicImage, Frame, ImageDecoder as _, ImageReader, imageops::FilterType};
+
+use crate::{
+    idle::IdleMonitor,
+    net::Net,
+    skeletons::{Shimmer, SkeletonColors},
+};
+
+/// How many bytes of decoded images the cache keeps.
+const CACHE_BUDGET: usize = 128 * 1024 * 1024;
+
+/// An image to load: a path, or an `http(s)` URL (cached by [`Net`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageRequest {
+    pub source: SharedString,
+    /// Downscaled to fit, in physical pixels, the full size when `None`.
+    pub max_size: Option<Size<u32>>,
+    /// Gaussian blur sigma, in image pixels.
+    pub blur: f32,
+}
+
+impl ImageRequest {
+    pub fn new(source: impl Into<SharedString>) -> Self {
+        Self {
+            source: source.into(),
+            max_size: None,
+            blur: 0.,
+        }
+    }
+
+    pub fn max_size(mut self, max_size: Size<u32>) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn blur(mut self, blur: f32) -> Self {
+        self.blur = blur;
+        self
+    }
+
+    fn key(&self) -> CacheKey {
+        CacheKey {
+            source: self.source.clone(),
+            max_size: self.max_size.map(|size| (size.width, size.height)),
+            blur: self.blur.to_bits(),
+        }
+    }
+
+    fn is_url(&self) -> bool {
+        self.source.starts_with("http://") || self.source.starts_with("https://")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    source: SharedString,
+    max_size: Option<(u32, u32)>,
+    blur: u32,
+}
+
+type Loading = Shared<Task<Result<Arc<RenderImage>, SharedString>>>;
+
+struct CachedEntry {
+    image: Arc<RenderImage>,
+    bytes: usize,
+    last_used: u64,
+}
+
+/// The decoded images, and the ones loading, so each is loaded once however often it's asked
+/// for.
+#[derive(Default)]
+pub struct Images {
+    cache: HashMap<CacheKey, CachedEntry>,
+    loading: HashMap<CacheKey, Loading>,
+    /// The images that failed to load, not retried by [`CachedImage`].
+    failed: HashSet<CacheKey>,
+    /// The bytes of the images in the cache.
+    size: usize,
+    /// Counts the lookups, ordering the entries by when they were used.
+    clock: u64,
+}
+
+impl Global for Images {}
+
+impl Images {
+    /// The image, if it's in the cache.
+    pub fn get(request: &ImageRequest, cx: &mut App) -> Option<Arc<RenderImage>> {
+        let this = cx.global_mut::<Self>();
+        this.clock += 1;
+        let clock = this.clock;
+        let entry = this.cache.get_mut(&request.key())?;
+        entry.last_used = clock;
+        Some(entry.image.clone())
+    }
+
+    /// The bytes of the decoded images in the cache.
+    pub fn cache_size(cx: &App) -> usize {
+        cx.try_global::<Self>().map_or(0, |this| this.size)
+    }
+
+    /// Load the image, from the cache when it's there.
+    pub fn load(request: ImageRequest, cx: &mut App) -> Task<anyhow::Result<Arc<RenderImage>>> {
+        if let Some(image) = Self::get(&request, cx) {
+            return Task::ready(Ok(image));
+        }
+
+        let key = request.key();
+        cx.global_mut::<Self>().failed.remove(&key);
+        let loading = match cx.global::<Self>().loading.get(&key) {
+            Some(loading) => loading.clone(),
+            None => {
+                let loading = Self::start(request, cx).shared();
+                cx.global_mut::<Self>()
+                    .loading
+                    .insert(key.clone(), loading.clone());
+                loading
+            }
+        };
+        cx.spawn(async move |cx| {
+            let result = loading.await;
+            cx.update(|cx| {
+                let this = cx.global_mut::<Self>();
+                if this.loading.remove(&key).is_none() {
+                    return;
+                }
+                match &result {
+                    Ok(image) => {
+                        let evicted = this.insert(key, image.clone());
+                        drop_textures(evicted, cx);
+                    }
+                    Err(_) => _ = this.failed.insert(key),
+                }
+            })?;
+            result.map_err(|err| anyhow!("{}", err))
+        })
+    }
+
+    fn start(request: ImageRequest, cx: &mut App) -> Task<Result<Arc<RenderImage>, SharedString>> {
+        let bytes: Task<anyhow::Result<Vec<u8>>> = if request.is_url() {
+            Net::get(request.source.to_string(), cx)
+        } else {
+            let path = PathBuf::from(request.source.as_ref());
+            cx.background_spawn(async move { Ok(std::fs::read(path)?) })
+        };
+        let background = cx.background_executor().clone();
+        cx.spawn(async move |_| {
+            let result = match bytes.await {
+                Ok(bytes) => {
+                    background
+                        .spawn(async move { decode(&bytes, request.max_size, request.blur) })
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            result.map_err(|err| {
+                tracing::error!("Failed to load the image {}: {}", request.source, err);
+                SharedString::from(err.to_string())
+            })
+        })
+    }
+
+    /// Cache the image, returning the ones evicted to stay within the budget.
+    fn insert(&mut self, key: CacheKey, image: Arc<RenderImage>) -> Vec<Arc<RenderImage>> {
+        let bytes = image_bytes(&image);
+        self.clock += 1;
+        self.size += bytes;
+        if let Some(old) = self.cache.insert(
+            key,
+            CachedEntry {
+                image,
+                bytes,
+                last_used: self.clock,
+            },
+        ) {
+            self.size -= old.bytes;
+        }
+
+        let mut evicted = vec![];
+        while self.size > CACHE_BUDGET && self.cache.len() > 1 {
+            let Some(key) = self
+                .cache
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.cache.remove(&key) {
+                self.size -= entry.bytes;
+                evicted.push(entry.image);
+            }
+        }
+        evicted
+    }
+}
+
+/// Free the textures of evicted images in every window.
+fn drop_textures(images: Vec<Arc<RenderImage>>, cx: &mut App) {
+    if images.is_empty() {
+        return;
+    }
+    for window in cx.windows() {
+        _ = window.update(cx, |_, window, _| {
+            for image in &images {
+                _ = window.drop_image(image.clone());
+            }
+        });
+    }
+}
+
+fn image_bytes(image: &RenderImage) -> usize {
+    (0..image.frame_count())
+        .map(|ix| image.as_bytes(ix).map_or(0, <[u8]>::len))
+        .sum()
+}
+
+/// Decode an image, upright by its EXIF orientation and downscaled to fit `max_size`.
+pub(crate) fn decode(
+    bytes: &[u8],
+    max_size: Option<Size<u32>>,
+    blur: f32,
+) -> anyhow::Result<Arc<RenderImage>> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    if let Some(max_size) = max_size
+        && (image.width() > max_size.width || image.height() > max_size.height)
+    {
+        image = image.resize(max_size.width, max_size.height, FilterType::Triangle);
+    }
+    if blur > 0. {
+        image = image.blur(blur);
+    }
+
+    // GPUI expects BGRA.
+    let mut buffer = image.into_rgba8();
+    for pixel in buffer.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Ok(Arc::new(RenderImage::new(vec![Frame::new(buffer)])))
+}
+
+/// The size of an element styled with `style` in physical pixels, when it's fixed.
+pub(crate) fn physical_size(style: &StyleRefinement, window: &Window) -> Option<Size<u32>> {
+    let to_pixels = |length: Option<Length>| match length? {
+        Length::Definite(DefiniteLength::Absolute(length)) => {
+            Some(length.to_pixels(window.rem_size()))
+        }
+        _ => None,
+    };
+    let (width, height): (Pixels, Pixels) =
+        (to_pixels(style.size.width)?, to_pixels(style.size.height)?);
+    let scale = window.scale_factor();
+    Some(size(
+        (width.as_f32() * scale).ceil() as u32,
+        (height.as_f32() * scale).ceil() as u32,
+    ))
+}
+
+/// An image from a file or URL, decoded at the size it's shown at and cached, with a shimmer
+/// while it loads.
+///
+/// Give it a size, like any `img`, the layout doesn't wait for the image.
+#[derive(IntoElement)]
+pub struct CachedImage {
+    source: SharedString,
+    object_fit: ObjectFit,
+    style: StyleRefinement,
+}
+
+impl CachedImage {
+    pub fn new(source: impl Into<SharedString>) -> Self {
+        Self {
+            source: source.into(),
+            object_fit: ObjectFit::Contain,
+            style: StyleRefinement::default(),
+        }
+    }
+
+    pub fn object_fit(mut self, object_fit: ObjectFit) -> Self {
+        self.object_fit = object_fit;
+        self
+    }
+}
+
+impl Styled for CachedImage {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for CachedImage {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let mut request = ImageRequest::new(self.source.clone());
+        // Decoded at the size it's shown at when that's fixed, or at full size.
+        request.max_size = physical_size(&self.style, window);
+
+        if let Some(image) = Images::get(&request, cx) {
+            let mut image = img(image).object_fit(self.object_fit);
+            image.style().refine(&self.style);
+            return image.into_any_element();
+        }
+
+        let images = cx.global::<Images>();
+        let key = request.key();
+        if images.failed.contains(&key) {
+            let mut placeholder = div().bg(SkeletonColors::base(cx));
+            placeholder.style().refine(&self.style);
+            return placeholder.into_any_element();
+        }
+        if IdleMonitor::is_idle(cx) {
+            // The window is dimmed while idle, the image loads when the user is back.
+            IdleMonitor::when_active("images", |cx| cx.refresh_windows(), cx);
+        } else if !images.loading.contains_key(&key) {
+            let loading = Images::load(request, cx);
+            cx.spawn(async move |cx| {
+                if loading.await.is_ok() {
+                    _ = cx.update(|cx| cx.refresh_windows());
+                }
+            })
+            .detach();
+        }
+        let mut placeholder = div()
+            .child(Shimmer::new(SharedString::from(format!("image-{}", self.source))).size_full());
+        placeholder.style().refine(&self.style);
+        placeholder.into_any_element()
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Images::default());
+}
@@ -0,0 +1,91 @@
+use gpui::{
+    App, Entity, EntityInputHandler as _, Focusable as _, Pixels, Point, ScrollHandle,
+    ScrollWheelEvent, Window, point, px,
+};
+use gpui_component::input::InputState;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{gestures::GestureSettings, settings::Settings};
+
+/// The range `scroll_speed` is clamped to, so a typo in the file can't stop or fling scrolling.
+pub(crate) const SCROLL_SPEED: (f32, f32) = (0.25, 5.);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct InputSettings {
+    /// How far a scroll goes compared to the OS, e.g. `2.0` scrolls twice as far.
+    pub scroll_speed: f32,
+    /// Scroll the other way round than the OS, e.g. natural scrolling on a mouse only.
+    pub reverse_scrolling: bool,
+    /// Paste the primary selection into text fields with the middle button, on Linux.
+    pub middle_click_paste: bool,
+    /// Focus split panes when the mouse moves over them, without a click.
+    pub focus_follows_mouse: bool,
+    pub gestures: GestureSettings,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            scroll_speed: 1.,
+            reverse_scrolling: false,
+            middle_click_paste: true,
+            focus_follows_mouse: false,
+            gestures: GestureSettings::default(),
+        }
+    }
+}
+
+impl InputSettings {
+    pub fn global(cx: &App) -> &Self {
+        &Settings::global(cx).input
+    }
+
+    /// The distance of a scroll of `delta` after the speed and direction settings.
+    pub fn scroll_delta(&self, delta: Point<Pixels>) -> Point<Pixels> {
+        let mut factor = self.scroll_speed.clamp(SCROLL_SPEED.0, SCROLL_SPEED.1);
+        if self.reverse_scrolling {
+            factor = -factor;
+        }
+        point(delta.x * factor, delta.y * factor)
+    }
+}
+
+/// Scroll `handle` by the part of `event` the settings add to what the element scrolling with
+/// the handle already does, so it follows the scroll speed and direction.
+pub fn scroll(handle: &ScrollHandle, event: &ScrollWheelEvent, window: &Window, cx: &App) {
+    let delta = event.delta.pixel_delta(window.line_height());
+    let extra = InputSettings::global(cx).scroll_delta(delta) - delta;
+    if extra == Point::default() {
+        return;
+    }
+    let max = handle.max_offset();
+    let offset = handle.offset() + extra;
+    handle.set_offset(point(
+        offset.x.clamp(-max.width, px(0.)),
+        offset.y.clamp(-max.height, px(0.)),
+    ));
+}
+
+/// Whether a middle click pastes the primary selection, only there on Linux.
+pub(crate) fn middle_click_paste(cx: &App) -> bool {
+    cfg!(any(target_os = "linux", target_os = "freebsd"))
+        && InputSettings::global(cx).middle_click_paste
+}
+
+/// Paste the primary selection, the last selected text, at the cursor of `state`.
+pub(crate) fn paste_primary(state: &Entity<InputState>, window: &mut Window, cx: &mut App) {
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    {
+        let Some(text) = cx.read_from_primary().and_then(|item| item.text()) else {
+            return;
+        };
+        window.focus(&state.focus_handle(cx));
+        state.update(cx, |state, cx| {
+            state.replace_text_in_range(None, &text, window, cx)
+        });
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+    let _ = (state, window, cx);
+}
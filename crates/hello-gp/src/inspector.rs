@@ -0,0 +1,322 @@
+use gpui::{
+    AbsoluteLength, AnyElement, App, Background, Context, DefiniteLength, DivInspectorState,
+    EdgesRefinement, Fill, Hsla, Inspector, InspectorElementId, InteractiveElement as _,
+    IntoElement, KeyBinding, ParentElement as _, Pixels, StatefulInteractiveElement as _,
+    Styled as _, Window, actions, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Colorize as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+actions!(dev, [ToggleInspector]);
+
+const PANEL_WIDTH: Pixels = px(320.);
+const LABEL_WIDTH: Pixels = px(88.);
+
+fn row(label: &'static str, value: impl Into<String>, cx: &App) -> impl IntoElement {
+    h_flex()
+        .gap_2()
+        .items_start()
+        .child(
+            div()
+                .w(LABEL_WIDTH)
+                .text_color(cx.theme().muted_foreground)
+                .child(label),
+        )
+        .child(div().flex_1().child(value.into()))
+}
+
+fn format_length(length: &DefiniteLength) -> String {
+    match length {
+        DefiniteLength::Absolute(AbsoluteLength::Pixels(pixels)) => pixels.to_string(),
+        DefiniteLength::Absolute(AbsoluteLength::Rems(rems)) => format!("{:?}", rems),
+        DefiniteLength::Fraction(fraction) => format!("{}%", fraction * 100.),
+    }
+}
+
+/// Top, right, bottom and left, like CSS. `None` when no edge is set.
+fn format_edges(edges: &EdgesRefinement<DefiniteLength>) -> Option<String> {
+    let sides = [&edges.top, &edges.right, &edges.bottom, &edges.left];
+    if sides.iter().all(|side| side.is_none()) {
+        return None;
+    }
+    Some(
+        sides
+            .iter()
+            .map(|side| side.as_ref().map_or("0".to_string(), format_length))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Where the hovered element is, and how it lays out its children.
+fn render_layout(id: &InspectorElementId, state: &DivInspectorState, cx: &App) -> impl IntoElement {
+    let style = &state.base_style;
+    let bounds = state.bounds;
+    let flex = [
+        style
+            .flex_direction
+            .map(|value| format!("direction {:?}", value)),
+        style.flex_wrap.map(|value| format!("wrap {:?}", value)),
+        style.align_items.map(|value| format!("align {:?}", value)),
+        style
+            .justify_content
+            .map(|value| format!("justify {:?}", value)),
+        style.flex_grow.map(|value| format!("grow {}", value)),
+        style.flex_shrink.map(|value| format!("shrink {}", value)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    let gap = [&style.gap.width, &style.gap.height]
+        .into_iter()
+        .map(|gap| gap.as_ref().map_or("0".to_string(), format_length))
+        .collect::<Vec<_>>();
+
+    v_flex()
+        .gap_1()
+        .child(div().font_semibold().child("Layout"))
+        .child(row("Rendered by", id.path.source_location.to_string(), cx))
+        .child(row(
+            "Bounds",
+            format!(
+                "{} × {} at {}, {}",
+                bounds.size.width, bounds.size.height, bounds.origin.x, bounds.origin.y
+            ),
+            cx,
+        ))
+        .child(row(
+            "Content",
+            format!(
+                "{} × {}",
+                state.content_size.width, state.content_size.height
+            ),
+            cx,
+        ))
+        .child(row(
+            "Padding",
+            format_edges(&style.padding).unwrap_or_else(|| "-".to_string()),
+            cx,
+        ))
+        .child(row(
+            "Margin",
+            format_edges(&style.margin).unwrap_or_else(|| "-".to_string()),
+            cx,
+        ))
+        .child(row(
+            "Display",
+            style
+                .display
+                .map_or("-".to_string(), |display| format!("{:?}", display)),
+            cx,
+        ))
+        .child(row(
+            "Flex",
+            if flex.is_empty() {
+                "-".to_string()
+            } else {
+                flex.join(", ")
+            },
+            cx,
+        ))
+        .child(row("Gap", gap.join(" "), cx))
+}
+
+/// The colors of the active theme by token, e.g. `border -> #e4e4e7`.
+fn theme_colors(cx: &App) -> Vec<(String, Hsla)> {
+    let Ok(serde_json::Value::Object(colors)) = serde_json::to_value(cx.theme().colors) else {
+        return vec![];
+    };
+    colors
+        .into_iter()
+        .filter_map(|(token, value)| Some((token, parse_color(&value)?)))
+        .collect()
+}
+
+/// A color as serialized by the theme, either a hex string or its HSLA channels.
+fn parse_color(value: &serde_json::Value) -> Option<Hsla> {
+    match value {
+        serde_json::Value::String(hex) => Hsla::parse_hex(hex).ok(),
+        serde_json::Value::Object(channels) => {
+            let channel = |name: &str| Some(channels.get(name)?.as_f64()? as f32);
+            Some(gpui::hsla(
+                channel("h")?,
+                channel("s")?,
+                channel("l")?,
+                channel("a")?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// A row of the tokens an element is painted with, `matches` tells whether a token has the
+/// painted color.
+fn token_row(
+    label: &'static str,
+    painted: Option<Hsla>,
+    colors: &[(String, Hsla)],
+    matches: impl Fn(Hsla) -> bool,
+    cx: &App,
+) -> impl IntoElement {
+    let tokens = colors
+        .iter()
+        .filter(|(_, color)| matches(*color))
+        .map(|(token, _)| token.as_str())
+        .collect::<Vec<_>>();
+    let value = match (painted, tokens.is_empty()) {
+        (None, _) => "-".to_string(),
+        (Some(_), false) => tokens.join(", "),
+        (Some(color), true) => format!("{}, not a theme color", color.to_hex()),
+    };
+
+    h_flex()
+        .gap_2()
+        .items_start()
+        .child(
+            div()
+                .w(LABEL_WIDTH)
+                .text_color(cx.theme().muted_foreground)
+                .child(label),
+        )
+        .when_some(painted, |this, color| {
+            this.child(
+                div()
+                    .mt(px(2.))
+                    .size_3()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(color),
+            )
+        })
+        .child(div().flex_1().child(value))
+}
+
+/// Which theme tokens the hovered element uses for its background, text and border.
+fn render_theme_tokens(state: &DivInspectorState, cx: &App) -> impl IntoElement {
+    let colors = theme_colors(cx);
+    let style = &state.base_style;
+    let background = match style.background.as_ref() {
+        Some(Fill::Color(background)) => Some(*background),
+        _ => None,
+    };
+    let background_color = background.and_then(|background| {
+        colors
+            .iter()
+            .find(|(_, color)| Background::from(*color) == background)
+            .map(|(_, color)| *color)
+    });
+
+    v_flex()
+        .gap_1()
+        .child(div().font_semibold().child("Theme tokens"))
+        .child(token_row(
+            "Background",
+            background_color,
+            &colors,
+            |color| background == Some(Background::from(color)),
+            cx,
+        ))
+        .when(background.is_some() && background_color.is_none(), |this| {
+            this.child(
+                div()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("The background is a gradient or not a theme color."),
+            )
+        })
+        .child(token_row(
+            "Foreground",
+            style.text.color,
+            &colors,
+            |color| style.text.color == Some(color),
+            cx,
+        ))
+        .child(token_row(
+            "Border",
+            style.border_color,
+            &colors,
+            |color| style.border_color == Some(color),
+            cx,
+        ))
+}
+
+fn render_div_state(
+    id: InspectorElementId,
+    state: &DivInspectorState,
+    _: &mut Window,
+    cx: &mut App,
+) -> impl IntoElement {
+    v_flex()
+        .gap_3()
+        .child(render_layout(&id, state, cx))
+        .child(render_theme_tokens(state, cx))
+}
+
+/// The panel GPUI shows next to the window while inspecting.
+fn render_inspector(
+    inspector: &mut Inspector,
+    window: &mut Window,
+    cx: &mut Context<Inspector>,
+) -> AnyElement {
+    let states = inspector.render_inspector_states(window, cx);
+    let picking = inspector.is_picking();
+
+    v_flex()
+        .id("inspector")
+        .w(PANEL_WIDTH)
+        .h_full()
+        .p_3()
+        .gap_3()
+        .overflow_y_scroll()
+        .text_sm()
+        .bg(cx.theme().background)
+        .text_color(cx.theme().foreground)
+        .border_l_1()
+        .border_color(cx.theme().border)
+        .child(
+            h_flex()
+                .justify_between()
+                .child(div().text_base().font_semibold().child("Inspector"))
+                .when(!picking, |this| {
+                    this.child(
+                        Button::new("pick-element")
+                            .small()
+                            .outline()
+                            .label("Pick Element")
+                            .on_click(cx.listener(|inspector, _, window, _| {
+                                inspector.start_picking();
+                                window.refresh();
+                            })),
+                    )
+                }),
+        )
+        .when(picking, |this| {
+            this.child(
+                div().text_color(cx.theme().muted_foreground).child(
+                    "Hover an element to see how it's laid out and painted, click to pin it.",
+                ),
+            )
+        })
+        .children(states)
+        .into_any_element()
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_inspector_renderer(Box::new(render_inspector));
+    cx.register_inspector_element(render_div_state);
+
+    cx.on_action(|_: &ToggleInspector, cx| {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_, window, cx| window.toggle_inspector(cx));
+        }
+    });
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-i", ToggleInspector, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-i", ToggleInspector, None),
+    ]);
+}
@@ -0,0 +1,297 @@
+use std::{
+    io::{BufRead as _, BufReader, Write as _},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::PathBuf,
+    time::Duration,
+};
+
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::App;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Cli, deep_links,
+    history::{self, ChangeSource},
+    themes::{self, get_data_dir},
+};
+
+/// Where the running instance writes the port it listens on, and the token to send with
+/// every message so that other local processes can't drive the app.
+const LOCK_FILE: &str = "instance.lock";
+/// How long a connection gets to send its message, so one that stays silent doesn't hold up
+/// the ones after it.
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a second launch of the app asks the running instance to do, before exiting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstanceMessage {
+    /// Bring the app to the front.
+    Activate,
+    /// Run an entry of the dock menu (the jump list on Windows), by index.
+    DockAction { index: usize },
+    /// Open a `hello-gp://` link, passed on the command line by the OS.
+    OpenUrl { url: String },
+    /// Install a theme file, opened from the file manager.
+    OpenFile { path: PathBuf },
+}
+
+impl InstanceMessage {
+    fn from_cli(cli: &Cli) -> Self {
+        if let Some(url) = cli.url.clone() {
+            Self::OpenUrl { url }
+        } else if let Some(path) = cli.file.clone() {
+            Self::OpenFile { path }
+        } else if let Some(index) = cli.dock_action {
+            Self::DockAction { index }
+        } else {
+            Self::Activate
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    token: String,
+    message: InstanceMessage,
+}
+
+fn lock_path() -> PathBuf {
+    get_data_dir().join(LOCK_FILE)
+}
+
+/// `org.freedesktop.Application` on the session bus, so that the desktop can activate the
+/// app by its name (see the `DBusActivatable` desktop entry), and later launches reach the
+/// running instance without the lock file.
+#[cfg(target_os = "linux")]
+mod dbus {
+    use std::collections::HashMap;
+
+    use futures::channel::mpsc::UnboundedSender;
+    use gpui::{App, Global};
+    use zbus::{
+        blocking::{Connection, Proxy, connection, fdo::DBusProxy},
+        names::BusName,
+        zvariant::{OwnedValue, Value},
+    };
+
+    use super::InstanceMessage;
+    use crate::{deep_links, integration::APP_ID};
+
+    const INTERFACE: &str = "org.freedesktop.Application";
+
+    fn object_path() -> String {
+        format!("/{}", APP_ID.replace('.', "/"))
+    }
+
+    fn message_for_uri(uri: String) -> InstanceMessage {
+        match uri.strip_prefix("file://") {
+            Some(path) => InstanceMessage::OpenFile {
+                path: deep_links::percent_decode(path).into(),
+            },
+            None => InstanceMessage::OpenUrl { url: uri },
+        }
+    }
+
+    struct Application {
+        tx: UnboundedSender<InstanceMessage>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Application")]
+    impl Application {
+        fn activate(&self, _platform_data: HashMap<String, OwnedValue>) {
+            _ = self.tx.unbounded_send(InstanceMessage::Activate);
+        }
+
+        fn open(&self, uris: Vec<String>, _platform_data: HashMap<String, OwnedValue>) {
+            for uri in uris {
+                _ = self.tx.unbounded_send(message_for_uri(uri));
+            }
+        }
+
+        /// The app exports no actions, activating one only brings it to the front.
+        fn activate_action(
+            &self,
+            _action_name: String,
+            _parameter: Vec<OwnedValue>,
+            _platform_data: HashMap<String, OwnedValue>,
+        ) {
+            _ = self.tx.unbounded_send(InstanceMessage::Activate);
+        }
+    }
+
+    /// Keeps the name on the bus for as long as the app runs.
+    struct Service {
+        _connection: Connection,
+    }
+
+    impl Global for Service {}
+
+    pub(super) fn serve(tx: UnboundedSender<InstanceMessage>, cx: &mut App) {
+        let result = connection::Builder::session()
+            .and_then(|builder| builder.name(APP_ID))
+            .and_then(|builder| builder.serve_at(object_path(), Application { tx }))
+            .and_then(|builder| builder.build());
+        match result {
+            Ok(connection) => cx.set_global(Service {
+                _connection: connection,
+            }),
+            Err(err) => tracing::warn!("Failed to own {} on the session bus: {}", APP_ID, err),
+        }
+    }
+
+    /// Hand `message` to the instance that owns the name, returns whether there is one.
+    pub(super) fn forward(message: &InstanceMessage) -> bool {
+        let result = (|| {
+            let connection = Connection::session()?;
+            // Calling the name would start the app through the bus when nobody owns it,
+            // while this process is about to become the running instance anyway.
+            if !DBusProxy::new(&connection)?.name_has_owner(BusName::try_from(APP_ID)?)? {
+                return Ok(false);
+            }
+            let proxy = Proxy::new(&connection, APP_ID, object_path(), INTERFACE)?;
+            let platform_data = HashMap::<&str, Value>::new();
+            let uri = match message {
+                InstanceMessage::OpenUrl { url } => Some(url.clone()),
+                InstanceMessage::OpenFile { path } => Some(format!(
+                    "file://{}",
+                    path.display()
+                        .to_string()
+                        .replace('%', "%25")
+                        .replace(' ', "%20")
+                )),
+                InstanceMessage::Activate | InstanceMessage::DockAction { .. } => None,
+            };
+            match uri {
+                Some(uri) => proxy.call_method("Open", &(vec![uri], platform_data))?,
+                None => proxy.call_method("Activate", &(platform_data,))?,
+            };
+            zbus::Result::Ok(true)
+        })();
+        result.unwrap_or_else(|err| {
+            tracing::debug!("Failed to reach the running instance over D-Bus: {}", err);
+            false
+        })
+    }
+}
+
+/// Pass the command line on to the instance that is already running, if there is one.
+///
+/// Returns `true` when it was handed over, and this process should exit.
+pub fn forward(cli: &Cli) -> bool {
+    #[cfg(target_os = "linux")]
+    if dbus::forward(&InstanceMessage::from_cli(cli)) {
+        return true;
+    }
+
+    let Ok(lock) = std::fs::read_to_string(lock_path()) else {
+        return false;
+    };
+    let Some((port, token)) = lock.trim().split_once(' ') else {
+        return false;
+    };
+    let Ok(port) = port.parse::<u16>() else {
+        return false;
+    };
+
+    // A lock file without a listener is left over from a crash, start as the primary instance.
+    let Ok(mut stream) = TcpStream::connect_timeout(
+        &(Ipv4Addr::LOCALHOST, port).into(),
+        Duration::from_millis(500),
+    ) else {
+        return false;
+    };
+    let envelope = Envelope {
+        token: token.to_string(),
+        message: InstanceMessage::from_cli(cli),
+    };
+    let Ok(line) = serde_json::to_string(&envelope) else {
+        return false;
+    };
+    writeln!(stream, "{}", line).is_ok()
+}
+
+fn handle(message: InstanceMessage, cx: &mut App) {
+    tracing::info!("Message from another instance: {:?}", message);
+    cx.activate(true);
+    if let Some(window) = cx.windows().into_iter().next() {
+        _ = window.update(cx, |_, window, _| window.activate_window());
+    }
+
+    match message {
+        InstanceMessage::Activate => {}
+        InstanceMessage::OpenUrl { url } => {
+            history::with_source(ChangeSource::Ipc, cx, |cx| deep_links::open(&url, cx))
+        }
+        InstanceMessage::OpenFile { path } => themes::install_theme_file(&path, cx),
+        #[cfg(target_os = "windows")]
+        InstanceMessage::DockAction { index } => cx.perform_dock_menu_action(index),
+        // The dock menu runs its actions in this process, outside Windows it never comes
+        // through here.
+        #[cfg(not(target_os = "windows"))]
+        InstanceMessage::DockAction { .. } => {}
+    }
+}
+
+/// Listen for [`InstanceMessage`]s from later launches of the app.
+pub fn init(cx: &mut App) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::error!("Failed to listen for other instances: {}", err);
+            return;
+        }
+    };
+    let Ok(addr) = listener.local_addr() else {
+        return;
+    };
+    let token = format!("{:032x}", rand::random::<u128>());
+    let path = lock_path();
+    if let Some(dir) = path.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    if let Err(err) = std::fs::write(&path, format!("{} {}", addr.port(), token)) {
+        tracing::error!("Failed to write {}: {}", path.display(), err);
+        return;
+    }
+
+    let (tx, mut rx) = mpsc::unbounded::<InstanceMessage>();
+    #[cfg(target_os = "linux")]
+    dbus::serve(tx.clone(), cx);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(err) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+                tracing::warn!("Failed to set the timeout of an instance message: {}", err);
+                continue;
+            }
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            match serde_json::from_str::<Envelope>(&line) {
+                Ok(envelope) if envelope.token == token => {
+                    if tx.unbounded_send(envelope.message).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => tracing::warn!("Ignored an instance message with a wrong token"),
+                Err(err) => tracing::warn!("Invalid instance message: {}", err),
+            }
+        }
+    });
+
+    cx.spawn(async move |cx| {
+        while let Some(message) = rx.next().await {
+            if cx.update(|cx| handle(message, cx)).is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+
+    cx.on_app_quit(move |_| {
+        _ = std::fs::remove_file(&path);
+        async {}
+    })
+    .detach();
+}
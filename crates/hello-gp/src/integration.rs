@@ -0,0 +1,425 @@
+use gpui::{App, Global};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// The id of the app on the desktop, the name of its `.desktop` file and of its D-Bus name.
+pub(crate) const APP_ID: &str = "cn.o0x0o.HelloGp";
+
+/// How the app hooks into the desktop, applied on every start so a moved app keeps working.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct IntegrationSettings {
+    /// Open `hello-gp://` links with the app.
+    pub url_scheme: bool,
+    /// Open `.hgptheme` files with the app, which installs the themes in them.
+    pub file_associations: bool,
+    /// A shortcut to the app in the Start Menu (Windows) or the applications menu (Linux).
+    pub start_menu_shortcut: bool,
+}
+
+impl Default for IntegrationSettings {
+    fn default() -> Self {
+        Self {
+            url_scheme: true,
+            file_associations: true,
+            start_menu_shortcut: true,
+        }
+    }
+}
+
+/// The settings last applied, to only touch the system when they change.
+struct AppliedIntegration(IntegrationSettings);
+
+impl Global for AppliedIntegration {}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::path::PathBuf;
+
+    use windows::{
+        Win32::{
+            System::{
+                Com::{CLSCTX_INPROC_SERVER, CoCreateInstance, IPersistFile},
+                Registry::{
+                    HKEY, HKEY_CURRENT_USER, KEY_WRITE, REG_OPTION_NON_VOLATILE, REG_SZ,
+                    RegCloseKey, RegCreateKeyExW, RegDeleteTreeW, RegSetValueExW,
+                },
+            },
+            UI::Shell::{IShellLinkW, SHCNE_ASSOCCHANGED, SHCNF_IDLIST, SHChangeNotify, ShellLink},
+        },
+        core::{HSTRING, Interface as _, PCWSTR},
+    };
+
+    use super::IntegrationSettings;
+    use crate::{deep_links::SCHEME, themes::THEME_FILE_EXTENSION};
+
+    /// The ProgID the theme files are associated with.
+    fn prog_id() -> String {
+        format!("{}.theme", env!("CARGO_PKG_NAME"))
+    }
+
+    fn exe() -> anyhow::Result<PathBuf> {
+        Ok(std::env::current_exe()?)
+    }
+
+    fn open_command() -> anyhow::Result<String> {
+        Ok(format!("\"{}\" \"%1\"", exe()?.display()))
+    }
+
+    /// Set the value `name` (the default value for `None`) of `HKCU\Software\Classes\<key>`.
+    fn set_class_value(key: &str, name: Option<&str>, value: &str) -> anyhow::Result<()> {
+        let path = HSTRING::from(format!(r"Software\Classes\{}", key));
+        let mut hkey = HKEY::default();
+        // SAFETY: plain registry calls with valid, NUL terminated strings and an owned key.
+        unsafe {
+            RegCreateKeyExW(
+                HKEY_CURRENT_USER,
+                &path,
+                None,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_WRITE,
+                None,
+                &mut hkey,
+                None,
+            )
+            .ok()?;
+            // REG_SZ data is the UTF-16 string with its NUL.
+            let data = HSTRING::from(value);
+            let bytes = std::slice::from_raw_parts(
+                data.as_ptr().cast::<u8>(),
+                (data.len() + 1) * std::mem::size_of::<u16>(),
+            );
+            let name = name.map(HSTRING::from);
+            let result = RegSetValueExW(
+                hkey,
+                name.as_ref()
+                    .map_or(PCWSTR::null(), |name| PCWSTR(name.as_ptr())),
+                None,
+                REG_SZ,
+                Some(bytes),
+            );
+            _ = RegCloseKey(hkey);
+            result.ok()?;
+        }
+        Ok(())
+    }
+
+    fn delete_class(key: &str) {
+        let path = HSTRING::from(format!(r"Software\Classes\{}", key));
+        // SAFETY: deletes a key of our own below HKCU, missing keys are fine.
+        _ = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, &path) };
+    }
+
+    fn register_url_scheme() -> anyhow::Result<()> {
+        set_class_value(SCHEME, None, &format!("URL:{}", SCHEME))?;
+        set_class_value(SCHEME, Some("URL Protocol"), "")?;
+        set_class_value(
+            &format!(r"{}\DefaultIcon", SCHEME),
+            None,
+            &format!("{},0", exe()?.display()),
+        )?;
+        set_class_value(
+            &format!(r"{}\shell\open\command", SCHEME),
+            None,
+            &open_command()?,
+        )
+    }
+
+    fn register_file_associations() -> anyhow::Result<()> {
+        let prog_id = prog_id();
+        set_class_value(&format!(".{}", THEME_FILE_EXTENSION), None, &prog_id)?;
+        set_class_value(&prog_id, None, &format!("{} Theme", env!("CARGO_PKG_NAME")))?;
+        set_class_value(
+            &format!(r"{}\DefaultIcon", prog_id),
+            None,
+            &format!("{},0", exe()?.display()),
+        )?;
+        set_class_value(
+            &format!(r"{}\shell\open\command", prog_id),
+            None,
+            &open_command()?,
+        )
+    }
+
+    fn unregister_file_associations() {
+        delete_class(&format!(".{}", THEME_FILE_EXTENSION));
+        delete_class(&prog_id());
+    }
+
+    fn shortcut_path() -> Option<PathBuf> {
+        let app_data = std::env::var_os("APPDATA")?;
+        Some(
+            PathBuf::from(app_data)
+                .join(r"Microsoft\Windows\Start Menu\Programs")
+                .join(format!("{}.lnk", env!("CARGO_PKG_NAME"))),
+        )
+    }
+
+    fn create_shortcut() -> anyhow::Result<()> {
+        let path = shortcut_path().ok_or_else(|| anyhow::anyhow!("no APPDATA"))?;
+        let exe = exe()?;
+        // SAFETY: COM is initialized on the main thread by GPUI.
+        unsafe {
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+            link.SetPath(&HSTRING::from(exe.as_os_str()))?;
+            if let Some(dir) = exe.parent() {
+                link.SetWorkingDirectory(&HSTRING::from(dir.as_os_str()))?;
+            }
+            link.SetDescription(&HSTRING::from(env!("CARGO_PKG_NAME")))?;
+            link.cast::<IPersistFile>()?
+                .Save(&HSTRING::from(path.as_os_str()), true)?;
+        }
+        Ok(())
+    }
+
+    fn remove_shortcut() {
+        if let Some(path) = shortcut_path()
+            && path.exists()
+            && let Err(err) = std::fs::remove_file(&path)
+        {
+            tracing::warn!("Failed to remove {}: {}", path.display(), err);
+        }
+    }
+
+    /// Tell Explorer the associations changed, so icons and "Open with" update right away.
+    fn notify_shell() {
+        // SAFETY: no items are passed with `SHCNE_ASSOCCHANGED`.
+        unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, None, None) };
+    }
+
+    pub(super) fn apply(settings: &IntegrationSettings) {
+        let log = |what: &str, result: anyhow::Result<()>| {
+            if let Err(err) = result {
+                tracing::error!("Failed to {}: {}", what, err);
+            }
+        };
+
+        if settings.url_scheme {
+            log("register the URL scheme", register_url_scheme());
+        } else {
+            delete_class(SCHEME);
+        }
+        if settings.file_associations {
+            log(
+                "register the file associations",
+                register_file_associations(),
+            );
+        } else {
+            unregister_file_associations();
+        }
+        if settings.start_menu_shortcut {
+            log("create the Start Menu shortcut", create_shortcut());
+        } else {
+            remove_shortcut();
+        }
+        notify_shell();
+    }
+
+    pub(super) fn unregister() {
+        delete_class(SCHEME);
+        unregister_file_associations();
+        remove_shortcut();
+        notify_shell();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{
+        path::{Path, PathBuf},
+        process::Command,
+    };
+
+    use directories::BaseDirs;
+
+    use super::{APP_ID, IntegrationSettings};
+    use crate::{deep_links::SCHEME, themes::THEME_FILE_EXTENSION};
+
+    const ICON: &str = include_str!("../assets/app-icon.svg");
+    const THEME_MIME_TYPE: &str = "application/x-hgptheme";
+
+    fn data_dir() -> Option<PathBuf> {
+        Some(BaseDirs::new()?.data_dir().to_path_buf())
+    }
+
+    fn desktop_entry(exe: &Path, settings: &IntegrationSettings) -> String {
+        let mut mime_types = vec![];
+        if settings.url_scheme {
+            mime_types.push(format!("x-scheme-handler/{};", SCHEME));
+        }
+        if settings.file_associations {
+            mime_types.push(format!("{};", THEME_MIME_TYPE));
+        }
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name={name}\n\
+             Exec=\"{exe}\" %U\n\
+             Icon={APP_ID}\n\
+             Terminal=false\n\
+             Categories=Development;\n\
+             StartupWMClass={APP_ID}\n\
+             DBusActivatable=true\n\
+             MimeType={mime_types}\n\
+             NoDisplay={no_display}\n",
+            name = env!("CARGO_PKG_NAME"),
+            exe = exe.display(),
+            mime_types = mime_types.concat(),
+            no_display = !settings.start_menu_shortcut,
+        )
+    }
+
+    /// Lets the session bus start the app when it's activated by its name, e.g. by the
+    /// desktop for a `DBusActivatable` entry.
+    fn dbus_service(exe: &Path) -> String {
+        format!(
+            "[D-BUS Service]\nName={}\nExec=\"{}\"\n",
+            APP_ID,
+            exe.display()
+        )
+    }
+
+    fn mime_package() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<mime-info xmlns="http://www.freedesktop.org/standards/shared-mime-info">
+  <mime-type type="{}">
+    <comment>{} theme</comment>
+    <glob pattern="*.{}"/>
+  </mime-type>
+</mime-info>
+"#,
+            THEME_MIME_TYPE,
+            env!("CARGO_PKG_NAME"),
+            THEME_FILE_EXTENSION
+        )
+    }
+
+    /// Write `contents` to `path` unless it's there already, returns whether it changed.
+    fn refresh(path: &Path, contents: &str) -> anyhow::Result<bool> {
+        if std::fs::read_to_string(path).is_ok_and(|current| current == contents) {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, contents)?;
+        tracing::info!("Wrote {}", path.display());
+        Ok(true)
+    }
+
+    fn remove(path: &Path) -> bool {
+        path.exists() && std::fs::remove_file(path).is_ok()
+    }
+
+    /// Rebuild the caches of the desktop, where the tools are installed.
+    fn update_databases(data_dir: &Path) {
+        for (tool, dir) in [
+            ("update-desktop-database", "applications"),
+            ("update-mime-database", "mime"),
+        ] {
+            if let Err(err) = Command::new(tool).arg(data_dir.join(dir)).status() {
+                tracing::debug!("Failed to run {}: {}", tool, err);
+            }
+        }
+    }
+
+    struct Paths {
+        desktop_entry: PathBuf,
+        dbus_service: PathBuf,
+        mime_package: PathBuf,
+        icon: PathBuf,
+    }
+
+    impl Paths {
+        fn new(data_dir: &Path) -> Self {
+            Self {
+                desktop_entry: data_dir.join(format!("applications/{}.desktop", APP_ID)),
+                dbus_service: data_dir.join(format!("dbus-1/services/{}.service", APP_ID)),
+                mime_package: data_dir.join(format!("mime/packages/{}.xml", APP_ID)),
+                icon: data_dir.join(format!("icons/hicolor/scalable/apps/{}.svg", APP_ID)),
+            }
+        }
+    }
+
+    /// Write the desktop entry and its icon to the XDG data dir, and refresh them when the
+    /// app moved or the settings changed.
+    pub(super) fn apply(settings: &IntegrationSettings) {
+        let (Some(data_dir), Ok(exe)) = (data_dir(), std::env::current_exe()) else {
+            return;
+        };
+        let paths = Paths::new(&data_dir);
+        let result = (|| {
+            let mut changed = refresh(&paths.desktop_entry, &desktop_entry(&exe, settings))?;
+            changed |= refresh(&paths.dbus_service, &dbus_service(&exe))?;
+            changed |= refresh(&paths.icon, ICON)?;
+            changed |= if settings.file_associations {
+                refresh(&paths.mime_package, &mime_package())?
+            } else {
+                remove(&paths.mime_package)
+            };
+            anyhow::Ok(changed)
+        })();
+        match result {
+            Ok(true) => update_databases(&data_dir),
+            Ok(false) => {}
+            Err(err) => tracing::error!("Failed to write the desktop entry: {}", err),
+        }
+    }
+
+    pub(super) fn unregister() {
+        let Some(data_dir) = data_dir() else {
+            return;
+        };
+        let paths = Paths::new(&data_dir);
+        for path in [
+            &paths.desktop_entry,
+            &paths.dbus_service,
+            &paths.mime_package,
+            &paths.icon,
+        ] {
+            remove(path);
+        }
+        update_databases(&data_dir);
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::IntegrationSettings;
+
+    /// The bundle declares the scheme and the file types.
+    pub(super) fn apply(_: &IntegrationSettings) {}
+
+    pub(super) fn unregister() {}
+}
+
+/// Remove everything the app registered with the system, run by the uninstaller through
+/// `--unregister`.
+pub fn unregister() {
+    platform::unregister();
+}
+
+fn sync(cx: &mut App) {
+    let settings = Settings::global(cx).integration.clone();
+    if cx
+        .try_global::<AppliedIntegration>()
+        .is_some_and(|applied| applied.0 == settings)
+    {
+        return;
+    }
+    platform::apply(&settings);
+    cx.set_global(AppliedIntegration(settings));
+}
+
+pub fn init(cx: &mut App) {
+    // A kiosk runs from a fixed install, and shouldn't leave traces on the machine.
+    if crate::kiosk::enabled() {
+        return;
+    }
+    sync(cx);
+    cx.observe_global::<Settings>(sync).detach();
+}
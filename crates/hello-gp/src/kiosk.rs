@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gpui::{Action, App};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+static KIOSK: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KioskSettings {
+    /// The actions that still work in `--kiosk` mode, by name (`story::OpenStory`) or by
+    /// namespace (`input::*`). Everything else is unbound and ignored from deep links.
+    pub allowed_actions: Vec<String>,
+}
+
+impl Default for KioskSettings {
+    fn default() -> Self {
+        Self {
+            allowed_actions: [
+                "dismiss::Dismiss",
+                "input::*",
+                "list::*",
+                "story::OpenStory",
+                "story::ToggleSearch",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl KioskSettings {
+    fn allows(&self, name: &str) -> bool {
+        self.allowed_actions
+            .iter()
+            .any(|allowed| match allowed.strip_suffix("*") {
+                Some(namespace) => name.starts_with(namespace),
+                None => name == allowed,
+            })
+    }
+}
+
+/// Whether the app runs in kiosk mode, for shared machines and exhibition displays: windows
+/// are fullscreen without a title bar (and its theme switcher), nothing is written to the
+/// config dir, and only the allowed actions can be used.
+pub fn enabled() -> bool {
+    KIOSK.load(Ordering::Relaxed)
+}
+
+/// Turn on kiosk mode, must run before the configs are first saved. Same as `--kiosk`.
+pub fn enter(enabled: bool) {
+    KIOSK.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether `action` may run, always in a normal run.
+pub fn allows(action: &dyn Action, cx: &App) -> bool {
+    !enabled() || Settings::global(cx).kiosk.allows(action.name())
+}
+
+/// Drop the key bindings of the actions that aren't allowed, after everything was bound.
+pub(crate) fn init(cx: &mut App) {
+    if !enabled() {
+        return;
+    }
+
+    let settings = Settings::global(cx).kiosk.clone();
+    let bindings = cx
+        .key_bindings()
+        .borrow()
+        .bindings()
+        .filter(|binding| settings.allows(binding.action().name()))
+        .cloned()
+        .collect::<Vec<_>>();
+    cx.clear_key_bindings();
+    cx.bind_keys(bindings);
+    tracing::info!(
+        "Kiosk mode, {} key bindings allowed",
+        cx.key_bindings().borrow().bindings().len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kiosk::KioskSettings;
+
+    #[test]
+    fn test_allows() {
+        let settings = KioskSettings::default();
+        assert!(settings.allows("story::OpenStory"));
+        assert!(settings.allows("input::Backspace"));
+        assert!(settings.allows("list::SelectNext"));
+        assert!(!settings.allows("story::OpenStoryInNewWindow"));
+        assert!(!settings.allows("settings::ShowSettings"));
+        // A namespace only covers its own actions.
+        assert!(!settings.allows("input_settings::Reset"));
+
+        let settings = KioskSettings {
+            allowed_actions: vec![],
+        };
+        assert!(!settings.allows("story::OpenStory"));
+    }
+}
@@ -0,0 +1,209 @@
+use gpui::{
+    Action, App, AppContext as _, Global, ParentElement as _, SharedString, Styled as _, Window,
+    actions,
+};
+use gpui_component::{
+    ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::InputState,
+    notification::Notification,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_menus, config,
+    session::Session,
+    split::{SplitAxis, SplitLayout},
+    text_field::TextField,
+    themes::get_config_dir,
+};
+
+actions!(layouts, [SaveLayoutPreset]);
+
+/// Switch the gallery to the layout preset with this name.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = layouts, no_json)]
+pub struct ApplyLayoutPreset(pub SharedString);
+
+pub(crate) const LAYOUTS_NAME: &str = "layouts";
+
+/// How many recently applied presets the dock menu lists.
+const MAX_RECENT: usize = 5;
+
+/// A saved arrangement of the gallery: the sidebar and the splits of the center area.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutPreset {
+    pub name: String,
+    /// Whether the sidebar is expanded, or collapsed to icons.
+    pub sidebar: bool,
+    /// Width of the expanded sidebar, in pixels.
+    pub sidebar_width: f32,
+    pub split: SplitLayout,
+}
+
+impl Default for LayoutPreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            sidebar: true,
+            sidebar_width: 255.,
+            split: SplitLayout::default(),
+        }
+    }
+}
+
+/// The layout presets, persisted as `layouts.toml` in the config dir.
+///
+/// Until the first preset is saved this holds the built-in ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutPresets {
+    pub presets: Vec<LayoutPreset>,
+}
+
+impl Default for LayoutPresets {
+    fn default() -> Self {
+        let pane = |story: Option<&str>| SplitLayout::Pane {
+            story: story.map(str::to_string),
+        };
+
+        Self {
+            presets: vec![
+                LayoutPreset {
+                    name: "coding".into(),
+                    split: SplitLayout::Split {
+                        axis: SplitAxis::Horizontal,
+                        children: vec![pane(None), pane(Some("Input"))],
+                        ratios: vec![0.6, 0.4],
+                    },
+                    ..Default::default()
+                },
+                LayoutPreset {
+                    name: "review".into(),
+                    sidebar: false,
+                    split: SplitLayout::Split {
+                        axis: SplitAxis::Horizontal,
+                        children: vec![pane(None), pane(Some("Table"))],
+                        ratios: vec![0.5, 0.5],
+                    },
+                    ..Default::default()
+                },
+                LayoutPreset {
+                    name: "minimal".into(),
+                    sidebar: false,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+}
+
+impl Global for LayoutPresets {}
+
+impl LayoutPresets {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LayoutPreset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Add `preset`, replacing the one of the same name, and write the presets back to disk.
+    pub fn save(preset: LayoutPreset, cx: &mut App) {
+        let mut presets = Self::global(cx).clone();
+        match presets.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => presets.presets.push(preset),
+        }
+
+        if let Err(err) = config::save(&get_config_dir(), LAYOUTS_NAME, &presets) {
+            tracing::error!("Failed to save layout presets: {}", err);
+        }
+        cx.set_global(presets);
+    }
+
+    /// The presets applied last, most recent first, skipping ones that were since removed.
+    pub fn recent<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a LayoutPreset> {
+        Session::global(cx)
+            .recent_layouts
+            .iter()
+            .filter_map(|name| self.get(name))
+    }
+
+    /// Remember that the preset `name` was applied, for the dock menu.
+    pub fn mark_used(name: &str, cx: &mut App) {
+        if Session::global(cx)
+            .recent_layouts
+            .first()
+            .map(String::as_str)
+            == Some(name)
+        {
+            return;
+        }
+        Session::update(cx, |session| {
+            session.recent_layouts.retain(|recent| recent != name);
+            session.recent_layouts.insert(0, name.to_string());
+            session.recent_layouts.truncate(MAX_RECENT);
+        });
+        app_menus::refresh(cx);
+    }
+}
+
+/// Ask for a name and save `preset` under it.
+pub fn open_save_dialog(preset: LayoutPreset, window: &mut Window, cx: &mut App) {
+    let input = cx.new(|cx| InputState::new(window, cx).placeholder("Preset name"));
+
+    window.open_modal(cx, move |modal, _, _| {
+        let input = input.clone();
+        let preset = preset.clone();
+        modal.title("Save Layout").child(
+            v_flex()
+                .gap_3()
+                .child(TextField::new("layout-name", &input))
+                .child(
+                    h_flex().justify_end().child(
+                        Button::new("save-layout")
+                            .small()
+                            .primary()
+                            .label("Save")
+                            .on_click(move |_, window, cx| {
+                                let name = input.read(cx).value().trim().to_string();
+                                if name.is_empty() {
+                                    return;
+                                }
+
+                                LayoutPresets::save(
+                                    LayoutPreset {
+                                        name: name.clone(),
+                                        ..preset.clone()
+                                    },
+                                    cx,
+                                );
+                                app_menus::refresh(cx);
+                                window.close_modal(cx);
+                                window.push_notification(
+                                    Notification::success(format!(
+                                        "Saved the layout \"{}\".",
+                                        name
+                                    )),
+                                    cx,
+                                );
+                            }),
+                    ),
+                ),
+        )
+    });
+}
+
+pub fn init(cx: &mut App) {
+    let presets =
+        config::load::<LayoutPresets>(&get_config_dir(), LAYOUTS_NAME).unwrap_or_else(|err| {
+            tracing::error!("Failed to load layout presets: {}", err);
+            LayoutPresets::default()
+        });
+    cx.set_global(presets);
+}
@@ -1,116 +1,318 @@
+mod about;
 mod accordion_story;
 mod alert_story;
 mod app_menus;
+mod app_progress;
+mod appearance;
 mod assets;
 mod avatar_story;
 mod badge_story;
+#[cfg(feature = "bench")]
+pub mod bench;
 mod button_story;
 mod calendar_story;
 mod chart_story;
 mod checkbox_story;
+mod cli;
 mod clipboard_story;
+mod color_input;
 mod color_picker_story;
+mod combobox;
+mod confetti;
+mod config;
+mod cursor;
+mod data_table;
 mod date_picker_story;
+mod date_time_picker;
+mod deep_links;
 mod description_list_story;
+mod diagnostics;
+pub mod dialogs;
+mod dismiss;
+mod drag_drop;
 mod drawer_story;
 mod dropdown_story;
+mod encryption;
+mod events;
+mod extension_manager;
+mod extensions;
+mod factory_reset;
+mod file_picker;
 mod form_story;
+pub mod format;
+pub mod forms;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+mod gestures;
 mod group_box_story;
+mod history;
 mod icon_story;
+mod icons;
+mod idle;
 mod image_story;
+mod image_viewer;
+mod images;
 mod indicator_story;
+mod input_settings;
 mod input_story;
+#[cfg(any(feature = "inspector", debug_assertions))]
+mod inspector;
+mod instance;
+mod integration;
 mod kbd_story;
+mod kiosk;
 mod label_story;
+mod layouts;
 mod list_story;
+mod lock;
+mod logs;
+mod macos;
+mod macros;
+mod markdown;
+mod memory;
 mod menu_story;
 mod modal_story;
+mod native_notifications;
+mod navigation;
+mod net;
+mod net_settings;
 mod notification_story;
+mod notifications;
+mod number_field;
 mod number_input_story;
 mod otp_input_story;
+mod palette;
+mod persistence;
+mod pointer;
+mod policy;
+mod popout;
 mod popover_story;
+mod power;
+mod profiles;
 mod progress_story;
+mod prototypes;
+mod quit;
 mod radio_story;
+mod recovery;
+mod renderer;
 mod resizable_story;
+mod safe_mode;
+mod scopes;
+mod scripting;
 mod scrollable_story;
+mod secrets;
+mod session;
+mod settings;
+mod settings_editor;
+mod settings_window;
+mod shortcuts;
+mod shutdown;
 mod sidebar_story;
 mod skeleton_story;
+mod skeletons;
 mod slider_story;
+mod snapshots;
+mod sounds;
+mod spell_check;
+mod split;
+mod startup;
+mod status_bar;
+mod style_guide;
 mod switch_story;
 mod table_story;
 mod tabs_story;
 mod tag_story;
+mod taskbar;
+mod tasks;
+mod telemetry;
+mod text_field;
 mod textarea_story;
+mod theme_diff;
 mod themes;
+mod tinted_icons;
 mod title_bar;
 mod toggle_story;
 mod tooltip_story;
+mod tooltips;
 mod tree_story;
+mod tree_view;
 mod virtual_list_story;
+mod virtual_rows;
+mod wallpaper;
+mod watchdog;
+mod watcher;
 mod webview_story;
 mod welcome_story;
+mod whats_new;
+mod window_frame;
 
 pub use assets::Assets;
 use gpui::{
     Action, AnyElement, AnyView, App, AppContext, Bounds, Context, Div, Entity, EventEmitter,
-    Focusable, Global, Hsla, InteractiveElement, IntoElement, KeyBinding, ParentElement, Pixels,
-    Render, RenderOnce, SharedString, Size, StatefulInteractiveElement, StyleRefinement, Styled,
-    Window, WindowBounds, WindowKind, WindowOptions, actions, div, prelude::FluentBuilder as _, px,
-    rems, size,
+    Focusable, Global, Hsla, InteractiveElement, IntoElement, KeyBinding, ObjectFit, ParentElement,
+    Pixels, Point, Render, RenderOnce, ScrollHandle, SharedString, Size,
+    StatefulInteractiveElement, StyleRefinement, Styled, Subscription, Window, WindowBounds,
+    WindowKind, WindowOptions, actions, div, img, prelude::FluentBuilder as _, px, rems, size,
 };
 
+pub use about::{LicensesView, ShowLicenses, ThirdPartyLicense};
 pub use accordion_story::AccordionStory;
 pub use alert_story::AlertStory;
+pub use app_progress::{AppProgress, AppProgressMode};
 pub use avatar_story::AvatarStory;
 pub use badge_story::BadgeStory;
 pub use button_story::ButtonStory;
 pub use calendar_story::CalendarStory;
 pub use chart_story::ChartStory;
 pub use checkbox_story::CheckboxStory;
+pub use cli::Cli;
 pub use clipboard_story::ClipboardStory;
+pub use color_input::{ColorInput, ColorInputEvent};
 pub use color_picker_story::ColorPickerStory;
+pub use combobox::{Combobox, ComboboxEvent, ComboboxItem, OptionsLoader};
+pub use confetti::{Confetti, celebrate};
+pub use config::schema as config_schema;
+pub use cursor::{CursorExt, CursorRole, CursorShape, Cursors};
+pub use data_table::{DataColumn, DataTable, DataTableDelegate};
 pub use date_picker_story::DatePickerStory;
+pub use date_time_picker::{DateTimePicker, DateTimePickerEvent, TimePicker, TimePickerEvent};
+pub use deep_links::{DeepLinks, OpenStory, RouteParams, open as open_deep_link};
 pub use description_list_story::DescriptionListStory;
+pub use diagnostics::{GpuDiagnostics, ReportIssue, ShowGpuDiagnostics, issue_body};
+pub use dismiss::{Dismiss, DismissLayer, DismissStack, dismiss as dismiss_topmost};
+pub use drag_drop::{DragDropExt, DragPreview, move_item as move_dragged_item};
 pub use drawer_story::DrawerStory;
 pub use dropdown_story::DropdownStory;
+pub use encryption::ToggleConfigEncryption;
+pub use extension_manager::{ExtensionManager, ShowExtensions};
+pub use extensions::{
+    ExtensionLoader, ExtensionManifest, ExtensionRegistry, ExtensionRuntime, RunExtensionCommand,
+};
+pub use factory_reset::{ResetAllSettings, apply as apply_factory_reset};
+pub use file_picker::{FilePicker, PickerMode, PickerOptions, pick as pick_file};
 pub use form_story::FormStory;
 pub use group_box_story::GroupBoxStory;
+pub use history::{
+    Change, ChangeSource, History, UndoSettingsChange, with_source as with_change_source,
+};
 pub use icon_story::IconStory;
+pub use icons::{IconPack, Icons, SelectIconPack};
+pub use idle::{IdleEvent, IdleMonitor};
 pub use image_story::ImageStory;
+pub use image_viewer::{ImageViewer, ZoomMode};
+pub use images::{CachedImage, ImageRequest, Images};
 pub use indicator_story::IndicatorStory;
+pub use input_settings::InputSettings;
 pub use input_story::InputStory;
+#[cfg(any(feature = "inspector", debug_assertions))]
+pub use inspector::ToggleInspector;
+pub use instance::{InstanceMessage, forward as forward_to_running_instance};
+pub use integration::{IntegrationSettings, unregister as unregister_integration};
 pub use kbd_story::KbdStory;
+pub use kiosk::{KioskSettings, enter as enter_kiosk};
 pub use label_story::LabelStory;
+pub use layouts::{
+    ApplyLayoutPreset, LayoutPreset, LayoutPresets, SaveLayoutPreset,
+    open_save_dialog as open_save_layout_dialog,
+};
 pub use list_story::ListStory;
+pub use lock::{AppLock, LockApp, LockScreen, SetLockPassphrase};
+pub use macos::{BringAllToFront, Minimize, ToggleFullScreen, Zoom, reopen as reopen_windows};
+pub use macros::{Macro, Macros, ReplayLastMacro, ReplayMacro, ToggleMacroRecording};
+pub use markdown::Markdown;
+pub use memory::{Memory, MemorySample};
 pub use menu_story::MenuStory;
 pub use modal_story::ModalStory;
+pub use navigation::{Breadcrumb, BreadcrumbItem, NavItem, NavRail};
+pub use net::{Net, NetSettings, ProxyMode};
+pub use net_settings::{NetworkSettingsView, ShowNetworkSettings};
 pub use notification_story::NotificationStory;
+pub use notifications::{NotificationCenter, NotificationEntry, NotificationList, Toast};
+pub use number_field::{NumberField, NumberFieldEvent};
 pub use number_input_story::NumberInputStory;
 pub use otp_input_story::OtpInputStory;
+pub use palette::{CommandPalette, Commands, ToggleCommandPalette};
+pub use persistence::Persistence;
+pub use pointer::{
+    PointerDiagnostics, PointerEvent, PointerExt, PointerKind, PointerPhase,
+    ShowPointerDiagnostics, Stylus, StylusSample,
+};
+pub use policy::Policy;
+pub use popout::Popouts;
 pub use popover_story::PopoverStory;
+pub use power::{FramePacer, Visibility, VisibilityEvent};
+pub use profiles::{
+    NewProfile, ProfileLayout, SwitchProfile, active_layout as active_profile_layout,
+    open_new_dialog as open_new_profile_dialog, switch as switch_profile,
+};
 pub use progress_story::ProgressStory;
+pub use prototypes::{OpenPrototypes, PrototypesView};
+pub use quit::{QuitGuards, request as request_quit};
 pub use radio_story::RadioStory;
+pub use recovery::guard_startup;
+pub use renderer::{
+    DisplayInfo, DisplayServer, RenderSettings, WindowDecorations, display_info,
+    prepare as prepare_renderer,
+};
 pub use resizable_story::ResizableStory;
+pub use safe_mode::{
+    enabled as safe_mode_enabled, enter as enter_safe_mode, failed_starts, restart_normally,
+};
+pub use scopes::{KeymapBinding, KeymapSettings, ShortcutScope};
 pub use scrollable_story::ScrollableStory;
+pub use secrets::Secrets;
 use serde::{Deserialize, Serialize};
+use session::Session;
+use settings::Settings;
+pub use settings_editor::{EditRawSettings, RawSettingsEditor};
+pub use settings_window::{
+    RevealSetting, SettingControl, SettingEntry, SettingsView, ShowSettings,
+};
+pub use shortcuts::ShowShortcuts;
 pub use sidebar_story::SidebarStory;
 pub use skeleton_story::SkeletonStory;
+pub use skeletons::{Loadable, Shimmer, SkeletonColors, SkeletonLines, SkeletonRows};
 pub use slider_story::SliderStory;
+pub use sounds::{SelectSoundPack, SoundEvent, SoundPack, SoundSettings, Sounds, ToggleMute};
+pub use spell_check::{SpellCheck, SpellCheckSettings};
+pub use split::{
+    CloseSplit, DraggedStory, FocusNextSplit, FocusPreviousSplit, SplitAxis, SplitDown,
+    SplitLayout, SplitRight, SplitView,
+};
+pub use startup::{StartupSettings, run as run_startup_actions};
+pub use status_bar::AppStatusBar;
+pub use style_guide::{ShowStyleGuide, StyleGuide};
 pub use switch_story::SwitchStory;
 pub use table_story::TableStory;
 pub use tabs_story::TabsStory;
 pub use tag_story::TagStory;
+pub use taskbar::Taskbar;
+pub use tasks::{TaskHandle, TaskId, TaskScheduler, TaskStatus};
+pub use telemetry::{ShowTelemetry, Telemetry};
+pub use text_field::TextField;
 pub use textarea_story::TextareaStory;
+pub use theme_diff::{CompareThemes, ThemeDiff};
+pub use themes::{ExportTheme, ImportTheme, install_theme_file};
+pub use tinted_icons::{TintedIcon, TintedIcons};
 pub use title_bar::AppTitleBar;
 pub use toggle_story::ToggleStory;
 pub use tooltip_story::TooltipStory;
+pub use tooltips::{RichTooltip, WithTooltip};
 pub use tree_story::TreeStory;
+pub use tree_view::{TreeDelegate, TreeNode, TreeView, TreeViewEvent};
 pub use virtual_list_story::VirtualListStory;
+pub use virtual_rows::{RowsDelegate, VirtualRows, VirtualRowsEvent};
+pub use wallpaper::{Wallpaper, WallpaperSpec};
+pub use watchdog::{WatchdogSettings, longest_stall as longest_ui_stall, stalls as ui_stalls};
+pub use watcher::{FileWatcher, WatchId};
 pub use webview_story::WebViewStory;
 pub use welcome_story::WelcomeStory;
+pub use whats_new::{ShowWhatsNew, show_if_updated as show_whats_new};
+pub use window_frame::{Maximize, RestoreOrMinimize, ShowWindowMenu};
 
 use gpui_component::{
     ActiveTheme, ContextModal, IconName, Root, TitleBar,
+    alert::Alert,
     button::Button,
     context_menu::ContextMenuExt,
     dock::{Panel, PanelControl, PanelEvent, PanelInfo, PanelState, TitleStyle, register_panel},
@@ -195,37 +397,68 @@ pub fn create_new_window_with_size<F, E>(
 {
     let mut window_size = window_size.unwrap_or(size(px(1600.0), px(900.0)));
     if let Some(display) = cx.primary_display() {
-        let display_size = display.bounds().size;
+        let display_size = macos::usable_size(display.bounds().size);
         window_size.width = window_size.width.min(display_size.width * 0.85);
         window_size.height = window_size.height.min(display_size.height * 0.85);
     }
     let window_bounds = Bounds::centered(None, window_size, cx);
+    create_new_window_with_bounds(title, window_bounds, crate_view_fn, cx);
+}
+
+/// Open a window at `window_bounds`, e.g. restored from the session.
+pub fn create_new_window_with_bounds<F, E>(
+    title: &str,
+    window_bounds: Bounds<Pixels>,
+    crate_view_fn: F,
+    cx: &mut App,
+) where
+    E: Into<AnyView>,
+    F: FnOnce(&mut Window, &mut App) -> E + Send + 'static,
+{
     let title = SharedString::from(title.to_string());
 
     cx.spawn(async move |cx| {
+        let kiosk = kiosk::enabled();
         let options = WindowOptions {
-            window_bounds: Some(WindowBounds::Windowed(window_bounds)),
-            titlebar: Some(TitleBar::title_bar_options()),
+            window_bounds: Some(if kiosk {
+                WindowBounds::Fullscreen(window_bounds)
+            } else {
+                WindowBounds::Windowed(window_bounds)
+            }),
+            titlebar: (!kiosk).then(TitleBar::title_bar_options),
             window_min_size: Some(gpui::Size {
                 width: px(480.),
                 height: px(320.),
             }),
             kind: WindowKind::Normal,
+            // Matches the windows to the desktop entry on Wayland.
+            app_id: Some(integration::APP_ID.to_string()),
             #[cfg(target_os = "linux")]
             window_background: gpui::WindowBackgroundAppearance::Transparent,
             #[cfg(target_os = "linux")]
-            window_decorations: Some(gpui::WindowDecorations::Client),
+            window_decorations: Some(renderer::window_decorations()),
             ..Default::default()
         };
 
+        let mut tracked = None;
         let window = cx
             .open_window(options, |window, cx| {
-                let view = crate_view_fn(window, cx);
+                let view: AnyView = crate_view_fn(window, cx).into();
+                let weak_view = view.downgrade();
                 let root = cx.new(|cx| StoryRoot::new(title.clone(), view, window, cx));
+                tracked = Some((weak_view, root.downgrade()));
 
                 cx.new(|cx| Root::new(root.into(), window, cx))
             })
             .expect("failed to open window");
+        if let Some((view, root)) = tracked {
+            let handle = window.into();
+            cx.update(|cx| {
+                Memory::track(title.clone(), handle, move || view.upgrade().is_some(), cx);
+                let name = format!("{} (root)", title);
+                Memory::track(name, handle, move || root.upgrade().is_some(), cx);
+            })?;
+        }
 
         window
             .update(cx, |_, window, _| {
@@ -233,6 +466,9 @@ pub fn create_new_window_with_size<F, E>(
                 window.set_window_title(&title);
             })
             .expect("failed to update window");
+        renderer::first_window_opened();
+        cx.update(|cx| safe_mode::first_window_opened(cx))?;
+        cx.update(|cx| scripting::emit("window_opened", title.to_string(), cx))?;
 
         Ok::<_, anyhow::Error>(())
     })
@@ -241,7 +477,10 @@ pub fn create_new_window_with_size<F, E>(
 
 struct StoryRoot {
     title_bar: Entity<AppTitleBar>,
+    status_bar: Entity<AppStatusBar>,
+    lock_screen: Entity<LockScreen>,
     view: AnyView,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl StoryRoot {
@@ -252,9 +491,25 @@ impl StoryRoot {
         cx: &mut Context<Self>,
     ) -> Self {
         let title_bar = cx.new(|cx| AppTitleBar::new(title, window, cx));
+        let status_bar = cx.new(|cx| AppStatusBar::new(window, cx));
+        let lock_screen = cx.new(|cx| LockScreen::new(window, cx));
+        let _subscriptions = vec![
+            cx.subscribe(&IdleMonitor::global(cx), |_, _, _, cx| {
+                cx.notify();
+            }),
+            cx.observe(&AppLock::global(cx), |_, _, cx| cx.notify()),
+            cx.observe(&TaskScheduler::global(cx), |_, _, cx| cx.notify()),
+            cx.observe_window_appearance(window, |_, _, cx| appearance::sync_system_mode(cx)),
+            cx.observe_window_activation(window, |_, _, cx| Visibility::window_changed(cx)),
+            cx.observe_window_bounds(window, |_, _, cx| Visibility::window_changed(cx)),
+        ];
+
         Self {
             title_bar,
+            status_bar,
+            lock_screen,
             view: view.into(),
+            _subscriptions,
         }
     }
 }
@@ -264,26 +519,133 @@ impl Render for StoryRoot {
         let drawer_layer = Root::render_drawer_layer(window, cx);
         let modal_layer = Root::render_modal_layer(window, cx);
         let notification_layer = Root::render_notification_layer(window, cx);
+        let shortcuts_layer = shortcuts::render_layer(window, cx);
+        let confetti_layer = confetti::render_layer(window, cx);
+        Visibility::frame_drawn(cx);
+        let locked = AppLock::is_locked(cx);
+        let dimmed = Settings::global(cx).idle.dim && IdleMonitor::is_idle(cx) && !locked;
+        let wallpaper = Wallpaper::global(cx);
+        let wallpaper = wallpaper.read(cx);
+        let (wallpaper, tint) = (wallpaper.image(), wallpaper.tint());
 
         div()
+            .key_context(scopes::root_context(window, cx))
+            .on_action(|_: &Dismiss, window, cx| {
+                if !dismiss::dismiss(window, cx) {
+                    cx.propagate();
+                }
+            })
             .size_full()
+            .font(appearance::ui_font(cx))
+            .on_mouse_move(|_, _, cx| IdleMonitor::touch(cx))
+            .on_scroll_wheel(|_, _, cx| IdleMonitor::touch(cx))
+            .capture_any_mouse_down(|_, _, cx| IdleMonitor::touch(cx))
+            .capture_key_down(shortcuts::key_down)
+            .on_modifiers_changed(shortcuts::modifiers_changed)
+            .child(gestures::listener())
+            .when_some(wallpaper, |this, image| {
+                this.child(
+                    img(image)
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .object_fit(ObjectFit::Cover),
+                )
+            })
+            .when_some(tint, |this, tint| {
+                this.child(div().absolute().top_0().left_0().size_full().bg(tint))
+            })
             .child(
                 v_flex()
                     .size_full()
-                    .child(self.title_bar.clone())
-                    .child(div().flex_1().overflow_hidden().child(self.view.clone())),
+                    .when(!kiosk::enabled(), |this| this.child(self.title_bar.clone()))
+                    .child(AppProgress::new())
+                    .when_some(themes::theme_error(cx).cloned(), |this, err| {
+                        this.child(
+                            Alert::error(
+                                "theme-error",
+                                format!(
+                                    "{}:{}:{}: {}",
+                                    err.path.display(),
+                                    err.line,
+                                    err.column,
+                                    err.message
+                                ),
+                            )
+                            .title("Failed to load the active theme, the last good version is still used."),
+                        )
+                    })
+                    .when(safe_mode::enabled(), |this| {
+                        this.child(
+                            h_flex()
+                                .gap_2()
+                                .pr_2()
+                                .child(
+                                    div().flex_1().child(
+                                        Alert::warning(
+                                            "safe-mode",
+                                            "User themes, extensions and scripts are not loaded.",
+                                        )
+                                        .banner()
+                                        .title("Safe Mode"),
+                                    ),
+                                )
+                                .child(
+                                    Button::new("restart-normally")
+                                        .label("Restart Normally")
+                                        .on_click(|_, _, cx| safe_mode::restart_normally(cx)),
+                                ),
+                        )
+                    })
+                    .map(|this| {
+                        // Nothing of the content, not even in overlays, while locked.
+                        if locked {
+                            this.child(div().flex_1().child(self.lock_screen.clone()))
+                        } else {
+                            this.child(div().flex_1().overflow_hidden().child(self.view.clone()))
+                                .child(self.status_bar.clone())
+                        }
+                    }),
             )
-            .children(drawer_layer)
-            .children(modal_layer)
-            .children(notification_layer)
+            .when(!locked, |this| {
+                this.children(drawer_layer)
+                    .children(modal_layer)
+                    .children(notification_layer)
+                    .children(confetti_layer)
+                    .children(shortcuts_layer)
+            })
+            .when(dimmed, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .bg(gpui::black().opacity(0.4)),
+                )
+            })
+            .children(window_frame::resize_handles(window))
     }
 }
 
 impl Global for AppState {}
 
-pub fn init(cx: &mut App) {
+/// Logging and the components, all the startup recovery window needs before the rest is
+/// initialized.
+pub(crate) fn init_base(cx: &mut App) {
+    if cx.has_global::<gpui_component::Theme>() {
+        return;
+    }
+    let file_layer = logs::file().map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(file)
+    });
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .with(logs::RecentErrors)
         .with(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive("gpui_component=trace".parse().unwrap()),
@@ -291,8 +653,78 @@ pub fn init(cx: &mut App) {
         .init();
 
     gpui_component::init(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_base(cx);
     AppState::init(cx);
+    events::init(cx);
+    persistence::init(cx);
+    quit::init(cx);
+    shutdown::init(cx);
+    snapshots::init(cx);
+    factory_reset::init(cx);
+    whats_new::init(cx);
+    about::init(cx);
+    shortcuts::init(cx);
+    dismiss::init(cx);
+    secrets::init(cx);
+    watcher::init(cx);
+    history::init(cx);
+    encryption::load_key(cx);
+    settings::init(cx);
+    settings_editor::init(cx);
+    settings_window::init(cx);
+    encryption::init(cx);
+    session::init(cx);
+    profiles::init(cx);
+    instance::init(cx);
+    integration::init(cx);
+    idle::init(cx);
+    lock::init(cx);
+    power::init(cx);
+    popout::init(cx);
+    split::init(cx);
+    layouts::init(cx);
+    deep_links::init(cx);
+    net::init(cx);
+    net_settings::init(cx);
+    telemetry::init(cx);
+    extensions::init(cx);
+    extension_manager::init(cx);
+    tasks::init(cx);
+    notifications::init(cx);
+    taskbar::init(cx);
+    diagnostics::init(cx);
+    pointer::init(cx);
+    watchdog::init(cx);
+    memory::init(cx);
     themes::init(cx);
+    appearance::init(cx);
+    macos::init(cx);
+    window_frame::init(cx);
+    icons::init(cx);
+    sounds::init(cx);
+    spell_check::init(cx);
+    format::init(cx);
+    gestures::init(cx);
+    images::init(cx);
+    tinted_icons::init(cx);
+    wallpaper::init(cx);
+    cursor::init(cx);
+    skeletons::init(cx);
+    virtual_rows::init(cx);
+    tree_view::init(cx);
+    image_viewer::init(cx);
+    number_field::init(cx);
+    combobox::init(cx);
+    navigation::init(cx);
+    theme_diff::init(cx);
+    style_guide::init(cx);
+    #[cfg(any(feature = "inspector", debug_assertions))]
+    inspector::init(cx);
+    scripting::init(cx);
+    prototypes::init(cx);
     input_story::init(cx);
     number_input_story::init(cx);
     textarea_story::init(cx);
@@ -303,27 +735,26 @@ pub fn init(cx: &mut App) {
     tooltip_story::init(cx);
     otp_input_story::init(cx);
     tree_story::init(cx);
-
-    let http_client = std::sync::Arc::new(
-        reqwest_client::ReqwestClient::user_agent("gpui-component/story").unwrap(),
-    );
-    cx.set_http_client(http_client);
+    palette::init(cx);
 
     cx.bind_keys([
-        KeyBinding::new("/", ToggleSearch, None),
+        KeyBinding::new("/", ToggleSearch, Some(ShortcutScope::Global.context())),
         #[cfg(target_os = "macos")]
-        KeyBinding::new("cmd-o", Open, None),
+        KeyBinding::new("cmd-ctrl-l", LockApp, None),
         #[cfg(not(target_os = "macos"))]
-        KeyBinding::new("ctrl-o", Open, None),
+        KeyBinding::new("ctrl-shift-l", LockApp, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-o", Open, Some(ShortcutScope::Global.context())),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-o", Open, Some(ShortcutScope::Global.context())),
         #[cfg(target_os = "macos")]
         KeyBinding::new("cmd-q", Quit, None),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("alt-f4", Quit, None),
     ]);
-
-    cx.on_action(|_: &Quit, cx: &mut App| {
-        cx.quit();
-    });
+    scopes::init(cx);
+    macros::init(cx);
+    kiosk::init(cx);
 
     register_panel(cx, PANEL_NAME, |_, _, info, window, cx| {
         let story_state = match info {
@@ -464,6 +895,9 @@ pub struct StoryContainer {
     zoomable: Option<PanelControl>,
     paddings: Pixels,
     on_active: Option<fn(AnyView, bool, &mut Window, &mut App)>,
+    scroll_handle: ScrollHandle,
+    /// The scroll offset last written to the session.
+    saved_scroll: Point<Pixels>,
 }
 
 #[derive(Debug)]
@@ -537,6 +971,8 @@ impl StoryContainer {
             zoomable: Some(PanelControl::default()),
             paddings: px(16.),
             on_active: None,
+            scroll_handle: ScrollHandle::new(),
+            saved_scroll: Point::default(),
         }
     }
 
@@ -557,6 +993,7 @@ impl StoryContainer {
             story.description = description.into();
             story.title_bg = S::title_bg();
             story.paddings = S::paddings();
+            story.restore_scroll(cx);
             story
         });
 
@@ -584,6 +1021,28 @@ impl StoryContainer {
         self
     }
 
+    /// Scroll to where the story was when the app quit.
+    fn restore_scroll(&mut self, cx: &App) {
+        if let Some(state) = Session::global(cx).views.get(self.name.as_ref()) {
+            self.saved_scroll = state.scroll_offset();
+            self.scroll_handle.set_offset(self.saved_scroll);
+        }
+    }
+
+    /// Write the scroll offset to the session when it changed since the last frame.
+    fn save_scroll(&mut self, cx: &mut App) {
+        let offset = self.scroll_handle.offset();
+        if offset == self.saved_scroll {
+            return;
+        }
+        self.saved_scroll = offset;
+        let name = self.name.to_string();
+        // Keep drawing free of side effects, the session is written after the frame.
+        cx.defer(move |cx| {
+            Session::update(cx, |session| _ = session.views.insert(name, offset.into()))
+        });
+    }
+
     fn on_action_panel_info(
         &mut self,
         _: &ShowPanelInfo,
@@ -771,10 +1230,16 @@ impl Focusable for StoryContainer {
 }
 impl Render for StoryContainer {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.save_scroll(cx);
+
         v_flex()
             .id("story-container")
             .size_full()
             .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .on_scroll_wheel(cx.listener(|this, event, window, cx| {
+                input_settings::scroll(&this.scroll_handle, event, window, cx)
+            }))
             .track_focus(&self.focus_handle)
             .on_action(cx.listener(Self::on_action_panel_info))
             .on_action(cx.listener(Self::on_action_toggle_search))
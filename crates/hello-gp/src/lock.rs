@@ -0,0 +1,488 @@
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher as _, PasswordVerifier as _,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use gpui::{
+    App, AppContext as _, Context, Entity, Focusable as _, Global, IntoElement, ParentElement as _,
+    Render, SharedString, Styled as _, Subscription, Window, actions, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputEvent, InputState},
+    notification::Notification,
+    v_flex,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    idle::{IdleEvent, IdleMonitor},
+    settings::Settings,
+    text_field::TextField,
+};
+
+actions!(lock, [LockApp, SetLockPassphrase]);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LockSettings {
+    /// Argon2 hash of the passphrase that unlocks the app, set with "Set Lock Passphrase...".
+    pub passphrase_hash: Option<String>,
+    /// Also offer the OS to unlock: Touch ID (or the account password) on macOS, Windows
+    /// Hello, and polkit on Linux.
+    pub system_auth: bool,
+    /// Lock whenever the app goes idle, see `idle.threshold_minutes`. Only once there is a way
+    /// to unlock it again, a passphrase or the OS.
+    pub lock_on_idle: bool,
+}
+
+impl LockSettings {
+    fn can_lock(&self) -> bool {
+        self.passphrase_hash.is_some() || (self.system_auth && os_auth::available())
+    }
+
+    fn verify(&self, passphrase: &str) -> bool {
+        let Some(hash) = self.passphrase_hash.as_deref() else {
+            return false;
+        };
+        match PasswordHash::new(hash) {
+            Ok(hash) => Argon2::default()
+                .verify_password(passphrase.as_bytes(), &hash)
+                .is_ok(),
+            Err(err) => {
+                tracing::error!("Invalid lock passphrase hash: {}", err);
+                false
+            }
+        }
+    }
+}
+
+/// Whether the app can be locked and unlocked again: with the passphrase, or by the OS.
+pub(crate) fn can_lock(cx: &App) -> bool {
+    Settings::global(cx).lock.can_lock()
+}
+
+fn hash_passphrase(passphrase: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map_err(|err| anyhow::anyhow!("{}", err))?
+        .to_string())
+}
+
+struct GlobalAppLock(Entity<AppLock>);
+
+impl Global for GlobalAppLock {}
+
+/// Whether the windows hide their content behind the [`LockScreen`].
+pub struct AppLock {
+    locked: bool,
+}
+
+impl AppLock {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalAppLock>().0.clone()
+    }
+
+    pub fn is_locked(cx: &App) -> bool {
+        Self::global(cx).read(cx).locked
+    }
+
+    /// Lock all windows, or ask for a passphrase first when there is no way to unlock yet.
+    pub fn lock(cx: &mut App) {
+        if !Settings::global(cx).lock.can_lock() {
+            if let Some(window) = cx.active_window() {
+                _ = window.update(cx, |_, window, cx| open_passphrase_dialog(window, cx));
+            }
+            return;
+        }
+
+        tracing::info!("Locking the app");
+        Self::set_locked(true, cx);
+    }
+
+    fn set_locked(locked: bool, cx: &mut App) {
+        Self::global(cx).update(cx, |this, cx| {
+            if this.locked != locked {
+                this.locked = locked;
+                cx.notify();
+            }
+        });
+    }
+}
+
+/// What a locked window shows instead of its content, with the passphrase prompt.
+///
+/// GPUI can't blur a subtree, so the content isn't rendered at all while locked, which
+/// also keeps it out of screenshots and screen sharing.
+pub struct LockScreen {
+    input: Entity<InputState>,
+    error: Option<SharedString>,
+    authenticating: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LockScreen {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .masked(true)
+                .placeholder("Passphrase")
+        });
+        let _subscriptions = vec![
+            cx.subscribe_in(&input, window, |this, _, event, window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.unlock_with_passphrase(window, cx);
+                }
+            }),
+            cx.observe_in(&AppLock::global(cx), window, |this, lock, window, cx| {
+                if lock.read(cx).locked {
+                    window.focus(&this.input.focus_handle(cx));
+                }
+                cx.notify();
+            }),
+        ];
+
+        Self {
+            input,
+            error: None,
+            authenticating: false,
+            _subscriptions,
+        }
+    }
+
+    fn unlock_with_passphrase(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let passphrase = self.input.read(cx).value().to_string();
+        if !Settings::global(cx).lock.verify(&passphrase) {
+            self.error = Some("Wrong passphrase.".into());
+            cx.notify();
+            return;
+        }
+
+        self.unlocked(window, cx);
+    }
+
+    fn unlock_with_system(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.authenticating = true;
+        self.error = None;
+        let verified = os_auth::authenticate("unlock hello-gp".into());
+        cx.spawn_in(window, async move |this, cx| {
+            let verified = verified.await.unwrap_or(false);
+            _ = this.update_in(cx, |this, window, cx| {
+                this.authenticating = false;
+                if verified {
+                    this.unlocked(window, cx);
+                } else {
+                    this.error = Some("Authentication failed.".into());
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn unlocked(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.error = None;
+        // Don't leave the passphrase around for the next lock.
+        self.input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        AppLock::set_locked(false, cx);
+    }
+}
+
+impl Render for LockScreen {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = Settings::global(cx).lock.clone();
+        let system_auth = settings.system_auth && os_auth::available();
+
+        v_flex()
+            .size_full()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().background)
+            .child(
+                v_flex()
+                    .w(px(320.))
+                    .gap_3()
+                    .items_center()
+                    .child(Icon::new(IconName::EyeOff).size_8())
+                    .child(div().text_lg().font_semibold().child("Locked"))
+                    .when(settings.passphrase_hash.is_some(), |this| {
+                        this.child(TextField::new("passphrase", &self.input)).child(
+                            Button::new("unlock")
+                                .primary()
+                                .w_full()
+                                .label("Unlock")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.unlock_with_passphrase(window, cx)
+                                })),
+                        )
+                    })
+                    .when(system_auth, |this| {
+                        this.child(
+                            Button::new("unlock-system")
+                                .outline()
+                                .w_full()
+                                .label(os_auth::LABEL)
+                                .loading(self.authenticating)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.unlock_with_system(window, cx)
+                                })),
+                        )
+                    })
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+                    }),
+            )
+    }
+}
+
+/// Set, change or remove the passphrase that unlocks the app.
+fn open_passphrase_dialog(window: &mut Window, cx: &mut App) {
+    let passphrase = cx.new(|cx| {
+        InputState::new(window, cx)
+            .masked(true)
+            .placeholder("New passphrase")
+    });
+    let confirm = cx.new(|cx| {
+        InputState::new(window, cx)
+            .masked(true)
+            .placeholder("Repeat the passphrase")
+    });
+
+    window.open_modal(cx, move |modal, _, cx| {
+        let has_passphrase = Settings::global(cx).lock.passphrase_hash.is_some();
+        let (passphrase, confirm) = (passphrase.clone(), confirm.clone());
+
+        modal.title("Lock Passphrase").child(
+            v_flex()
+                .gap_3()
+                .child(TextField::new("new-passphrase", &passphrase))
+                .child(TextField::new("confirm-passphrase", &confirm))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .justify_end()
+                        .when(has_passphrase, |this| {
+                            this.child(
+                                Button::new("remove-passphrase")
+                                    .small()
+                                    .ghost()
+                                    .label("Remove")
+                                    .on_click(|_, window, cx| {
+                                        Settings::update(cx, |settings, _| {
+                                            settings.lock.passphrase_hash = None
+                                        });
+                                        window.close_modal(cx);
+                                    }),
+                            )
+                        })
+                        .child(
+                            Button::new("save-passphrase")
+                                .small()
+                                .primary()
+                                .label("Save")
+                                .on_click(move |_, window, cx| {
+                                    let value = passphrase.read(cx).value().to_string();
+                                    if value.is_empty() || value != confirm.read(cx).value() {
+                                        window.push_notification(
+                                            Notification::warning(
+                                                "The passphrases are empty or don't match.",
+                                            ),
+                                            cx,
+                                        );
+                                        return;
+                                    }
+
+                                    match hash_passphrase(&value) {
+                                        Ok(hash) => {
+                                            Settings::update(cx, |settings, _| {
+                                                settings.lock.passphrase_hash = Some(hash)
+                                            });
+                                            window.close_modal(cx);
+                                            window.push_notification(
+                                                Notification::success("Saved the lock passphrase."),
+                                                cx,
+                                            );
+                                        }
+                                        Err(err) => {
+                                            tracing::error!("Failed to hash passphrase: {}", err)
+                                        }
+                                    }
+                                }),
+                        ),
+                ),
+        )
+    });
+}
+
+#[cfg(target_os = "linux")]
+mod os_auth {
+    use futures::channel::oneshot;
+
+    use std::sync::OnceLock;
+
+    pub(super) const LABEL: &str = "Unlock with System Password";
+
+    /// Whether pkexec can actually ask for the password: it's installed, a polkit agent runs
+    /// to show its prompt, and the user may authenticate as an admin. Without all three, the
+    /// user couldn't unlock the app again. Checked once, it scans the running processes.
+    pub(super) fn available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| {
+            let available = has_pkexec() && has_polkit_agent() && is_admin();
+            if !available {
+                tracing::info!("polkit can't unlock the app, a passphrase is needed");
+            }
+            available
+        })
+    }
+
+    fn has_pkexec() -> bool {
+        std::env::var_os("PATH").is_some_and(|path| {
+            std::env::split_paths(&path).any(|dir| dir.join("pkexec").is_file())
+        })
+    }
+
+    /// The agents of the desktops run as their own process, or in GNOME Shell.
+    fn has_polkit_agent() -> bool {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return false;
+        };
+        entries.flatten().any(|entry| {
+            let Ok(comm) = std::fs::read_to_string(entry.path().join("comm")) else {
+                return false;
+            };
+            let comm = comm.trim();
+            (comm.contains("polkit") && comm != "polkitd") || comm == "gnome-shell"
+        })
+    }
+
+    /// The default polkit rules authenticate as a member of one of the admin groups.
+    fn is_admin() -> bool {
+        std::process::Command::new("id")
+            .arg("-Gn")
+            .output()
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .any(|group| matches!(group, "root" | "sudo" | "wheel" | "admin"))
+            })
+    }
+
+    /// polkit has no generic "is this the user" check, so ask it to authorize a no-op.
+    pub(super) fn authenticate(_reason: String) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        std::thread::spawn(move || {
+            let status = std::process::Command::new("pkexec").arg("true").status();
+            _ = tx.send(status.is_ok_and(|status| status.success()));
+        });
+        rx
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod os_auth {
+    use std::sync::Mutex;
+
+    use block2::RcBlock;
+    use futures::channel::oneshot;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSError, NSString};
+    use objc2_local_authentication::{LAContext, LAPolicy};
+
+    pub(super) const LABEL: &str = "Unlock with Touch ID";
+
+    pub(super) fn available() -> bool {
+        true
+    }
+
+    /// Touch ID, falling back to the account password on Macs without it.
+    pub(super) fn authenticate(reason: String) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let reply = RcBlock::new(move |success: Bool, _: *mut NSError| {
+            if let Some(tx) = tx.lock().ok().and_then(|mut tx| tx.take()) {
+                _ = tx.send(success.as_bool());
+            }
+        });
+
+        // SAFETY: the context and the block outlive the call, the block is copied by it.
+        unsafe {
+            let context = LAContext::new();
+            context.evaluatePolicy_localizedReason_reply(
+                LAPolicy::DeviceOwnerAuthentication,
+                &NSString::from_str(&reason),
+                &reply,
+            );
+        }
+        rx
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod os_auth {
+    use futures::channel::oneshot;
+    use windows::{
+        Security::Credentials::UI::{UserConsentVerificationResult, UserConsentVerifier},
+        core::HSTRING,
+    };
+
+    pub(super) const LABEL: &str = "Unlock with Windows Hello";
+
+    pub(super) fn available() -> bool {
+        true
+    }
+
+    pub(super) fn authenticate(reason: String) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        std::thread::spawn(move || {
+            let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+                .and_then(|operation| operation.get());
+            _ = tx.send(matches!(
+                result,
+                Ok(UserConsentVerificationResult::Verified)
+            ));
+        });
+        rx
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod os_auth {
+    use futures::channel::oneshot;
+
+    pub(super) const LABEL: &str = "";
+
+    pub(super) fn available() -> bool {
+        false
+    }
+
+    pub(super) fn authenticate(_reason: String) -> oneshot::Receiver<bool> {
+        oneshot::channel().1
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let lock = cx.new(|_| AppLock { locked: false });
+    cx.set_global(GlobalAppLock(lock));
+
+    cx.on_action(|_: &LockApp, cx| AppLock::lock(cx));
+    cx.on_action(|_: &SetLockPassphrase, cx| {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_, window, cx| open_passphrase_dialog(window, cx));
+        }
+    });
+
+    cx.subscribe(&IdleMonitor::global(cx), |_, event, cx| {
+        let settings = &Settings::global(cx).lock;
+        if *event == IdleEvent::Idle && settings.lock_on_idle && settings.can_lock() {
+            AppLock::lock(cx);
+        }
+    })
+    .detach();
+}
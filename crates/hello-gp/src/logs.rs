@@ -0,0 +1,92 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    fs::File,
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use chrono::Local;
+use gpui::App;
+use tracing::{Event, Level, Subscriber, field::Field};
+use tracing_subscriber::{Layer, layer::Context};
+
+use crate::themes::get_data_dir;
+
+const LOG_FILE: &str = "hello-gp.log";
+/// The log of the last run, which has the crash when the app recovers from one.
+const PREVIOUS_LOG_FILE: &str = "hello-gp.previous.log";
+/// How many warnings and errors [`recent_errors`] keeps.
+const MAX_RECENT_ERRORS: usize = 20;
+
+static RECENT_ERRORS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+pub fn dir() -> PathBuf {
+    get_data_dir().join("logs")
+}
+
+/// The file the log of this run is written to, the log of the last run is kept next to it.
+pub(crate) fn file() -> Option<Mutex<File>> {
+    let dir = dir();
+    std::fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(LOG_FILE);
+    _ = std::fs::rename(&path, dir.join(PREVIOUS_LOG_FILE));
+    File::create(path).ok().map(Mutex::new)
+}
+
+/// Show the logs in the file manager.
+pub fn open(cx: &App) {
+    let dir = dir();
+    _ = std::fs::create_dir_all(&dir);
+    cx.open_with_system(&dir);
+}
+
+/// The message of an event followed by its other fields, e.g. `Failed to load path=a.toml`.
+#[derive(Default)]
+struct EventText(String);
+
+impl tracing::field::Visit for EventText {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            _ = write!(self.0, "{:?}", value);
+        } else {
+            _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Keeps the last warnings and errors in memory, for bug reports.
+pub(crate) struct RecentErrors;
+
+impl<S: Subscriber> Layer<S> for RecentErrors {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let metadata = event.metadata();
+        if *metadata.level() > Level::WARN {
+            return;
+        }
+        let mut text = EventText::default();
+        event.record(&mut text);
+
+        let Ok(mut recent) = RECENT_ERRORS.lock() else {
+            return;
+        };
+        if recent.len() == MAX_RECENT_ERRORS {
+            recent.pop_front();
+        }
+        recent.push_back(format!(
+            "{} {} {}: {}",
+            Local::now().format("%H:%M:%S"),
+            metadata.level(),
+            metadata.target(),
+            text.0
+        ));
+    }
+}
+
+/// The last warnings and errors of this run, oldest first.
+pub fn recent_errors() -> Vec<String> {
+    RECENT_ERRORS
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
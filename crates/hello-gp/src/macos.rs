@@ -0,0 +1,135 @@
+use gpui::{App, KeyBinding, MenuItem, Pixels, Size, Window, actions, px};
+use gpui_component::ActiveTheme as _;
+
+use crate::events::{self, ThemeChanged};
+
+actions!(window, [Minimize, Zoom, ToggleFullScreen, BringAllToFront]);
+
+/// Width of the strip of recent apps Stage Manager keeps on the left of the screen.
+const STAGE_STRIP_WIDTH: Pixels = px(180.);
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use gpui_component::ThemeMode;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::{
+        NSAppearance, NSAppearanceCustomization as _, NSAppearanceNameAqua,
+        NSAppearanceNameDarkAqua, NSApplication,
+    };
+    use objc2_foundation::{NSNumber, NSString, NSUserDefaults};
+
+    /// Force the native chrome (title bar, menus, panels) to the mode of the theme, like
+    /// `NSRequiresAquaSystemAppearance` does for light, rather than following the system.
+    pub(super) fn set_appearance(mode: ThemeMode) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        // SAFETY: the appearance names are constants of AppKit.
+        let name = unsafe {
+            match mode {
+                ThemeMode::Light => NSAppearanceNameAqua,
+                ThemeMode::Dark => NSAppearanceNameDarkAqua,
+            }
+        };
+        let appearance = NSAppearance::appearanceNamed(name);
+        NSApplication::sharedApplication(mtm).setAppearance(appearance.as_deref());
+    }
+
+    pub(super) fn bring_all_to_front() {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        // SAFETY: `arrangeInFront:` takes any sender, including none.
+        unsafe { NSApplication::sharedApplication(mtm).arrangeInFront(None) };
+    }
+
+    /// Whether Stage Manager is on, as set in the Desktop & Dock settings.
+    pub(super) fn stage_manager_enabled() -> bool {
+        let defaults = NSUserDefaults::standardUserDefaults();
+        let Some(domain) =
+            defaults.persistentDomainForName(&NSString::from_str("com.apple.WindowManager"))
+        else {
+            return false;
+        };
+        domain
+            .objectForKey(&NSString::from_str("GloballyEnabled"))
+            .and_then(|value| value.downcast::<NSNumber>().ok())
+            .is_some_and(|value| value.boolValue())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use gpui_component::ThemeMode;
+
+    pub(super) fn set_appearance(_: ThemeMode) {}
+
+    pub(super) fn bring_all_to_front() {}
+
+    pub(super) fn stage_manager_enabled() -> bool {
+        false
+    }
+}
+
+/// The space a new window may take on a display of `size`, leaving the Stage Manager strip
+/// uncovered on both sides of a centered window.
+pub(crate) fn usable_size(mut size: Size<Pixels>) -> Size<Pixels> {
+    if platform::stage_manager_enabled() {
+        size.width = (size.width - STAGE_STRIP_WIDTH * 2.).max(size.width / 2.);
+    }
+    size
+}
+
+/// The standard items of the Window menu. AppKit adds the list of open windows below them.
+pub(crate) fn window_menu_items() -> Vec<MenuItem> {
+    let mut items = vec![
+        MenuItem::action("Minimize", Minimize),
+        MenuItem::action("Zoom", Zoom),
+        MenuItem::action("Toggle Full Screen", ToggleFullScreen),
+    ];
+    if cfg!(target_os = "macos") {
+        items.extend([
+            MenuItem::separator(),
+            MenuItem::action("Bring All to Front", BringAllToFront),
+        ]);
+    }
+    items
+}
+
+pub(crate) fn with_active_window(cx: &mut App, f: impl FnOnce(&mut Window)) {
+    if let Some(window) = cx.active_window() {
+        _ = window.update(cx, |_, window, _| f(window));
+    }
+}
+
+/// Show the windows again when the dock icon is clicked with none of them visible. Opens a
+/// window with `open` when every window was closed.
+pub fn reopen(cx: &mut App, open: impl FnOnce(&mut App)) {
+    let Some(window) = cx.windows().into_iter().next() else {
+        open(cx);
+        return;
+    };
+    // Ordering a minimized window to the front also restores it.
+    _ = window.update(cx, |_, window, _| window.activate_window());
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &Minimize, cx| with_active_window(cx, |window| window.minimize_window()));
+    cx.on_action(|_: &Zoom, cx| with_active_window(cx, |window| window.zoom_window()));
+    cx.on_action(|_: &ToggleFullScreen, cx| {
+        with_active_window(cx, |window| window.toggle_fullscreen())
+    });
+    cx.on_action(|_: &BringAllToFront, _| platform::bring_all_to_front());
+
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-m", Minimize, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("ctrl-cmd-f", ToggleFullScreen, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("f11", ToggleFullScreen, None),
+    ]);
+
+    platform::set_appearance(cx.theme().mode);
+    events::subscribe::<ThemeChanged>(cx, |event, _| platform::set_appearance(event.mode)).detach();
+}
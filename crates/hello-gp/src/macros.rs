@@ -0,0 +1,316 @@
+//! Macros: the actions run while recording, by shortcut or from the command palette, replayed
+//! in order on demand. Lightweight automation of what's done over and over, without writing a
+//! script.
+//!
+//! They're persisted as `macros.json5` in the config dir, and each can have a shortcut:
+//!
+//! ```json5
+//! {
+//!   macros: [
+//!     { name: "Side by side", keys: "ctrl-alt-1", actions: ["split::SplitRight", "story::ToggleSearch"] },
+//!   ],
+//! }
+//! ```
+
+use gpui::{
+    Action, App, AppContext as _, Global, KeyBinding, KeystrokeEvent, ParentElement as _,
+    SharedString, Styled as _, Window, actions,
+};
+use gpui_component::{
+    ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::InputState,
+    notification::Notification,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{self, ConfigFormat},
+    deep_links, kiosk,
+    palette::Commands,
+    scopes::{self, ShortcutScope},
+    text_field::TextField,
+    themes::get_config_dir,
+};
+
+actions!(macros, [ToggleMacroRecording, ReplayLastMacro]);
+
+/// Replay the macro with this name.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = macros, no_json)]
+pub struct ReplayMacro(pub SharedString);
+
+pub(crate) const MACROS_NAME: &str = "macros";
+
+/// Namespaces of the actions left out of recordings: editing text and moving through lists act
+/// on what's focused at the time, and the command run from the palette is recorded rather than
+/// opening it.
+const NOT_RECORDED: &[&str] = &["macros::", "palette::", "dismiss::", "input::", "list::"];
+
+/// A recorded sequence of actions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Macro {
+    pub name: String,
+    /// A shortcut replaying it anywhere in the window, e.g. `ctrl-alt-1`.
+    pub keys: Option<String>,
+    /// The names of the actions, in the order they ran.
+    pub actions: Vec<String>,
+}
+
+/// The saved macros.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Macros {
+    pub macros: Vec<Macro>,
+}
+
+/// The recording in progress, and the last one, replayed by [`ReplayLastMacro`].
+#[derive(Default)]
+struct MacroRecorder {
+    recording: Option<Vec<String>>,
+    last: Vec<String>,
+}
+
+impl Global for Macros {}
+impl Global for MacroRecorder {}
+
+impl Macros {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Macro> {
+        self.macros.iter().find(|m| m.name == name)
+    }
+
+    /// Add `m`, replacing the one of the same name, and write the macros back to disk.
+    pub fn save(m: Macro, cx: &mut App) {
+        let mut macros = Self::global(cx).clone();
+        match macros
+            .macros
+            .iter_mut()
+            .find(|existing| existing.name == m.name)
+        {
+            Some(existing) => *existing = m,
+            None => {
+                Commands::register(
+                    format!("Macro: {}", m.name),
+                    ReplayMacro(m.name.clone().into()),
+                    cx,
+                );
+                macros.macros.push(m);
+            }
+        }
+
+        if let Err(err) =
+            config::save_as(&get_config_dir(), MACROS_NAME, &macros, ConfigFormat::Json5)
+        {
+            tracing::error!("Failed to save macros: {}", err);
+        }
+        cx.set_global(macros);
+        rebind(cx);
+    }
+}
+
+/// Whether a macro is being recorded.
+pub fn is_recording(cx: &App) -> bool {
+    cx.global::<MacroRecorder>().recording.is_some()
+}
+
+/// Add `action` to the recording, if there's one. Actions that need data can't be replayed by
+/// name, they're left out, and so are the [`NOT_RECORDED`] ones.
+pub fn record(action: &dyn Action, cx: &mut App) {
+    let name = action.name();
+    if !is_recording(cx) || NOT_RECORDED.iter().any(|ns| name.starts_with(ns)) {
+        return;
+    }
+    if cx.build_action(name, None).is_err() {
+        tracing::debug!("Not recording {}, it takes data", name);
+        return;
+    }
+    if let Some(recording) = &mut cx.global_mut::<MacroRecorder>().recording {
+        recording.push(name.to_string());
+    }
+}
+
+/// Run the actions of a macro in the active window, in order.
+pub fn replay(actions: &[String], cx: &mut App) {
+    for name in actions {
+        match cx.build_action(name, None) {
+            Ok(action) if kiosk::allows(action.as_ref(), cx) => deep_links::dispatch(action, cx),
+            Ok(_) => tracing::warn!("{} isn't allowed in kiosk mode", name),
+            Err(err) => tracing::error!("Invalid macro action {}: {}", name, err),
+        }
+    }
+}
+
+fn toggle_recording(window: &mut Window, cx: &mut App) {
+    let recorder = cx.global_mut::<MacroRecorder>();
+    let Some(actions) = recorder.recording.take() else {
+        recorder.recording = Some(vec![]);
+        window.push_notification(
+            Notification::info("Recording a macro, run the actions and stop the recording."),
+            cx,
+        );
+        return;
+    };
+    if actions.is_empty() {
+        window.push_notification(Notification::info("Nothing was recorded."), cx);
+        return;
+    }
+    recorder.last = actions.clone();
+    open_save_dialog(actions, window, cx);
+}
+
+/// Ask for a name and save the recorded `actions` under it. Unnamed they're still replayed by
+/// [`ReplayLastMacro`].
+fn open_save_dialog(actions: Vec<String>, window: &mut Window, cx: &mut App) {
+    let input = cx.new(|cx| InputState::new(window, cx).placeholder("Macro name"));
+    let keys = cx.new(|cx| InputState::new(window, cx).placeholder("Shortcut, e.g. ctrl-alt-1"));
+
+    window.open_modal(cx, move |modal, _, _| {
+        let input = input.clone();
+        let keys = keys.clone();
+        let actions = actions.clone();
+        modal
+            .title(format!("Save Macro of {} Actions", actions.len()))
+            .child(
+                v_flex()
+                    .gap_3()
+                    .child(TextField::new("macro-name", &input))
+                    .child(TextField::new("macro-keys", &keys))
+                    .child(
+                        h_flex().justify_end().child(
+                            Button::new("save-macro")
+                                .small()
+                                .primary()
+                                .label("Save")
+                                .on_click(move |_, window, cx| {
+                                    let name = input.read(cx).value().trim().to_string();
+                                    if name.is_empty() {
+                                        return;
+                                    }
+                                    let keys = keys.read(cx).value().trim().to_string();
+
+                                    Macros::save(
+                                        Macro {
+                                            name: name.clone(),
+                                            keys: (!keys.is_empty()).then_some(keys),
+                                            actions: actions.clone(),
+                                        },
+                                        cx,
+                                    );
+                                    window.close_modal(cx);
+                                    window.push_notification(
+                                        Notification::success(format!(
+                                            "Saved the macro \"{}\".",
+                                            name
+                                        )),
+                                        cx,
+                                    );
+                                }),
+                        ),
+                    ),
+            )
+    });
+}
+
+/// The key bindings of the macros with a shortcut.
+fn key_bindings(macros: &Macros, cx: &App) -> Vec<KeyBinding> {
+    macros
+        .macros
+        .iter()
+        .filter_map(|m| {
+            let keys = m.keys.as_ref()?;
+            let action = Box::new(ReplayMacro(m.name.clone().into()));
+            scopes::key_binding(keys, action, ShortcutScope::Global.context(), cx)
+                .inspect_err(|err| {
+                    tracing::error!(
+                        "Invalid shortcut {} for the macro {}: {}",
+                        keys,
+                        m.name,
+                        err
+                    )
+                })
+                .ok()
+        })
+        .collect()
+}
+
+/// Replace the key bindings of the macros with those of the saved ones, so a changed or
+/// cleared shortcut no longer replays them. The keymap can't drop single bindings, it's
+/// rebuilt like [`kiosk::init`] does.
+fn rebind(cx: &mut App) {
+    let mut bindings = cx
+        .key_bindings()
+        .borrow()
+        .bindings()
+        .filter(|binding| !binding.action().as_any().is::<ReplayMacro>())
+        .cloned()
+        .collect::<Vec<_>>();
+    bindings.extend(
+        key_bindings(Macros::global(cx), cx)
+            .into_iter()
+            .filter(|binding| kiosk::allows(binding.action(), cx)),
+    );
+    cx.clear_key_bindings();
+    cx.bind_keys(bindings);
+}
+
+pub fn init(cx: &mut App) {
+    let macros = config::load::<Macros>(&get_config_dir(), MACROS_NAME).unwrap_or_else(|err| {
+        tracing::error!("Failed to load macros: {}", err);
+        Macros::default()
+    });
+    for m in &macros.macros {
+        Commands::register(
+            format!("Macro: {}", m.name),
+            ReplayMacro(m.name.clone().into()),
+            cx,
+        );
+    }
+    cx.bind_keys(key_bindings(&macros, cx));
+    cx.set_global(macros);
+    cx.set_global(MacroRecorder::default());
+
+    Commands::register("Macros: Start or Stop Recording", ToggleMacroRecording, cx);
+    Commands::register("Macros: Replay Last Recording", ReplayLastMacro, cx);
+
+    cx.observe_keystrokes(|event: &KeystrokeEvent, _, cx| {
+        if let Some(action) = &event.action {
+            record(action.as_ref(), cx);
+        }
+    })
+    .detach();
+
+    cx.on_action(|_: &ToggleMacroRecording, cx| {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_, window, cx| toggle_recording(window, cx));
+        }
+    });
+    cx.on_action(|_: &ReplayLastMacro, cx| {
+        let actions = cx.global::<MacroRecorder>().last.clone();
+        replay(&actions, cx);
+    });
+    cx.on_action(|action: &ReplayMacro, cx| {
+        let Some(m) = Macros::global(cx).get(&action.0).cloned() else {
+            tracing::warn!("There's no macro {}", action.0);
+            return;
+        };
+        replay(&m.actions, cx);
+    });
+
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-r", ToggleMacroRecording, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-alt-r", ToggleMacroRecording, None),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-alt-p", ReplayLastMacro, None),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-alt-p", ReplayLastMacro, None),
+    ]);
+}
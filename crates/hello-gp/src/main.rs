@@ -1,6 +1,9 @@
+use futures::StreamExt as _;
 use gpui::{prelude::*, *};
 use gpui_component::{
-    ActiveTheme as _, Icon, IconName, h_flex,
+    ActiveTheme as _, Icon, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
     input::{InputEvent, InputState, TextInput},
     resizable::{ResizableState, h_resizable, resizable_panel},
     sidebar::{Sidebar, SidebarGroup, SidebarHeader, SidebarMenu, SidebarMenuItem},
@@ -9,27 +12,39 @@ use gpui_component::{
 use hello_gp as story;
 use story::*;
 
+const COLLAPSED_SIDEBAR_WIDTH: Pixels = px(48.);
+
 pub struct Gallery {
     stories: Vec<(&'static str, Vec<Entity<StoryContainer>>)>,
     active_group_index: Option<usize>,
     active_index: Option<usize>,
     collapsed: bool,
+    sidebar_width: Pixels,
     search_input: Entity<InputState>,
     sidebar_state: Entity<ResizableState>,
+    split: Entity<SplitView>,
     _subscriptions: Vec<Subscription>,
 }
 
 impl Gallery {
     pub fn new(init_story: Option<&str>, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search..."));
-        let _subscriptions = vec![cx.subscribe(&search_input, |this, _, e, cx| match e {
-            InputEvent::Change => {
-                this.active_group_index = Some(0);
-                this.active_index = Some(0);
-                cx.notify()
-            }
-            _ => {}
-        })];
+        let mut _subscriptions = vec![
+            cx.subscribe(&search_input, |this, _, e, cx| match e {
+                InputEvent::Change => {
+                    this.active_group_index = Some(0);
+                    this.active_index = Some(0);
+                    this.show_active_story(cx);
+                    cx.notify()
+                }
+                _ => {}
+            }),
+            cx.observe(&Popouts::global(cx), |_, _, cx| cx.notify()),
+            cx.observe_window_bounds(window, |_, window, cx| {
+                Popouts::set_dock_target(window.bounds(), cx)
+            }),
+        ];
+        Popouts::set_dock_target(window.bounds(), cx);
         let stories = vec![
             (
                 "Getting Started",
@@ -86,16 +101,35 @@ impl Gallery {
             ),
         ];
 
+        let panels = stories
+            .iter()
+            .flat_map(|(_, stories)| stories.iter().cloned())
+            .collect::<Vec<_>>();
+        let split = SplitView::view(&panels, window, cx);
+        _subscriptions.push(cx.observe(&split, |_, _, cx| cx.notify()));
+        Popouts::restore(&panels, cx);
+
+        // The sidebar as the active profile left it.
+        let profile = ProfileLayout::default();
+        let profile = story::active_profile_layout(cx).unwrap_or(&profile);
+        let (collapsed, sidebar_width) = (!profile.sidebar, px(profile.sidebar_width));
+
         let mut this = Self {
             search_input,
             stories,
+            split,
             active_group_index: Some(0),
             active_index: Some(0),
-            collapsed: false,
+            collapsed,
+            sidebar_width,
             sidebar_state: ResizableState::new(cx),
             _subscriptions,
         };
 
+        // A restored layout keeps its stories, otherwise show the first one.
+        if this.split.read(cx).active_story().is_none() {
+            this.show_active_story(cx);
+        }
         if let Some(init_story) = init_story {
             this.set_active_story(init_story, window, cx);
         }
@@ -103,24 +137,11 @@ impl Gallery {
         this
     }
 
-    fn set_active_story(&mut self, name: &str, window: &mut Window, cx: &mut App) {
-        let name = name.to_string();
-        self.search_input.update(cx, |this, cx| {
-            this.set_value(&name, window, cx);
-        })
-    }
-
-    fn view(init_story: Option<&str>, window: &mut Window, cx: &mut App) -> Entity<Self> {
-        cx.new(|cx| Self::new(init_story, window, cx))
-    }
-}
-
-impl Render for Gallery {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    /// The story groups matching the search query.
+    fn filtered_stories(&self, cx: &App) -> Vec<(&'static str, Vec<Entity<StoryContainer>>)> {
         let query = self.search_input.read(cx).value().trim().to_lowercase();
 
-        let stories: Vec<_> = self
-            .stories
+        self.stories
             .iter()
             .filter_map(|(name, items)| {
                 let filtered_items: Vec<_> = items
@@ -130,179 +151,422 @@ impl Render for Gallery {
                     .collect();
 
                 if !filtered_items.is_empty() {
-                    Some((name, filtered_items))
+                    Some((*name, filtered_items))
                 } else {
                     None
                 }
             })
-            .collect();
+            .collect()
+    }
+
+    /// Show the story selected in the sidebar in the focused split.
+    fn show_active_story(&mut self, cx: &mut Context<Self>) {
+        let stories = self.filtered_stories(cx);
+        let story = self
+            .active_group_index
+            .and_then(|index| stories.get(index))
+            .zip(self.active_index)
+            .and_then(|(group, index)| group.1.get(index).cloned());
+        if let Some(story) = story {
+            self.split
+                .update(cx, |split, cx| split.set_story(story, cx));
+        }
+    }
+
+    fn apply_layout_preset(
+        &mut self,
+        action: &ApplyLayoutPreset,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(preset) = LayoutPresets::global(cx).get(&action.0).cloned() else {
+            return;
+        };
+        LayoutPresets::mark_used(&preset.name, cx);
+
+        self.collapsed = !preset.sidebar;
+        self.sidebar_width = px(preset.sidebar_width);
+        // A fresh state, so the panel starts out at the preset's size.
+        self.sidebar_state = ResizableState::new(cx);
+        let stories = self
+            .stories
+            .iter()
+            .flat_map(|(_, stories)| stories.iter().cloned())
+            .collect::<Vec<_>>();
+        self.split.update(cx, |split, cx| {
+            split.set_layout(&preset.split, &stories, cx)
+        });
+        if self.split.read(cx).active_story().is_none() {
+            self.show_active_story(cx);
+        }
+        cx.notify();
+    }
+
+    /// The width of the expanded sidebar, as last resized.
+    fn current_sidebar_width(&self, cx: &App) -> Pixels {
+        self.sidebar_state
+            .read(cx)
+            .sizes()
+            .first()
+            .copied()
+            .filter(|_| !self.collapsed)
+            .unwrap_or(self.sidebar_width)
+    }
 
-        let active_group = self.active_group_index.and_then(|index| stories.get(index));
-        let active_story = self
-            .active_index
-            .and(active_group)
-            .and_then(|group| group.1.get(self.active_index.unwrap()));
+    fn save_layout_preset(
+        &mut self,
+        _: &SaveLayoutPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let preset = LayoutPreset {
+            name: String::new(),
+            sidebar: !self.collapsed,
+            sidebar_width: self.current_sidebar_width(cx).as_f32(),
+            split: self.split.read(cx).layout(cx),
+        };
+        open_save_layout_dialog(preset, window, cx);
+    }
+
+    /// Keep the layout for the active profile, and show the one of the profile switched to:
+    /// the sidebar, the splits and the panels popped out, all at once.
+    fn switch_profile(&mut self, action: &SwitchProfile, _: &mut Window, cx: &mut Context<Self>) {
+        let panels = self
+            .stories
+            .iter()
+            .flat_map(|(_, stories)| stories.iter().cloned())
+            .collect::<Vec<_>>();
+        let next = story::switch_profile(
+            &action.0,
+            !self.collapsed,
+            self.current_sidebar_width(cx).as_f32(),
+            cx,
+        );
+
+        self.collapsed = !next.sidebar;
+        self.sidebar_width = px(next.sidebar_width);
+        self.sidebar_state = ResizableState::new(cx);
+        for panel in &panels {
+            let name = panel.read(cx).name.clone();
+            let popped_out = Popouts::is_popped_out(&name, cx);
+            if next.popouts.contains(name.as_ref()) && !popped_out {
+                Popouts::pop_out(panel.clone(), cx);
+            } else if !next.popouts.contains(name.as_ref()) && popped_out {
+                Popouts::dock(&name, cx);
+            }
+        }
+        let split = next.split.unwrap_or_default();
+        self.split
+            .update(cx, |this, cx| this.set_layout(&split, &panels, cx));
+        if self.split.read(cx).active_story().is_none() {
+            self.show_active_story(cx);
+        }
+        cx.notify();
+    }
+
+    fn new_profile(&mut self, _: &NewProfile, window: &mut Window, cx: &mut Context<Self>) {
+        open_new_profile_dialog(window, cx);
+    }
+
+    fn open_story(&mut self, action: &OpenStory, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_active_story(&action.0, window, cx);
+    }
+
+    fn set_active_story(&mut self, name: &str, window: &mut Window, cx: &mut App) {
+        let name = name.to_string();
+        self.search_input.update(cx, |this, cx| {
+            this.set_value(&name, window, cx);
+        })
+    }
+
+    fn view(init_story: Option<&str>, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(init_story, window, cx))
+    }
+}
+
+impl Render for Gallery {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let stories = self.filtered_stories(cx);
+        let active_story = self.split.read(cx).active_story().cloned();
         let (story_name, description) =
             if let Some(story) = active_story.as_ref().map(|story| story.read(cx)) {
                 (story.name.clone(), story.description.clone())
             } else {
                 ("".into(), "".into())
             };
+        let popped_out = Popouts::is_popped_out(&story_name, cx);
+
+        let (sidebar_width, sidebar_range) = if self.collapsed {
+            (
+                COLLAPSED_SIDEBAR_WIDTH,
+                COLLAPSED_SIDEBAR_WIDTH..COLLAPSED_SIDEBAR_WIDTH,
+            )
+        } else {
+            (self.sidebar_width, px(200.)..px(320.))
+        };
 
-        h_resizable("gallery-container", self.sidebar_state.clone())
+        div()
+            .size_full()
+            .on_action(cx.listener(Self::apply_layout_preset))
+            .on_action(cx.listener(Self::save_layout_preset))
+            .on_action(cx.listener(Self::switch_profile))
+            .on_action(cx.listener(Self::new_profile))
+            .on_action(cx.listener(Self::open_story))
             .child(
-                resizable_panel()
-                    .size(px(255.))
-                    .size_range(px(200.)..px(320.))
+                h_resizable("gallery-container", self.sidebar_state.clone())
                     .child(
-                        Sidebar::left()
-                            .width(relative(1.))
-                            .border_width(px(0.))
-                            .collapsed(self.collapsed)
-                            .header(
-                                v_flex()
-                                    .w_full()
-                                    .gap_4()
-                                    .child(
-                                        SidebarHeader::new()
+                        resizable_panel()
+                            .size(sidebar_width)
+                            .size_range(sidebar_range)
+                            .child(
+                                Sidebar::left()
+                                    .width(relative(1.))
+                                    .border_width(px(0.))
+                                    .collapsed(self.collapsed)
+                                    .header(
+                                        v_flex()
                                             .w_full()
+                                            .gap_4()
                                             .child(
-                                                div()
-                                                    .flex()
-                                                    .items_center()
-                                                    .justify_center()
-                                                    .rounded(cx.theme().radius)
-                                                    .bg(cx.theme().primary)
-                                                    .text_color(cx.theme().primary_foreground)
-                                                    .size_8()
-                                                    .flex_shrink_0()
-                                                    .when(!self.collapsed, |this| {
-                                                        this.child(Icon::new(
-                                                            IconName::GalleryVerticalEnd,
-                                                        ))
-                                                    })
-                                                    .when(self.collapsed, |this| {
-                                                        this.size_4()
+                                                SidebarHeader::new()
+                                                    .w_full()
+                                                    .child(
+                                                        div()
+                                                            .flex()
+                                                            .items_center()
+                                                            .justify_center()
+                                                            .rounded(cx.theme().radius)
+                                                            .bg(cx.theme().primary)
+                                                            .text_color(
+                                                                cx.theme().primary_foreground,
+                                                            )
+                                                            .size_8()
+                                                            .flex_shrink_0()
+                                                            .when(!self.collapsed, |this| {
+                                                                this.child(Icon::new(
+                                                                    IconName::GalleryVerticalEnd,
+                                                                ))
+                                                            })
+                                                            .when(self.collapsed, |this| {
+                                                                this.size_4()
                                                             .bg(cx.theme().transparent)
                                                             .text_color(cx.theme().foreground)
                                                             .child(Icon::new(
                                                                 IconName::GalleryVerticalEnd,
                                                             ))
-                                                    })
-                                                    .rounded_lg(),
+                                                            })
+                                                            .rounded_lg(),
+                                                    )
+                                                    .when(!self.collapsed, |this| {
+                                                        this.child(
+                                                            v_flex()
+                                                                .gap_0()
+                                                                .text_sm()
+                                                                .flex_1()
+                                                                .line_height(relative(1.25))
+                                                                .overflow_hidden()
+                                                                .text_ellipsis()
+                                                                .child("GPUI Component")
+                                                                .child(
+                                                                    div()
+                                                                        .text_color(
+                                                                            cx.theme()
+                                                                                .muted_foreground,
+                                                                        )
+                                                                        .child("Gallery")
+                                                                        .text_xs(),
+                                                                ),
+                                                        )
+                                                    }),
                                             )
-                                            .when(!self.collapsed, |this| {
-                                                this.child(
-                                                    v_flex()
-                                                        .gap_0()
-                                                        .text_sm()
-                                                        .flex_1()
-                                                        .line_height(relative(1.25))
-                                                        .overflow_hidden()
-                                                        .text_ellipsis()
-                                                        .child("GPUI Component")
-                                                        .child(
-                                                            div()
-                                                                .text_color(
-                                                                    cx.theme().muted_foreground,
-                                                                )
-                                                                .child("Gallery")
-                                                                .text_xs(),
-                                                        ),
-                                                )
-                                            }),
-                                    )
-                                    .child(
-                                        div()
-                                            .bg(cx.theme().sidebar_accent)
-                                            .px_1()
-                                            .rounded_full()
-                                            .flex_1()
-                                            .mx_1()
                                             .child(
-                                                TextInput::new(&self.search_input)
-                                                    .appearance(false)
-                                                    .cleanable(),
+                                                div()
+                                                    .bg(cx.theme().sidebar_accent)
+                                                    .px_1()
+                                                    .rounded_full()
+                                                    .flex_1()
+                                                    .mx_1()
+                                                    .child(
+                                                        TextInput::new(&self.search_input)
+                                                            .appearance(false)
+                                                            .cleanable(),
+                                                    ),
                                             ),
-                                    ),
-                            )
-                            .children(stories.clone().into_iter().enumerate().map(
-                                |(group_ix, (group_name, sub_stories))| {
-                                    SidebarGroup::new(*group_name).child(
-                                        SidebarMenu::new().children(
-                                            sub_stories.iter().enumerate().map(|(ix, story)| {
-                                                SidebarMenuItem::new(story.read(cx).name.clone())
-                                                    .active(
-                                                        self.active_group_index == Some(group_ix)
-                                                            && self.active_index == Some(ix),
-                                                    )
-                                                    .on_click(cx.listener(
-                                                        move |this, _: &ClickEvent, _, cx| {
-                                                            this.active_group_index =
-                                                                Some(group_ix);
-                                                            this.active_index = Some(ix);
-                                                            cx.notify();
-                                                        },
-                                                    ))
-                                            }),
-                                        ),
                                     )
-                                },
-                            )),
-                    ),
-            )
-            .child(
-                v_flex()
-                    .flex_1()
-                    .h_full()
-                    .overflow_x_hidden()
-                    .child(
-                        h_flex()
-                            .id("header")
-                            .p_4()
-                            .border_b_1()
-                            .border_color(cx.theme().border)
-                            .justify_between()
-                            .items_start()
-                            .child(
-                                v_flex()
-                                    .gap_1()
-                                    .child(div().text_xl().child(story_name))
-                                    .child(
-                                        div()
-                                            .text_color(cx.theme().muted_foreground)
-                                            .child(description),
-                                    ),
+                                    .children(stories.clone().into_iter().enumerate().map(
+                                        |(group_ix, (group_name, sub_stories))| {
+                                            SidebarGroup::new(group_name).child(
+                                                SidebarMenu::new().children(
+                                                    sub_stories.iter().enumerate().map(
+                                                        |(ix, story)| {
+                                                            SidebarMenuItem::new(
+                                                            story.read(cx).name.clone(),
+                                                        )
+                                                        .active(
+                                                            self.active_group_index
+                                                                == Some(group_ix)
+                                                                && self.active_index == Some(ix),
+                                                        )
+                                                        .on_click(cx.listener(
+                                                            move |this, _: &ClickEvent, _, cx| {
+                                                                this.active_group_index =
+                                                                    Some(group_ix);
+                                                                this.active_index = Some(ix);
+                                                                this.show_active_story(cx);
+                                                                cx.notify();
+                                                            },
+                                                        ))
+                                                        },
+                                                    ),
+                                                ),
+                                            )
+                                        },
+                                    )),
                             ),
                     )
                     .child(
-                        div()
-                            .id("story")
+                        v_flex()
                             .flex_1()
-                            .overflow_y_scroll()
-                            .when_some(active_story, |this, active_story| {
-                                this.child(active_story.clone())
-                            }),
-                    )
-                    .into_any_element(),
+                            .h_full()
+                            .overflow_x_hidden()
+                            .child(
+                                h_flex()
+                                    .id("header")
+                                    .p_4()
+                                    .border_b_1()
+                                    .border_color(cx.theme().border)
+                                    .justify_between()
+                                    .items_start()
+                                    .child(
+                                        v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .id("story-title")
+                                                    .text_xl()
+                                                    .when_some(
+                                                        active_story.clone(),
+                                                        |this, story| {
+                                                            this.draggable(
+                                                                DraggedStory(story),
+                                                                story_name.clone(),
+                                                                cx,
+                                                            )
+                                                        },
+                                                    )
+                                                    .child(story_name),
+                                            )
+                                            .child(
+                                                div()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .child(description),
+                                            ),
+                                    )
+                                    .when_some(
+                                        active_story.filter(|_| !popped_out),
+                                        |this, story| {
+                                            this.child(
+                                                Button::new("pop-out")
+                                                    .small()
+                                                    .ghost()
+                                                    .icon(IconName::ExternalLink)
+                                                    .tooltip("Open in a New Window")
+                                                    .on_click(move |_, _, cx| {
+                                                        Popouts::pop_out(story.clone(), cx)
+                                                    }),
+                                            )
+                                        },
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .id("story")
+                                    .flex_1()
+                                    .min_h_0()
+                                    .child(self.split.clone()),
+                            )
+                            .into_any_element(),
+                    ),
             )
     }
 }
 
 fn main() {
-    let app = Application::new().with_assets(Assets);
-
     // Parse `cargo run -- <story_name>`
-    let name = std::env::args().nth(1);
+    let cli = Cli::parse();
+    if let Some(name) = cli.dump_config_schema {
+        match config_schema(name.as_deref()) {
+            Ok(schema) => println!("{:#}", schema),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if cli.unregister {
+        unregister_integration();
+        return;
+    }
+    if story::forward_to_running_instance(&cli) {
+        return;
+    }
+    apply_factory_reset(cli.reset_state);
+    enter_kiosk(cli.kiosk);
+    enter_safe_mode(cli.safe_mode);
+    prepare_renderer(&cli);
+    let name = cli.story;
+    let url = cli.url;
+    let file = cli.file;
+
+    let app = Application::new().with_assets(Assets);
+    // macOS passes links through the app delegate rather than the command line.
+    let (open_urls_tx, mut open_urls_rx) = futures::channel::mpsc::unbounded::<Vec<String>>();
+    app.on_open_urls(move |urls| _ = open_urls_tx.unbounded_send(urls));
+    // Clicking the dock icon with no window visible brings one back.
+    app.on_reopen(|cx| {
+        reopen_windows(cx, |cx| {
+            story::create_new_window(
+                "GPUI Component",
+                |window, cx| Gallery::view(None, window, cx),
+                cx,
+            )
+        })
+    });
 
     app.run(move |cx| {
-        story::init(cx);
-        cx.activate(true);
+        // After failed starts the user picks how to start first.
+        guard_startup(cx, move |cx| {
+            story::init(cx);
+            cx.activate(true);
 
-        story::create_new_window(
-            "GPUI Component",
-            move |window, cx| Gallery::view(name.as_deref(), window, cx),
-            cx,
-        );
+            story::create_new_window(
+                "GPUI Component",
+                move |window, cx| Gallery::view(name.as_deref(), window, cx),
+                cx,
+            );
+
+            cx.spawn(async move |cx| {
+                cx.update(|cx| run_startup_actions(cx))?;
+                cx.update(|cx| show_whats_new(cx))?;
+                if let Some(url) = url {
+                    cx.update(|cx| open_deep_link(&url, cx))?;
+                }
+                if let Some(path) = file {
+                    cx.update(|cx| install_theme_file(&path, cx))?;
+                }
+                while let Some(urls) = open_urls_rx.next().await {
+                    cx.update(|cx| {
+                        with_change_source(ChangeSource::Ipc, cx, |cx| {
+                            for url in urls {
+                                open_deep_link(&url, cx);
+                            }
+                        })
+                    })?;
+                }
+                Ok::<_, anyhow::Error>(())
+            })
+            .detach();
+        });
     });
 }
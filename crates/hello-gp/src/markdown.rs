@@ -0,0 +1,68 @@
+use gpui::{
+    App, ElementId, IntoElement, ParentElement as _, Refineable as _, RenderOnce, SharedString,
+    StyleRefinement, Styled, Window, div, prelude::FluentBuilder as _,
+};
+use gpui_component::{ActiveTheme as _, text::TextView};
+
+/// Markdown in the colors and fonts of the active theme, for text that comes from elsewhere:
+/// changelogs, notifications and the descriptions of extensions.
+///
+/// Links open with the OS, `https` ones in the browser and [`crate::deep_links::SCHEME`] ones
+/// back in the app.
+///
+/// ```ignore
+/// Markdown::new("description", "Adds **Nord** themes, see [the docs](https://example.com).")
+///     .muted()
+/// ```
+#[derive(IntoElement)]
+pub struct Markdown {
+    id: ElementId,
+    source: SharedString,
+    style: StyleRefinement,
+    muted: bool,
+    scrollable: bool,
+}
+
+impl Markdown {
+    pub fn new(id: impl Into<ElementId>, source: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            source: source.into(),
+            style: StyleRefinement::default(),
+            muted: false,
+            scrollable: false,
+        }
+    }
+
+    /// In the muted foreground color, for secondary text like descriptions.
+    pub fn muted(mut self) -> Self {
+        self.muted = true;
+        self
+    }
+
+    /// Scroll when taller than the space given, e.g. in a dialog.
+    pub fn scrollable(mut self) -> Self {
+        self.scrollable = true;
+        self
+    }
+}
+
+impl Styled for Markdown {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Markdown {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let text = TextView::markdown(self.id, self.source, window, cx)
+            .selectable()
+            .when(self.scrollable, |this| this.size_full().scrollable());
+
+        let mut container = div().text_sm().when(self.muted, |this| {
+            this.text_color(cx.theme().muted_foreground)
+        });
+        container.style().refine(&self.style);
+        container.child(text)
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use gpui::{AnyWindowHandle, App, Global, SharedString, Timer};
+use gpui_component::{ContextModal as _, notification::Notification};
+
+use crate::{idle::IdleMonitor, themes::PROJECT_NAME};
+
+/// How often memory usage is sampled.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+/// How many samples are kept, for the sparkline in the diagnostics.
+const MAX_SAMPLES: usize = 60;
+/// How long the views of a closed window get to be released before they count as leaked.
+const LEAK_GRACE: Duration = Duration::from_secs(10);
+
+/// Resident memory of the process in bytes, where the OS tells.
+#[cfg(target_os = "linux")]
+fn resident_size() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn resident_size() -> Option<u64> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    // SAFETY: `info` is a buffer of `size` bytes for the task info of our own process.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            std::process::id() as libc::c_int,
+            libc::PROC_PIDTASKINFO,
+            0,
+            info.as_mut_ptr().cast(),
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    // SAFETY: fully written by `proc_pidinfo`.
+    Some(unsafe { info.assume_init() }.pti_resident_size)
+}
+
+#[cfg(target_os = "windows")]
+fn resident_size() -> Option<u64> {
+    use windows::Win32::System::{
+        ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+        Threading::GetCurrentProcess,
+    };
+
+    let mut counters = PROCESS_MEMORY_COUNTERS::default();
+    let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    // SAFETY: `counters` is a buffer of `size` bytes, the pseudo handle needs no closing.
+    unsafe { GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size) }.ok()?;
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn resident_size() -> Option<u64> {
+    None
+}
+
+/// `HELLO_GP_LEAK_CANARY=1` reports the views that outlive their window, on by default in
+/// debug builds.
+fn leak_canary_enabled() -> bool {
+    match std::env::var(format!("{}_LEAK_CANARY", PROJECT_NAME.clone())) {
+        Ok(value) => matches!(value.trim(), "1" | "true" | "yes"),
+        Err(_) => cfg!(debug_assertions),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MemorySample {
+    /// Resident memory in bytes, `None` where the OS doesn't tell.
+    pub resident: Option<u64>,
+    /// Live root views of the windows.
+    pub views: usize,
+}
+
+/// A root view of a window, held weakly to notice when it's never released.
+struct TrackedView {
+    name: SharedString,
+    window: AnyWindowHandle,
+    is_alive: Box<dyn Fn() -> bool>,
+    closed_at: Option<Instant>,
+    reported: bool,
+}
+
+/// Memory usage over time, and the views that leaked.
+pub struct Memory {
+    samples: VecDeque<MemorySample>,
+    views: Vec<TrackedView>,
+    leak_canary: bool,
+    leaks: Vec<SharedString>,
+}
+
+impl Global for Memory {}
+
+impl Memory {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// The samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &MemorySample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&MemorySample> {
+        self.samples.back()
+    }
+
+    pub fn leak_canary(&self) -> bool {
+        self.leak_canary
+    }
+
+    /// The views that outlived their window, by name.
+    pub fn leaks(&self) -> &[SharedString] {
+        &self.leaks
+    }
+
+    /// Watch a root view of `window`, `is_alive` tells whether it's still around.
+    pub fn track(
+        name: impl Into<SharedString>,
+        window: AnyWindowHandle,
+        is_alive: impl Fn() -> bool + 'static,
+        cx: &mut App,
+    ) {
+        let Some(memory) = cx.try_global_mut::<Self>() else {
+            return;
+        };
+        memory.views.push(TrackedView {
+            name: name.into(),
+            window,
+            is_alive: Box::new(is_alive),
+            closed_at: None,
+            reported: false,
+        });
+    }
+
+    fn sample(cx: &mut App) {
+        let open_windows = cx
+            .windows()
+            .iter()
+            .map(|window| window.window_id())
+            .collect::<Vec<_>>();
+        let memory = cx.global_mut::<Self>();
+        let now = Instant::now();
+
+        let mut leaked = vec![];
+        memory.views.retain_mut(|view| {
+            if !(view.is_alive)() {
+                return false;
+            }
+            if open_windows.contains(&view.window.window_id()) {
+                return true;
+            }
+            let closed_at = *view.closed_at.get_or_insert(now);
+            if memory.leak_canary && !view.reported && now - closed_at > LEAK_GRACE {
+                view.reported = true;
+                leaked.push(view.name.clone());
+            }
+            true
+        });
+        memory.samples.push_back(MemorySample {
+            resident: resident_size(),
+            views: memory.views.len(),
+        });
+        if memory.samples.len() > MAX_SAMPLES {
+            memory.samples.pop_front();
+        }
+
+        for name in &leaked {
+            tracing::error!("Leak canary: {} outlived its window", name);
+        }
+        memory.leaks.extend(leaked.iter().cloned());
+        if !leaked.is_empty()
+            && let Some(window) = cx.active_window()
+        {
+            let message = format!("Leaked after closing the window: {}", leaked.join(", "));
+            _ = window.update(cx, |_, window, cx| {
+                window.push_notification(Notification::warning(message), cx);
+            });
+        }
+    }
+}
+
+/// Format `bytes` as MiB, for the diagnostics.
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024. * 1024.))
+}
+
+pub fn init(cx: &mut App) {
+    let leak_canary = leak_canary_enabled();
+    if leak_canary {
+        tracing::info!("Leak canary is on, views outliving their window are reported");
+    }
+    cx.set_global(Memory {
+        samples: VecDeque::new(),
+        views: vec![],
+        leak_canary,
+        leaks: vec![],
+    });
+
+    cx.spawn(async move |cx| {
+        loop {
+            // Sampling waits for the user to be back, like the other background work.
+            let sampled = cx.update(|cx| {
+                if !IdleMonitor::is_idle(cx) {
+                    Memory::sample(cx);
+                }
+            });
+            if sampled.is_err() {
+                break;
+            }
+            Timer::after(SAMPLE_INTERVAL).await;
+        }
+    })
+    .detach();
+}
@@ -0,0 +1,80 @@
+use gpui::{AnyWindowHandle, App, SharedString};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{notifications::ActionHandler, settings::Settings};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Also show important notifications (errors, finished tasks) in the OS notification
+    /// center while no window of the app has focus.
+    pub native: bool,
+}
+
+/// Show a notification through the OS, when enabled and the app is in the background.
+///
+/// Clicking it focuses `window`, and clicking one of `actions` also runs it. The OS only
+/// reports clicks back on Linux (freedesktop notifications), elsewhere it just shows up.
+pub(crate) fn mirror(
+    title: Option<SharedString>,
+    message: SharedString,
+    actions: Vec<(SharedString, ActionHandler)>,
+    window: AnyWindowHandle,
+    cx: &mut App,
+) {
+    if !Settings::global(cx).notifications.native || cx.active_window().is_some() {
+        return;
+    }
+
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname(env!("CARGO_PKG_NAME"))
+        .summary(title.as_deref().unwrap_or(env!("CARGO_PKG_NAME")))
+        .body(&message);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        // "default" is what the notification server reports for a click on the body.
+        notification.action("default", "Open");
+        for (ix, (label, _)) in actions.iter().enumerate() {
+            notification.action(&ix.to_string(), label);
+        }
+    }
+
+    let handle = match notification.show() {
+        Ok(handle) => handle,
+        Err(err) => {
+            tracing::error!("Failed to show a native notification: {}", err);
+            return;
+        }
+    };
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let clicked = cx.background_spawn(async move {
+            let mut clicked = None;
+            handle.wait_for_action(|action| clicked = Some(action.to_string()));
+            clicked
+        });
+        cx.spawn(async move |cx| {
+            let Some(action) = clicked.await.filter(|action| action != "__closed") else {
+                return;
+            };
+            _ = cx.update(|cx| {
+                cx.activate(true);
+                _ = window.update(cx, |_, window, cx| {
+                    window.activate_window();
+                    let handler = action.parse::<usize>().ok().and_then(|ix| actions.get(ix));
+                    if let Some((_, handler)) = handler {
+                        handler(window, cx);
+                    }
+                });
+            });
+        })
+        .detach();
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        _ = (handle, actions, window);
+    }
+}
@@ -0,0 +1,337 @@
+//! Where a view is and where it can go: a breadcrumb of the path to the current page, and a
+//! rail of the pages beside them.
+//!
+//! ```ignore
+//! NavRail::new("pages")
+//!     .item(NavItem::new(IconName::Settings, "Settings"))
+//!     .item(NavItem::new(IconName::Calendar, "History"))
+//!     .selected_index(self.page)
+//!     .on_select(cx.listener(|this, ix: &usize, _, cx| this.page = *ix));
+//! ```
+
+use std::rc::Rc;
+
+use gpui::{
+    AnyElement, App, ElementId, InteractiveElement as _, IntoElement, KeyBinding,
+    ParentElement as _, RenderOnce, SharedString, StatefulInteractiveElement as _, Styled as _,
+    Window, actions, div, prelude::FluentBuilder as _,
+};
+use gpui_component::{
+    ActiveTheme as _, Colorize as _, Icon, IconName, Sizable as _, h_flex, v_flex,
+};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    tooltips::RichTooltip,
+};
+
+actions!(
+    navigation,
+    [
+        Activate,
+        FocusPrev,
+        FocusNext,
+        SelectPrev,
+        SelectNext,
+        SelectFirst,
+        SelectLast
+    ]
+);
+
+const BREADCRUMB_CONTEXT: &str = "Breadcrumb";
+const RAIL_CONTEXT: &str = "NavRail";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("enter", Activate, Some(BREADCRUMB_CONTEXT)),
+        KeyBinding::new("space", Activate, Some(BREADCRUMB_CONTEXT)),
+        KeyBinding::new("left", FocusPrev, Some(BREADCRUMB_CONTEXT)),
+        KeyBinding::new("right", FocusNext, Some(BREADCRUMB_CONTEXT)),
+        KeyBinding::new("up", SelectPrev, Some(RAIL_CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(RAIL_CONTEXT)),
+        KeyBinding::new("home", SelectFirst, Some(RAIL_CONTEXT)),
+        KeyBinding::new("end", SelectLast, Some(RAIL_CONTEXT)),
+    ]);
+}
+
+type SelectHandler = Rc<dyn Fn(&usize, &mut Window, &mut App)>;
+
+/// A step of a [`Breadcrumb`].
+pub struct BreadcrumbItem {
+    label: SharedString,
+    on_click: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+}
+
+impl BreadcrumbItem {
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            on_click: None,
+        }
+    }
+
+    /// Go back to this step, by a click, or Enter when it's focused.
+    pub fn on_click(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.on_click = Some(Rc::new(handler));
+        self
+    }
+}
+
+/// The path to the current page, the last item, each step before it going back there.
+///
+/// The steps with a handler are tab stops, and the left and right arrows move between them.
+#[derive(IntoElement)]
+pub struct Breadcrumb {
+    id: ElementId,
+    items: Vec<BreadcrumbItem>,
+}
+
+impl Breadcrumb {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            items: vec![],
+        }
+    }
+
+    pub fn item(mut self, item: BreadcrumbItem) -> Self {
+        self.items.push(item);
+        self
+    }
+}
+
+impl RenderOnce for Breadcrumb {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let last = self.items.len().saturating_sub(1);
+        let mut children: Vec<AnyElement> = vec![];
+        for (ix, item) in self.items.into_iter().enumerate() {
+            if ix > 0 {
+                children.push(
+                    Icon::new(IconName::ChevronRight)
+                        .xsmall()
+                        .text_color(cx.theme().muted_foreground)
+                        .into_any_element(),
+                );
+            }
+            let current = ix == last;
+            let crumb = div()
+                .id(ix)
+                .px_1()
+                .rounded(cx.theme().radius)
+                .text_color(if current {
+                    cx.theme().foreground
+                } else {
+                    cx.theme().muted_foreground
+                })
+                .when(current, |this| this.font_semibold())
+                .child(item.label);
+            let crumb = match item.on_click {
+                Some(on_click) if !current => {
+                    let focus_handle = window
+                        .use_keyed_state(
+                            ElementId::NamedInteger(self.id.to_string().into(), ix as u64),
+                            cx,
+                            |_, cx| cx.focus_handle().tab_stop(true),
+                        )
+                        .read(cx)
+                        .clone();
+                    let on_activate = on_click.clone();
+                    crumb
+                        .track_focus(&focus_handle)
+                        .when(focus_handle.is_focused(window), |this| {
+                            this.border_1().border_color(cx.theme().ring)
+                        })
+                        .cursor_role(CursorRole::Pointer, cx)
+                        .hover(|this| this.text_color(cx.theme().foreground))
+                        .on_action(move |_: &Activate, window, cx| on_activate(window, cx))
+                        .on_click(move |_, window, cx| on_click(window, cx))
+                        .into_any_element()
+                }
+                _ => crumb.into_any_element(),
+            };
+            children.push(crumb);
+        }
+
+        h_flex()
+            .id(self.id)
+            .key_context(BREADCRUMB_CONTEXT)
+            .on_action(|_: &FocusPrev, window, _| window.focus_prev())
+            .on_action(|_: &FocusNext, window, _| window.focus_next())
+            .gap_1()
+            .text_sm()
+            .children(children)
+    }
+}
+
+/// A page of a [`NavRail`].
+pub struct NavItem {
+    icon: IconName,
+    label: SharedString,
+    badge: Option<SharedString>,
+}
+
+impl NavItem {
+    pub fn new(icon: IconName, label: impl Into<SharedString>) -> Self {
+        Self {
+            icon,
+            label: label.into(),
+            badge: None,
+        }
+    }
+
+    /// A count or a word after the label, e.g. how many changes there are.
+    pub fn badge(mut self, badge: impl Into<SharedString>) -> Self {
+        self.badge = Some(badge.into());
+        self
+    }
+}
+
+/// The pages of a view in a column, the selected one marked.
+///
+/// The rail is one tab stop, the arrow keys, home and end select the pages while it's focused.
+/// Collapsed, it shows only the icons, their labels in tooltips.
+#[derive(IntoElement)]
+pub struct NavRail {
+    id: ElementId,
+    items: Vec<NavItem>,
+    selected: Option<usize>,
+    collapsed: bool,
+    on_select: Option<SelectHandler>,
+}
+
+impl NavRail {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            items: vec![],
+            selected: None,
+            collapsed: false,
+            on_select: None,
+        }
+    }
+
+    pub fn item(mut self, item: NavItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn selected_index(mut self, ix: impl Into<Option<usize>>) -> Self {
+        self.selected = ix.into();
+        self
+    }
+
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    pub fn on_select(mut self, handler: impl Fn(&usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_select = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for NavRail {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let focus_handle = window
+            .use_keyed_state(self.id.clone(), cx, |_, cx| {
+                cx.focus_handle().tab_stop(true)
+            })
+            .read(cx)
+            .clone();
+        let focused = focus_handle.is_focused(window);
+        let count = self.items.len();
+        let selected = self.selected;
+        let select = {
+            let on_select = self.on_select.clone();
+            move |ix: usize, window: &mut Window, cx: &mut App| {
+                if Some(ix) != selected
+                    && let Some(on_select) = &on_select
+                {
+                    on_select(&ix, window, cx);
+                }
+            }
+        };
+        let step = {
+            let select = select.clone();
+            move |delta: isize, window: &mut Window, cx: &mut App| {
+                if count == 0 {
+                    return;
+                }
+                let ix = match selected {
+                    Some(ix) => (ix as isize + delta).clamp(0, count as isize - 1) as usize,
+                    None => 0,
+                };
+                select(ix, window, cx);
+            }
+        };
+
+        v_flex()
+            .id(self.id)
+            .key_context(RAIL_CONTEXT)
+            .track_focus(&focus_handle)
+            .on_action({
+                let step = step.clone();
+                move |_: &SelectPrev, window, cx| step(-1, window, cx)
+            })
+            .on_action(move |_: &SelectNext, window, cx| step(1, window, cx))
+            .on_action({
+                let select = select.clone();
+                move |_: &SelectFirst, window, cx| select(0, window, cx)
+            })
+            .on_action({
+                let select = select.clone();
+                move |_: &SelectLast, window, cx| select(count.saturating_sub(1), window, cx)
+            })
+            .gap_0p5()
+            .p_1()
+            .h_full()
+            .bg(cx.theme().sidebar)
+            .border_r_1()
+            .border_color(cx.theme().sidebar_border)
+            .children(self.items.into_iter().enumerate().map(|(ix, item)| {
+                let active = Some(ix) == selected;
+                let select = select.clone();
+                let label = item.label.clone();
+                h_flex()
+                    .id(ix)
+                    .gap_2()
+                    .px_2()
+                    .py_1p5()
+                    .rounded(cx.theme().radius)
+                    .text_sm()
+                    .text_color(cx.theme().sidebar_foreground)
+                    .cursor_role(CursorRole::Pointer, cx)
+                    .hover(|this| this.bg(cx.theme().sidebar_accent.opacity(0.5)))
+                    .when(active, |this| {
+                        this.bg(cx.theme().sidebar_accent)
+                            .text_color(cx.theme().sidebar_accent_foreground)
+                            .font_semibold()
+                    })
+                    .when(active && focused, |this| {
+                        this.border_1().border_color(cx.theme().ring)
+                    })
+                    .child(Icon::new(item.icon).small())
+                    .map(|this| {
+                        if self.collapsed {
+                            this.tooltip(move |window, cx| {
+                                RichTooltip::new(label.clone()).build(window, cx)
+                            })
+                        } else {
+                            this.child(div().flex_1().child(item.label)).when_some(
+                                item.badge,
+                                |this, badge| {
+                                    this.child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(badge),
+                                    )
+                                },
+                            )
+                        }
+                    })
+                    .on_click(move |_, window, cx| select(ix, window, cx))
+            }))
+    }
+}
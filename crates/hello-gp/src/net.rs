@@ -0,0 +1,428 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, anyhow};
+use futures::AsyncReadExt as _;
+use gpui::{App, AppContext as _, AsyncApp, Global, Task, Timer};
+use gpui_component::{ContextModal as _, notification::Notification};
+use http_client::{
+    AsyncBody, HttpClient, RedirectPolicy, Url,
+    http::{Request, StatusCode, header},
+};
+use reqwest_client::{ClientOptions, ReqwestClient};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::Settings, themes::get_data_dir};
+
+const USER_AGENT: &str = "gpui-component/story";
+/// What "Test Connection" requests when there is no extension feed to try instead.
+const TEST_URL: &str = "https://github.com/";
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const PROXY_ENV_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// The `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY` env vars. The proxy set in the OS settings
+    /// isn't read, it has to be exported or set as `manual`.
+    #[default]
+    System,
+    /// The `proxy` URL.
+    Manual,
+    /// Always connect directly.
+    None,
+}
+
+impl ProxyMode {
+    pub const ALL: [Self; 3] = [Self::System, Self::Manual, Self::None];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Manual => "Manual",
+            Self::None => "No Proxy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NetSettings {
+    /// Where the proxy comes from. When unset, `manual` if `proxy` is set and `system` otherwise.
+    pub proxy_mode: Option<ProxyMode>,
+    /// Proxy URL, e.g. `http://127.0.0.1:7890` or `socks5://127.0.0.1:1080`.
+    pub proxy: Option<String>,
+    /// PEM files with extra CA certificates to trust, e.g. of a TLS-inspecting corporate proxy.
+    pub ca_certificates: Vec<PathBuf>,
+    /// Serve responses from the on-disk cache without revalidating.
+    pub prefer_cache: bool,
+}
+
+impl NetSettings {
+    pub fn proxy_mode(&self) -> ProxyMode {
+        self.proxy_mode.unwrap_or(match &self.proxy {
+            Some(proxy) if !proxy.trim().is_empty() => ProxyMode::Manual,
+            _ => ProxyMode::System,
+        })
+    }
+
+    pub fn proxy_url(&self) -> Option<Url> {
+        let proxy = match self.proxy_mode() {
+            ProxyMode::System => PROXY_ENV_VARS
+                .iter()
+                .find_map(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))?,
+            ProxyMode::Manual => self
+                .proxy
+                .clone()
+                .filter(|proxy| !proxy.trim().is_empty())?,
+            ProxyMode::None => return None,
+        };
+
+        Url::parse(proxy.trim())
+            .inspect_err(|err| tracing::error!("Invalid proxy URL {}: {}", proxy, err))
+            .ok()
+    }
+
+    fn client_options(&self) -> anyhow::Result<ClientOptions> {
+        let root_certificates = self
+            .ca_certificates
+            .iter()
+            .map(|path| std::fs::read(path).with_context(|| format!("read {}", path.display())))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(ClientOptions {
+            user_agent: Some(USER_AGENT.to_string()),
+            proxy: self.proxy_url(),
+            no_proxy: self.proxy_mode() == ProxyMode::None,
+            root_certificates,
+        })
+    }
+
+    /// The HTTP client for these settings.
+    pub fn client(&self) -> anyhow::Result<Arc<dyn HttpClient>> {
+        Ok(Arc::new(ReqwestClient::with_options(
+            self.client_options()?,
+        )?))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64,
+}
+
+/// Shared HTTP service used by features that talk to the network (updates, theme marketplace).
+///
+/// Responses are cached under `get_data_dir()/cache/http`, failed requests are retried
+/// with exponential backoff, and the cached copy is served when the network is unreachable.
+pub struct Net {
+    client: Arc<dyn HttpClient>,
+    online: bool,
+}
+
+impl Global for Net {}
+
+impl Net {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn is_online(cx: &App) -> bool {
+        Self::global(cx).online
+    }
+
+    pub fn client(cx: &App) -> Arc<dyn HttpClient> {
+        Self::global(cx).client.clone()
+    }
+
+    /// Recreate the HTTP client, after the network settings changed.
+    pub fn reload(cx: &mut App) {
+        let client = build_client(&Settings::global(cx).net);
+        cx.set_http_client(client.clone());
+        cx.global_mut::<Self>().client = client;
+    }
+
+    /// Request a page through a client for `settings`, which may not be saved yet, and
+    /// report how long it took.
+    pub fn test_connection(settings: &NetSettings, cx: &App) -> Task<anyhow::Result<Duration>> {
+        let client = match settings.client() {
+            Ok(client) => client,
+            Err(err) => return Task::ready(Err(err)),
+        };
+        let url = Settings::global(cx)
+            .extension_manager
+            .feed_url
+            .clone()
+            .unwrap_or_else(|| TEST_URL.to_string());
+
+        cx.background_spawn(async move {
+            let started = Instant::now();
+            let request = Request::get(&url)
+                .extension(RedirectPolicy::FollowAll)
+                .body(AsyncBody::empty())?;
+            let response = client.send(request).await?;
+            if !response.status().is_success() {
+                return Err(anyhow!("GET {} failed: {}", url, response.status()));
+            }
+            Ok(started.elapsed())
+        })
+    }
+
+    fn cache_dir() -> PathBuf {
+        get_data_dir().join("cache").join("http")
+    }
+
+    /// GET the `url`, returning the response body.
+    pub fn get(url: impl Into<String>, cx: &mut App) -> Task<anyhow::Result<Vec<u8>>> {
+        let url = url.into();
+        let client = Self::client(cx);
+        let prefer_cache = Settings::global(cx).net.prefer_cache;
+
+        cx.spawn(async move |cx| {
+            let cache = ResponseCache::new(&url);
+            let cached = cx
+                .background_spawn({
+                    let cache = cache.clone();
+                    async move { cache.load() }
+                })
+                .await;
+            if prefer_cache && let Some((_, body)) = cached.as_ref() {
+                return Ok(body.clone());
+            }
+
+            let result = fetch_with_retry(&client, &url, cached.as_ref().map(|(e, _)| e)).await;
+            match result {
+                Ok(Fetched::NotModified) => {
+                    Self::set_online(true, cx);
+                    cached
+                        .map(|(_, body)| body)
+                        .ok_or_else(|| anyhow!("missing cache entry for {}", url))
+                }
+                Ok(Fetched::Body(entry, body)) => {
+                    Self::set_online(true, cx);
+                    cx.background_spawn({
+                        let body = body.clone();
+                        async move { cache.store(&entry, &body) }
+                    })
+                    .detach();
+                    Ok(body)
+                }
+                Err(FetchError::Network(err)) => {
+                    Self::set_online(false, cx);
+                    match cached {
+                        Some((_, body)) => {
+                            tracing::warn!("Offline, serving cached {}: {}", url, err);
+                            Ok(body)
+                        }
+                        None => Err(err),
+                    }
+                }
+                Err(FetchError::Status(status)) => Err(anyhow!("GET {} failed: {}", url, status)),
+            }
+        })
+    }
+
+    /// Record whether the network was reachable, telling the user when that changes.
+    pub(crate) fn set_online(online: bool, cx: &mut AsyncApp) {
+        _ = cx.update(|cx| {
+            if Self::global(cx).online == online {
+                return;
+            }
+            cx.global_mut::<Self>().online = online;
+
+            struct OfflineNotification;
+            for window in cx.windows() {
+                _ = window.update(cx, |_, window, cx| {
+                    let note = if online {
+                        Notification::success("You are back online.")
+                    } else {
+                        Notification::warning("Unable to reach the network, using cached data.")
+                            .autohide(false)
+                    };
+                    window.push_notification(note.id::<OfflineNotification>(), cx);
+                });
+            }
+        });
+    }
+}
+
+enum Fetched {
+    NotModified,
+    Body(CacheEntry, Vec<u8>),
+}
+
+enum FetchError {
+    /// The request never reached the server (DNS, connection refused, timeout...).
+    Network(anyhow::Error),
+    Status(StatusCode),
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+async fn fetch_with_retry(
+    client: &Arc<dyn HttpClient>,
+    url: &str,
+    cached: Option<&CacheEntry>,
+) -> Result<Fetched, FetchError> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let result = fetch(client, url, cached).await;
+        let retry = match &result {
+            Err(FetchError::Network(_)) => true,
+            Err(FetchError::Status(status)) => is_retryable(*status),
+            Ok(_) => false,
+        };
+        if !retry || attempt >= MAX_ATTEMPTS {
+            return result;
+        }
+
+        tracing::debug!("GET {} failed, retry in {:?}", url, backoff);
+        Timer::after(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+async fn fetch(
+    client: &Arc<dyn HttpClient>,
+    url: &str,
+    cached: Option<&CacheEntry>,
+) -> Result<Fetched, FetchError> {
+    let mut request = Request::get(url).extension(RedirectPolicy::FollowAll);
+    if let Some(entry) = cached {
+        if let Some(etag) = &entry.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let request = request
+        .body(AsyncBody::empty())
+        .map_err(|err| FetchError::Network(err.into()))?;
+
+    let mut response = client.send(request).await.map_err(FetchError::Network)?;
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(Fetched::NotModified);
+    }
+    if !status.is_success() {
+        return Err(FetchError::Status(status));
+    }
+
+    let header_value = |name: header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    };
+    let entry = CacheEntry {
+        url: url.to_string(),
+        etag: header_value(header::ETAG),
+        last_modified: header_value(header::LAST_MODIFIED),
+        fetched_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+    };
+
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .map_err(|err| FetchError::Network(err.into()))?;
+
+    Ok(Fetched::Body(entry, body))
+}
+
+/// 64-bit FNV-1a of `bytes`. Unlike the hasher of `std`, the same on every build, so cache
+/// entries outlive toolchain updates.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[derive(Clone)]
+struct ResponseCache {
+    meta_path: PathBuf,
+    body_path: PathBuf,
+}
+
+impl ResponseCache {
+    fn new(url: &str) -> Self {
+        let key = format!("{:016x}", fnv1a(url.as_bytes()));
+        let dir = Net::cache_dir();
+
+        Self {
+            meta_path: dir.join(format!("{}.json", key)),
+            body_path: dir.join(format!("{}.bin", key)),
+        }
+    }
+
+    fn load(&self) -> Option<(CacheEntry, Vec<u8>)> {
+        let meta = std::fs::read_to_string(&self.meta_path).ok()?;
+        let entry = serde_json::from_str(&meta).ok()?;
+        let body = std::fs::read(&self.body_path).ok()?;
+        Some((entry, body))
+    }
+
+    fn store(&self, entry: &CacheEntry, body: &[u8]) {
+        let result = (|| {
+            std::fs::create_dir_all(Net::cache_dir())?;
+            std::fs::write(&self.body_path, body)?;
+            std::fs::write(&self.meta_path, serde_json::to_string(entry)?)
+                .context("write cache entry")
+        })();
+        if let Err(err) = result {
+            tracing::warn!("Failed to cache {}: {}", entry.url, err);
+        }
+    }
+}
+
+fn build_client(settings: &NetSettings) -> Arc<dyn HttpClient> {
+    if let Some(proxy) = settings.proxy_url() {
+        tracing::info!("Using proxy: {}", proxy);
+    }
+
+    match settings.client() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!(
+                "Failed to create HTTP client with the network settings: {}",
+                err
+            );
+            Arc::new(ReqwestClient::user_agent(USER_AGENT).unwrap())
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let client = build_client(&Settings::global(cx).net);
+
+    cx.set_http_client(client.clone());
+    cx.set_global(Net {
+        client,
+        online: true,
+    });
+}
@@ -0,0 +1,275 @@
+use std::{path::PathBuf, time::Duration};
+
+use gpui::{
+    App, AppContext as _, Context, Div, Entity, InteractiveElement as _, IntoElement,
+    ParentElement as _, Render, SharedString, Stateful, StatefulInteractiveElement as _,
+    Styled as _, Task, Window, actions, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::InputState,
+    notification::Notification,
+    radio::RadioGroup,
+    tooltip::Tooltip,
+    v_flex,
+};
+
+use crate::{
+    net::{Net, NetSettings, ProxyMode},
+    policy,
+    settings::Settings,
+    text_field::TextField,
+};
+
+actions!(net, [ShowNetworkSettings]);
+
+enum TestStatus {
+    Testing,
+    Connected(Duration),
+    Failed(SharedString),
+}
+
+/// The proxy and certificate settings, edited in a dialog and only applied on save.
+pub struct NetworkSettingsView {
+    mode: ProxyMode,
+    proxy_input: Entity<InputState>,
+    certificate_input: Entity<InputState>,
+    certificates: Vec<PathBuf>,
+    status: Option<TestStatus>,
+    _test_task: Option<Task<()>>,
+}
+
+impl NetworkSettingsView {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let settings = Settings::global(cx).net.clone();
+        let proxy = settings.proxy.clone().unwrap_or_default();
+        let proxy_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("http://127.0.0.1:7890")
+                .default_value(proxy)
+        });
+        let certificate_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("/path/to/ca.pem"));
+
+        Self {
+            mode: settings.proxy_mode(),
+            proxy_input,
+            certificate_input,
+            certificates: settings.ca_certificates,
+            status: None,
+            _test_task: None,
+        }
+    }
+
+    /// The settings as currently entered in the dialog.
+    fn settings(&self, cx: &App) -> NetSettings {
+        let proxy = self.proxy_input.read(cx).value().trim().to_string();
+        NetSettings {
+            proxy_mode: Some(self.mode),
+            proxy: (!proxy.is_empty()).then_some(proxy),
+            ca_certificates: self.certificates.clone(),
+            ..Settings::global(cx).net.clone()
+        }
+    }
+
+    fn add_certificate(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = self.certificate_input.read(cx).value().trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            self.status = Some(TestStatus::Failed(
+                format!("{} is not a file.", path.display()).into(),
+            ));
+            cx.notify();
+            return;
+        }
+
+        if !self.certificates.contains(&path) {
+            self.certificates.push(path);
+        }
+        self.certificate_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        cx.notify();
+    }
+
+    fn test_connection(&mut self, cx: &mut Context<Self>) {
+        let task = Net::test_connection(&self.settings(cx), cx);
+        self.status = Some(TestStatus::Testing);
+        self._test_task = Some(cx.spawn(async move |this, cx| {
+            let status = match task.await {
+                Ok(elapsed) => TestStatus::Connected(elapsed),
+                Err(err) => TestStatus::Failed(format!("{:#}", err).into()),
+            };
+            _ = this.update(cx, |this, cx| {
+                this.status = Some(status);
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    fn save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let net = self.settings(cx);
+        Settings::update(cx, |settings, _| settings.net = net);
+        Net::reload(cx);
+        window.close_modal(cx);
+        window.push_notification(Notification::success("Saved the network settings."), cx);
+    }
+}
+
+impl Render for NetworkSettingsView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = ProxyMode::ALL.iter().position(|mode| *mode == self.mode);
+        let muted = cx.theme().muted_foreground;
+        let mode_locked = policy::is_locked("net.proxy_mode");
+        let proxy_locked = policy::is_locked("net.proxy");
+        let certificates_locked = policy::is_locked("net.ca_certificates");
+
+        v_flex()
+            .gap_4()
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(div().text_sm().font_semibold().child("Proxy"))
+                    .child(
+                        locked_tooltip("proxy-mode-locked", mode_locked).child(
+                            RadioGroup::horizontal("proxy-mode")
+                                .children(ProxyMode::ALL.map(ProxyMode::label))
+                                .selected_index(selected)
+                                .disabled(mode_locked)
+                                .on_change(cx.listener(|this, ix: &usize, _, cx| {
+                                    this.mode = ProxyMode::ALL[*ix];
+                                    cx.notify();
+                                })),
+                        ),
+                    )
+                    .child(
+                        locked_tooltip("proxy-locked", proxy_locked).child(
+                            TextField::new("proxy", &self.proxy_input)
+                                .small()
+                                .disabled(self.mode != ProxyMode::Manual || proxy_locked),
+                        ),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(div().text_sm().font_semibold().child("CA Certificates"))
+                    .when(self.certificates.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(muted)
+                                .child("Only the system certificates are trusted."),
+                        )
+                    })
+                    .children(self.certificates.iter().enumerate().map(|(ix, path)| {
+                        h_flex()
+                            .gap_2()
+                            .justify_between()
+                            .child(div().text_sm().child(path.display().to_string()))
+                            .child(
+                                Button::new(("remove-certificate", ix))
+                                    .xsmall()
+                                    .ghost()
+                                    .icon(IconName::Close)
+                                    .disabled(certificates_locked)
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.certificates.remove(ix);
+                                        cx.notify();
+                                    })),
+                            )
+                    }))
+                    .child(
+                        locked_tooltip("certificates-locked", certificates_locked)
+                            .flex()
+                            .gap_2()
+                            .child(
+                                TextField::new("certificate", &self.certificate_input)
+                                    .small()
+                                    .disabled(certificates_locked),
+                            )
+                            .child(
+                                Button::new("add-certificate")
+                                    .small()
+                                    .outline()
+                                    .label("Add")
+                                    .disabled(certificates_locked)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.add_certificate(window, cx)
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .justify_between()
+                    .child(div().text_sm().max_w(px(240.)).map(|this| {
+                        match &self.status {
+                            None => this,
+                            Some(TestStatus::Testing) => {
+                                this.text_color(muted).child("Connecting...")
+                            }
+                            Some(TestStatus::Connected(elapsed)) => this
+                                .text_color(cx.theme().success)
+                                .child(format!("Connected in {} ms.", elapsed.as_millis())),
+                            Some(TestStatus::Failed(err)) => {
+                                this.text_color(cx.theme().danger).child(err.clone())
+                            }
+                        }
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("test-connection")
+                                    .small()
+                                    .outline()
+                                    .label("Test Connection")
+                                    .loading(matches!(self.status, Some(TestStatus::Testing)))
+                                    .on_click(
+                                        cx.listener(|this, _, _, cx| this.test_connection(cx)),
+                                    ),
+                            )
+                            .child(
+                                Button::new("save-network-settings")
+                                    .small()
+                                    .primary()
+                                    .label("Save")
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.save(window, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+    }
+}
+
+/// A wrapper explaining why the controls in it are disabled, when the settings are `locked`.
+fn locked_tooltip(id: &'static str, locked: bool) -> Stateful<Div> {
+    div().id(id).when(locked, |this| {
+        this.tooltip(|window, cx| Tooltip::new(policy::LOCKED_TOOLTIP).build(window, cx))
+    })
+}
+
+fn open_dialog(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        let view = cx.new(|cx| NetworkSettingsView::new(window, cx));
+        window.open_modal(cx, move |modal, _, _| {
+            modal.title("Network").child(view.clone())
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ShowNetworkSettings, cx| open_dialog(cx));
+}
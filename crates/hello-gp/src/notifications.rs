@@ -0,0 +1,462 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use chrono::{DateTime, Local};
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, Context, DismissEvent, ElementId, Entity, EventEmitter,
+    FocusHandle, Focusable, Global, InteractiveElement as _, IntoElement, ParentElement as _,
+    Render, SharedString, StatefulInteractiveElement as _, Styled as _, Subscription, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    notification::{Notification, NotificationType},
+    progress::Progress,
+    v_flex,
+};
+
+use crate::{
+    format,
+    markdown::Markdown,
+    native_notifications,
+    sounds::{SoundEvent, Sounds},
+    tasks::{TaskId, TaskScheduler, TaskStatus},
+};
+
+/// How many notifications the notification center keeps.
+const MAX_ENTRIES: usize = 100;
+
+pub(crate) type ActionHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+/// A button on a [`Toast`], the toast is dismissed after the handler ran.
+#[derive(Clone)]
+struct ToastAction {
+    label: SharedString,
+    handler: ActionHandler,
+}
+
+/// A notification with action buttons and an optional progress bar, recorded in the
+/// [`NotificationCenter`].
+///
+/// ```ignore
+/// Toast::new(NotificationType::Success, "Exported 3 themes")
+///     .action("Open Folder", move |_, cx| cx.open_with_system(&dir))
+///     .show(window, cx);
+/// ```
+#[derive(Clone)]
+pub struct Toast {
+    kind: NotificationType,
+    title: Option<SharedString>,
+    message: SharedString,
+    actions: Vec<ToastAction>,
+    task: Option<TaskId>,
+    important: bool,
+    markdown: bool,
+}
+
+impl Toast {
+    pub fn new(kind: NotificationType, message: impl Into<SharedString>) -> Self {
+        Self {
+            kind,
+            title: None,
+            message: message.into(),
+            actions: vec![],
+            task: None,
+            important: matches!(kind, NotificationType::Error),
+            markdown: false,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<SharedString>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Also show the toast through the OS while the app is in the background, errors always are.
+    pub fn important(mut self) -> Self {
+        self.important = true;
+        self
+    }
+
+    /// Render the message as Markdown, e.g. with links or code. The OS notification shows
+    /// it as is.
+    pub fn markdown(mut self) -> Self {
+        self.markdown = true;
+        self
+    }
+
+    pub fn action(
+        mut self,
+        label: impl Into<SharedString>,
+        handler: impl Fn(&mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.actions.push(ToastAction {
+            label: label.into(),
+            handler: Rc::new(handler),
+        });
+        self
+    }
+
+    /// Follow a task of the [`TaskScheduler`] with a progress bar, the toast stays until the
+    /// task finished and then turns into a success or error toast.
+    pub fn progress(task: TaskId, title: impl Into<SharedString>) -> Self {
+        Self {
+            task: Some(task),
+            ..Self::new(NotificationType::Info, title)
+        }
+    }
+
+    fn id(&self) -> ElementId {
+        match self.task {
+            Some(task) => ("task-toast", task.0).into(),
+            None => ("toast", self.message.clone()).into(),
+        }
+    }
+
+    fn notification(&self) -> Notification {
+        let toast = self.clone();
+        let mut notification = Notification::new()
+            .with_type(self.kind)
+            .id1::<Toast>(self.id())
+            .autohide(self.task.is_none() && self.actions.is_empty())
+            .content(move |window, cx| toast.render_content(window, cx));
+        if let Some(title) = self.title.clone() {
+            notification = notification.title(title);
+        }
+        notification
+    }
+
+    fn render_content(&self, _: &mut Window, cx: &mut Context<Notification>) -> gpui::AnyElement {
+        let notification = cx.entity();
+        let progress = self.task.and_then(|id| {
+            TaskScheduler::global(cx)
+                .read(cx)
+                .tasks()
+                .iter()
+                .find(|task| task.id == id)
+                .map(|task| task.progress.unwrap_or(0.))
+        });
+
+        v_flex()
+            .gap_2()
+            .map(|this| {
+                if self.markdown {
+                    this.child(Markdown::new("toast-message", self.message.clone()))
+                } else {
+                    this.child(div().text_sm().child(self.message.clone()))
+                }
+            })
+            .when_some(progress, |this, progress| {
+                this.child(Progress::new().value(progress * 100.))
+            })
+            .when(!self.actions.is_empty(), |this| {
+                this.child(h_flex().gap_2().justify_end().children(
+                    self.actions.iter().enumerate().map(|(ix, action)| {
+                        let handler = action.handler.clone();
+                        let notification = notification.clone();
+                        Button::new(("toast-action", ix))
+                            .xsmall()
+                            .outline()
+                            .label(action.label.clone())
+                            .on_click(move |_, window, cx| {
+                                handler(window, cx);
+                                notification.update(cx, |notification, cx| {
+                                    notification.dismiss(window, cx)
+                                });
+                            })
+                    }),
+                ))
+            })
+            .into_any_element()
+    }
+
+    /// Add the toast to the notification center, and to the OS one if it's important.
+    fn record(&self, window: AnyWindowHandle, cx: &mut App) {
+        NotificationCenter::record(
+            self.kind,
+            self.title.clone(),
+            self.message.clone(),
+            self.markdown,
+            cx,
+        );
+        if self.important {
+            let actions = self
+                .actions
+                .iter()
+                .map(|action| (action.label.clone(), action.handler.clone()))
+                .collect();
+            native_notifications::mirror(
+                self.title.clone(),
+                self.message.clone(),
+                actions,
+                window,
+                cx,
+            );
+        }
+    }
+
+    /// Show the toast in `window` and add it to the notification center.
+    pub fn show(self, window: &mut Window, cx: &mut App) {
+        self.record(window.window_handle(), cx);
+        window.push_notification(self.notification(), cx);
+
+        if let Some(task) = self.task {
+            follow_task(task, self, window.window_handle(), cx);
+        }
+    }
+}
+
+/// Redraw the progress toast while `task` runs, and swap it for the outcome once it's done.
+fn follow_task(task: TaskId, toast: Toast, window: AnyWindowHandle, cx: &mut App) {
+    let subscription: Rc<RefCell<Option<Subscription>>> = Rc::default();
+    let scheduler = TaskScheduler::global(cx);
+    *subscription.borrow_mut() = Some(cx.observe(&scheduler, {
+        let subscription = subscription.clone();
+        move |scheduler, cx| {
+            let status = scheduler
+                .read(cx)
+                .tasks()
+                .iter()
+                .find(|info| info.id == task)
+                .map(|info| info.status.clone());
+            let done = match status {
+                Some(TaskStatus::Running) => None,
+                Some(TaskStatus::Completed) => Some((
+                    NotificationType::Success,
+                    format!("{} finished", toast.message),
+                )),
+                Some(TaskStatus::Failed(err)) => Some((
+                    NotificationType::Error,
+                    format!("{} failed: {}", toast.message, err),
+                )),
+                Some(TaskStatus::Cancelled) | None => Some((
+                    NotificationType::Warning,
+                    format!("{} cancelled", toast.message),
+                )),
+            };
+
+            let Some((kind, message)) = done else {
+                return;
+            };
+            subscription.borrow_mut().take();
+            // Same id, so the outcome replaces the progress toast.
+            let outcome = Toast {
+                kind,
+                message: message.into(),
+                task: None,
+                // Likely finished while the user was doing something else.
+                important: true,
+                ..toast.clone()
+            };
+            outcome.record(window, cx);
+            let notification = outcome.notification().id1::<Toast>(toast.id());
+            _ = window.update(cx, |_, window, cx| {
+                window.push_notification(notification, cx)
+            });
+        }
+    }));
+}
+
+/// A notification as kept by the [`NotificationCenter`].
+#[derive(Debug, Clone)]
+pub struct NotificationEntry {
+    pub kind: NotificationType,
+    pub title: Option<SharedString>,
+    pub message: SharedString,
+    /// Whether `message` is Markdown.
+    pub markdown: bool,
+    pub created: DateTime<Local>,
+}
+
+struct GlobalNotificationCenter(Entity<NotificationCenter>);
+
+impl Global for GlobalNotificationCenter {}
+
+/// The recent notifications, for the list behind the bell in the title bar.
+pub struct NotificationCenter {
+    entries: VecDeque<NotificationEntry>,
+    unread: usize,
+}
+
+impl NotificationCenter {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalNotificationCenter>().0.clone()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &NotificationEntry> {
+        self.entries.iter()
+    }
+
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn record(
+        kind: NotificationType,
+        title: Option<SharedString>,
+        message: SharedString,
+        markdown: bool,
+        cx: &mut App,
+    ) {
+        Sounds::play(
+            if matches!(kind, NotificationType::Error) {
+                SoundEvent::Error
+            } else {
+                SoundEvent::Notification
+            },
+            cx,
+        );
+        Self::global(cx).update(cx, |this, cx| {
+            this.entries.push_front(NotificationEntry {
+                kind,
+                title,
+                message,
+                markdown,
+                created: Local::now(),
+            });
+            this.entries.truncate(MAX_ENTRIES);
+            this.unread += 1;
+            cx.notify();
+        });
+    }
+
+    pub fn mark_read(&mut self, cx: &mut Context<Self>) {
+        if self.unread > 0 {
+            self.unread = 0;
+            cx.notify();
+        }
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.unread = 0;
+        cx.notify();
+    }
+}
+
+/// The notification center list shown in the title bar popover.
+pub struct NotificationList {
+    focus_handle: FocusHandle,
+    center: Entity<NotificationCenter>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl NotificationList {
+    pub fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        let center = NotificationCenter::global(cx);
+        let _subscriptions = vec![cx.observe(&center, |_, _, cx| cx.notify())];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            center,
+            _subscriptions,
+        }
+    }
+
+    fn render_entry(&self, ix: usize, entry: &NotificationEntry, cx: &App) -> impl IntoElement {
+        let color = match entry.kind {
+            NotificationType::Info => cx.theme().info,
+            NotificationType::Success => cx.theme().success,
+            NotificationType::Warning => cx.theme().warning,
+            NotificationType::Error => cx.theme().danger,
+        };
+
+        h_flex()
+            .id(("notification-entry", ix))
+            .gap_2()
+            .items_start()
+            .child(
+                div()
+                    .mt_1p5()
+                    .size_2()
+                    .flex_shrink_0()
+                    .rounded_full()
+                    .bg(color),
+            )
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .when_some(entry.title.clone(), |this, title| {
+                        this.child(div().text_sm().font_semibold().child(title))
+                    })
+                    .map(|this| {
+                        if entry.markdown {
+                            this.child(Markdown::new(
+                                ("notification-message", ix),
+                                entry.message.clone(),
+                            ))
+                        } else {
+                            this.child(div().text_sm().child(entry.message.clone()))
+                        }
+                    })
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format::time(&entry.created)),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<DismissEvent> for NotificationList {}
+
+impl Focusable for NotificationList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for NotificationList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self.center.read(cx).entries().cloned().collect::<Vec<_>>();
+
+        v_flex()
+            .id("notification-list")
+            .p_3()
+            .gap_3()
+            .w(px(360.))
+            .max_h(px(480.))
+            .overflow_y_scroll()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(div().font_semibold().child("Notifications"))
+                    .when(!entries.is_empty(), |this| {
+                        this.child(
+                            Button::new("clear-notifications")
+                                .xsmall()
+                                .ghost()
+                                .label("Clear")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.center.update(cx, |center, cx| center.clear(cx));
+                                })),
+                        )
+                    }),
+            )
+            .when(entries.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("No notifications."),
+                )
+            })
+            .children(
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| self.render_entry(ix, entry, cx))
+                    .collect::<Vec<_>>(),
+            )
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let center = cx.new(|_| NotificationCenter {
+        entries: VecDeque::new(),
+        unread: 0,
+    });
+    cx.set_global(GlobalNotificationCenter(center));
+}
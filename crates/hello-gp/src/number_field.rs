@@ -0,0 +1,372 @@
+//! A number input with a stepper, a unit after the value, and a slider to scrub through the
+//! range.
+//!
+//! ```ignore
+//! let max_fps = cx.new(|cx| {
+//!     NumberField::new(60., window, cx)
+//!         .range(0., 240.)
+//!         .step(10.)
+//!         .unit("fps")
+//!         .slider()
+//! });
+//! cx.subscribe(&max_fps, |_, _, NumberFieldEvent::Change(value), _| println!("{}", value));
+//! ```
+
+use gpui::{
+    App, AppContext as _, Bounds, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, KeyBinding, MouseButton, MouseDownEvent, MouseMoveEvent,
+    ParentElement as _, Pixels, Render, SharedString, Styled as _, Subscription, Window, actions,
+    canvas, div, prelude::FluentBuilder as _, px, relative,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _, h_flex,
+    input::{InputEvent, InputState, NumberInput, NumberInputEvent, StepAction},
+    v_flex,
+};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    forms,
+};
+
+actions!(
+    number_field,
+    [StepUp, StepDown, PageUp, PageDown, ToMin, ToMax]
+);
+
+const SLIDER_CONTEXT: &str = "NumberFieldSlider";
+/// How many steps a page up or down goes.
+const PAGE_STEPS: f64 = 10.;
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("right", StepUp, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("up", StepUp, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("left", StepDown, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("down", StepDown, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("pageup", PageUp, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("shift-right", PageUp, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("pagedown", PageDown, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("shift-left", PageDown, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("home", ToMin, Some(SLIDER_CONTEXT)),
+        KeyBinding::new("end", ToMax, Some(SLIDER_CONTEXT)),
+    ]);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NumberFieldEvent {
+    /// The value was stepped, scrubbed or typed, within the range.
+    Change(f64),
+}
+
+/// A number from `min` to `max` in steps of `step`.
+///
+/// The stepper, the arrow keys and the slider keep the value within the range. A typed value
+/// outside of it is flagged under the field rather than clamped, and not taken.
+pub struct NumberField {
+    input: Entity<InputState>,
+    value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+    unit: Option<SharedString>,
+    slider: bool,
+    error: Option<SharedString>,
+    slider_focus: FocusHandle,
+    track: Bounds<Pixels>,
+    scrubbing: bool,
+    /// Set while the text is set from the value, so it isn't parsed back.
+    syncing: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl NumberField {
+    /// A field from 0 to 100 in steps of 1, until given a range and a step.
+    pub fn new(value: f64, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).default_value(format_value(value)));
+        let _subscriptions = vec![
+            cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+                match event {
+                    InputEvent::Change if !this.syncing => this.parse(cx),
+                    // Show the value taken when leaving a field with an error.
+                    InputEvent::Blur if this.error.is_some() => {
+                        this.set_value(this.value, window, cx)
+                    }
+                    _ => {}
+                }
+            }),
+            cx.subscribe_in(
+                &input,
+                window,
+                |this, _, event: &NumberInputEvent, window, cx| {
+                    let NumberInputEvent::Step(action) = event;
+                    let steps = match action {
+                        StepAction::Increment => 1.,
+                        StepAction::Decrement => -1.,
+                    };
+                    this.step_by(steps, window, cx);
+                },
+            ),
+        ];
+
+        Self {
+            input,
+            value,
+            min: 0.,
+            max: 100.,
+            step: 1.,
+            unit: None,
+            slider: false,
+            error: None,
+            slider_focus: cx.focus_handle().tab_stop(true),
+            track: Bounds::default(),
+            scrubbing: false,
+            syncing: false,
+            _subscriptions,
+        }
+    }
+
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// A unit shown after the value, e.g. `px` or `%`.
+    pub fn unit(mut self, unit: impl Into<SharedString>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Show a slider beside the field.
+    pub fn slider(mut self) -> Self {
+        self.slider = true;
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Whether the typed value is taken, rather than flagged.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Set the value without emitting [`NumberFieldEvent::Change`], clamped to the range.
+    pub fn set_value(&mut self, value: f64, window: &mut Window, cx: &mut Context<Self>) {
+        self.value = value.clamp(self.min, self.max);
+        self.error = None;
+        self.syncing = true;
+        let text = format_value(self.value);
+        self.input
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+        self.syncing = false;
+        cx.notify();
+    }
+
+    fn change(&mut self, value: f64, window: &mut Window, cx: &mut Context<Self>) {
+        let value = self.snap(value);
+        let changed = value != self.value;
+        self.set_value(value, window, cx);
+        if changed {
+            cx.emit(NumberFieldEvent::Change(self.value));
+        }
+    }
+
+    /// Round to a step from `min`, within the range.
+    fn snap(&self, value: f64) -> f64 {
+        let value = value.clamp(self.min, self.max);
+        if self.step <= 0. {
+            return value;
+        }
+        let steps = ((value - self.min) / self.step).round();
+        (self.min + steps * self.step).clamp(self.min, self.max)
+    }
+
+    fn step_by(&mut self, steps: f64, window: &mut Window, cx: &mut Context<Self>) {
+        self.change(self.value + steps * self.step, window, cx);
+    }
+
+    fn parse(&mut self, cx: &mut Context<Self>) {
+        let text = self.input.read(cx).value().trim().to_string();
+        let whole = self.step.fract() == 0. && self.min.fract() == 0.;
+        self.error = forms::number_in(self.min, self.max)(&text)
+            .and_then(|()| {
+                if whole {
+                    forms::whole_number(&text)
+                } else {
+                    Ok(())
+                }
+            })
+            .err();
+        if self.error.is_none()
+            && let Ok(value) = text.parse::<f64>()
+            && value != self.value
+        {
+            self.value = value;
+            cx.emit(NumberFieldEvent::Change(value));
+        }
+        cx.notify();
+    }
+
+    fn scrub_to(&mut self, x: Pixels, window: &mut Window, cx: &mut Context<Self>) {
+        let width = self.track.size.width.as_f32();
+        if width <= 0. {
+            return;
+        }
+        let fraction = ((x - self.track.origin.x).as_f32() / width).clamp(0., 1.) as f64;
+        self.change(self.min + fraction * (self.max - self.min), window, cx);
+    }
+
+    fn mouse_down(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        window.focus(&self.slider_focus);
+        self.scrubbing = true;
+        self.scrub_to(event.position.x, window, cx);
+    }
+
+    fn mouse_move(&mut self, event: &MouseMoveEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.scrubbing {
+            return;
+        }
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.scrubbing = false;
+            cx.notify();
+            return;
+        }
+        self.scrub_to(event.position.x, window, cx);
+    }
+
+    fn render_slider(&self, window: &Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entity = cx.entity();
+        let fraction = if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)) as f32
+        } else {
+            0.
+        };
+        let focused = self.slider_focus.is_focused(window);
+
+        div()
+            .id("number-field-slider")
+            .key_context(SLIDER_CONTEXT)
+            .track_focus(&self.slider_focus)
+            .on_action(cx.listener(|this, _: &StepUp, window, cx| this.step_by(1., window, cx)))
+            .on_action(cx.listener(|this, _: &StepDown, window, cx| this.step_by(-1., window, cx)))
+            .on_action(
+                cx.listener(|this, _: &PageUp, window, cx| this.step_by(PAGE_STEPS, window, cx)),
+            )
+            .on_action(
+                cx.listener(|this, _: &PageDown, window, cx| this.step_by(-PAGE_STEPS, window, cx)),
+            )
+            .on_action(cx.listener(|this, _: &ToMin, window, cx| this.change(this.min, window, cx)))
+            .on_action(cx.listener(|this, _: &ToMax, window, cx| this.change(this.max, window, cx)))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::mouse_down))
+            .on_mouse_move(cx.listener(Self::mouse_move))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| {
+                    this.scrubbing = false;
+                    cx.notify();
+                }),
+            )
+            .cursor_role(
+                if self.scrubbing {
+                    CursorRole::Grabbing
+                } else {
+                    CursorRole::Grab
+                },
+                cx,
+            )
+            .relative()
+            .flex_1()
+            .min_w(px(96.))
+            .h_6()
+            .flex()
+            .items_center()
+            .child(
+                canvas(
+                    move |bounds, _, cx| entity.update(cx, |this, _| this.track = bounds),
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .h_1p5()
+                    .rounded_full()
+                    .bg(cx.theme().secondary)
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(fraction))
+                            .rounded_full()
+                            .bg(cx.theme().primary),
+                    ),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .left(relative(fraction))
+                    .ml(px(-7.))
+                    .size(px(14.))
+                    .rounded_full()
+                    .border_2()
+                    .border_color(if focused {
+                        cx.theme().ring
+                    } else {
+                        cx.theme().primary
+                    })
+                    .bg(cx.theme().background),
+            )
+    }
+}
+
+/// The shortest form of the value, `0.3` rather than `0.30000000000000004`.
+fn format_value(value: f64) -> String {
+    (value as f32).to_string()
+}
+
+impl EventEmitter<NumberFieldEvent> for NumberField {}
+
+impl Focusable for NumberField {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.input.read(cx).focus_handle(cx)
+    }
+}
+
+impl Render for NumberField {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .gap_3()
+                    .when(self.slider, |this| {
+                        this.child(self.render_slider(window, cx))
+                    })
+                    .child(div().w(px(128.)).child(
+                        NumberInput::new(&self.input).small().when_some(
+                            self.unit.clone(),
+                            |this, unit| {
+                                this.suffix(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(unit),
+                                )
+                            },
+                        ),
+                    )),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_xs().text_color(cx.theme().danger).child(error))
+            })
+    }
+}
@@ -4,7 +4,7 @@ use gpui::{
 };
 use regex::Regex;
 
-use crate::section;
+use crate::{number_field::NumberField, section};
 use gpui_component::{
     ActiveTheme, Disableable, IconName, Sizable,
     button::{Button, ButtonVariants},
@@ -24,6 +24,7 @@ pub struct NumberInputStory {
     number_input4: Entity<InputState>,
     number_input4_value: f64,
     disabled_input: Entity<InputState>,
+    number_field: Entity<NumberField>,
 
     _subscriptions: Vec<Subscription>,
 }
@@ -89,6 +90,14 @@ impl NumberInputStory {
                 .placeholder("Disabled input")
         });
 
+        let number_field = cx.new(|cx| {
+            NumberField::new(16., window, cx)
+                .range(8., 32.)
+                .step(1.)
+                .unit("px")
+                .slider()
+        });
+
         let _subscriptions = vec![
             cx.subscribe_in(&number_input1, window, Self::on_input_event),
             cx.subscribe_in(&number_input1, window, Self::on_number_input_event),
@@ -112,6 +121,7 @@ impl NumberInputStory {
             number_input4,
             number_input4_value: 0.0,
             disabled_input,
+            number_field,
             _subscriptions,
         }
     }
@@ -244,6 +254,11 @@ impl Render for NumberInputStory {
                     .max_w_md()
                     .child(NumberInput::new(&self.number_input3)),
             )
+            .child(
+                section("With slider and unit")
+                    .max_w_md()
+                    .child(self.number_field.clone()),
+            )
             .child(
                 section("Without appearance").max_w_md().child(
                     div()
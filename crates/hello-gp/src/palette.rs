@@ -0,0 +1,245 @@
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, FocusHandle, Focusable, Global,
+    InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Render, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Window, actions, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _,
+    input::{InputEvent, InputState},
+    v_flex,
+};
+
+use crate::{
+    about::ShowLicenses,
+    cursor::{CursorExt as _, CursorRole},
+    diagnostics::{ReportIssue, ShowGpuDiagnostics},
+    dismiss::{DismissLayer, DismissStack},
+    extension_manager::ShowExtensions,
+    history::UndoSettingsChange,
+    kiosk,
+    lock::LockApp,
+    macos::ToggleFullScreen,
+    macros,
+    net_settings::ShowNetworkSettings,
+    pointer::ShowPointerDiagnostics,
+    profiles::NewProfile,
+    prototypes::{self, OpenPrototypes},
+    scopes::ShortcutScope,
+    settings_window::{ENTRIES, RevealSetting, ShowSettings},
+    shortcuts::ShowShortcuts,
+    sounds::ToggleMute,
+    style_guide::ShowStyleGuide,
+    telemetry::ShowTelemetry,
+    text_field::TextField,
+    themes::{ExportTheme, ImportTheme, ToggleThemeMode},
+    whats_new::ShowWhatsNew,
+};
+
+actions!(palette, [ToggleCommandPalette]);
+
+/// How many matching commands the palette lists.
+const MAX_RESULTS: usize = 12;
+
+struct Command {
+    title: SharedString,
+    action: Box<dyn Action>,
+}
+
+/// The commands of the command palette, by title.
+#[derive(Default)]
+pub struct Commands {
+    commands: Vec<Command>,
+}
+
+impl Global for Commands {}
+
+impl Commands {
+    /// List `action` in the command palette as `title`, e.g. "Settings: Accent color".
+    pub fn register(title: impl Into<SharedString>, action: impl Action, cx: &mut App) {
+        cx.global_mut::<Self>().commands.push(Command {
+            title: title.into(),
+            action: Box::new(action),
+        });
+    }
+
+    /// The commands with every word of `query` in their title, that may run right now.
+    fn matching(query: &str, cx: &App) -> Vec<(SharedString, Box<dyn Action>)> {
+        let query = query.to_lowercase();
+        cx.global::<Self>()
+            .commands
+            .iter()
+            .filter(|command| {
+                let title = command.title.to_lowercase();
+                query.split_whitespace().all(|word| title.contains(word))
+            })
+            .filter(|command| kiosk::allows(command.action.as_ref(), cx))
+            .take(MAX_RESULTS)
+            .map(|command| (command.title.clone(), command.action.boxed_clone()))
+            .collect()
+    }
+}
+
+/// Find and run a command by its title.
+pub struct CommandPalette {
+    focus_handle: FocusHandle,
+    input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+    _dismiss: Subscription,
+}
+
+impl CommandPalette {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Type a command..."));
+        let _subscriptions = vec![
+            cx.subscribe_in(&input, window, |this, _, event, window, cx| match event {
+                InputEvent::Change => cx.notify(),
+                InputEvent::PressEnter { .. } => {
+                    let query = this.input.read(cx).value().to_string();
+                    if let Some((_, action)) = Commands::matching(&query, cx).into_iter().next() {
+                        Self::run(action, window, cx);
+                    }
+                }
+                _ => {}
+            }),
+        ];
+        let _dismiss = DismissStack::register(DismissLayer::Palette, window, cx, |window, cx| {
+            window.close_modal(cx);
+            true
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            input,
+            _subscriptions,
+            _dismiss,
+        }
+    }
+
+    fn run(action: Box<dyn Action>, window: &mut Window, cx: &mut App) {
+        window.close_modal(cx);
+        macros::record(action.as_ref(), cx);
+        window.dispatch_action(action, cx);
+    }
+}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.input.read(cx).value().to_string();
+        let commands = Commands::matching(&query, cx);
+        let hover_bg = cx.theme().accent;
+
+        v_flex()
+            .gap_2()
+            .child(TextField::new("palette-input", &self.input))
+            .child(
+                v_flex()
+                    .id("palette-commands")
+                    .max_h(px(360.))
+                    .overflow_y_scroll()
+                    .when(commands.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_2()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No matching commands."),
+                        )
+                    })
+                    .children(
+                        commands
+                            .into_iter()
+                            .enumerate()
+                            .map(|(ix, (title, action))| {
+                                div()
+                                    .id(("palette-command", ix))
+                                    .p_2()
+                                    .text_sm()
+                                    .rounded(cx.theme().radius)
+                                    .cursor_role(CursorRole::Pointer, cx)
+                                    .when(ix == 0, |this| this.bg(hover_bg))
+                                    .hover(|this| this.bg(hover_bg))
+                                    .child(title)
+                                    .on_click(move |_, window, cx| {
+                                        Self::run(action.boxed_clone(), window, cx)
+                                    })
+                            }),
+                    ),
+            )
+    }
+}
+
+fn open(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    _ = window.update(cx, |_, window, cx| {
+        let palette = cx.new(|cx| CommandPalette::new(window, cx));
+        window.open_modal(cx, move |modal, _, _| {
+            modal.title("Commands").child(palette.clone())
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Commands::default());
+
+    Commands::register("Settings: Open", ShowSettings, cx);
+    Commands::register("Settings: Undo Last Change", UndoSettingsChange, cx);
+    for entry in ENTRIES {
+        Commands::register(
+            format!("Settings: {}", entry.title),
+            RevealSetting(entry.key.into()),
+            cx,
+        );
+    }
+    Commands::register("Appearance: Toggle Dark Mode", ToggleThemeMode, cx);
+    Commands::register("Appearance: Style Guide", ShowStyleGuide, cx);
+    Commands::register("Appearance: Import Theme", ImportTheme, cx);
+    Commands::register("Appearance: Export Theme", ExportTheme, cx);
+    Commands::register("Window: Toggle Full Screen", ToggleFullScreen, cx);
+    Commands::register("Window: New Layout Profile", NewProfile, cx);
+    Commands::register("Lock", LockApp, cx);
+    Commands::register("Sounds: Toggle Mute", ToggleMute, cx);
+    Commands::register("Network Settings", ShowNetworkSettings, cx);
+    Commands::register("Extensions", ShowExtensions, cx);
+    Commands::register("Telemetry", ShowTelemetry, cx);
+    Commands::register("GPU Diagnostics", ShowGpuDiagnostics, cx);
+    Commands::register("Pointer Diagnostics", ShowPointerDiagnostics, cx);
+    Commands::register("Report an Issue", ReportIssue, cx);
+    Commands::register("What's New", ShowWhatsNew, cx);
+    Commands::register("Keyboard Shortcuts", ShowShortcuts, cx);
+    Commands::register("Third-Party Licenses", ShowLicenses, cx);
+    if prototypes::enabled() {
+        Commands::register("Developer: Open Prototypes", OpenPrototypes, cx);
+    }
+    #[cfg(any(feature = "inspector", debug_assertions))]
+    Commands::register(
+        "Developer: Toggle Inspector",
+        crate::inspector::ToggleInspector,
+        cx,
+    );
+
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new(
+            "cmd-shift-p",
+            ToggleCommandPalette,
+            Some(ShortcutScope::Global.context()),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new(
+            "ctrl-shift-p",
+            ToggleCommandPalette,
+            Some(ShortcutScope::Global.context()),
+        ),
+    ]);
+    cx.on_action(|_: &ToggleCommandPalette, cx| open(cx));
+}
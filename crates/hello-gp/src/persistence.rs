@@ -0,0 +1,145 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use gpui::{App, AppContext as _, Context, Entity, Global, Timer};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{config, settings::Settings, themes::get_config_dir};
+
+/// How long after the first unsaved change everything pending is written. Later changes
+/// don't push the write back, so a steady stream of them is still written twice a second.
+const WRITE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveMode {
+    /// Shortly after every change.
+    #[default]
+    OnChange,
+    /// Every `interval` seconds, while something changed.
+    Interval,
+    /// Only when the app quits, or the OS ends it.
+    OnExit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PersistenceSettings {
+    /// When changes to the settings, the session and the theme are written to disk.
+    pub save: SaveMode,
+    /// Seconds between writes, with `save = "interval"`.
+    pub interval: u64,
+}
+
+impl Default for PersistenceSettings {
+    fn default() -> Self {
+        Self {
+            save: SaveMode::OnChange,
+            interval: 30,
+        }
+    }
+}
+
+impl PersistenceSettings {
+    /// How long after the first unsaved change the pending writes are flushed, `None` until
+    /// the app quits.
+    fn delay(&self) -> Option<Duration> {
+        match self.save {
+            SaveMode::OnChange => Some(WRITE_DELAY),
+            SaveMode::Interval => Some(Duration::from_secs(self.interval.max(1))),
+            SaveMode::OnExit => None,
+        }
+    }
+}
+
+type Write = Box<dyn FnOnce() -> anyhow::Result<()>>;
+
+/// Config writes waiting to hit the disk, at most one per config.
+///
+/// State that changes as the user moves things around (the session, the theme) is written
+/// through here, so dragging a split doesn't rewrite the file on every frame. When exactly
+/// is up to [`PersistenceSettings`], everything pending is flushed when the app quits.
+pub struct Persistence {
+    pending: BTreeMap<&'static str, Write>,
+    flush_scheduled: bool,
+}
+
+struct GlobalPersistence(Entity<Persistence>);
+
+impl Global for GlobalPersistence {}
+
+impl Persistence {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalPersistence>().0.clone()
+    }
+
+    /// Write `value` as the config `name` later, replacing a pending write of the same config.
+    pub fn schedule<T>(name: &'static str, value: T, cx: &mut App)
+    where
+        T: Serialize + DeserializeOwned + Default + 'static,
+    {
+        // Writes during startup may come before the settings are loaded.
+        let delay = cx
+            .try_global::<Settings>()
+            .map(|settings| settings.persistence.delay())
+            .unwrap_or(Some(WRITE_DELAY));
+        Self::global(cx).update(cx, |this, cx| {
+            this.pending.insert(
+                name,
+                Box::new(move || config::save(&get_config_dir(), name, &value)),
+            );
+            if let Some(delay) = delay
+                && !this.flush_scheduled
+            {
+                this.flush_scheduled = true;
+                cx.spawn(async move |this, cx| {
+                    Timer::after(delay).await;
+                    _ = this.update(cx, |this, cx| this.flush(cx));
+                })
+                .detach();
+            }
+            cx.notify();
+        });
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// The names of the configs with unsaved changes.
+    pub fn pending(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.pending.keys().copied()
+    }
+
+    fn flush(&mut self, cx: &mut Context<Self>) {
+        self.flush_scheduled = false;
+        if self.pending.is_empty() {
+            return;
+        }
+        for (name, write) in std::mem::take(&mut self.pending) {
+            if let Err(err) = write() {
+                tracing::error!("Failed to save {}: {}", name, err);
+            }
+        }
+        cx.notify();
+    }
+}
+
+/// Write everything pending right away.
+pub fn flush(cx: &mut App) {
+    Persistence::global(cx).update(cx, |this, cx| this.flush(cx));
+}
+
+pub fn init(cx: &mut App) {
+    let persistence = cx.new(|_| Persistence {
+        pending: BTreeMap::new(),
+        flush_scheduled: false,
+    });
+    cx.set_global(GlobalPersistence(persistence));
+
+    cx.on_app_quit(|cx| {
+        flush(cx);
+        async {}
+    })
+    .detach();
+}
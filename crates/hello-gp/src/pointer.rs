@@ -0,0 +1,322 @@
+use std::{
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use gpui::{
+    App, AppContext as _, Bounds, Context, Entity, FocusHandle, Focusable, Global, Hsla,
+    InteractiveElement, IntoElement, Modifiers, MouseButton, ParentElement as _, Pixels, Point,
+    Render, StatefulInteractiveElement as _, Styled as _, Window, actions, canvas, div, fill, px,
+    size,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::cursor::{CursorExt as _, CursorRole};
+
+actions!(diagnostics, [ShowPointerDiagnostics]);
+
+/// How long a stylus sample applies to the mouse events after it.
+const SAMPLE_LIFETIME: Duration = Duration::from_millis(50);
+/// How many events the diagnostics keep and draw.
+const MAX_EVENTS: usize = 500;
+/// How many of the latest events the diagnostics list.
+const LISTED_EVENTS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerKind {
+    Mouse,
+    Pen,
+    Touch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
+}
+
+/// What a pen or a touch screen reports on top of the mouse.
+#[derive(Debug, Clone, Copy)]
+pub struct StylusSample {
+    pub kind: PointerKind,
+    /// From 0 to 1.
+    pub pressure: f32,
+    /// The angles of the pen from upright towards x and y, in degrees from -90 to 90.
+    pub tilt: Option<(f32, f32)>,
+}
+
+/// A pointer event of any kind of pointer, for views that draw with it.
+#[derive(Debug, Clone)]
+pub struct PointerEvent {
+    pub kind: PointerKind,
+    pub phase: PointerPhase,
+    /// In window coordinates.
+    pub position: Point<Pixels>,
+    /// Whether the pointer touches, or the primary button is down.
+    pub pressed: bool,
+    /// From 0 to 1; 0.5 while a pointer without pressure is pressed, and 0 while it isn't.
+    pub pressure: f32,
+    pub tilt: Option<(f32, f32)>,
+    pub modifiers: Modifiers,
+    pub time: Instant,
+}
+
+/// The latest sample of a pen or touch screen.
+///
+/// GPUI delivers pens and touches as the mouse, without pressure or tilt. A platform source,
+/// e.g. a tablet driver, passes them to [`Stylus::report`], and the mouse events right after
+/// a sample become events of that pointer.
+#[derive(Default)]
+pub struct Stylus {
+    sample: Option<(StylusSample, Instant)>,
+}
+
+impl Global for Stylus {}
+
+impl Stylus {
+    pub fn report(sample: StylusSample, cx: &mut App) {
+        cx.global_mut::<Self>().sample = Some((sample, Instant::now()));
+    }
+
+    fn sample(cx: &App) -> Option<StylusSample> {
+        let (sample, time) = cx.try_global::<Self>()?.sample?;
+        (time.elapsed() < SAMPLE_LIFETIME).then_some(sample)
+    }
+}
+
+impl PointerEvent {
+    fn new(
+        phase: PointerPhase,
+        position: Point<Pixels>,
+        pressed: bool,
+        modifiers: Modifiers,
+        cx: &App,
+    ) -> Self {
+        let sample = Stylus::sample(cx);
+        let pressure = match sample {
+            Some(sample) => sample.pressure.clamp(0., 1.),
+            None if pressed => 0.5,
+            None => 0.,
+        };
+        Self {
+            kind: sample.map_or(PointerKind::Mouse, |sample| sample.kind),
+            phase,
+            position,
+            pressed,
+            pressure,
+            tilt: sample.and_then(|sample| sample.tilt),
+            modifiers,
+            time: Instant::now(),
+        }
+    }
+}
+
+/// Listen for [`PointerEvent`]s over an element, instead of the mouse events.
+pub trait PointerExt: InteractiveElement + Sized {
+    fn on_pointer(self, listener: impl Fn(&PointerEvent, &mut Window, &mut App) + 'static) -> Self {
+        let listener = Rc::new(listener);
+        self.on_mouse_down(MouseButton::Left, {
+            let listener = listener.clone();
+            move |event, window, cx| {
+                let event = PointerEvent::new(
+                    PointerPhase::Down,
+                    event.position,
+                    true,
+                    event.modifiers,
+                    cx,
+                );
+                listener(&event, window, cx)
+            }
+        })
+        .on_mouse_move({
+            let listener = listener.clone();
+            move |event, window, cx| {
+                let pressed = event.pressed_button == Some(MouseButton::Left);
+                let event = PointerEvent::new(
+                    PointerPhase::Move,
+                    event.position,
+                    pressed,
+                    event.modifiers,
+                    cx,
+                );
+                listener(&event, window, cx)
+            }
+        })
+        .on_mouse_up(MouseButton::Left, move |event, window, cx| {
+            let event =
+                PointerEvent::new(PointerPhase::Up, event.position, false, event.modifiers, cx);
+            listener(&event, window, cx)
+        })
+    }
+}
+
+impl<E: InteractiveElement> PointerExt for E {}
+
+/// The pointer events over a pad, drawn as they come in and listed, to debug pens and touch
+/// screens.
+pub struct PointerDiagnostics {
+    focus_handle: FocusHandle,
+    events: VecDeque<PointerEvent>,
+}
+
+impl PointerDiagnostics {
+    pub fn view(_: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self {
+            focus_handle: cx.focus_handle(),
+            events: VecDeque::new(),
+        })
+    }
+
+    fn push(&mut self, event: &PointerEvent, cx: &mut Context<Self>) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        cx.notify();
+    }
+
+    /// A dot per event, the bigger the more pressure, and faint while hovering.
+    fn paint_events(events: &[PointerEvent], color: Hsla, window: &mut Window) {
+        for event in events {
+            let radius = px(2. + 8. * event.pressure);
+            let bounds = Bounds::centered_at(event.position, size(radius * 2., radius * 2.));
+            let color = if event.pressed {
+                color
+            } else {
+                color.opacity(0.3)
+            };
+            window.paint_quad(fill(bounds, color).corner_radii(radius));
+        }
+    }
+
+    fn render_event(ix: usize, event: &PointerEvent, cx: &App) -> impl IntoElement {
+        let tilt = event
+            .tilt
+            .map(|(x, y)| format!("{:.0}°, {:.0}°", x, y))
+            .unwrap_or_else(|| "-".to_string());
+        let columns = [
+            format!("{:?}", event.kind),
+            format!("{:?}", event.phase),
+            format!(
+                "{:.0}, {:.0}",
+                event.position.x.as_f32(),
+                event.position.y.as_f32()
+            ),
+            format!("{:.2}", event.pressure),
+            tilt,
+        ];
+
+        h_flex()
+            .id(("pointer-event", ix))
+            .gap_2()
+            .text_xs()
+            .font_family("monospace")
+            .text_color(if event.pressed {
+                cx.theme().foreground
+            } else {
+                cx.theme().muted_foreground
+            })
+            .children(
+                columns
+                    .into_iter()
+                    .map(|column| div().w(px(96.)).child(column)),
+            )
+    }
+}
+
+impl Focusable for PointerDiagnostics {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for PointerDiagnostics {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let events = self.events.iter().cloned().collect::<Vec<_>>();
+        let color = cx.theme().primary;
+        let latest = self
+            .events
+            .iter()
+            .rev()
+            .take(LISTED_EVENTS)
+            .enumerate()
+            .map(|(ix, event)| Self::render_event(ix, event, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Draw on the pad, the dots grow with the pressure."),
+                    )
+                    .child(
+                        Button::new("clear-pointer-events")
+                            .small()
+                            .outline()
+                            .label("Clear")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.events.clear();
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .id("pointer-pad")
+                    .cursor_role(CursorRole::Crosshair, cx)
+                    .flex_1()
+                    .min_h(px(200.))
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().secondary)
+                    .overflow_hidden()
+                    .on_pointer(cx.listener(|this, event, _, cx| this.push(event, cx)))
+                    .child(
+                        canvas(
+                            |_, _, _| {},
+                            move |_, _, window, _| Self::paint_events(&events, color, window),
+                        )
+                        .size_full(),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        h_flex().gap_2().text_xs().font_semibold().children(
+                            ["Kind", "Phase", "Position", "Pressure", "Tilt"]
+                                .map(|column| div().w(px(96.)).child(column)),
+                        ),
+                    )
+                    .children(latest),
+            )
+    }
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Pointer Diagnostics",
+        Some(size(px(640.), px(600.))),
+        |window, cx| PointerDiagnostics::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Stylus::default());
+    cx.on_action(|_: &ShowPointerDiagnostics, cx| open_window(cx));
+}
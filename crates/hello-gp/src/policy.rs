@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::settings::{self, Settings};
+#[cfg(debug_assertions)]
+use crate::themes::PROJECT_NAME;
+
+/// Shown on the controls of locked settings.
+pub(crate) const LOCKED_TOOLTIP: &str = "Managed by your administrator";
+
+lazy_static! {
+    static ref POLICY: Policy = Policy::load();
+}
+
+/// Settings locked by an administrator, read from `policy.json` in a system location:
+///
+/// - Linux: `/etc/hello-gp/policy.json`
+/// - macOS: `/Library/Application Support/hello-gp/policy.json`
+/// - Windows: `%ProgramData%\hello-gp\policy.json`
+///
+/// In debug builds, `HELLO_GP_POLICY` points to another file to try policies with; a release
+/// build only reads the system location, so users can't opt out of it. Locked settings are
+/// keyed by their path in the settings file, a table locks everything in it:
+///
+/// ```json
+/// { "locked": { "telemetry.enabled": false, "net.proxy_mode": "system" } }
+/// ```
+///
+/// The locked values win over the user's settings on every load and save, and the settings
+/// screens show them disabled.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    locked: BTreeMap<String, serde_json::Value>,
+}
+
+fn path() -> PathBuf {
+    #[cfg(debug_assertions)]
+    if let Ok(path) = std::env::var(format!("{}_POLICY", PROJECT_NAME.clone())) {
+        return PathBuf::from(path);
+    }
+    let name = env!("CARGO_PKG_NAME");
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support").join(name)
+    } else if cfg!(target_os = "windows") {
+        std::env::var("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(r"C:\ProgramData"))
+            .join(name)
+    } else {
+        PathBuf::from("/etc").join(name)
+    }
+    .join("policy.json")
+}
+
+impl Policy {
+    /// Read once, the policy doesn't change while the app runs.
+    fn load() -> Self {
+        let path = path();
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<Self>(&json) {
+            Ok(policy) => {
+                tracing::info!(
+                    "{} settings locked by {}",
+                    policy.locked.len(),
+                    path.display()
+                );
+                policy
+            }
+            // Better to run unmanaged than not at all, but loudly.
+            Err(err) => {
+                tracing::error!("Ignoring invalid policy {}: {}", path.display(), err);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn global() -> &'static Self {
+        &POLICY
+    }
+
+    /// Whether the setting at `key` (e.g. `telemetry.enabled`) is locked, by itself or
+    /// along with its table.
+    pub fn is_locked(&self, key: &str) -> bool {
+        self.locked.keys().any(|locked| {
+            key == locked
+                || key
+                    .strip_prefix(locked.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    /// Overwrite the locked settings with the values of the policy.
+    pub fn enforce(&self, settings: &mut Settings) {
+        if self.locked.is_empty() {
+            return;
+        }
+        let Ok(mut value) = serde_json::to_value(&*settings) else {
+            return;
+        };
+        for (key, locked) in &self.locked {
+            settings::set_value(&mut value, key, locked.clone());
+        }
+
+        match serde_json::from_value(value) {
+            Ok(enforced) => *settings = enforced,
+            Err(err) => tracing::error!("Failed to apply the settings policy: {}", err),
+        }
+    }
+}
+
+/// Whether the setting at `key` is locked by the [`Policy`].
+pub(crate) fn is_locked(key: &str) -> bool {
+    Policy::global().is_locked(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{policy::Policy, settings::Settings};
+
+    fn policy(locked: serde_json::Value) -> Policy {
+        serde_json::from_value(json!({ "locked": locked })).unwrap()
+    }
+
+    #[test]
+    fn test_is_locked() {
+        let policy = policy(json!({ "telemetry.enabled": false, "net": {} }));
+        assert!(policy.is_locked("telemetry.enabled"));
+        assert!(!policy.is_locked("telemetry.endpoint"));
+        assert!(!policy.is_locked("telemetry"));
+        // A table locks everything in it, but not the keys it's a prefix of.
+        assert!(policy.is_locked("net"));
+        assert!(policy.is_locked("net.proxy_mode"));
+        assert!(!policy.is_locked("network"));
+
+        assert!(!Policy::default().is_locked("telemetry.enabled"));
+    }
+
+    #[test]
+    fn test_enforce() {
+        let policy = policy(json!({ "telemetry.enabled": false }));
+        let mut settings = Settings::default()
+            .with_value("telemetry.enabled", true.into())
+            .unwrap();
+        policy.enforce(&mut settings);
+        assert_eq!(settings.value("telemetry.enabled"), Some(false.into()));
+    }
+}
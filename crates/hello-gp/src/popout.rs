@@ -0,0 +1,212 @@
+use std::{collections::HashMap, time::Duration};
+
+use gpui::{
+    AnyWindowHandle, App, AppContext as _, Bounds, Context, Entity, Global, IntoElement,
+    ParentElement as _, Pixels, Render, SharedString, Styled as _, Subscription, Task, Timer,
+    Window, div, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::{StoryContainer, session::Session};
+
+/// How long a popout window has to stay put before its geometry is saved.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Height of the strip at the top of the gallery window a popout is docked back by dragging onto.
+const DOCK_ZONE_HEIGHT: Pixels = px(48.);
+
+struct GlobalPopouts(Entity<Popouts>);
+
+impl Global for GlobalPopouts {}
+
+/// Panels popped out of the gallery into their own windows.
+///
+/// A panel is docked again by closing its window, the Dock button, or by dragging the window
+/// onto the title bar of the gallery. The last geometry of every popout is kept in the
+/// [`Session`].
+pub struct Popouts {
+    windows: HashMap<SharedString, AnyWindowHandle>,
+    /// The gallery window, where popouts can be dragged back onto.
+    dock_target: Option<Bounds<Pixels>>,
+}
+
+impl Popouts {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalPopouts>().0.clone()
+    }
+
+    pub fn is_popped_out(name: &str, cx: &App) -> bool {
+        Self::global(cx).read(cx).windows.contains_key(name)
+    }
+
+    pub fn set_dock_target(bounds: Bounds<Pixels>, cx: &mut App) {
+        Self::global(cx).update(cx, |this, _| this.dock_target = Some(bounds));
+    }
+
+    /// Move `panel` into a window of its own, where it was the last time.
+    pub fn pop_out(panel: Entity<StoryContainer>, cx: &mut App) {
+        let name = panel.read(cx).name.clone();
+        if Self::is_popped_out(&name, cx) {
+            return;
+        }
+
+        let geometry = Session::global(cx)
+            .popouts
+            .get(name.as_ref())
+            .and_then(|popout| popout.geometry);
+        Session::update(cx, |session| {
+            session.popouts.entry(name.to_string()).or_default().open = true;
+        });
+
+        let view = move |window: &mut Window, cx: &mut App| PopoutView::view(panel, window, cx);
+        match geometry {
+            Some(geometry) => {
+                crate::create_new_window_with_bounds(&name, geometry.into(), view, cx)
+            }
+            None => {
+                crate::create_new_window_with_size(&name, Some(size(px(800.), px(600.))), view, cx)
+            }
+        }
+    }
+
+    /// Pop out the panels that were popped out when the app quit.
+    pub fn restore(panels: &[Entity<StoryContainer>], cx: &mut App) {
+        for panel in panels {
+            let name = panel.read(cx).name.clone();
+            let open = Session::global(cx)
+                .popouts
+                .get(name.as_ref())
+                .is_some_and(|popout| popout.open);
+            if open {
+                Self::pop_out(panel.clone(), cx);
+            }
+        }
+    }
+
+    /// Close the window of a popped out panel, showing it in the gallery again.
+    pub fn dock(name: &SharedString, cx: &mut App) {
+        let Some(window) = Self::forget(name, cx) else {
+            return;
+        };
+        _ = window.update(cx, |_, window, _| window.remove_window());
+    }
+
+    /// Bookkeeping for a popout window that is closing, returns its handle.
+    fn forget(name: &SharedString, cx: &mut App) -> Option<AnyWindowHandle> {
+        let window = Self::global(cx).update(cx, |this, cx| {
+            cx.notify();
+            this.windows.remove(name)
+        })?;
+        Session::update(cx, |session| {
+            session.popouts.entry(name.to_string()).or_default().open = false;
+        });
+        Some(window)
+    }
+
+    /// Whether a popout at `bounds` was dragged onto the gallery's title bar.
+    fn in_dock_zone(&self, bounds: Bounds<Pixels>) -> bool {
+        let Some(target) = self.dock_target else {
+            return false;
+        };
+        let zone = Bounds {
+            origin: target.origin,
+            size: size(target.size.width, DOCK_ZONE_HEIGHT),
+        };
+        zone.contains(&bounds.origin)
+    }
+}
+
+/// The root view of a popout window.
+struct PopoutView {
+    name: SharedString,
+    panel: Entity<StoryContainer>,
+    _save_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PopoutView {
+    fn view(panel: Entity<StoryContainer>, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let name = panel.read(cx).name.clone();
+        let handle = window.window_handle();
+        Popouts::global(cx).update(cx, |this, cx| {
+            this.windows.insert(name.clone(), handle);
+            cx.notify();
+        });
+        window.on_window_should_close(cx, {
+            let name = name.clone();
+            move |_, cx| {
+                Popouts::forget(&name, cx);
+                true
+            }
+        });
+
+        cx.new(|cx| Self {
+            name,
+            panel,
+            _save_task: None,
+            _subscriptions: vec![cx.observe_window_bounds(window, Self::bounds_changed)],
+        })
+    }
+
+    fn bounds_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if Popouts::global(cx).read(cx).in_dock_zone(window.bounds()) {
+            Popouts::forget(&self.name, cx);
+            window.remove_window();
+            return;
+        }
+
+        // The restored size when maximized, so unmaximizing on the next start works.
+        let geometry = window.window_bounds().get_bounds().into();
+        let name = self.name.to_string();
+        self._save_task = Some(cx.spawn(async move |_, cx| {
+            Timer::after(SAVE_DELAY).await;
+            _ = cx.update(|cx| {
+                Session::update(cx, |session| {
+                    session.popouts.entry(name).or_default().geometry = Some(geometry);
+                })
+            });
+        }));
+    }
+}
+
+impl Render for PopoutView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let description = self.panel.read(cx).description.clone();
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .px_4()
+                    .py_2()
+                    .gap_4()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(description),
+                    )
+                    .child(Button::new("dock").small().ghost().label("Dock").on_click(
+                        cx.listener(|this, _, window, cx| {
+                            Popouts::forget(&this.name, cx);
+                            window.remove_window();
+                        }),
+                    )),
+            )
+            .child(div().flex_1().min_h_0().child(self.panel.clone()))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let popouts = cx.new(|_| Popouts {
+        windows: HashMap::new(),
+        dock_target: None,
+    });
+    cx.set_global(GlobalPopouts(popouts));
+}
@@ -0,0 +1,209 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Task, Timer, Window};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{idle::IdleMonitor, settings::Settings};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PowerSettings {
+    /// Cap for redraws caused by background updates, like task progress. `0` is uncapped.
+    pub max_fps: u32,
+    /// Stop animations in windows without focus, so they only redraw on input or timers.
+    pub pause_unfocused: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            max_fps: 30,
+            pause_unfocused: true,
+        }
+    }
+}
+
+/// Rate limits redraws from a source of frequent updates to [`PowerSettings::max_fps`].
+///
+/// ```ignore
+/// self.pacer.notify(cx);
+/// ```
+#[derive(Debug, Default)]
+pub struct FramePacer {
+    /// When the last redraw happened, or when the trailing one will.
+    last_frame: Option<Instant>,
+    trailing: Option<Task<()>>,
+}
+
+impl FramePacer {
+    /// Redraw now if enough time has passed since the last redraw, or else once it has, so the
+    /// last update of a burst is drawn too.
+    pub fn notify<T: 'static>(&mut self, cx: &mut Context<T>) {
+        // Every window is refreshed once one is visible again.
+        if Visibility::is_hidden(cx) {
+            return;
+        }
+
+        let max_fps = Settings::global(cx).power.max_fps;
+        let now = Instant::now();
+        match self.last_frame {
+            // The trailing redraw is still to come, and will show this update.
+            Some(last_frame) if last_frame > now => {}
+            Some(last_frame)
+                if max_fps > 0 && now - last_frame < Duration::from_secs(1) / max_fps =>
+            {
+                let next_frame = last_frame + Duration::from_secs(1) / max_fps;
+                self.last_frame = Some(next_frame);
+                self.trailing = Some(cx.spawn(async move |this, cx| {
+                    Timer::after(next_frame - now).await;
+                    _ = this.update(cx, |_, cx| cx.notify());
+                }));
+            }
+            _ => {
+                self.last_frame = Some(now);
+                cx.notify();
+            }
+        }
+    }
+}
+
+/// Whether continuous animations (spinners etc.) should run in `window`.
+pub fn animations_enabled(window: &Window, cx: &App) -> bool {
+    window.is_window_active() || !Settings::global(cx).power.pause_unfocused
+}
+
+/// How long a window gets to draw after a refresh before it counts as hidden.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibilityEvent {
+    Hidden,
+    Visible,
+}
+
+/// Tracks whether any window is on screen, and holds back work while all of them are
+/// minimized or occluded.
+///
+/// GPUI doesn't report occlusion, but the platforms stop handing out frames to windows
+/// that aren't visible (macOS occlusion, Wayland frame callbacks, minimized windows).
+/// So when the last window loses focus, or one is moved, resized or minimized while none
+/// has it, the windows are refreshed and are considered hidden if none of them draws. Any
+/// frame drawn afterwards makes them visible again.
+pub struct Visibility {
+    hidden: bool,
+    last_frame: Cell<Instant>,
+    /// Work deferred until a window is visible, keyed so repeated requests run once.
+    pending: HashMap<&'static str, Box<dyn FnOnce(&mut App)>>,
+    _probe: Option<Task<()>>,
+}
+
+struct GlobalVisibility(Entity<Visibility>);
+
+impl Global for GlobalVisibility {}
+
+impl EventEmitter<VisibilityEvent> for Visibility {}
+
+impl Visibility {
+    fn new(_: &mut Context<Self>) -> Self {
+        Self {
+            hidden: false,
+            last_frame: Cell::new(Instant::now()),
+            pending: HashMap::new(),
+            _probe: None,
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalVisibility>().0.clone()
+    }
+
+    pub fn is_hidden(cx: &App) -> bool {
+        Self::global(cx).read(cx).hidden
+    }
+
+    /// Run `f` now, or once a window is visible again if all are hidden.
+    pub fn when_visible(key: &'static str, f: impl FnOnce(&mut App) + 'static, cx: &mut App) {
+        let visibility = Self::global(cx);
+        if !visibility.read(cx).hidden {
+            f(cx);
+            return;
+        }
+
+        visibility.update(cx, |this, _| {
+            this.pending.insert(key, Box::new(f));
+        });
+    }
+
+    /// Called by the window roots on every frame they draw.
+    pub(crate) fn frame_drawn(cx: &mut App) {
+        let visibility = Self::global(cx);
+        let this = visibility.read(cx);
+        this.last_frame.set(Instant::now());
+        if this.hidden {
+            // Drawing happens inside a window update, leave room for it to finish first.
+            cx.defer(move |cx| visibility.update(cx, |this, cx| this.set_hidden(false, cx)));
+        }
+    }
+
+    /// Called by the window roots when their window is activated or deactivated, moved,
+    /// resized or minimized.
+    pub(crate) fn window_changed(cx: &mut App) {
+        Self::global(cx).update(cx, |this, cx| this.probe(cx));
+    }
+
+    /// Refresh the windows to find out whether any of them still draws.
+    fn probe(&mut self, cx: &mut Context<Self>) {
+        if cx.active_window().is_some() {
+            self.set_hidden(false, cx);
+            return;
+        }
+        // Redrawing every window to probe them is wasted while nobody's looking.
+        if cx.windows().is_empty() || IdleMonitor::is_idle(cx) {
+            return;
+        }
+
+        let started = Instant::now();
+        cx.refresh_windows();
+        self._probe = Some(cx.spawn(async move |this, cx| {
+            Timer::after(PROBE_TIMEOUT).await;
+            _ = this.update(cx, |this, cx| {
+                let drawn = this.last_frame.get() >= started;
+                this.set_hidden(!drawn, cx);
+            });
+        }));
+    }
+
+    fn set_hidden(&mut self, hidden: bool, cx: &mut Context<Self>) {
+        if self.hidden == hidden {
+            return;
+        }
+
+        self.hidden = hidden;
+        if hidden {
+            tracing::info!("All windows are hidden, pausing rendering");
+            cx.emit(VisibilityEvent::Hidden);
+        } else {
+            tracing::info!("A window is visible again, resuming rendering");
+            let pending = std::mem::take(&mut self.pending);
+            cx.emit(VisibilityEvent::Visible);
+            cx.defer(move |cx| {
+                for (_, f) in pending {
+                    f(cx);
+                }
+                // Updates skipped while hidden didn't notify, redraw everything.
+                cx.refresh_windows();
+            });
+        }
+        cx.notify();
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let visibility = cx.new(Visibility::new);
+    cx.set_global(GlobalVisibility(visibility));
+}
@@ -0,0 +1,165 @@
+//! Layout profiles: what's shown where, the sidebar, the splits and the popped out panels, kept
+//! per named profile so switching to another one brings back its layout at once.
+//!
+//! The profiles are part of the session, the live layout in it is the active profile's.
+
+use std::collections::BTreeSet;
+
+use gpui::{
+    Action, App, AppContext as _, ParentElement as _, SharedString, Styled as _, Window, actions,
+};
+use gpui_component::{
+    ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::InputState,
+    notification::Notification,
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{app_menus, session::Session, split::SplitLayout, text_field::TextField};
+
+actions!(profiles, [NewProfile]);
+
+/// Switch to the profile with this name, made from the current layout if there's none.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = profiles, no_json)]
+pub struct SwitchProfile(pub SharedString);
+
+/// The profile the layout of sessions from before profiles goes to.
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// The layout of a profile, as it was when another one was switched to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileLayout {
+    /// Whether the sidebar is expanded, or collapsed to icons.
+    pub sidebar: bool,
+    /// Width of the expanded sidebar, in pixels.
+    pub sidebar_width: f32,
+    /// The panes of the center area, `None` for a single pane.
+    pub split: Option<SplitLayout>,
+    /// The panels in windows of their own, by name.
+    pub popouts: BTreeSet<String>,
+}
+
+impl Default for ProfileLayout {
+    fn default() -> Self {
+        Self {
+            sidebar: true,
+            sidebar_width: 255.,
+            split: None,
+            popouts: BTreeSet::new(),
+        }
+    }
+}
+
+impl ProfileLayout {
+    /// The live layout of the session, with the sidebar it doesn't keep.
+    fn of_session(session: &Session, sidebar: bool, sidebar_width: f32) -> Self {
+        Self {
+            sidebar,
+            sidebar_width,
+            split: session.split.clone(),
+            popouts: session
+                .popouts
+                .iter()
+                .filter(|(_, popout)| popout.open)
+                .map(|(panel, _)| panel.clone())
+                .collect(),
+        }
+    }
+}
+
+/// The layout the active profile was left in, for what the session doesn't keep live, like
+/// the sidebar.
+pub fn active_layout(cx: &App) -> Option<&ProfileLayout> {
+    let session = Session::global(cx);
+    session.profiles.get(&session.profile)
+}
+
+/// Keep the layout of the session as the active profile's and make `name` the active one, in
+/// one write of the session. Returns the layout of `name` to show, the current one for a new
+/// profile.
+pub fn switch(name: &str, sidebar: bool, sidebar_width: f32, cx: &mut App) -> ProfileLayout {
+    let current = ProfileLayout::of_session(Session::global(cx), sidebar, sidebar_width);
+    let mut next = current.clone();
+    Session::update(cx, |session| {
+        let active = std::mem::replace(&mut session.profile, name.to_string());
+        session.profiles.insert(active, current);
+        next = session
+            .profiles
+            .entry(name.to_string())
+            .or_insert(next)
+            .clone();
+        session.split = next.split.clone();
+        for (panel, popout) in &mut session.popouts {
+            popout.open = next.popouts.contains(panel);
+        }
+    });
+    app_menus::refresh(cx);
+    next
+}
+
+/// Ask for a name and switch to a new profile of that name, starting out with the current
+/// layout.
+pub fn open_new_dialog(window: &mut Window, cx: &mut App) {
+    let input = cx.new(|cx| InputState::new(window, cx).placeholder("Profile name"));
+
+    window.open_modal(cx, move |modal, _, _| {
+        let input = input.clone();
+        modal.title("New Profile").child(
+            v_flex()
+                .gap_3()
+                .child(TextField::new("profile-name", &input))
+                .child(
+                    h_flex().justify_end().child(
+                        Button::new("create-profile")
+                            .small()
+                            .primary()
+                            .label("Create")
+                            .on_click(move |_, window, cx| {
+                                let name = input.read(cx).value().trim().to_string();
+                                if name.is_empty() {
+                                    return;
+                                }
+                                if Session::global(cx).profiles.contains_key(&name) {
+                                    window.push_notification(
+                                        Notification::error(format!(
+                                            "There's a profile \"{}\" already.",
+                                            name
+                                        )),
+                                        cx,
+                                    );
+                                    return;
+                                }
+
+                                window.close_modal(cx);
+                                window.dispatch_action(Box::new(SwitchProfile(name.into())), cx);
+                            }),
+                    ),
+                ),
+        )
+    });
+}
+
+/// Sessions from before profiles have a single layout, it becomes the default profile's.
+fn migrate(cx: &mut App) {
+    let session = Session::global(cx);
+    if !session.profile.is_empty() && session.profiles.contains_key(&session.profile) {
+        return;
+    }
+    Session::update(cx, |session| {
+        if session.profile.is_empty() {
+            session.profile = DEFAULT_PROFILE.to_string();
+        }
+        let default = ProfileLayout::default();
+        let layout = ProfileLayout::of_session(session, default.sidebar, default.sidebar_width);
+        session.profiles.insert(session.profile.clone(), layout);
+    });
+}
+
+pub fn init(cx: &mut App) {
+    migrate(cx);
+}
@@ -1,9 +1,14 @@
+use std::time::Duration;
+
 use gpui::{
     App, AppContext, Context, Entity, Focusable, IntoElement, ParentElement, Render, Styled, Window,
 };
 use gpui_component::{IconName, Sizable, button::Button, h_flex, progress::Progress, v_flex};
 
-use crate::section;
+use crate::{section, tasks::TaskScheduler};
+
+/// How long the example tasks of the window progress run.
+const EXAMPLE_TASK_DURATION: Duration = Duration::from_secs(4);
 
 pub struct ProgressStory {
     focus_handle: gpui::FocusHandle,
@@ -39,6 +44,27 @@ impl ProgressStory {
     pub fn set_value(&mut self, value: f32) {
         self.value = value;
     }
+
+    /// Run a task that reports its progress in steps, or doesn't when `indeterminate`.
+    fn run_example_task(indeterminate: bool, cx: &mut App) {
+        let title = if indeterminate {
+            "Example task"
+        } else {
+            "Example task with progress"
+        };
+        TaskScheduler::spawn(title, cx, async move |handle, cx| {
+            const STEPS: u32 = 20;
+            for step in 1..=STEPS {
+                cx.background_executor()
+                    .timer(EXAMPLE_TASK_DURATION / STEPS)
+                    .await;
+                if !indeterminate {
+                    handle.set_progress(step as f32 / STEPS as f32, cx);
+                }
+            }
+            Ok(())
+        });
+    }
 }
 
 impl Focusable for ProgressStory {
@@ -49,53 +75,74 @@ impl Focusable for ProgressStory {
 
 impl Render for ProgressStory {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        v_flex().items_center().gap_y_3().child(
-            section("Progress Bar").max_w_md().child(
-                v_flex()
-                    .w_full()
-                    .gap_3()
-                    .justify_center()
-                    .items_center()
-                    .child(
-                        h_flex()
-                            .gap_2()
-                            .child(Button::new("button-1").small().label("0%").on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value(0.);
-                                }),
-                            ))
-                            .child(Button::new("button-2").small().label("25%").on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value(25.);
-                                }),
-                            ))
-                            .child(Button::new("button-3").small().label("75%").on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value(75.);
-                                }),
-                            ))
-                            .child(Button::new("button-4").small().label("100%").on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value(100.);
-                                }),
-                            )),
-                    )
-                    .child(Progress::new().value(self.value))
-                    .child(
-                        h_flex()
-                            .gap_x_2()
-                            .child(Button::new("button-5").icon(IconName::Minus).on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value((this.value - 1.).max(0.));
-                                }),
-                            ))
-                            .child(Button::new("button-6").icon(IconName::Plus).on_click(
-                                cx.listener(|this, _, _, _| {
-                                    this.set_value((this.value + 1.).min(100.));
-                                }),
-                            )),
-                    ),
-            ),
-        )
+        v_flex()
+            .items_center()
+            .gap_y_3()
+            .child(
+                section("Progress Bar").max_w_md().child(
+                    v_flex()
+                        .w_full()
+                        .gap_3()
+                        .justify_center()
+                        .items_center()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(Button::new("button-1").small().label("0%").on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value(0.);
+                                    }),
+                                ))
+                                .child(Button::new("button-2").small().label("25%").on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value(25.);
+                                    }),
+                                ))
+                                .child(Button::new("button-3").small().label("75%").on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value(75.);
+                                    }),
+                                ))
+                                .child(Button::new("button-4").small().label("100%").on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value(100.);
+                                    }),
+                                )),
+                        )
+                        .child(Progress::new().value(self.value))
+                        .child(
+                            h_flex()
+                                .gap_x_2()
+                                .child(Button::new("button-5").icon(IconName::Minus).on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value((this.value - 1.).max(0.));
+                                    }),
+                                ))
+                                .child(Button::new("button-6").icon(IconName::Plus).on_click(
+                                    cx.listener(|this, _, _, _| {
+                                        this.set_value((this.value + 1.).min(100.));
+                                    }),
+                                )),
+                        ),
+                ),
+            )
+            .child(
+                section("Window Progress").max_w_md().child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("run-task")
+                                .small()
+                                .label("Run Task")
+                                .on_click(|_, _, cx| Self::run_example_task(false, cx)),
+                        )
+                        .child(
+                            Button::new("run-indeterminate-task")
+                                .small()
+                                .label("Run Indeterminate Task")
+                                .on_click(|_, _, cx| Self::run_example_task(true, cx)),
+                        ),
+                ),
+            )
     }
 }
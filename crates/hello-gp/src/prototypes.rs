@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, FocusHandle, Focusable, IntoElement,
+    ParentElement as _, Render, SharedString, Styled as _, Window, actions, div,
+    prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    alert::Alert,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    notification::Notification,
+    v_flex,
+};
+use serde::Deserialize;
+
+use crate::{
+    config::ConfigFormat,
+    themes::{PROJECT_NAME, get_data_dir},
+    watcher::FileWatcher,
+};
+
+actions!(prototypes, [OpenPrototypes]);
+
+/// A piece of a prototype screen, e.g.
+///
+/// ```json
+/// {
+///   "type": "v_stack",
+///   "gap": 8,
+///   "children": [
+///     { "type": "label", "text": "Appearance" },
+///     { "type": "button", "label": "Toggle Dark Mode", "action": "themes::ToggleThemeMode" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Node {
+    /// Children on top of each other.
+    VStack {
+        #[serde(default)]
+        gap: f32,
+        #[serde(default)]
+        padding: f32,
+        #[serde(default)]
+        children: Vec<Node>,
+    },
+    /// Children next to each other.
+    HStack {
+        #[serde(default)]
+        gap: f32,
+        #[serde(default)]
+        padding: f32,
+        #[serde(default)]
+        children: Vec<Node>,
+    },
+    Label {
+        text: String,
+        #[serde(default)]
+        muted: bool,
+    },
+    /// Dispatches `action` by name, e.g. `themes::ToggleThemeMode`.
+    Button {
+        label: String,
+        action: String,
+        #[serde(default)]
+        primary: bool,
+    },
+    Divider,
+    /// Takes the space left in its stack.
+    Spacer,
+}
+
+/// `HELLO_GP_PROTOTYPES=1` turns on prototyping in release builds, it's on by default in
+/// debug builds.
+pub fn enabled() -> bool {
+    match std::env::var(format!("{}_PROTOTYPES", PROJECT_NAME.clone())) {
+        Ok(value) => matches!(value.trim(), "1" | "true" | "yes"),
+        Err(_) => cfg!(debug_assertions),
+    }
+}
+
+fn dir() -> PathBuf {
+    get_data_dir().join("prototypes")
+}
+
+/// A screen defined in a file of the prototypes dir.
+struct Prototype {
+    name: SharedString,
+    path: PathBuf,
+    /// The last version that parsed, kept while the file is broken half way through an edit.
+    node: Option<Node>,
+    error: Option<String>,
+}
+
+fn load(path: &Path) -> anyhow::Result<Node> {
+    let format = ConfigFormat::from_path(path)
+        .ok_or_else(|| anyhow::anyhow!("Unknown format: {}", path.display()))?;
+    format.parse(&std::fs::read_to_string(path)?)
+}
+
+fn render_node(node: &Node, id: &str, cx: &App) -> AnyElement {
+    let children = |children: &[Node]| {
+        children
+            .iter()
+            .enumerate()
+            .map(|(ix, child)| render_node(child, &format!("{}-{}", id, ix), cx))
+            .collect::<Vec<_>>()
+    };
+
+    match node {
+        Node::VStack {
+            gap,
+            padding,
+            children: nodes,
+        } => v_flex()
+            .gap(px(*gap))
+            .p(px(*padding))
+            .children(children(nodes))
+            .into_any_element(),
+        Node::HStack {
+            gap,
+            padding,
+            children: nodes,
+        } => h_flex()
+            .gap(px(*gap))
+            .p(px(*padding))
+            .children(children(nodes))
+            .into_any_element(),
+        Node::Label { text, muted } => div()
+            .when(*muted, |this| this.text_color(cx.theme().muted_foreground))
+            .child(text.clone())
+            .into_any_element(),
+        Node::Button {
+            label,
+            action,
+            primary,
+        } => {
+            let action = action.clone();
+            Button::new(SharedString::from(id.to_string()))
+                .small()
+                .label(label.clone())
+                .map(|this| {
+                    if *primary {
+                        this.primary()
+                    } else {
+                        this.outline()
+                    }
+                })
+                .on_click(move |_, window, cx| match cx.build_action(&action, None) {
+                    Ok(action) => window.dispatch_action(action, cx),
+                    Err(err) => window.push_notification(
+                        Notification::error(format!("Unknown action {}: {}", action, err)),
+                        cx,
+                    ),
+                })
+                .into_any_element()
+        }
+        Node::Divider => div()
+            .h(px(1.))
+            .w_full()
+            .bg(cx.theme().border)
+            .into_any_element(),
+        Node::Spacer => div().flex_1().into_any_element(),
+    }
+}
+
+/// Renders the prototypes of the data dir, reloading them whenever they change on disk.
+pub struct PrototypesView {
+    focus_handle: FocusHandle,
+    prototypes: Vec<Prototype>,
+    active: usize,
+}
+
+impl PrototypesView {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        _ = std::fs::create_dir_all(dir());
+        let this = cx.weak_entity();
+        let watch_id = FileWatcher::watch([dir()], cx, move |_, cx| {
+            _ = this.update(cx, |this, cx| {
+                this.reload();
+                cx.notify();
+            });
+        });
+        cx.on_release(move |_, cx| FileWatcher::unwatch(watch_id, cx))
+            .detach();
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            prototypes: vec![],
+            active: 0,
+        };
+        this.reload();
+        this
+    }
+
+    fn reload(&mut self) {
+        let mut paths = std::fs::read_dir(dir())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| ConfigFormat::from_path(path).is_some())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        paths.sort();
+
+        let mut previous = std::mem::take(&mut self.prototypes);
+        self.prototypes = paths
+            .into_iter()
+            .map(|path| {
+                let last_good = previous
+                    .iter_mut()
+                    .find(|prototype| prototype.path == path)
+                    .and_then(|prototype| prototype.node.take());
+                let name = path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let (node, error) = match load(&path) {
+                    Ok(node) => (Some(node), None),
+                    Err(err) => {
+                        tracing::warn!("Failed to load prototype {}: {}", path.display(), err);
+                        (last_good, Some(err.to_string()))
+                    }
+                };
+                Prototype {
+                    name: name.into(),
+                    path,
+                    node,
+                    error,
+                }
+            })
+            .collect();
+        self.active = self.active.min(self.prototypes.len().saturating_sub(1));
+    }
+}
+
+impl Focusable for PrototypesView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for PrototypesView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(prototype) = self.prototypes.get(self.active) else {
+            return v_flex().size_full().p_4().child(
+                div().text_color(cx.theme().muted_foreground).child(format!(
+                    "No prototypes yet. Put a screen as JSON, JSON5 or TOML into {}, it's \
+                         shown here and reloaded whenever it changes.",
+                    dir().display()
+                )),
+            );
+        };
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .flex_wrap()
+                    .children(self.prototypes.iter().enumerate().map(|(ix, prototype)| {
+                        Button::new(("prototype", ix))
+                            .small()
+                            .label(prototype.name.clone())
+                            .map(|this| {
+                                if ix == self.active {
+                                    this.primary()
+                                } else {
+                                    this.ghost()
+                                }
+                            })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.active = ix;
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .when_some(prototype.error.clone(), |this, err| {
+                this.child(Alert::error("prototype-error", err).title(format!(
+                    "Failed to load {}, the last good version is shown.",
+                    prototype.path.display()
+                )))
+            })
+            .child(
+                div()
+                    .flex_1()
+                    .p_4()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .when_some(prototype.node.as_ref(), |this, node| {
+                        this.child(render_node(node, "prototype-node", cx))
+                    }),
+            )
+    }
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Prototypes",
+        Some(size(px(800.), px(600.))),
+        |window, cx| PrototypesView::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    if !enabled() {
+        return;
+    }
+    tracing::info!(
+        "Prototyping is on, screens are loaded from {}",
+        dir().display()
+    );
+    cx.on_action(|_: &OpenPrototypes, cx| open_window(cx));
+}
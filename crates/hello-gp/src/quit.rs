@@ -0,0 +1,86 @@
+use gpui::{App, Global, ParentElement as _, SharedString, Styled as _, div};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::Quit;
+
+type QuitGuard = Box<dyn Fn(&App) -> Option<SharedString>>;
+
+/// Checks run before the app quits, each may hold up the quit with a reason.
+///
+/// Work that only needs a moment to finish, like pending config writes, should rather
+/// finish in `cx.on_app_quit`, which quitting waits for.
+#[derive(Default)]
+pub struct QuitGuards {
+    guards: Vec<QuitGuard>,
+}
+
+impl Global for QuitGuards {}
+
+impl QuitGuards {
+    /// Ask before quitting while `guard` returns a reason, e.g. "2 downloads are running".
+    pub fn add(cx: &mut App, guard: impl Fn(&App) -> Option<SharedString> + 'static) {
+        cx.global_mut::<Self>().guards.push(Box::new(guard));
+    }
+
+    /// Why quitting now would interrupt something, empty when it's fine to quit.
+    pub fn reasons(cx: &App) -> Vec<SharedString> {
+        cx.global::<Self>()
+            .guards
+            .iter()
+            .filter_map(|guard| guard(cx))
+            .collect()
+    }
+}
+
+/// Quit, after confirming with the user when something is still running.
+pub fn request(cx: &mut App) {
+    let reasons = QuitGuards::reasons(cx);
+    let Some(window) = cx.active_window().filter(|_| !reasons.is_empty()) else {
+        cx.quit();
+        return;
+    };
+
+    _ = window.update(cx, move |_, window, cx| {
+        window.open_modal(cx, move |modal, _, cx| {
+            modal.title("Quit anyway?").child(
+                v_flex()
+                    .gap_3()
+                    .child("Some work is still in progress and will be lost.")
+                    .children(reasons.iter().map(|reason| {
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(reason.clone())
+                    }))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .justify_end()
+                            .child(
+                                Button::new("cancel-quit")
+                                    .small()
+                                    .outline()
+                                    .label("Cancel")
+                                    .on_click(|_, window, cx| window.close_modal(cx)),
+                            )
+                            .child(
+                                Button::new("quit-anyway")
+                                    .small()
+                                    .danger()
+                                    .label("Quit Anyway")
+                                    .on_click(|_, _, cx| cx.quit()),
+                            ),
+                    ),
+            )
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(QuitGuards::default());
+    cx.on_action(|_: &Quit, cx| request(cx));
+}
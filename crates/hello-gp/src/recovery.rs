@@ -0,0 +1,226 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use gpui::{
+    App, AppContext as _, Bounds, Context, IntoElement, ParentElement as _, Render, Styled as _,
+    Window, WindowBounds, WindowOptions, div, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Root, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex, v_flex,
+};
+
+use crate::{factory_reset, integration, logs, safe_mode};
+
+/// The path a corrupted `path` is backed up to, e.g. `state.json.bak`.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Handle a config file that failed to parse.
+///
+/// The file is backed up next to itself before the app overwrites it with defaults, and the
+/// user is asked whether to look at the backup or reset the file. `on_reset` runs after the
+/// file has been removed.
+pub(crate) fn recover_corrupted(
+    path: &Path,
+    err: impl Display,
+    on_reset: fn(&mut App),
+    cx: &mut App,
+) {
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    tracing::error!("Failed to parse {}: {}", path.display(), err);
+
+    let backup = backup_path(path);
+    if let Err(err) = std::fs::copy(path, &backup) {
+        tracing::error!("Failed to back up {}: {}", path.display(), err);
+        return;
+    }
+
+    let path = path.to_path_buf();
+    let message = format!(
+        "{} could not be read and the defaults are used instead: {}",
+        file_name, err
+    );
+    // Defer so the dialog opens in the first window.
+    cx.defer(move |cx| {
+        let Some(window) = cx.active_window() else {
+            return;
+        };
+        _ = window.update(cx, |_, window, cx| {
+            window.open_modal(cx, move |modal, _, cx| {
+                let backup = backup.clone();
+                let path = path.clone();
+                modal.title(format!("{} is corrupted", file_name)).child(
+                    v_flex()
+                        .gap_3()
+                        .child(message.clone())
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("A backup was saved to {}", backup.display())),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_end()
+                                .child(
+                                    Button::new("view-backup")
+                                        .small()
+                                        .outline()
+                                        .label("View File")
+                                        .on_click(move |_, _, cx| cx.open_with_system(&backup)),
+                                )
+                                .child(
+                                    Button::new("reset-file")
+                                        .small()
+                                        .danger()
+                                        .label("Reset")
+                                        .on_click(move |_, window, cx| {
+                                            if let Err(err) = std::fs::remove_file(&path) {
+                                                tracing::error!(
+                                                    "Failed to remove {}: {}",
+                                                    path.display(),
+                                                    err
+                                                );
+                                            }
+                                            on_reset(cx);
+                                            window.close_modal(cx);
+                                        }),
+                                ),
+                        ),
+                )
+            });
+        });
+    });
+}
+
+/// Shown instead of the app when the last starts failed, to start it without what may have
+/// broken it.
+struct StartupRecovery {
+    start: Option<Box<dyn FnOnce(&mut App)>>,
+}
+
+impl StartupRecovery {
+    fn start(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(start) = self.start.take() else {
+            return;
+        };
+        window.remove_window();
+        cx.defer(move |cx| start(cx));
+    }
+}
+
+impl Render for StartupRecovery {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .p_6()
+            .gap_4()
+            .bg(cx.theme().background)
+            .child(
+                div()
+                    .text_xl()
+                    .font_semibold()
+                    .child("The app failed to start"),
+            )
+            .child(format!(
+                "The last {} starts failed. Start without your themes, extensions and scripts to \
+                 remove the one that breaks it, or start with the default settings.",
+                safe_mode::failed_starts()
+            ))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("The log of the last start may tell what went wrong."),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .flex_wrap()
+                    .child(
+                        Button::new("safe-mode")
+                            .small()
+                            .primary()
+                            .label("Start in Safe Mode")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                safe_mode::enable();
+                                this.start(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("reset-all-settings")
+                            .small()
+                            .danger()
+                            .label("Reset All Settings")
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                if let Err(err) = factory_reset::reset() {
+                                    tracing::error!("Failed to reset the settings: {}", err);
+                                }
+                                this.start(window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("open-logs")
+                            .small()
+                            .outline()
+                            .label("Open Logs")
+                            .on_click(|_, _, cx| logs::open(cx)),
+                    )
+                    .child(
+                        Button::new("start-normally")
+                            .small()
+                            .ghost()
+                            .label("Start Normally")
+                            .on_click(cx.listener(|this, _, window, cx| this.start(window, cx))),
+                    ),
+            )
+    }
+}
+
+/// Run `start`, or when the last starts failed, ask how to start first rather than crashing
+/// the same way again.
+pub fn guard_startup(cx: &mut App, start: impl FnOnce(&mut App) + 'static) {
+    if !safe_mode::needs_recovery() {
+        start(cx);
+        return;
+    }
+    crate::init_base(cx);
+    tracing::warn!(
+        "The last {} starts failed, asking how to start",
+        safe_mode::failed_starts()
+    );
+
+    let start: Box<dyn FnOnce(&mut App)> = Box::new(start);
+    let options = WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+            None,
+            size(px(520.), px(280.)),
+            cx,
+        ))),
+        app_id: Some(integration::APP_ID.to_string()),
+        ..Default::default()
+    };
+    cx.open_window(options, |window, cx| {
+        // Closing the window without picking quits, the next start asks again.
+        window.on_window_should_close(cx, |_, cx| {
+            cx.quit();
+            true
+        });
+        let view = cx.new(|_| StartupRecovery { start: Some(start) });
+        cx.new(|cx| Root::new(view.into(), window, cx))
+    })
+    .expect("failed to open window");
+    cx.activate(true);
+}
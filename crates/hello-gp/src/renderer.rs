@@ -0,0 +1,192 @@
+use std::sync::{
+    OnceLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Cli, config,
+    policy::Policy,
+    settings::{SETTINGS_NAME, Settings},
+    themes::{get_config_dir, get_data_dir},
+};
+
+/// Created before the first window opens and removed once it did, a leftover file means the
+/// last start died while setting up the GPU.
+const PROBE_FILE: &str = "render-probe";
+
+static SOFTWARE_RENDER: AtomicBool = AtomicBool::new(false);
+static DISPLAY: OnceLock<DisplayInfo> = OnceLock::new();
+
+/// The display server to run on, on Linux.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayServer {
+    /// Wayland when there's a compositor, X11 otherwise.
+    #[default]
+    Auto,
+    Wayland,
+    /// X11, through XWayland on a Wayland session.
+    X11,
+}
+
+/// Who draws the title bar and the borders of the windows, on Linux.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowDecorations {
+    /// The app, with its own title bar.
+    #[default]
+    Client,
+    /// The compositor or the window manager, where it supports them.
+    Server,
+}
+
+impl WindowDecorations {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "client" => Some(Self::Client),
+            "server" => Some(Self::Server),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RenderSettings {
+    /// Render on the CPU (llvmpipe/lavapipe where available) and turn off costly effects,
+    /// for VMs and remote desktops without a usable GPU. Same as `--software-render`.
+    pub software: bool,
+    /// The display server on Linux. Same as `--x11` and `--wayland`.
+    pub display_server: DisplayServer,
+    /// A fixed scale factor, e.g. `1.25`, for X11 (and XWayland) where `Xft.dpi` is wrong,
+    /// as often over remote desktops. Wayland windows use the fractional scale of the
+    /// compositor where it supports `wp-fractional-scale-v1`, and a whole one otherwise.
+    /// Same as `--scale-factor`.
+    pub scale_factor: Option<f32>,
+    /// Who draws the window decorations on Linux. Same as `--decorations`.
+    pub decorations: WindowDecorations,
+}
+
+/// The display setup on Linux, as picked before the app started.
+#[derive(Debug, Clone)]
+pub struct DisplayInfo {
+    /// `XDG_SESSION_TYPE`, e.g. `wayland`, `x11` or `tty`.
+    pub session_type: Option<String>,
+    /// What GPUI connects to: `Wayland`, `X11` or `XWayland`.
+    pub server: &'static str,
+    /// Whether the display server was picked by a setting or a flag.
+    pub forced: bool,
+    pub scale_factor: Option<f32>,
+    pub decorations: WindowDecorations,
+}
+
+/// The display setup, `None` outside Linux.
+pub fn display_info() -> Option<&'static DisplayInfo> {
+    DISPLAY.get()
+}
+
+/// The decorations to ask for when opening a window.
+#[cfg(target_os = "linux")]
+pub(crate) fn window_decorations() -> gpui::WindowDecorations {
+    match DISPLAY.get().map(|display| display.decorations) {
+        Some(WindowDecorations::Server) => gpui::WindowDecorations::Server,
+        _ => gpui::WindowDecorations::Client,
+    }
+}
+
+/// Pick the display server, scale and decorations. GPUI connects to Wayland whenever
+/// `WAYLAND_DISPLAY` is set, and reads the X11 scale from `GPUI_X11_SCALE_FACTOR`.
+fn prepare_display(settings: &RenderSettings, cli: &Cli) {
+    if !cfg!(target_os = "linux") {
+        return;
+    }
+    let server = cli.display_server.unwrap_or(settings.display_server);
+    let scale_factor = cli
+        .scale_factor
+        .or(settings.scale_factor)
+        .filter(|scale| (0.5..=4.).contains(scale));
+    let has_wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    let server_name = match server {
+        DisplayServer::X11 if has_wayland => {
+            // SAFETY: called from `main` before any other thread is started.
+            unsafe { std::env::remove_var("WAYLAND_DISPLAY") };
+            "XWayland"
+        }
+        DisplayServer::Wayland if !has_wayland => {
+            eprintln!("No Wayland compositor (WAYLAND_DISPLAY isn't set), using X11.");
+            "X11"
+        }
+        _ if has_wayland => "Wayland",
+        _ => "X11",
+    };
+    if let Some(scale_factor) = scale_factor {
+        // SAFETY: called from `main` before any other thread is started.
+        unsafe { std::env::set_var("GPUI_X11_SCALE_FACTOR", scale_factor.to_string()) };
+        if server_name == "Wayland" {
+            eprintln!("The scale factor only applies to X11, Wayland uses the compositor's.");
+        }
+    }
+
+    _ = DISPLAY.set(DisplayInfo {
+        session_type: std::env::var("XDG_SESSION_TYPE").ok(),
+        server: server_name,
+        forced: server != DisplayServer::Auto,
+        scale_factor,
+        decorations: cli.decorations.unwrap_or(settings.decorations),
+    });
+}
+
+/// Whether to skip costly effects like wallpapers and blur.
+pub fn reduced_effects() -> bool {
+    SOFTWARE_RENDER.load(Ordering::Relaxed)
+}
+
+/// Pick the renderer and the display server, must run before the GPUI application is
+/// created.
+///
+/// Software rendering is used when `--software-render` is passed, when enabled in the
+/// settings, or when the last start failed to initialize the GPU, which also turns the
+/// setting on for next time.
+pub fn prepare(cli: &Cli) {
+    let config_dir = get_config_dir();
+    let probe = get_data_dir().join(PROBE_FILE);
+    let mut settings = config::load::<Settings>(&config_dir, SETTINGS_NAME).unwrap_or_default();
+    Policy::global().enforce(&mut settings);
+
+    let crashed = probe.exists();
+    if crashed && !settings.render.software && !Policy::global().is_locked("render.software") {
+        eprintln!("The last start failed to initialize the GPU, switching to software rendering.");
+        settings.render.software = true;
+        if let Err(err) = config::save(&config_dir, SETTINGS_NAME, &settings) {
+            eprintln!("Failed to save settings: {}", err);
+        }
+    }
+
+    prepare_display(&settings.render, cli);
+    let software = cli.software_render || settings.render.software;
+    SOFTWARE_RENDER.store(software, Ordering::Relaxed);
+    if software {
+        // SAFETY: called from `main` before any other thread is started.
+        unsafe {
+            // Mesa's software rasterizers, for GL and (through the device select layer) Vulkan.
+            std::env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");
+            std::env::set_var("GALLIUM_DRIVER", "llvmpipe");
+            std::env::set_var("MESA_VK_DEVICE_SELECT", "10005:0");
+            std::env::set_var("MESA_VK_DEVICE_SELECT_FORCE_DEFAULT_DEVICE", "1");
+        }
+    }
+
+    if let Some(dir) = probe.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    _ = std::fs::write(&probe, "");
+}
+
+/// The first window is up, so the GPU works.
+pub(crate) fn first_window_opened() {
+    _ = std::fs::remove_file(get_data_dir().join(PROBE_FILE));
+}
@@ -0,0 +1,91 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::Duration,
+};
+
+use gpui::{App, Timer};
+
+use crate::themes::get_data_dir;
+
+/// Written when a start begins and removed once it got through, holds how many starts in a row
+/// didn't. Whatever ended them, a panic, a crash of the GPU driver or being killed, leaves it.
+const SENTINEL_FILE: &str = "startup-sentinel";
+/// How many failed starts in a row bring up the recovery window on the next one.
+const MAX_FAILED_STARTS: u32 = 2;
+/// How long the app has to run after the first window opened for the start to count as good.
+const STARTUP_GRACE: Duration = Duration::from_secs(10);
+
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+static WINDOW_OPENED: AtomicBool = AtomicBool::new(false);
+static FAILED_STARTS: AtomicU32 = AtomicU32::new(0);
+
+fn sentinel_path() -> PathBuf {
+    get_data_dir().join(SENTINEL_FILE)
+}
+
+fn start_succeeded() {
+    _ = std::fs::remove_file(sentinel_path());
+}
+
+/// How many starts in a row failed before this one.
+pub fn failed_starts() -> u32 {
+    FAILED_STARTS.load(Ordering::Relaxed)
+}
+
+/// Whether the app runs in safe mode: the user themes, extensions and scripts aren't loaded,
+/// so a broken one can be removed from the app it broke.
+pub fn enabled() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
+/// Turn on safe mode for the rest of the run, before the app is initialized.
+pub(crate) fn enable() {
+    SAFE_MODE.store(true, Ordering::Relaxed);
+}
+
+/// Mark the start as begun, and turn on safe mode when `force` is set (`--safe-mode`).
+/// Must run before the app is initialized.
+pub fn enter(force: bool) {
+    let path = sentinel_path();
+    let failed = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0);
+    FAILED_STARTS.store(failed, Ordering::Relaxed);
+    if let Some(dir) = path.parent() {
+        _ = std::fs::create_dir_all(dir);
+    }
+    _ = std::fs::write(&path, (failed + 1).to_string());
+    SAFE_MODE.store(force, Ordering::Relaxed);
+}
+
+/// Whether the last starts failed and the user gets to pick how to start, see
+/// [`crate::guard_startup`].
+pub(crate) fn needs_recovery() -> bool {
+    !enabled() && failed_starts() >= MAX_FAILED_STARTS
+}
+
+/// The first window is up, the start counts as good if nothing crashes for a little while, or
+/// the app is quit before.
+pub(crate) fn first_window_opened(cx: &mut App) {
+    if WINDOW_OPENED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    cx.spawn(async move |_| {
+        Timer::after(STARTUP_GRACE).await;
+        start_succeeded();
+    })
+    .detach();
+    cx.on_app_quit(|_| {
+        start_succeeded();
+        async {}
+    })
+    .detach();
+}
+
+/// Start normally next time, e.g. after removing the broken theme or extension.
+pub fn restart_normally(cx: &mut App) {
+    start_succeeded();
+    cx.restart(None);
+}
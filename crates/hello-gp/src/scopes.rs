@@ -0,0 +1,175 @@
+//! Where a shortcut works: everywhere in the window, in a panel, or in a dialog.
+//!
+//! The window root carries the global scope only while nothing captures the input, so e.g. the
+//! command palette doesn't open over a modal while typing in it. The scopes are key contexts,
+//! bound to with [`ShortcutScope::context`], and the `[[keymap.bindings]]` of the settings name
+//! one for each of the user's own shortcuts.
+
+use std::rc::Rc;
+
+use gpui::{
+    Action, App, KeyBinding, KeyBindingContextPredicate, KeyBindingMetaIndex, KeyContext,
+    PlatformKeyboardMapper, Window,
+};
+use gpui_component::ContextModal as _;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{kiosk, lock::AppLock, settings::Settings};
+
+/// Marks the bindings of the settings' keymap, so a reload can replace them.
+const USER_KEYMAP: KeyBindingMetaIndex = KeyBindingMetaIndex(1);
+
+/// Where a key binding works.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutScope {
+    /// Anywhere in the window, unless a dialog is open or the app is locked.
+    #[default]
+    Global,
+    /// While a split pane, or something in it, is focused.
+    Panel,
+    /// While a dialog is open.
+    Dialog,
+}
+
+impl ShortcutScope {
+    /// The key context of the scope, to bind to.
+    pub const fn context(self) -> &'static str {
+        match self {
+            Self::Global => "GlobalScope",
+            Self::Panel => "PanelScope",
+            Self::Dialog => "DialogScope",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KeymapSettings {
+    /// The user's own shortcuts, bound on start after the built-in ones so they win.
+    pub bindings: Vec<KeymapBinding>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KeymapBinding {
+    /// The keystrokes, e.g. `ctrl-k ctrl-t`.
+    pub keys: String,
+    /// The name of the action, e.g. `themes::ToggleThemeMode`.
+    pub action: String,
+    /// Where the shortcut works.
+    pub scope: ShortcutScope,
+    /// A key context within the scope, e.g. `Combobox` or `NavRail`.
+    pub context: Option<String>,
+}
+
+impl KeymapBinding {
+    fn predicate(&self) -> String {
+        match &self.context {
+            Some(context) => format!("{} > {}", self.scope.context(), context),
+            None => self.scope.context().to_string(),
+        }
+    }
+
+    fn build(&self, cx: &App) -> anyhow::Result<KeyBinding> {
+        let action = cx.build_action(&self.action, None)?;
+        self.load(action, cx.keyboard_mapper().as_ref())
+    }
+
+    /// Bind the keys to `action`, what [`Self::build`] does once the action is found.
+    pub(crate) fn load(
+        &self,
+        action: Box<dyn Action>,
+        keyboard_mapper: &dyn PlatformKeyboardMapper,
+    ) -> anyhow::Result<KeyBinding> {
+        load_key_binding(&self.keys, action, &self.predicate(), keyboard_mapper)
+    }
+}
+
+/// Bind `keys` to `action` where `predicate` matches, e.g. [`ShortcutScope::context`], for
+/// bindings made at runtime from what the user wrote.
+pub(crate) fn key_binding(
+    keys: &str,
+    action: Box<dyn Action>,
+    predicate: &str,
+    cx: &App,
+) -> anyhow::Result<KeyBinding> {
+    load_key_binding(keys, action, predicate, cx.keyboard_mapper().as_ref())
+}
+
+fn load_key_binding(
+    keys: &str,
+    action: Box<dyn Action>,
+    predicate: &str,
+    keyboard_mapper: &dyn PlatformKeyboardMapper,
+) -> anyhow::Result<KeyBinding> {
+    let predicate = KeyBindingContextPredicate::parse(predicate)?;
+    Ok(KeyBinding::load(
+        keys,
+        action,
+        Some(Rc::new(predicate)),
+        false,
+        None,
+        keyboard_mapper,
+    )?)
+}
+
+/// The key context of the window root, the scopes that are active.
+pub fn root_context(window: &mut Window, cx: &mut App) -> KeyContext {
+    let mut context = KeyContext::default();
+    if AppLock::is_locked(cx) {
+        return context;
+    }
+    if window.has_active_modal(cx) {
+        context.add(ShortcutScope::Dialog.context());
+    } else {
+        context.add(ShortcutScope::Global.context());
+    }
+    context
+}
+
+/// The key bindings of the settings' keymap, the invalid ones left out.
+fn user_bindings(cx: &App) -> Vec<KeyBinding> {
+    Settings::global(cx)
+        .keymap
+        .bindings
+        .iter()
+        .filter_map(|binding| match binding.build(cx) {
+            Ok(key_binding) => Some(key_binding.with_meta(USER_KEYMAP)),
+            Err(err) => {
+                tracing::error!(
+                    "Invalid key binding {} for {}: {}",
+                    binding.keys,
+                    binding.action,
+                    err
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Replace the bindings of the keymap with those of the current settings, after they were
+/// edited. The keymap can't drop single bindings, it's rebuilt like [`kiosk::init`] does.
+pub(crate) fn rebind(cx: &mut App) {
+    let mut bindings = cx
+        .key_bindings()
+        .borrow()
+        .bindings()
+        .filter(|binding| binding.meta() != Some(USER_KEYMAP))
+        .cloned()
+        .collect::<Vec<_>>();
+    bindings.extend(
+        user_bindings(cx)
+            .into_iter()
+            .filter(|binding| kiosk::allows(binding.action(), cx)),
+    );
+    cx.clear_key_bindings();
+    cx.bind_keys(bindings);
+}
+
+/// Bind the keymap of the settings, before kiosk mode drops what it doesn't allow.
+pub(crate) fn init(cx: &mut App) {
+    cx.bind_keys(user_bindings(cx));
+}
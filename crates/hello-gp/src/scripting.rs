@@ -0,0 +1,265 @@
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+
+use gpui::{Action, App, Global, SharedString};
+use gpui_component::{ActiveTheme as _, ContextModal as _, ThemeMode};
+use rhai::{AST, CallFnOptions, Dynamic, Engine, Scope};
+
+use crate::{
+    events::{self, ThemeChanged},
+    kiosk, safe_mode,
+    settings::Settings,
+    themes::{SwitchTheme, SwitchThemeMode, get_config_dir},
+    watcher::FileWatcher,
+};
+
+const SCRIPT_EXTENSION: &str = "rhai";
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// What a script asked the app to do, applied once the script returns.
+enum ScriptCommand {
+    Action(Box<dyn Action>),
+    ActionByName(String),
+    Notify(String),
+}
+
+#[derive(Default)]
+struct ScriptContext {
+    settings: serde_json::Value,
+    commands: Vec<ScriptCommand>,
+}
+
+struct Script {
+    path: PathBuf,
+    ast: AST,
+}
+
+/// Runs user scripts from `get_config_dir()/scripts`.
+///
+/// The top-level statements of each script run on startup, and a script can handle events by
+/// defining `on_<event>` functions, e.g. `fn on_theme_changed(theme) { ... }`.
+///
+/// Scripts have no IO, they can only use the API registered here:
+///
+/// - `log(message)`
+/// - `notify(message)`
+/// - `setting("idle.threshold_minutes")`
+/// - `dispatch("story::ToggleSearch")`
+/// - `set_theme("Default Dark")`, `set_theme_mode("dark")`
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<Script>,
+    context: Rc<RefCell<ScriptContext>>,
+}
+
+impl Global for ScriptHost {}
+
+impl ScriptHost {
+    fn new() -> Self {
+        let context = Rc::new(RefCell::new(ScriptContext::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(32);
+
+        engine.register_fn("log", |message: &str| {
+            tracing::info!(target: "script", "{}", message);
+        });
+        engine.register_fn("notify", {
+            let context = context.clone();
+            move |message: &str| {
+                let command = ScriptCommand::Notify(message.to_string());
+                context.borrow_mut().commands.push(command);
+            }
+        });
+        engine.register_fn("dispatch", {
+            let context = context.clone();
+            move |action: &str| {
+                let command = ScriptCommand::ActionByName(action.to_string());
+                context.borrow_mut().commands.push(command);
+            }
+        });
+        engine.register_fn("set_theme", {
+            let context = context.clone();
+            move |name: &str| {
+                let action = Box::new(SwitchTheme(SharedString::from(name.to_string())));
+                context
+                    .borrow_mut()
+                    .commands
+                    .push(ScriptCommand::Action(action));
+            }
+        });
+        engine.register_fn("set_theme_mode", {
+            let context = context.clone();
+            move |mode: &str| {
+                let mode = if mode.eq_ignore_ascii_case("dark") {
+                    ThemeMode::Dark
+                } else {
+                    ThemeMode::Light
+                };
+                let command = ScriptCommand::Action(Box::new(SwitchThemeMode(mode)));
+                context.borrow_mut().commands.push(command);
+            }
+        });
+        engine.register_fn("setting", {
+            let context = context.clone();
+            move |key: &str| -> Dynamic {
+                let context = context.borrow();
+                key.split('.')
+                    .try_fold(&context.settings, |value, key| value.get(key))
+                    .and_then(|value| rhai::serde::to_dynamic(value).ok())
+                    .unwrap_or(Dynamic::UNIT)
+            }
+        });
+
+        Self {
+            engine,
+            scripts: vec![],
+            context,
+        }
+    }
+
+    pub fn dir() -> PathBuf {
+        get_config_dir().join("scripts")
+    }
+
+    fn load_scripts(&mut self) {
+        let Ok(entries) = std::fs::read_dir(Self::dir()) else {
+            return;
+        };
+
+        let mut paths = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        self.scripts = paths
+            .into_iter()
+            .filter_map(|path| match self.engine.compile_file(path.clone()) {
+                Ok(ast) => Some(Script { path, ast }),
+                Err(err) => {
+                    tracing::error!("Failed to compile script {}: {}", path.display(), err);
+                    None
+                }
+            })
+            .collect();
+    }
+
+    fn prepare(&self, cx: &App) {
+        self.context.borrow_mut().settings =
+            serde_json::to_value(Settings::global(cx)).unwrap_or_default();
+    }
+
+    fn run_startup(&self, cx: &App) {
+        self.prepare(cx);
+        for script in &self.scripts {
+            if let Err(err) = self.engine.run_ast(&script.ast) {
+                tracing::error!("Script {} failed: {}", script.path.display(), err);
+            }
+        }
+    }
+
+    fn run_event(&self, event: &str, arg: Dynamic, cx: &App) {
+        let name = format!("on_{}", event);
+        self.prepare(cx);
+        for script in &self.scripts {
+            if !script.ast.iter_functions().any(|f| f.name == name) {
+                continue;
+            }
+
+            let options = CallFnOptions::new().eval_ast(false);
+            let result = self.engine.call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &script.ast,
+                &name,
+                (arg.clone(),),
+            );
+            if let Err(err) = result {
+                tracing::error!("Script {} {} failed: {}", script.path.display(), name, err);
+            }
+        }
+    }
+
+    fn take_commands(&self) -> Vec<ScriptCommand> {
+        std::mem::take(&mut self.context.borrow_mut().commands)
+    }
+}
+
+fn apply_commands(commands: Vec<ScriptCommand>, cx: &mut App) {
+    for command in commands {
+        match command {
+            ScriptCommand::Action(action) if kiosk::allows(action.as_ref(), cx) => {
+                cx.dispatch_action(action.as_ref())
+            }
+            ScriptCommand::Action(action) => {
+                tracing::warn!("{} isn't allowed in kiosk mode", action.name())
+            }
+            ScriptCommand::ActionByName(name) => match cx.build_action(&name, None) {
+                Ok(action) if kiosk::allows(action.as_ref(), cx) => {
+                    cx.dispatch_action(action.as_ref())
+                }
+                Ok(_) => tracing::warn!("{} isn't allowed in kiosk mode", name),
+                Err(err) => tracing::error!("Script dispatched unknown action {}: {}", name, err),
+            },
+            ScriptCommand::Notify(message) => {
+                if let Some(window) = cx.active_window() {
+                    _ = window.update(cx, |_, window, cx| {
+                        window.push_notification(message, cx);
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Run the `on_<event>` handlers of all scripts.
+pub fn emit(event: &str, arg: impl Into<Dynamic>, cx: &mut App) {
+    let Some(host) = cx.try_global::<ScriptHost>() else {
+        return;
+    };
+    if host.scripts.is_empty() {
+        return;
+    }
+
+    host.run_event(event, arg.into(), cx);
+    let commands = host.take_commands();
+    apply_commands(commands, cx);
+}
+
+pub fn init(cx: &mut App) {
+    let mut host = ScriptHost::new();
+    if safe_mode::enabled() {
+        tracing::info!("Safe mode, the scripts are not loaded");
+    } else {
+        host.load_scripts();
+    }
+    cx.set_global(host);
+
+    // Edited scripts take effect on their next event, startup scripts aren't run again.
+    if !safe_mode::enabled() && std::fs::create_dir_all(ScriptHost::dir()).is_ok() {
+        FileWatcher::watch([ScriptHost::dir()], cx, |_, cx| {
+            let host = cx.global_mut::<ScriptHost>();
+            host.load_scripts();
+            tracing::info!("Reloaded {} scripts", host.scripts.len());
+        });
+    }
+
+    let host = cx.global::<ScriptHost>();
+    if !host.scripts.is_empty() {
+        tracing::info!("Loaded {} scripts", host.scripts.len());
+        host.run_startup(cx);
+        let commands = host.take_commands();
+        // Defer so startup scripts see the windows opened by the app.
+        cx.defer(move |cx| apply_commands(commands, cx));
+    }
+
+    let last_theme = RefCell::new(cx.theme().theme_name().clone());
+    events::subscribe::<ThemeChanged>(cx, move |event, cx| {
+        if event.name != *last_theme.borrow() {
+            *last_theme.borrow_mut() = event.name.clone();
+            emit("theme_changed", event.name.to_string(), cx);
+        }
+    })
+    .detach();
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use gpui::{App, Global};
+
+/// The service name the secrets are stored under in the keychain.
+const SERVICE: &str = env!("CARGO_PKG_NAME");
+
+enum Backend {
+    /// Secret Service on Linux, the Keychain on macOS, the Credential Manager on Windows.
+    Keychain,
+    /// Lost on quit, for systems without a keychain (e.g. a headless Linux without a
+    /// Secret Service provider).
+    Memory(HashMap<String, String>),
+}
+
+/// Tokens and passwords the app needs to keep, e.g. for the marketplace or sync.
+///
+/// Never put these in the settings: those end up in backups, snapshots and synced dirs.
+///
+/// ```ignore
+/// Secrets::set("marketplace.token", &token, cx)?;
+/// let token = Secrets::get("marketplace.token", cx)?;
+/// ```
+pub struct Secrets {
+    backend: Backend,
+}
+
+impl Global for Secrets {}
+
+impl Secrets {
+    /// Whether the secrets survive a restart, `false` when falling back to memory.
+    pub fn is_persistent(cx: &App) -> bool {
+        matches!(cx.global::<Self>().backend, Backend::Keychain)
+    }
+
+    pub fn get(key: &str, cx: &App) -> anyhow::Result<Option<String>> {
+        match &cx.global::<Self>().backend {
+            Backend::Keychain => match keyring::Entry::new(SERVICE, key)?.get_password() {
+                Ok(value) => Ok(Some(value)),
+                Err(keyring::Error::NoEntry) => Ok(None),
+                Err(err) => Err(err.into()),
+            },
+            Backend::Memory(secrets) => Ok(secrets.get(key).cloned()),
+        }
+    }
+
+    pub fn set(key: &str, value: &str, cx: &mut App) -> anyhow::Result<()> {
+        match &mut cx.global_mut::<Self>().backend {
+            Backend::Keychain => keyring::Entry::new(SERVICE, key)?.set_password(value)?,
+            Backend::Memory(secrets) => _ = secrets.insert(key.to_string(), value.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Remove the secret, it's fine if there is none.
+    pub fn delete(key: &str, cx: &mut App) -> anyhow::Result<()> {
+        match &mut cx.global_mut::<Self>().backend {
+            Backend::Keychain => match keyring::Entry::new(SERVICE, key)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(err) => return Err(err.into()),
+            },
+            Backend::Memory(secrets) => _ = secrets.remove(key),
+        }
+        Ok(())
+    }
+}
+
+/// Whether the keychain can be used, looking up an entry that doesn't exist.
+fn keychain_available() -> bool {
+    let entry = match keyring::Entry::new(SERVICE, "probe") {
+        Ok(entry) => entry,
+        Err(err) => {
+            tracing::warn!("No keychain, secrets are kept in memory only: {}", err);
+            return false;
+        }
+    };
+    match entry.get_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(err) => {
+            tracing::warn!("No keychain, secrets are kept in memory only: {}", err);
+            false
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let backend = if keychain_available() {
+        Backend::Keychain
+    } else {
+        Backend::Memory(HashMap::new())
+    };
+    cx.set_global(Secrets { backend });
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+use gpui::{App, Bounds, Global, Pixels, Point, point, px, size};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config, persistence::Persistence, profiles::ProfileLayout, split::SplitLayout,
+    themes::get_config_dir,
+};
+
+pub(crate) const SESSION_NAME: &str = "session";
+
+/// Window bounds in logical pixels, as stored in the session.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl From<Bounds<Pixels>> for WindowGeometry {
+    fn from(bounds: Bounds<Pixels>) -> Self {
+        Self {
+            x: bounds.origin.x.as_f32(),
+            y: bounds.origin.y.as_f32(),
+            width: bounds.size.width.as_f32(),
+            height: bounds.size.height.as_f32(),
+        }
+    }
+}
+
+impl From<WindowGeometry> for Bounds<Pixels> {
+    fn from(geometry: WindowGeometry) -> Self {
+        Bounds {
+            origin: point(px(geometry.x), px(geometry.y)),
+            size: size(px(geometry.width), px(geometry.height)),
+        }
+    }
+}
+
+/// Where a story was scrolled to, restored when it's shown again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub scroll_x: f32,
+    pub scroll_y: f32,
+}
+
+impl ViewState {
+    pub fn scroll_offset(&self) -> Point<Pixels> {
+        point(px(self.scroll_x), px(self.scroll_y))
+    }
+}
+
+impl From<Point<Pixels>> for ViewState {
+    fn from(offset: Point<Pixels>) -> Self {
+        Self {
+            scroll_x: offset.x.as_f32(),
+            scroll_y: offset.y.as_f32(),
+        }
+    }
+}
+
+/// How a table was left, keyed by column key, restored when it's shown again.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableState {
+    /// Column widths in logical pixels, of the columns that were resized.
+    pub widths: BTreeMap<String, f32>,
+    /// The column the rows were sorted by, and whether descending.
+    pub sort: Option<(String, bool)>,
+}
+
+/// Which nodes of a tree were expanded, by node id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TreeState {
+    pub expanded: BTreeSet<String>,
+}
+
+/// A panel shown in its own window instead of the gallery.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PopoutState {
+    /// Whether the panel was popped out when the app quit, to reopen it on the next start.
+    pub open: bool,
+    /// Where the window was last, reused the next time the panel pops out.
+    pub geometry: Option<WindowGeometry>,
+}
+
+/// Layout state the app keeps across restarts, persisted as `session.toml` in the config dir.
+///
+/// Unlike the settings this is written by the app as the user moves things
+/// around, so it isn't part of the config schema.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Keyed by panel name.
+    pub popouts: BTreeMap<String, PopoutState>,
+    /// The panes of the center area, `None` for a single pane.
+    pub split: Option<SplitLayout>,
+    /// The pane that had focus, by its position among the panes.
+    pub focused_pane: Option<usize>,
+    /// Keyed by story name.
+    pub views: BTreeMap<String, ViewState>,
+    /// Keyed by table id.
+    pub tables: BTreeMap<String, TableState>,
+    /// Keyed by tree id.
+    pub trees: BTreeMap<String, TreeState>,
+    /// The folder a file dialog was left in, keyed by what it was for.
+    pub dialog_dirs: BTreeMap<String, PathBuf>,
+    /// The layout profile the layout above is of.
+    pub profile: String,
+    /// The layouts of the profiles as they were left, keyed by profile name.
+    pub profiles: BTreeMap<String, ProfileLayout>,
+    /// Names of the layout presets applied last, most recent first.
+    pub recent_layouts: Vec<String>,
+    /// The version of the app that ran last, to tell what's new after an update.
+    pub last_version: Option<String>,
+}
+
+impl Global for Session {}
+
+impl Session {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// Update the session and write it back to disk, shortly.
+    pub fn update(cx: &mut App, f: impl FnOnce(&mut Self)) {
+        let mut session = Self::global(cx).clone();
+        f(&mut session);
+        Persistence::schedule(SESSION_NAME, session.clone(), cx);
+        cx.set_global(session);
+    }
+
+    fn path() -> PathBuf {
+        config::path(&get_config_dir(), SESSION_NAME)
+    }
+}
+
+pub fn init(cx: &mut App) {
+    // Losing the layout isn't worth bothering the user about, start over with the defaults.
+    let session = config::load::<Session>(&get_config_dir(), SESSION_NAME).unwrap_or_else(|err| {
+        tracing::warn!("Failed to parse {}: {}", Session::path().display(), err);
+        Session::default()
+    });
+    cx.set_global(session);
+}
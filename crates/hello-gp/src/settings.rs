@@ -0,0 +1,230 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use gpui::{App, Global, actions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    appearance::AppearanceSettings,
+    config,
+    encryption::{self, EncryptionSettings},
+    extensions::ExtensionsSettings,
+    format::FormatSettings,
+    history::{self, ChangeSource, History},
+    idle::IdleSettings,
+    input_settings::InputSettings,
+    integration::IntegrationSettings,
+    kiosk::KioskSettings,
+    lock::LockSettings,
+    native_notifications::NotificationSettings,
+    net::{Net, NetSettings},
+    persistence::{self, Persistence, PersistenceSettings},
+    policy::Policy,
+    power::PowerSettings,
+    recovery,
+    renderer::RenderSettings,
+    scopes::{self, KeymapSettings},
+    sounds::SoundSettings,
+    spell_check::SpellCheckSettings,
+    startup::StartupSettings,
+    telemetry::TelemetrySettings,
+    themes::get_config_dir,
+    watchdog::WatchdogSettings,
+    watcher::FileWatcher,
+};
+
+pub(crate) const SETTINGS_NAME: &str = "settings";
+
+actions!(settings, [OpenSettings]);
+
+/// User settings, persisted as `settings.toml` in the config dir.
+///
+/// `settings.json5` and the legacy `settings.json` are read too.
+///
+/// Every section falls back to its defaults, so a partial (or missing) file is fine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Settings {
+    pub appearance: AppearanceSettings,
+    pub encryption: EncryptionSettings,
+    pub format: FormatSettings,
+    pub idle: IdleSettings,
+    pub input: InputSettings,
+    pub integration: IntegrationSettings,
+    pub keymap: KeymapSettings,
+    pub kiosk: KioskSettings,
+    pub lock: LockSettings,
+    pub net: NetSettings,
+    pub notifications: NotificationSettings,
+    pub persistence: PersistenceSettings,
+    pub power: PowerSettings,
+    pub render: RenderSettings,
+    pub sounds: SoundSettings,
+    pub spell_check: SpellCheckSettings,
+    pub startup: StartupSettings,
+    pub telemetry: TelemetrySettings,
+    pub watchdog: WatchdogSettings,
+    pub extension_manager: ExtensionsSettings,
+    /// Values of the settings contributed by extensions, keyed by extension id.
+    pub extensions: BTreeMap<String, serde_json::Map<String, serde_json::Value>>,
+}
+
+impl Global for Settings {}
+
+impl Settings {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    /// Update the settings and write them back to disk, when [`Persistence`] gets to it.
+    ///
+    /// Settings locked by the [`Policy`] keep their locked values. The changes are recorded
+    /// in the [`History`].
+    pub fn update(cx: &mut App, f: impl FnOnce(&mut Self, &mut App)) {
+        let old = Self::global(cx).clone();
+        let mut settings = old.clone();
+        f(&mut settings, cx);
+        Policy::global().enforce(&mut settings);
+        cx.set_global(settings.clone());
+        history::record_settings(&old, &settings, History::source(cx), cx);
+        Persistence::schedule(SETTINGS_NAME, settings, cx);
+    }
+
+    /// The value of the setting at `key`, e.g. `telemetry.enabled`.
+    pub fn value(&self, key: &str) -> Option<serde_json::Value> {
+        let settings = serde_json::to_value(self).ok()?;
+        key.split('.')
+            .try_fold(&settings, |value, part| value.get(part))
+            .cloned()
+    }
+
+    /// A copy with the setting at `key` set to `value`, if that's a valid value for it.
+    pub fn with_value(&self, key: &str, value: serde_json::Value) -> anyhow::Result<Self> {
+        let mut settings = serde_json::to_value(self)?;
+        set_value(&mut settings, key, value);
+        Ok(serde_json::from_value(settings)?)
+    }
+
+    /// Whether the setting at `key` differs from its default.
+    pub fn is_modified(&self, key: &str) -> bool {
+        self.value(key) != Self::default().value(key)
+    }
+
+    /// Put the settings at `keys` back to their defaults, and write them to disk.
+    pub fn reset(keys: &[&str], cx: &mut App) {
+        let defaults = Self::default();
+        Self::update(cx, |settings, _| {
+            for key in keys {
+                let Some(value) = defaults.value(key) else {
+                    continue;
+                };
+                match settings.with_value(key, value) {
+                    Ok(reset) => *settings = reset,
+                    Err(err) => tracing::error!("Failed to reset {}: {}", key, err),
+                }
+            }
+        });
+    }
+
+    fn path() -> PathBuf {
+        config::path(&get_config_dir(), SETTINGS_NAME)
+    }
+
+    fn load() -> anyhow::Result<Self> {
+        config::load(&get_config_dir(), SETTINGS_NAME)
+    }
+
+    fn save(&self) {
+        if let Err(err) = config::save(&get_config_dir(), SETTINGS_NAME, self) {
+            tracing::error!("Failed to save settings: {}", err);
+        }
+    }
+}
+
+/// Set `key` (e.g. `telemetry.enabled`) in the JSON of the settings, adding the tables on
+/// the way.
+pub(crate) fn set_value(settings: &mut serde_json::Value, key: &str, value: serde_json::Value) {
+    let mut target = settings;
+    for part in key.split('.') {
+        if !target.is_object() {
+            *target = serde_json::Value::Object(Default::default());
+        }
+        target = target
+            .as_object_mut()
+            .expect("just made an object")
+            .entry(part)
+            .or_insert(serde_json::Value::Null);
+    }
+    *target = value;
+}
+
+/// Re-read the settings file, e.g. after it was replaced on disk.
+pub(crate) fn reload(cx: &mut App) {
+    let mut settings = Settings::load().unwrap_or_else(|err| {
+        tracing::error!("Failed to parse {}: {}", Settings::path().display(), err);
+        Settings::default()
+    });
+    Policy::global().enforce(&mut settings);
+    apply_reloaded(settings, cx);
+}
+
+/// Use the `settings` read from disk, and redo what only happens on start with the old ones.
+fn apply_reloaded(settings: Settings, cx: &mut App) {
+    let old = Settings::global(cx).clone();
+    cx.set_global(settings.clone());
+    history::record_settings(&old, &settings, ChangeSource::File, cx);
+    encryption::sync(cx);
+    scopes::rebind(cx);
+    Net::reload(cx);
+    cx.refresh_windows();
+}
+
+/// Pick up edits made outside the app, keeping the current settings while the file
+/// doesn't parse (e.g. half way through an edit).
+fn reload_changed(cx: &mut App) {
+    let mut settings = match Settings::load() {
+        Ok(settings) => settings,
+        Err(err) => {
+            tracing::warn!("Ignoring invalid {}: {}", Settings::path().display(), err);
+            return;
+        }
+    };
+    Policy::global().enforce(&mut settings);
+    // Our own saves come back as changes too.
+    if serde_json::to_value(&settings).ok() == serde_json::to_value(Settings::global(cx)).ok() {
+        return;
+    }
+
+    tracing::info!("Reloading the changed settings");
+    apply_reloaded(settings, cx);
+}
+
+pub fn init(cx: &mut App) {
+    let mut settings = Settings::load().unwrap_or_else(|err| {
+        recovery::recover_corrupted(&Settings::path(), err, reload, cx);
+        Settings::default()
+    });
+    Policy::global().enforce(&mut settings);
+    cx.set_global(settings);
+
+    let dir = get_config_dir();
+    let mut paths = config::file_names(SETTINGS_NAME)
+        .map(|name| dir.join(name))
+        .collect::<Vec<_>>();
+    // Changes go to the user's overlay when the config dir is shared and read-only.
+    let user_path = config::user_path(&dir, SETTINGS_NAME);
+    if !paths.contains(&user_path) {
+        paths.push(user_path);
+    }
+    FileWatcher::watch(paths, cx, |_, cx| reload_changed(cx));
+
+    // For everything the settings window doesn't cover, open the file in the default editor.
+    cx.on_action(|_: &OpenSettings, cx| {
+        let path = config::user_path(&get_config_dir(), SETTINGS_NAME);
+        persistence::flush(cx);
+        if !path.exists() {
+            Settings::global(cx).save();
+        }
+        cx.open_with_system(&path);
+    });
+}
@@ -0,0 +1,205 @@
+use gpui::{
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, InteractiveElement as _,
+    IntoElement, ParentElement as _, Render, SharedString, StatefulInteractiveElement as _,
+    Styled as _, Subscription, Window, actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputEvent, InputState, TextInput},
+    notification::Notification,
+    v_flex,
+};
+
+use crate::{
+    config,
+    settings::{SETTINGS_NAME, Settings},
+};
+
+actions!(settings, [EditRawSettings]);
+
+/// All the settings as JSON, for power users: checked against the config schema as they
+/// type, and only applied on request.
+pub struct RawSettingsEditor {
+    focus_handle: FocusHandle,
+    input: Entity<InputState>,
+    validator: Option<jsonschema::Validator>,
+    errors: Vec<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RawSettingsEditor {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .code_editor("json")
+                .line_number(true)
+                .default_value(Self::current(cx))
+        });
+        let _subscriptions = vec![cx.subscribe(&input, |this, _, event, cx| {
+            if let InputEvent::Change = event {
+                this.validate(cx);
+            }
+        })];
+        let validator = config::schema(Some(SETTINGS_NAME))
+            .and_then(|schema| {
+                jsonschema::validator_for(&schema).map_err(|err| anyhow::anyhow!("{}", err))
+            })
+            .inspect_err(|err| tracing::error!("Invalid settings schema: {}", err))
+            .ok();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            input,
+            validator,
+            errors: vec![],
+            _subscriptions,
+        }
+    }
+
+    /// The settings in effect, as pretty JSON.
+    fn current(cx: &App) -> String {
+        serde_json::to_string_pretty(Settings::global(cx)).unwrap_or_default()
+    }
+
+    /// The settings as edited, or what's wrong with them.
+    fn parse(&self, cx: &App) -> Result<Settings, Vec<SharedString>> {
+        let text = self.input.read(cx).value();
+        let value = serde_json::from_str::<serde_json::Value>(&text)
+            .map_err(|err| vec![err.to_string().into()])?;
+
+        if let Some(validator) = &self.validator {
+            let errors = validator
+                .iter_errors(&value)
+                .map(|err| {
+                    let path = err.instance_path.to_string();
+                    if path.is_empty() {
+                        err.to_string().into()
+                    } else {
+                        format!("{}: {}", path, err).into()
+                    }
+                })
+                .collect::<Vec<SharedString>>();
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+        }
+        serde_json::from_value(value).map_err(|err| vec![err.to_string().into()])
+    }
+
+    fn validate(&mut self, cx: &mut Context<Self>) {
+        self.errors = self.parse(cx).err().unwrap_or_default();
+        cx.notify();
+    }
+
+    fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let settings = match self.parse(cx) {
+            Ok(settings) => settings,
+            Err(errors) => {
+                self.errors = errors;
+                cx.notify();
+                return;
+            }
+        };
+
+        Settings::update(cx, |current, _| *current = settings);
+        cx.refresh_windows();
+        // Show what's in effect, e.g. with the locked settings put back.
+        self.revert(window, cx);
+        window.push_notification(Notification::success("Applied the settings."), cx);
+    }
+
+    fn revert(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = Self::current(cx);
+        self.input
+            .update(cx, |input, cx| input.set_value(text, window, cx));
+        self.errors.clear();
+        cx.notify();
+    }
+}
+
+impl Focusable for RawSettingsEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RawSettingsEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let valid = self.errors.is_empty();
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Every setting, checked against the settings schema."),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Button::new("revert-raw-settings")
+                                    .small()
+                                    .outline()
+                                    .label("Revert")
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.revert(window, cx)),
+                                    ),
+                            )
+                            .child(
+                                Button::new("apply-raw-settings")
+                                    .small()
+                                    .primary()
+                                    .label("Apply")
+                                    .disabled(!valid)
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.apply(window, cx)),
+                                    ),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(TextInput::new(&self.input).h_full()),
+            )
+            .when(!valid, |this| {
+                this.child(
+                    v_flex()
+                        .id("raw-settings-errors")
+                        .max_h(px(120.))
+                        .overflow_y_scroll()
+                        .gap_1()
+                        .text_sm()
+                        .text_color(cx.theme().danger)
+                        .children(self.errors.iter().map(|err| div().child(err.clone()))),
+                )
+            })
+    }
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Advanced Settings",
+        Some(size(px(800.), px(640.))),
+        |window, cx| RawSettingsEditor::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &EditRawSettings, cx| open_window(cx));
+}
@@ -0,0 +1,1402 @@
+use std::{collections::HashMap, time::Duration};
+
+use gpui::{
+    Action, AnyWindowHandle, App, AppContext as _, Context, Entity, FocusHandle, Focusable, Global,
+    InteractiveElement as _, IntoElement, ParentElement as _, Render, ScrollHandle, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Task, Timer, WeakEntity, Window,
+    actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, IconName, Sizable as _, Theme, ThemeRegistry,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    input::{InputEvent, InputState},
+    radio::RadioGroup,
+    switch::Switch,
+    v_flex,
+};
+
+use crate::{
+    appearance::{ChooseAccentColor, ChooseCursorColors},
+    combobox::{Combobox, ComboboxEvent, ComboboxItem},
+    cursor::{CursorExt as _, CursorRole},
+    encryption::ToggleConfigEncryption,
+    extensions::{ExtensionManifest, ExtensionRegistry, SettingContribution},
+    factory_reset::ResetAllSettings,
+    format,
+    history::{self, Change, History, UndoSettingsChange},
+    input_settings::{self, SCROLL_SPEED},
+    lock::{self, SetLockPassphrase},
+    navigation::{Breadcrumb, BreadcrumbItem, NavItem, NavRail},
+    net_settings::ShowNetworkSettings,
+    number_field::{NumberField, NumberFieldEvent},
+    policy,
+    settings::{OpenSettings, Settings},
+    settings_editor::EditRawSettings,
+    text_field::TextField,
+    themes,
+    tooltips::{RichTooltip, WithTooltip},
+    tree_view::{TreeDelegate, TreeNode, TreeView, TreeViewEvent},
+};
+
+actions!(settings, [ShowSettings]);
+
+/// Open the settings window, scrolled to the setting with this key and highlighting it. The
+/// name of a section, e.g. `Appearance`, reveals its first setting.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = settings, no_json)]
+pub struct RevealSetting(pub SharedString);
+
+/// How long a revealed setting stays highlighted.
+const HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+
+/// How an entry of the settings window is edited.
+pub enum SettingControl {
+    Toggle,
+    /// One of a few values, as labels and their JSON.
+    Choice(&'static [(&'static str, &'static str)]),
+    /// A dialog of its own, opened by a button with this label.
+    Dialog(&'static str, fn() -> Box<dyn Action>),
+    /// A number from `min` to `max` in steps of `step`, with a slider when `slider`.
+    Number {
+        min: f64,
+        max: f64,
+        step: f64,
+        unit: &'static str,
+        slider: bool,
+    },
+    /// One of many values, found by typing, with the options loaded when first opened.
+    ///
+    /// An option with an empty value sets `null`, shown as the placeholder.
+    Search {
+        placeholder: &'static str,
+        options: fn(&mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>>,
+    },
+}
+
+/// A setting shown in the settings window.
+pub struct SettingEntry {
+    /// The path of the setting in the settings file, e.g. `telemetry.enabled`.
+    pub key: &'static str,
+    pub section: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Other words the setting is found by, e.g. `dark` for the accent color.
+    pub keywords: &'static [&'static str],
+    pub control: SettingControl,
+}
+
+impl SettingEntry {
+    /// Whether every word of `query` is in the title, description, key or keywords.
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = [self.section, self.title, self.description, self.key]
+            .into_iter()
+            .chain(self.keywords.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| haystack.contains(word))
+    }
+}
+
+/// The settings in the window, in order of their sections.
+pub static ENTRIES: &[SettingEntry] = &[
+    SettingEntry {
+        key: "theme.name",
+        section: "Appearance",
+        title: "Theme",
+        description: "The colors of the app, from the built-in and installed themes.",
+        keywords: &["theme", "colors", "colours", "dark", "light", "scheme"],
+        control: SettingControl::Search {
+            placeholder: "Theme",
+            options: theme_options,
+        },
+    },
+    SettingEntry {
+        key: "appearance.follow_system",
+        section: "Appearance",
+        title: "Match the system",
+        description: "Switch between light and dark with the system.",
+        keywords: &["theme", "mode", "dark", "light", "auto"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "appearance.accent_color",
+        section: "Appearance",
+        title: "Accent color",
+        description: "Overrides the primary color of every theme.",
+        keywords: &["theme", "primary", "color", "colour"],
+        control: SettingControl::Dialog("Choose...", || Box::new(ChooseAccentColor)),
+    },
+    SettingEntry {
+        key: "appearance.cursor_color",
+        section: "Appearance",
+        title: "Cursor and selection",
+        description: "The colors of the text cursor and the selection.",
+        keywords: &["caret", "highlight", "color", "colour"],
+        control: SettingControl::Dialog("Choose...", || Box::new(ChooseCursorColors)),
+    },
+    SettingEntry {
+        key: "appearance.reduce_motion",
+        section: "Appearance",
+        title: "Reduce motion",
+        description: "Leave out decorative animations, like confetti.",
+        keywords: &["animation", "accessibility", "a11y", "celebration"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "appearance.emoji_font",
+        section: "Appearance",
+        title: "Emoji font",
+        description: "The font of emoji and other color glyphs the UI font lacks.",
+        keywords: &["emoji", "color font", "fallback", "glyph", "symbol"],
+        control: SettingControl::Search {
+            placeholder: "System",
+            options: font_options,
+        },
+    },
+    SettingEntry {
+        key: "format.locale",
+        section: "Appearance",
+        title: "Date and time format",
+        description: "The locale dates and times are written in.",
+        keywords: &["locale", "language", "region", "date", "time", "clock"],
+        control: SettingControl::Search {
+            placeholder: "System",
+            options: locale_options,
+        },
+    },
+    SettingEntry {
+        key: "spell_check.enabled",
+        section: "Spell Check",
+        title: "Check spelling",
+        description: "Underline misspelled words in the text fields that support it.",
+        keywords: &["spelling", "typo", "dictionary", "hunspell"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.scroll_speed",
+        section: "Mouse",
+        title: "Scroll speed",
+        description: "How far the content scrolls compared to the OS.",
+        keywords: &["wheel", "trackpad", "touchpad", "sensitivity"],
+        control: SettingControl::Number {
+            min: SCROLL_SPEED.0 as f64,
+            max: SCROLL_SPEED.1 as f64,
+            step: 0.25,
+            unit: "×",
+            slider: true,
+        },
+    },
+    SettingEntry {
+        key: "input.reverse_scrolling",
+        section: "Mouse",
+        title: "Reverse scrolling",
+        description: "Scroll the other way round than the OS, e.g. natural scrolling on a mouse.",
+        keywords: &["natural", "invert", "direction", "wheel", "trackpad"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.middle_click_paste",
+        section: "Mouse",
+        title: "Middle-click paste",
+        description: "Paste the last selected text with the middle button, on Linux.",
+        keywords: &["primary selection", "x11", "wayland", "clipboard"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.focus_follows_mouse",
+        section: "Mouse",
+        title: "Focus follows mouse",
+        description: "Focus split panes when the mouse moves over them, without a click.",
+        keywords: &["hover", "sloppy focus", "panes", "split"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.gestures.pinch_to_zoom",
+        section: "Touch",
+        title: "Pinch to zoom",
+        description: "Pinch, or hold ctrl and scroll, to change the font size.",
+        keywords: &["gesture", "trackpad", "touchpad", "scale", "font size"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.gestures.swipe_to_navigate",
+        section: "Touch",
+        title: "Swipe to navigate",
+        description: "Swipe sideways with two fingers to go back and forward in a pane.",
+        keywords: &["gesture", "trackpad", "touchpad", "back", "forward"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "input.gestures.long_press",
+        section: "Touch",
+        title: "Long press for context menus",
+        description: "Press and hold to open the context menu, like a right click.",
+        keywords: &["gesture", "touchscreen", "tap", "hold", "right click"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "sounds.enabled",
+        section: "Sounds",
+        title: "Play sounds",
+        description: "Play the sounds of the sound pack on app events.",
+        keywords: &["audio", "chime", "beep", "sound pack"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "sounds.muted",
+        section: "Sounds",
+        title: "Mute",
+        description: "Silence the sounds for now.",
+        keywords: &["audio", "quiet", "silent"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "sounds.volume",
+        section: "Sounds",
+        title: "Volume",
+        description: "How loud the sounds play.",
+        keywords: &["audio", "loudness"],
+        control: SettingControl::Choice(&[
+            ("25%", "0.25"),
+            ("50%", "0.5"),
+            ("75%", "0.75"),
+            ("100%", "1.0"),
+        ]),
+    },
+    SettingEntry {
+        key: "sounds.notification",
+        section: "Sounds",
+        title: "Notification chime",
+        description: "Play a chime for new notifications.",
+        keywords: &["audio", "alert", "toast"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "sounds.error",
+        section: "Sounds",
+        title: "Error sound",
+        description: "Play a sound when something fails.",
+        keywords: &["audio", "alert", "failure"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "idle.enabled",
+        section: "Idle",
+        title: "Detect idle",
+        description: "Notice when there was no input for a while.",
+        keywords: &["away", "inactive", "afk"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "idle.threshold_minutes",
+        section: "Idle",
+        title: "Idle after",
+        description: "Minutes without input before the app is idle.",
+        keywords: &["away", "timeout", "minutes"],
+        control: SettingControl::Number {
+            min: 1.,
+            max: 1440.,
+            step: 1.,
+            unit: "min",
+            slider: false,
+        },
+    },
+    SettingEntry {
+        key: "idle.dim",
+        section: "Idle",
+        title: "Dim while idle",
+        description: "Darken the windows while the app is idle.",
+        keywords: &["darken", "screen saver", "fade"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "lock.lock_on_idle",
+        section: "Lock",
+        title: "Lock when idle",
+        description: "Lock the app whenever it goes idle.",
+        keywords: &["security", "password", "away"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "lock.system_auth",
+        section: "Lock",
+        title: "Unlock with the OS",
+        description: "Also unlock with Touch ID, Windows Hello or polkit.",
+        keywords: &[
+            "biometric",
+            "fingerprint",
+            "touch id",
+            "windows hello",
+            "security",
+        ],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "lock.passphrase_hash",
+        section: "Lock",
+        title: "Passphrase",
+        description: "The passphrase that unlocks the app.",
+        keywords: &["password", "security", "pin"],
+        control: SettingControl::Dialog("Set...", || Box::new(SetLockPassphrase)),
+    },
+    SettingEntry {
+        key: "notifications.native",
+        section: "Notifications",
+        title: "System notifications",
+        description: "Show important notifications in the OS while the app is in the background.",
+        keywords: &["alerts", "toast", "os", "native"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "power.pause_unfocused",
+        section: "Performance",
+        title: "Pause animations in the background",
+        description: "Stop animations in windows without focus.",
+        keywords: &["battery", "energy", "cpu", "spinner"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "power.max_fps",
+        section: "Performance",
+        title: "Background redraw limit",
+        description: "Frames per second for redraws caused by background updates, 0 for no limit.",
+        keywords: &["fps", "frame rate", "battery", "energy"],
+        control: SettingControl::Number {
+            min: 0.,
+            max: 240.,
+            step: 10.,
+            unit: "fps",
+            slider: true,
+        },
+    },
+    SettingEntry {
+        key: "render.software",
+        section: "Performance",
+        title: "Software rendering",
+        description: "Render on the CPU, for machines without a usable GPU. Applies on restart.",
+        keywords: &["gpu", "graphics", "vm", "remote desktop", "llvmpipe"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "net.proxy",
+        section: "Network",
+        title: "Proxy and certificates",
+        description: "The proxy to connect through and extra CA certificates to trust.",
+        keywords: &["http", "socks", "tls", "ssl", "ca", "corporate"],
+        control: SettingControl::Dialog("Edit...", || Box::new(ShowNetworkSettings)),
+    },
+    SettingEntry {
+        key: "net.prefer_cache",
+        section: "Network",
+        title: "Prefer cached responses",
+        description: "Serve responses from the cache without revalidating.",
+        keywords: &["offline", "http", "cache"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "telemetry.enabled",
+        section: "Privacy",
+        title: "Send anonymous usage data",
+        description: "Upload the queued usage events.",
+        keywords: &["telemetry", "analytics", "tracking", "statistics"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "encryption.enabled",
+        section: "Privacy",
+        title: "Encrypt config files",
+        description: "Encrypt the config files with a key kept in the OS keychain.",
+        keywords: &["security", "keychain", "secret"],
+        control: SettingControl::Dialog("Toggle", || Box::new(ToggleConfigEncryption)),
+    },
+    SettingEntry {
+        key: "persistence.save",
+        section: "Data",
+        title: "Save changes",
+        description: "When changes to the settings and the layout are written to disk.",
+        keywords: &["auto save", "write", "disk", "persist"],
+        control: SettingControl::Choice(&[
+            ("On change", "\"on_change\""),
+            ("Every interval", "\"interval\""),
+            ("On exit", "\"on_exit\""),
+        ]),
+    },
+    SettingEntry {
+        key: "persistence.interval",
+        section: "Data",
+        title: "Save interval",
+        description: "Seconds between writes, when saving every interval.",
+        keywords: &["auto save", "seconds"],
+        control: SettingControl::Choice(&[
+            ("10 s", "10"),
+            ("30 s", "30"),
+            ("1 min", "60"),
+            ("5 min", "300"),
+        ]),
+    },
+    SettingEntry {
+        key: "reset",
+        section: "Data",
+        title: "Reset all settings",
+        description: "Move the settings, session and layouts to a backup and restart with the defaults.",
+        keywords: &["factory", "defaults", "clear", "backup", "restore"],
+        control: SettingControl::Dialog("Reset...", || Box::new(ResetAllSettings)),
+    },
+    SettingEntry {
+        key: "render.display_server",
+        section: "System",
+        title: "Display server (Linux)",
+        description: "Wayland or X11, applies on restart.",
+        keywords: &["wayland", "x11", "xwayland", "backend"],
+        control: SettingControl::Choice(&[
+            ("Automatic", "\"auto\""),
+            ("Wayland", "\"wayland\""),
+            ("X11", "\"x11\""),
+        ]),
+    },
+    SettingEntry {
+        key: "render.decorations",
+        section: "System",
+        title: "Window decorations (Linux)",
+        description: "Who draws the title bar, applies on restart.",
+        keywords: &["title bar", "csd", "ssd", "borders"],
+        control: SettingControl::Choice(&[("App", "\"client\""), ("System", "\"server\"")]),
+    },
+    SettingEntry {
+        key: "integration.url_scheme",
+        section: "System",
+        title: "Open hello-gp:// links",
+        description: "Register the app as the handler of its links.",
+        keywords: &["url", "protocol", "deep link", "registry"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "integration.file_associations",
+        section: "System",
+        title: "Open theme files",
+        description: "Install the themes of .hgptheme files opened from the file manager.",
+        keywords: &["file type", "association", "hgptheme", "registry"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "integration.start_menu_shortcut",
+        section: "System",
+        title: "Menu shortcut",
+        description: "Keep a shortcut to the app in the Start Menu or the applications menu.",
+        keywords: &["shortcut", "start menu", "launcher", "desktop entry"],
+        control: SettingControl::Toggle,
+    },
+    SettingEntry {
+        key: "startup.whats_new",
+        section: "System",
+        title: "What's new after updates",
+        description: "Show the changes of a new version on its first start.",
+        keywords: &["changelog", "release notes", "update", "version"],
+        control: SettingControl::Toggle,
+    },
+];
+
+pub fn entry(key: &str) -> Option<&'static SettingEntry> {
+    ENTRIES.iter().find(|entry| entry.key == key)
+}
+
+fn theme_options(cx: &mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>> {
+    let themes = ThemeRegistry::global(cx)
+        .sorted_themes()
+        .into_iter()
+        .map(|theme| {
+            let mode = if theme.mode.is_dark() {
+                "Dark"
+            } else {
+                "Light"
+            };
+            ComboboxItem::from(theme.name.clone()).detail(mode)
+        })
+        .collect();
+    Task::ready(Ok(themes))
+}
+
+/// Every installed font, there may be thousands.
+fn font_options(cx: &mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>> {
+    let text_system = cx.text_system().clone();
+    cx.background_executor().spawn(async move {
+        let mut names = text_system.all_font_names();
+        names.sort();
+        names.dedup();
+        Ok(std::iter::once(ComboboxItem::new("", "System"))
+            .chain(names.into_iter().map(ComboboxItem::from))
+            .collect())
+    })
+}
+
+fn locale_options(cx: &mut App) -> Task<anyhow::Result<Vec<ComboboxItem>>> {
+    cx.background_executor().spawn(async move {
+        Ok(std::iter::once(ComboboxItem::new("", "System"))
+            .chain(
+                format::available_locales()
+                    .into_iter()
+                    .map(ComboboxItem::from),
+            )
+            .collect())
+    })
+}
+
+/// The sections of the settings and their entries, beside the entries.
+struct SettingsNav;
+
+impl TreeDelegate for SettingsNav {
+    fn roots(&mut self, _: &mut App) -> Vec<TreeNode> {
+        let mut sections = ENTRIES
+            .iter()
+            .map(|entry| entry.section)
+            .collect::<Vec<_>>();
+        sections.dedup();
+        sections
+            .into_iter()
+            .map(|section| TreeNode::new(section, section).with_children())
+            .collect()
+    }
+
+    fn children(&mut self, node: &TreeNode, _: &mut App) -> Task<anyhow::Result<Vec<TreeNode>>> {
+        Task::ready(Ok(ENTRIES
+            .iter()
+            .filter(|entry| entry.section == node.id.as_ref())
+            .map(|entry| TreeNode::new(entry.key, entry.title))
+            .collect()))
+    }
+}
+
+/// The open settings window, there is only ever one.
+struct SettingsWindow {
+    window: AnyWindowHandle,
+    view: WeakEntity<SettingsView>,
+}
+
+impl Global for SettingsWindow {}
+
+/// Every setting with a control, grouped in sections and searchable.
+pub struct SettingsView {
+    focus_handle: FocusHandle,
+    search_input: Entity<InputState>,
+    nav: Entity<TreeView<SettingsNav>>,
+    scroll_handle: ScrollHandle,
+    /// The setting to scroll to on the next render.
+    scroll_to: Option<&'static str>,
+    highlighted: Option<&'static str>,
+    /// The setting last revealed, at the end of the breadcrumb.
+    current: Option<&'static str>,
+    /// The settings (0) or their history (1), picked in the rail.
+    page: usize,
+    /// The fields of the [`SettingControl::Number`] settings.
+    fields: HashMap<&'static str, Entity<NumberField>>,
+    /// The comboboxes of the [`SettingControl::Search`] settings.
+    searches: HashMap<&'static str, Entity<Combobox>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SettingsView {
+    pub fn view(reveal: Option<&'static str>, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let view = cx.new(|cx| Self::new(window, cx));
+        cx.set_global(SettingsWindow {
+            window: window.window_handle(),
+            view: view.downgrade(),
+        });
+        if let Some(key) = reveal {
+            view.update(cx, |this, cx| this.reveal(key, window, cx));
+        }
+        view
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder("Search settings..."));
+        let nav = cx.new(|cx| TreeView::new("settings-nav", SettingsNav, window, cx));
+        let mut _subscriptions = vec![
+            cx.subscribe_in(&nav, window, |this, _, event, window, cx| {
+                if let TreeViewEvent::Select(ids) = event
+                    && let Some(id) = ids.last()
+                    // A section reveals its first setting.
+                    && let Some(entry) = ENTRIES
+                        .iter()
+                        .find(|entry| entry.key == id.as_ref())
+                        .or_else(|| ENTRIES.iter().find(|entry| entry.section == id.as_ref()))
+                {
+                    this.reveal(entry.key, window, cx);
+                }
+            }),
+            cx.subscribe(&search_input, |_, _, event, cx| {
+                if let InputEvent::Change = event {
+                    cx.notify();
+                }
+            }),
+            cx.observe_global_in::<Settings>(window, |this, window, cx| {
+                this.sync_fields(window, cx);
+                this.sync_searches(cx);
+                cx.notify();
+            }),
+            cx.observe_global::<Theme>(|this, cx| this.sync_searches(cx)),
+            cx.observe_global::<History>(|_, cx| cx.notify()),
+            cx.observe(&ExtensionRegistry::global(cx), |_, _, cx| cx.notify()),
+        ];
+
+        let mut fields = HashMap::new();
+        for entry in ENTRIES {
+            let SettingControl::Number {
+                min,
+                max,
+                step,
+                unit,
+                slider,
+            } = entry.control
+            else {
+                continue;
+            };
+            let key = entry.key;
+            let value = Self::number(key, cx).unwrap_or(min);
+            let field = cx.new(|cx| {
+                let field = NumberField::new(value, window, cx)
+                    .range(min, max)
+                    .step(step)
+                    .unit(unit);
+                if slider { field.slider() } else { field }
+            });
+            _subscriptions.push(cx.subscribe(&field, move |_, _, event, cx| {
+                let NumberFieldEvent::Change(number) = *event;
+                // Whole numbers are written as integers, for the settings that are.
+                let value = if number.fract() == 0. {
+                    serde_json::Value::from(number as i64)
+                } else {
+                    serde_json::Value::from(number)
+                };
+                Self::set(key, value, cx);
+            }));
+            fields.insert(key, field);
+        }
+
+        let mut searches = HashMap::new();
+        for entry in ENTRIES {
+            let SettingControl::Search {
+                placeholder,
+                options,
+            } = entry.control
+            else {
+                continue;
+            };
+            let key = entry.key;
+            let selected = Self::search_value(key, cx).map(ComboboxItem::from);
+            let combobox = cx.new(|cx| {
+                let mut combobox = Combobox::new(key, options).placeholder(placeholder);
+                combobox.set_selected(selected, cx);
+                combobox
+            });
+            _subscriptions.push(cx.subscribe(&combobox, move |_, _, event, cx| {
+                let ComboboxEvent::Change(item) = event;
+                let value = if item.value.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    item.value.to_string().into()
+                };
+                Self::set(key, value, cx);
+            }));
+            searches.insert(key, combobox);
+        }
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            search_input,
+            nav,
+            scroll_handle: ScrollHandle::new(),
+            scroll_to: None,
+            highlighted: None,
+            current: None,
+            page: 0,
+            fields,
+            searches,
+            _subscriptions,
+        }
+    }
+
+    /// Scroll to the setting `key` and highlight it for a moment.
+    fn reveal(&mut self, key: &'static str, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+        self.page = 0;
+        self.scroll_to = Some(key);
+        self.current = Some(key);
+        self.highlighted = Some(key);
+        cx.spawn(async move |this, cx| {
+            Timer::after(HIGHLIGHT_DURATION).await;
+            _ = this.update(cx, |this, cx| {
+                if this.highlighted == Some(key) {
+                    this.highlighted = None;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn set(key: &str, value: serde_json::Value, cx: &mut App) {
+        // The theme is kept in the state file rather than the settings.
+        if key == "theme.name" {
+            if let Some(name) = value.as_str() {
+                themes::switch_theme(name.to_string().into(), cx);
+            }
+            return;
+        }
+        // Locking when idle with no way to unlock would lock the user out, ask for a
+        // passphrase first.
+        if key == "lock.lock_on_idle" && value == true && !lock::can_lock(cx) {
+            cx.dispatch_action(&SetLockPassphrase);
+            cx.refresh_windows();
+            return;
+        }
+        match Settings::global(cx).with_value(key, value) {
+            Ok(settings) => {
+                Settings::update(cx, |current, _| *current = settings);
+                cx.refresh_windows();
+            }
+            Err(err) => tracing::error!("Invalid value for {}: {}", key, err),
+        }
+    }
+
+    fn number(key: &str, cx: &App) -> Option<f64> {
+        Settings::global(cx).value(key)?.as_f64()
+    }
+
+    /// The value of a [`SettingControl::Search`] setting, `None` when it's `null`.
+    fn search_value(key: &str, cx: &App) -> Option<SharedString> {
+        if key == "theme.name" {
+            return Some(cx.theme().theme_name().clone());
+        }
+        Settings::global(cx)
+            .value(key)?
+            .as_str()
+            .map(|value| value.to_string().into())
+    }
+
+    /// Show the values of search settings changed elsewhere.
+    fn sync_searches(&mut self, cx: &mut Context<Self>) {
+        for (key, combobox) in &self.searches {
+            let value = Self::search_value(key, cx);
+            let selected = combobox
+                .read(cx)
+                .selected()
+                .map(|item| item.value.clone())
+                // The "System" option of a `null` setting.
+                .filter(|value| !value.is_empty());
+            if selected != value {
+                combobox.update(cx, |combobox, cx| {
+                    combobox.set_selected(value.map(ComboboxItem::from), cx)
+                });
+            }
+        }
+    }
+
+    /// Show the values of number settings changed elsewhere, unless one is being typed.
+    fn sync_fields(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for (key, field) in &self.fields {
+            let Some(value) = Self::number(key, cx) else {
+                continue;
+            };
+            let field_value = field.read(cx).value();
+            // An `f32` setting reads back a little off, `0.30000001192092896` for `0.3`.
+            if !field.read(cx).is_valid() || (field_value as f32) == (value as f32) {
+                continue;
+            }
+            field.update(cx, |field, cx| field.set_value(value, window, cx));
+        }
+    }
+
+    fn render_control(
+        &self,
+        entry: &'static SettingEntry,
+        locked: bool,
+        cx: &App,
+    ) -> impl IntoElement {
+        let value = Settings::global(cx).value(entry.key);
+        let key = entry.key;
+
+        match &entry.control {
+            SettingControl::Toggle => Switch::new(key)
+                .checked(value.and_then(|value| value.as_bool()).unwrap_or(false))
+                .disabled(locked)
+                .on_click(move |checked: &bool, _, cx| Self::set(key, (*checked).into(), cx))
+                .into_any_element(),
+            SettingControl::Choice(choices) => {
+                let selected = value.and_then(|value| {
+                    choices.iter().position(|(_, json)| {
+                        serde_json::from_str::<serde_json::Value>(json).ok() == Some(value.clone())
+                    })
+                });
+                RadioGroup::horizontal(key)
+                    .children(choices.iter().map(|(label, _)| *label))
+                    .selected_index(selected)
+                    .disabled(locked)
+                    .on_change(move |ix: &usize, _, cx| {
+                        if let Ok(value) = serde_json::from_str(choices[*ix].1) {
+                            Self::set(key, value, cx);
+                        }
+                    })
+                    .into_any_element()
+            }
+            SettingControl::Dialog(label, action) => Button::new(key)
+                .small()
+                .outline()
+                .label(*label)
+                .disabled(locked)
+                .on_click(move |_, window, cx| window.dispatch_action(action(), cx))
+                .into_any_element(),
+            SettingControl::Number { .. } => match self.fields.get(key) {
+                Some(field) if !locked => field.clone().into_any_element(),
+                _ => div()
+                    .text_sm()
+                    .child(
+                        Self::number(key, cx)
+                            .map(|number| (number as f32).to_string())
+                            .unwrap_or_default(),
+                    )
+                    .into_any_element(),
+            },
+            SettingControl::Search { placeholder, .. } => match self.searches.get(key) {
+                Some(combobox) if !locked => combobox.clone().into_any_element(),
+                _ => div()
+                    .text_sm()
+                    .child(Self::search_value(key, cx).unwrap_or((*placeholder).into()))
+                    .into_any_element(),
+            },
+        }
+    }
+
+    /// The page, and the section and setting last revealed on the settings page.
+    fn render_breadcrumb(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity().downgrade();
+        let page = if self.page == 1 {
+            "History"
+        } else {
+            "Settings"
+        };
+        let mut breadcrumb =
+            Breadcrumb::new("settings-breadcrumb").item(BreadcrumbItem::new(page).on_click({
+                let view = view.clone();
+                move |_, cx| {
+                    _ = view.update(cx, |this, cx| {
+                        this.current = None;
+                        this.scroll_handle.scroll_to_item(0);
+                        cx.notify();
+                    });
+                }
+            }));
+        if self.page == 0
+            && let Some(entry) = self.current.and_then(entry)
+        {
+            let section = entry.section;
+            breadcrumb = breadcrumb
+                .item(BreadcrumbItem::new(section).on_click(move |window, cx| {
+                    if let Some(first) = ENTRIES.iter().find(|entry| entry.section == section) {
+                        _ = view.update(cx, |this, cx| this.reveal(first.key, window, cx));
+                    }
+                }))
+                .item(BreadcrumbItem::new(entry.title));
+        }
+        breadcrumb
+    }
+
+    fn render_section_header(section: &'static str, cx: &App) -> impl IntoElement {
+        let keys = ENTRIES
+            .iter()
+            .filter(|entry| entry.section == section && !policy::is_locked(entry.key))
+            .map(|entry| entry.key)
+            .collect::<Vec<_>>();
+        let settings = Settings::global(cx);
+        let modified = keys.iter().any(|key| settings.is_modified(key));
+
+        h_flex()
+            .pt_3()
+            .pb_1()
+            .justify_between()
+            .child(div().text_sm().font_semibold().child(section))
+            .when(modified, |this| {
+                this.child(
+                    Button::new(SharedString::from(format!("reset-section-{}", section)))
+                        .xsmall()
+                        .ghost()
+                        .label("Reset Section")
+                        .on_click(move |_, _, cx| {
+                            Settings::reset(&keys, cx);
+                            cx.refresh_windows();
+                        }),
+                )
+            })
+    }
+
+    fn render_entry(&self, entry: &'static SettingEntry, cx: &App) -> impl IntoElement {
+        let locked = policy::is_locked(entry.key);
+        let highlighted = self.highlighted == Some(entry.key);
+        let modified = Settings::global(cx).is_modified(entry.key);
+        let key = entry.key;
+
+        h_flex()
+            .id(entry.key)
+            .w_full()
+            .p_2()
+            .gap_4()
+            .justify_between()
+            .rounded(cx.theme().radius)
+            .when(highlighted, |this| this.bg(cx.theme().accent))
+            .when(locked, |this| {
+                this.tooltip(move |window, cx| {
+                    RichTooltip::new(policy::LOCKED_TOOLTIP)
+                        .body(format!("`{}` is set by a policy.", key))
+                        .build(window, cx)
+                })
+            })
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        h_flex()
+                            .gap_1p5()
+                            .text_sm()
+                            .child(entry.title)
+                            .when(modified, |this| {
+                                this.child(
+                                    div()
+                                        .id("modified")
+                                        .size_1p5()
+                                        .rounded_full()
+                                        .bg(cx.theme().primary)
+                                        .tooltip(move |window, cx| {
+                                            RichTooltip::new("Modified")
+                                                .body(format!(
+                                                    "`{}` differs from the default.",
+                                                    key
+                                                ))
+                                                .build(window, cx)
+                                        }),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(entry.description),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(modified && !locked, |this| {
+                        this.child(WithTooltip::new(
+                            SharedString::from(format!("reset-tooltip-{}", key)),
+                            RichTooltip::new("Reset to Default")
+                                .body("The change can be undone from the history."),
+                            Button::new(SharedString::from(format!("reset-{}", key)))
+                                .xsmall()
+                                .ghost()
+                                .label("Reset")
+                                .on_click(move |_, _, cx| {
+                                    Settings::reset(&[key], cx);
+                                    cx.refresh_windows();
+                                }),
+                        ))
+                    })
+                    .child(self.render_control(entry, locked, cx)),
+            )
+    }
+
+    /// A setting contributed by an extension. Those have no declared control, a boolean is
+    /// toggled in place and other values are edited in the settings file.
+    fn render_extension_setting(
+        manifest: &ExtensionManifest,
+        setting: &SettingContribution,
+        cx: &App,
+    ) -> impl IntoElement {
+        let id = manifest.id.to_string();
+        let key = setting.key.to_string();
+        let value = Settings::global(cx)
+            .extensions
+            .get(&id)
+            .and_then(|values| values.get(&key))
+            .unwrap_or(&setting.default)
+            .clone();
+        let element_id = SharedString::from(format!("extension-{}-{}", id, key));
+
+        h_flex()
+            .id(element_id.clone())
+            .w_full()
+            .p_2()
+            .gap_4()
+            .justify_between()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .child(div().text_sm().child(setting.title.clone()))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(setting.description.clone()),
+                    ),
+            )
+            .child(match value {
+                serde_json::Value::Bool(checked) => Switch::new(element_id)
+                    .checked(checked)
+                    .on_click(move |checked: &bool, _, cx| {
+                        let (id, key, checked) = (id.clone(), key.clone(), *checked);
+                        Settings::update(cx, |settings, _| {
+                            settings
+                                .extensions
+                                .entry(id)
+                                .or_default()
+                                .insert(key, checked.into());
+                        });
+                        cx.refresh_windows();
+                    })
+                    .into_any_element(),
+                value => h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(value.to_string()),
+                    )
+                    .child(
+                        Button::new(element_id)
+                            .xsmall()
+                            .ghost()
+                            .label("Edit JSON")
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(EditRawSettings), cx)
+                            }),
+                    )
+                    .into_any_element(),
+            })
+    }
+
+    fn render_results(&self, query: &str, cx: &mut Context<Self>) -> impl IntoElement {
+        let results = ENTRIES
+            .iter()
+            .filter(|entry| entry.matches(query))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .gap_1()
+            .when(results.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("No matching settings."),
+                )
+            })
+            .children(results.into_iter().map(|entry| {
+                let key = entry.key;
+                h_flex()
+                    .id(SharedString::from(format!("result-{}", key)))
+                    .p_2()
+                    .gap_2()
+                    .rounded(cx.theme().radius)
+                    .cursor_role(CursorRole::Pointer, cx)
+                    .hover(|this| this.bg(cx.theme().accent))
+                    .child(div().text_sm().child(entry.title))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(entry.section),
+                    )
+                    .on_click(cx.listener(move |this, _, window, cx| this.reveal(key, window, cx)))
+            }))
+    }
+
+    fn render_change(ix: usize, change: &Change, cx: &App) -> impl IntoElement {
+        let time = format::from_timestamp(change.timestamp);
+        let title = entry(&change.key)
+            .map(|entry| format!("{}: {}", entry.section, entry.title))
+            .unwrap_or_else(|| change.key.clone());
+        let revertible = change.is_revertible() && !policy::is_locked(&change.key);
+        let revert = change.clone();
+
+        h_flex()
+            .id(("change", ix))
+            .w_full()
+            .p_2()
+            .gap_4()
+            .justify_between()
+            .child(
+                v_flex()
+                    .gap_0p5()
+                    .min_w_0()
+                    .child(div().text_sm().child(title))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{} → {}", change.old, change.new)),
+                    )
+                    .child(
+                        div()
+                            .id(("change-time", ix))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!(
+                                "{} · {}",
+                                time.map(|time| format::relative(&time)).unwrap_or_default(),
+                                change.source.label()
+                            ))
+                            .when_some(time, |this, time| {
+                                this.tooltip(move |window, cx| {
+                                    RichTooltip::new(format::date_time(&time)).build(window, cx)
+                                })
+                            }),
+                    ),
+            )
+            .when(revertible, |this| {
+                this.child(
+                    Button::new(("revert-change", ix))
+                        .xsmall()
+                        .ghost()
+                        .label("Revert")
+                        .tooltip("Restore the old value")
+                        .on_click(move |_, _, cx| history::revert(&[revert.clone()], cx)),
+                )
+            })
+    }
+
+    fn render_history(cx: &App) -> impl IntoElement {
+        let history = History::global(cx);
+        let changes = history.changes();
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Changes to the settings and the theme, newest first."),
+                    )
+                    .child(
+                        Button::new("undo-settings-change")
+                            .small()
+                            .outline()
+                            .label("Undo")
+                            .disabled(!history.can_undo())
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(UndoSettingsChange), cx)
+                            }),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("settings-history")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .when(changes.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No changes yet."),
+                        )
+                    })
+                    .children(
+                        changes
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(ix, change)| Self::render_change(ix, change, cx)),
+                    ),
+            )
+    }
+
+    /// The search, the breadcrumb, and the settings or their history.
+    fn render_page(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.search_input.read(cx).value().trim().to_string();
+
+        // Section titles and entries are the children of the list, in this order.
+        let mut rows = vec![];
+        let mut section = "";
+        for entry in ENTRIES {
+            if entry.section != section {
+                section = entry.section;
+                rows.push(Self::render_section_header(section, cx).into_any_element());
+            }
+            if self.scroll_to == Some(entry.key) {
+                self.scroll_handle.scroll_to_item(rows.len());
+            }
+            rows.push(self.render_entry(entry, cx).into_any_element());
+        }
+        // A section per enabled extension contributing settings, after the app's own.
+        let registry = ExtensionRegistry::global(cx);
+        let mut extension = None;
+        for (manifest, setting) in registry.read(cx).settings() {
+            if extension != Some(&manifest.id) {
+                extension = Some(&manifest.id);
+                rows.push(
+                    div()
+                        .pt_3()
+                        .pb_1()
+                        .text_sm()
+                        .font_semibold()
+                        .child(manifest.name.clone())
+                        .into_any_element(),
+                );
+            }
+            rows.push(Self::render_extension_setting(manifest, setting, cx).into_any_element());
+        }
+        self.scroll_to = None;
+
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div().flex_1().child(
+                            TextField::new("settings-search", &self.search_input).cleanable(),
+                        ),
+                    )
+                    .child(
+                        Button::new("open-settings-file")
+                            .small()
+                            .ghost()
+                            .label("Open File")
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(OpenSettings), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("edit-raw-settings")
+                            .small()
+                            .ghost()
+                            .label("Edit JSON")
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(EditRawSettings), cx)
+                            }),
+                    ),
+            )
+            .child(self.render_breadcrumb(cx))
+            .map(|this| {
+                if self.page == 1 {
+                    this.child(Self::render_history(cx))
+                } else if query.is_empty() {
+                    this.child(
+                        h_flex()
+                            .flex_1()
+                            .min_h_0()
+                            .items_start()
+                            .gap_3()
+                            .child(div().w(px(180.)).h_full().child(self.nav.clone()))
+                            .child(
+                                v_flex()
+                                    .id("settings-entries")
+                                    .flex_1()
+                                    .h_full()
+                                    .overflow_y_scroll()
+                                    .track_scroll(&self.scroll_handle)
+                                    .on_scroll_wheel(cx.listener(|this, event, window, cx| {
+                                        input_settings::scroll(
+                                            &this.scroll_handle,
+                                            event,
+                                            window,
+                                            cx,
+                                        )
+                                    }))
+                                    .children(rows),
+                            ),
+                    )
+                } else {
+                    this.child(
+                        div()
+                            .id("settings-results")
+                            .flex_1()
+                            .min_h_0()
+                            .overflow_y_scroll()
+                            .child(self.render_results(&query, cx)),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for SettingsView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SettingsView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .child(
+                NavRail::new("settings-pages")
+                    .collapsed(true)
+                    .item(NavItem::new(IconName::Settings, "Settings"))
+                    .item(NavItem::new(IconName::Calendar, "History"))
+                    .selected_index(self.page)
+                    .on_select(cx.listener(|this, ix: &usize, _, cx| {
+                        this.page = *ix;
+                        cx.notify();
+                    })),
+            )
+            .child(self.render_page(cx))
+    }
+}
+
+/// Show the settings window, revealing the setting `key`. Reuses the open window.
+pub fn open(reveal: Option<&'static str>, cx: &mut App) {
+    if let Some(open) = cx.try_global::<SettingsWindow>()
+        && let Some(view) = open.view.upgrade()
+    {
+        let window = open.window;
+        _ = window.update(cx, |_, window, cx| {
+            window.activate_window();
+            if let Some(key) = reveal {
+                view.update(cx, |this, cx| this.reveal(key, window, cx));
+            }
+        });
+        return;
+    }
+
+    crate::create_new_window_with_size(
+        "Settings",
+        Some(size(px(720.), px(640.))),
+        move |window, cx| SettingsView::view(reveal, window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ShowSettings, cx| open(None, cx));
+    cx.on_action(|reveal: &RevealSetting, cx| {
+        let entry = entry(&reveal.0).or_else(|| {
+            ENTRIES
+                .iter()
+                .find(|entry| entry.section.eq_ignore_ascii_case(&reveal.0))
+        });
+        match entry {
+            Some(entry) => open(Some(entry.key), cx),
+            None => tracing::warn!("Unknown setting: {}", reveal.0),
+        }
+    });
+}
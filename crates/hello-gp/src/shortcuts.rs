@@ -0,0 +1,238 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use gpui::{
+    AnyElement, App, Global, InteractiveElement as _, IntoElement, KeyBinding, KeyDownEvent,
+    Keystroke, Modifiers, ModifiersChangedEvent, MouseButton, ParentElement as _,
+    StatefulInteractiveElement as _, Styled as _, Timer, Window, WindowId, actions, div, px,
+    relative,
+};
+use gpui_component::{ActiveTheme as _, Kbd, h_flex, v_flex};
+
+use crate::scopes::ShortcutScope;
+
+actions!(help, [ShowShortcuts]);
+
+/// How long the platform modifier (cmd or ctrl) is held alone before the cheatsheet shows.
+const HOLD_DURATION: Duration = Duration::from_millis(1000);
+/// How many of the bindings of an action are shown, e.g. one per key context.
+const MAX_BINDINGS: usize = 2;
+
+#[derive(Default)]
+struct Shortcuts {
+    /// The window showing the cheatsheet.
+    window: Option<WindowId>,
+    /// Shown by holding the modifier, so it hides again when it's released.
+    held: bool,
+    /// Bumped on every press and release, so only the timer of the last press shows it.
+    press: usize,
+}
+
+impl Global for Shortcuts {}
+
+/// `ToggleThemeMode` as `Toggle Theme Mode`.
+fn humanize(name: &str) -> String {
+    let mut words = String::new();
+    for (ix, ch) in name.char_indices() {
+        if ix > 0 && ch.is_uppercase() {
+            words.push(' ');
+        }
+        if ix == 0 {
+            words.extend(ch.to_uppercase());
+        } else {
+            words.push(ch);
+        }
+    }
+    words.replace('_', " ")
+}
+
+/// The bound actions by category, the namespace of their name, e.g. `themes` for
+/// `themes::ToggleThemeMode`.
+///
+/// Read from the action registry and the keymap on every show, so it's never out of date with
+/// what's bound, including by extensions and in kiosk mode.
+fn groups(cx: &App) -> BTreeMap<String, Vec<(String, Vec<Vec<Keystroke>>)>> {
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    let mut bindings: HashMap<&str, Vec<Vec<Keystroke>>> = HashMap::new();
+    for binding in keymap.bindings() {
+        let keystrokes = binding.keystrokes().to_vec();
+        let entry = bindings.entry(binding.action().name()).or_default();
+        if !entry.contains(&keystrokes) && entry.len() < MAX_BINDINGS {
+            entry.push(keystrokes);
+        }
+    }
+
+    let mut groups: BTreeMap<String, Vec<_>> = BTreeMap::new();
+    for name in cx.all_action_names() {
+        let Some(keystrokes) = bindings.remove(name) else {
+            continue;
+        };
+        let (category, action) = name.rsplit_once("::").unwrap_or(("other", name));
+        groups
+            .entry(humanize(category))
+            .or_default()
+            .push((humanize(action), keystrokes));
+    }
+    for actions in groups.values_mut() {
+        actions.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    groups
+}
+
+fn show(window: WindowId, held: bool, cx: &mut App) {
+    let this = cx.global_mut::<Shortcuts>();
+    this.window = Some(window);
+    this.held = held;
+    cx.refresh_windows();
+}
+
+fn hide(cx: &mut App) {
+    let this = cx.global_mut::<Shortcuts>();
+    if this.window.take().is_some() {
+        this.held = false;
+        cx.refresh_windows();
+    }
+}
+
+/// Show the cheatsheet while the platform modifier is held alone, hooked up by the root view.
+pub(crate) fn modifiers_changed(event: &ModifiersChangedEvent, window: &mut Window, cx: &mut App) {
+    let this = cx.global_mut::<Shortcuts>();
+    this.press += 1;
+    let press = this.press;
+    if event.modifiers != Modifiers::secondary_key() {
+        if this.held {
+            hide(cx);
+        }
+        return;
+    }
+
+    let window = window.window_handle().window_id();
+    cx.spawn(async move |cx| {
+        Timer::after(HOLD_DURATION).await;
+        _ = cx.update(|cx| {
+            if cx.global::<Shortcuts>().press == press {
+                show(window, true, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// A key pressed with the modifier is a shortcut rather than a peek, and escape hides the
+/// cheatsheet.
+pub(crate) fn key_down(event: &KeyDownEvent, window: &mut Window, cx: &mut App) {
+    cx.global_mut::<Shortcuts>().press += 1;
+    let showing = cx.global::<Shortcuts>().window == Some(window.window_handle().window_id());
+    if showing && event.keystroke.key == "escape" {
+        hide(cx);
+        cx.stop_propagation();
+    }
+}
+
+/// The cheatsheet over the content of `window`, while it's shown there.
+pub(crate) fn render_layer(window: &Window, cx: &App) -> Option<AnyElement> {
+    if cx.global::<Shortcuts>().window != Some(window.window_handle().window_id()) {
+        return None;
+    }
+    let modifier = if cfg!(target_os = "macos") {
+        "cmd"
+    } else {
+        "ctrl"
+    };
+
+    let layer = div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .flex()
+        .items_center()
+        .justify_center()
+        .bg(gpui::black().opacity(0.4))
+        .on_mouse_down(MouseButton::Left, |_, _, cx| hide(cx))
+        .child(
+            v_flex()
+                .id("shortcuts")
+                .max_w(px(960.))
+                .max_h(relative(0.85))
+                .p_4()
+                .gap_4()
+                .overflow_y_scroll()
+                .rounded(cx.theme().radius)
+                .border_1()
+                .border_color(cx.theme().border)
+                .bg(cx.theme().background)
+                .shadow_lg()
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .child(div().text_lg().font_semibold().child("Keyboard Shortcuts"))
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("Hold {} to peek, escape to close", modifier)),
+                        ),
+                )
+                .child(h_flex().flex_wrap().items_start().gap_6().children(
+                    groups(cx).into_iter().map(|(category, actions)| {
+                        v_flex()
+                            .w(px(280.))
+                            .gap_1()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(category),
+                            )
+                            .children(actions.into_iter().map(|(action, bindings)| {
+                                h_flex()
+                                    .gap_2()
+                                    .justify_between()
+                                    .text_sm()
+                                    .child(action)
+                                    .child(h_flex().gap_2().children(bindings.into_iter().map(
+                                        |keystrokes| {
+                                            h_flex()
+                                                .gap_0p5()
+                                                .children(keystrokes.into_iter().map(Kbd::new))
+                                        },
+                                    )))
+                            }))
+                    }),
+                )),
+        );
+    Some(layer.into_any_element())
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Shortcuts::default());
+    cx.on_action(|_: &ShowShortcuts, cx| {
+        let Some(window) = cx.active_window() else {
+            return;
+        };
+        if cx.global::<Shortcuts>().window == Some(window.window_id()) {
+            hide(cx);
+        } else {
+            show(window.window_id(), false, cx);
+        }
+    });
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new(
+            "cmd-/",
+            ShowShortcuts,
+            Some(ShortcutScope::Global.context()),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new(
+            "ctrl-/",
+            ShowShortcuts,
+            Some(ShortcutScope::Global.context()),
+        ),
+    ]);
+}
@@ -0,0 +1,190 @@
+use std::{sync::mpsc, time::Duration};
+
+use futures::{StreamExt as _, channel::mpsc::UnboundedSender};
+use gpui::App;
+
+use crate::persistence;
+
+/// How long the OS is held up for the configs to be written.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Why the OS is ending the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shutdown {
+    /// `SIGTERM`, `SIGINT` or `SIGHUP`, e.g. from a process manager or a closed terminal.
+    Signal,
+    /// The user logs out or the machine shuts down, the OS ends the process itself.
+    #[cfg_attr(not(any(target_os = "linux", target_os = "windows")), allow(dead_code))]
+    Session,
+}
+
+/// A shutdown, with a channel to report back once the configs were written.
+type Request = (Shutdown, mpsc::Sender<()>);
+
+/// Tell the main thread about `shutdown` and wait (a bit) for it to flush the configs.
+fn notify(shutdown: Shutdown, tx: &UnboundedSender<Request>) {
+    let (done_tx, done_rx) = mpsc::channel();
+    if tx.unbounded_send((shutdown, done_tx)).is_ok() {
+        _ = done_rx.recv_timeout(FLUSH_TIMEOUT);
+    }
+}
+
+#[cfg(unix)]
+mod signals {
+    use futures::channel::mpsc::UnboundedSender;
+    use signal_hook::{
+        consts::{SIGHUP, SIGINT, SIGTERM},
+        iterator::Signals,
+        low_level::emulate_default_handler,
+    };
+
+    use super::{FLUSH_TIMEOUT, Request, Shutdown, notify};
+
+    pub(super) fn watch(tx: UnboundedSender<Request>) {
+        let mut signals = match Signals::new([SIGTERM, SIGINT, SIGHUP]) {
+            Ok(signals) => signals,
+            Err(err) => {
+                tracing::warn!("Failed to listen for signals: {}", err);
+                return;
+            }
+        };
+        std::thread::spawn(move || {
+            for (ix, signal) in signals.forever().enumerate() {
+                // A second one means the user doesn't want to wait for the quit.
+                if ix > 0 {
+                    tracing::warn!("Received signal {} again, exiting now", signal);
+                    terminate(signal);
+                }
+                tracing::info!("Received signal {}, shutting down", signal);
+                let tx = tx.clone();
+                std::thread::spawn(move || {
+                    notify(Shutdown::Signal, &tx);
+                    // The configs are written or the main thread is stuck, give the quit as
+                    // long again before ending the app the way the signal would have.
+                    std::thread::sleep(FLUSH_TIMEOUT);
+                    terminate(signal);
+                });
+            }
+        });
+    }
+
+    fn terminate(signal: i32) -> ! {
+        if let Err(err) = emulate_default_handler(signal) {
+            tracing::error!(
+                "Failed to run the default action of signal {}: {}",
+                signal,
+                err
+            );
+        }
+        std::process::exit(128 + signal);
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod session {
+    use futures::channel::mpsc::UnboundedSender;
+
+    use super::{Request, Shutdown, notify};
+
+    /// logind announces a shutdown (or reboot) before it starts killing sessions.
+    pub(super) fn watch(tx: UnboundedSender<Request>) {
+        std::thread::spawn(move || {
+            let result = (|| {
+                let connection = zbus::blocking::Connection::system()?;
+                let proxy = zbus::blocking::Proxy::new(
+                    &connection,
+                    "org.freedesktop.login1",
+                    "/org/freedesktop/login1",
+                    "org.freedesktop.login1.Manager",
+                )?;
+                for message in proxy.receive_signal("PrepareForShutdown")? {
+                    // Sent with `false` when a shutdown was cancelled.
+                    if message.body().deserialize::<bool>().unwrap_or(false) {
+                        tracing::info!("The system is shutting down");
+                        notify(Shutdown::Session, &tx);
+                    }
+                }
+                Ok::<_, zbus::Error>(())
+            })();
+            if let Err(err) = result {
+                tracing::debug!("Failed to watch for system shutdown: {}", err);
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod session {
+    use std::sync::OnceLock;
+
+    use futures::channel::mpsc::UnboundedSender;
+    use windows::{
+        Win32::System::Console::{
+            CTRL_BREAK_EVENT, CTRL_C_EVENT, CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT,
+            CTRL_SHUTDOWN_EVENT, SetConsoleCtrlHandler,
+        },
+        core::BOOL,
+    };
+
+    use super::{Request, Shutdown, notify};
+
+    static TX: OnceLock<UnboundedSender<Request>> = OnceLock::new();
+
+    /// Runs on a thread of its own, the process is ended once it returns.
+    unsafe extern "system" fn handler(event: u32) -> BOOL {
+        let shutdown = match event {
+            CTRL_C_EVENT | CTRL_BREAK_EVENT | CTRL_CLOSE_EVENT => Shutdown::Signal,
+            CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => Shutdown::Session,
+            _ => return false.into(),
+        };
+        if let Some(tx) = TX.get() {
+            notify(shutdown, tx);
+        }
+        // Let the default handler end the process.
+        false.into()
+    }
+
+    /// Logoff and shutdown reach the console control handler of console builds (`cargo run`),
+    /// windows of GUI builds get `WM_ENDSESSION`, which GPUI turns into a quit.
+    pub(super) fn watch(tx: UnboundedSender<Request>) {
+        _ = TX.set(tx);
+        // SAFETY: `handler` is a plain function that lives as long as the process.
+        if let Err(err) = unsafe { SetConsoleCtrlHandler(Some(handler), true) } {
+            tracing::debug!("Failed to set the console control handler: {}", err);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod session {
+    use futures::channel::mpsc::UnboundedSender;
+
+    use super::Request;
+
+    /// macOS asks the app to terminate on logout and shutdown, which runs the quit hooks.
+    pub(super) fn watch(_: UnboundedSender<Request>) {}
+}
+
+/// Write the pending configs when the OS ends the app, which doesn't always go through
+/// `cx.quit()` and so skips the quit hooks.
+pub fn init(cx: &mut App) {
+    let (tx, mut rx) = futures::channel::mpsc::unbounded::<Request>();
+    #[cfg(unix)]
+    signals::watch(tx.clone());
+    session::watch(tx);
+
+    cx.spawn(async move |cx| {
+        while let Some((shutdown, done)) = rx.next().await {
+            cx.update(|cx| {
+                persistence::flush(cx);
+                _ = done.send(());
+                // On logout the OS ends the process either way, don't get in its way.
+                if shutdown == Shutdown::Signal {
+                    cx.quit();
+                }
+            })?;
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
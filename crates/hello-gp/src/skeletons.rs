@@ -0,0 +1,272 @@
+//! Placeholders for views that load their data in the background.
+//!
+//! A view keeps the data in a [`Loadable`] and, while it's loading, renders placeholders of
+//! roughly the shape of the content, so the layout doesn't jump once the data is there.
+
+use std::time::Duration;
+
+use gpui::{
+    Animation, AnimationExt as _, App, ElementId, Global, Hsla, IntoElement, ParentElement as _,
+    Refineable as _, RenderOnce, SharedString, StyleRefinement, Styled, Window, div,
+    linear_color_stop, linear_gradient, prelude::FluentBuilder as _, px, relative,
+};
+use gpui_component::{ActiveTheme as _, Colorize as _, h_flex, v_flex};
+use serde::Deserialize;
+
+use crate::{
+    appearance,
+    events::{self, ThemeChanged},
+    power,
+    themes::get_data_dir,
+};
+
+/// How long the shimmer takes to pass over a placeholder.
+const SHIMMER_DURATION: Duration = Duration::from_millis(1400);
+
+/// Data a view loads in the background.
+#[derive(Debug, Clone, Default)]
+pub enum Loadable<T> {
+    #[default]
+    Loading,
+    Loaded(T),
+    Failed(SharedString),
+}
+
+impl<T> Loadable<T> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    pub fn loaded(&self) -> Option<&T> {
+        match self {
+            Self::Loaded(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T, E: std::fmt::Display> From<Result<T, E>> for Loadable<T> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Self::Loaded(value),
+            Err(err) => Self::Failed(err.to_string().into()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemeFileSkeletons {
+    themes: Vec<ThemeSkeleton>,
+}
+
+#[derive(Deserialize)]
+struct ThemeSkeleton {
+    name: SharedString,
+    #[serde(default)]
+    skeleton: Option<SkeletonSpec>,
+}
+
+/// The colors of the placeholders, declared by a theme next to its colors:
+///
+/// ```json
+/// { "name": "Forest", "mode": "dark", "skeleton": { "base": "#1f2a24", "highlight": "#2c3b33" } }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+struct SkeletonSpec {
+    base: Option<String>,
+    highlight: Option<String>,
+}
+
+/// The placeholder colors of the active theme, its muted colors when it doesn't declare any.
+#[derive(Default)]
+pub struct SkeletonColors {
+    spec: SkeletonSpec,
+}
+
+impl Global for SkeletonColors {}
+
+impl SkeletonColors {
+    pub fn base(cx: &App) -> Hsla {
+        cx.try_global::<Self>()
+            .and_then(|this| Hsla::parse_hex(this.spec.base.as_deref()?).ok())
+            .unwrap_or(cx.theme().skeleton)
+    }
+
+    /// The color of the shimmer passing over the placeholders.
+    pub fn highlight(cx: &App) -> Hsla {
+        cx.try_global::<Self>()
+            .and_then(|this| Hsla::parse_hex(this.spec.highlight.as_deref()?).ok())
+            .unwrap_or_else(|| {
+                if cx.theme().mode.is_dark() {
+                    Self::base(cx).lighten(0.3)
+                } else {
+                    Self::base(cx).darken(0.1)
+                }
+            })
+    }
+
+    fn find(theme: &SharedString) -> SkeletonSpec {
+        let Ok(entries) = std::fs::read_dir(get_data_dir().join("themes")) else {
+            return SkeletonSpec::default();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .find_map(|path| {
+                let json = std::fs::read_to_string(path).ok()?;
+                let file = serde_json::from_str::<ThemeFileSkeletons>(&json).ok()?;
+                file.themes
+                    .into_iter()
+                    .find(|entry| &entry.name == theme)?
+                    .skeleton
+            })
+            .unwrap_or_default()
+    }
+
+    fn update_theme(theme: &SharedString, cx: &mut App) {
+        let spec = Self::find(theme);
+        if spec != cx.global::<Self>().spec {
+            cx.global_mut::<Self>().spec = spec;
+            cx.refresh_windows();
+        }
+    }
+}
+
+/// A block in the place of content that's still loading, with a shimmer passing over it.
+///
+/// The shimmer stands still when motion is reduced, or while animations are paused.
+#[derive(IntoElement)]
+pub struct Shimmer {
+    id: ElementId,
+    style: StyleRefinement,
+}
+
+impl Shimmer {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            style: StyleRefinement::default(),
+        }
+    }
+}
+
+impl Styled for Shimmer {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Shimmer {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let animated = !appearance::reduce_motion(cx) && power::animations_enabled(window, cx);
+        let (base, highlight) = (SkeletonColors::base(cx), SkeletonColors::highlight(cx));
+
+        let mut block = div()
+            .relative()
+            .overflow_hidden()
+            .rounded(cx.theme().radius)
+            .bg(base);
+        block.style().refine(&self.style);
+        block.when(animated, |this| {
+            this.child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .bottom_0()
+                    .w(relative(0.5))
+                    .bg(linear_gradient(
+                        90.,
+                        linear_color_stop(base, 0.),
+                        linear_color_stop(highlight, 1.),
+                    ))
+                    .with_animation(
+                        self.id,
+                        Animation::new(SHIMMER_DURATION).repeat(),
+                        |this, delta| this.left(relative(delta * 1.5 - 0.5)),
+                    ),
+            )
+        })
+    }
+}
+
+/// Lines of text still loading, the last one shorter like the end of a paragraph.
+#[derive(IntoElement)]
+pub struct SkeletonLines {
+    id: SharedString,
+    lines: usize,
+}
+
+impl SkeletonLines {
+    pub fn new(id: impl Into<SharedString>, lines: usize) -> Self {
+        Self {
+            id: id.into(),
+            lines,
+        }
+    }
+}
+
+impl RenderOnce for SkeletonLines {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        let lines = self.lines;
+        v_flex().gap_2().children((0..lines).map(|ix| {
+            let width = if ix + 1 == lines && lines > 1 {
+                0.6
+            } else {
+                1.
+            };
+            Shimmer::new(ElementId::NamedInteger(self.id.clone(), ix as u64))
+                .h(px(12.))
+                .w(relative(width))
+        }))
+    }
+}
+
+/// Rows of a list still loading, a title and a detail each.
+#[derive(IntoElement)]
+pub struct SkeletonRows {
+    id: SharedString,
+    rows: usize,
+}
+
+impl SkeletonRows {
+    pub fn new(id: impl Into<SharedString>, rows: usize) -> Self {
+        Self {
+            id: id.into(),
+            rows,
+        }
+    }
+}
+
+impl RenderOnce for SkeletonRows {
+    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+        v_flex().gap_3().children((0..self.rows).map(|ix| {
+            // Vary the widths a little, so it reads as a list rather than a grid.
+            let width = [0.5, 0.35, 0.45, 0.3][ix % 4];
+            h_flex()
+                .gap_2()
+                .px_2()
+                .child(
+                    Shimmer::new(ElementId::NamedInteger(self.id.clone(), ix as u64 * 2))
+                        .h(px(14.))
+                        .w(relative(width)),
+                )
+                .child(div().flex_1())
+                .child(
+                    Shimmer::new(ElementId::NamedInteger(self.id.clone(), ix as u64 * 2 + 1))
+                        .h(px(12.))
+                        .w(px(72.)),
+                )
+        }))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(SkeletonColors::default());
+    SkeletonColors::update_theme(&cx.theme().theme_name().clone(), cx);
+
+    events::subscribe::<ThemeChanged>(cx, |event, cx| {
+        SkeletonColors::update_theme(&event.name, cx)
+    })
+    .detach();
+}
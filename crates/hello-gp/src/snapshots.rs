@@ -0,0 +1,213 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone as _};
+use gpui::{App, ParentElement as _, Styled as _, actions, div};
+use gpui_component::{
+    ActiveTheme as _, ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    notification::NotificationType,
+    v_flex,
+};
+
+use crate::{
+    config, format,
+    notifications::Toast,
+    settings::{self, SETTINGS_NAME},
+    themes::{self, STATE_NAME, get_config_dir, get_data_dir},
+};
+
+actions!(snapshots, [RestoreConfiguration]);
+
+const MAX_SNAPSHOTS: usize = 10;
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A copy of the config files taken at `created`, stored in `get_data_dir()/snapshots/<timestamp>`.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub created: DateTime<Local>,
+    pub path: PathBuf,
+}
+
+/// Every file the snapshotted configs may use, in any of the supported formats, by file name.
+///
+/// They're the files the user's changes are written to, the overlay ones when the config dir
+/// is read-only.
+fn user_files() -> Vec<(String, PathBuf)> {
+    let config_dir = get_config_dir();
+    [STATE_NAME, SETTINGS_NAME]
+        .into_iter()
+        .flat_map(|name| {
+            let user_path = config::user_path(&config_dir, name);
+            let dir = user_path.parent().unwrap_or(&config_dir).to_path_buf();
+            config::file_names(name).map(move |file| {
+                let path = dir.join(&file);
+                (file, path)
+            })
+        })
+        .collect()
+}
+
+fn dir() -> PathBuf {
+    get_data_dir().join("snapshots")
+}
+
+/// All snapshots, newest first.
+pub fn list() -> Vec<Snapshot> {
+    let Ok(entries) = std::fs::read_dir(dir()) else {
+        return vec![];
+    };
+
+    let mut snapshots = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let created = NaiveDateTime::parse_from_str(name.to_str()?, TIMESTAMP_FORMAT).ok()?;
+            Some(Snapshot {
+                created: Local.from_local_datetime(&created).single()?,
+                path: entry.path(),
+            })
+        })
+        .collect::<Vec<_>>();
+    snapshots.sort_by(|a, b| b.created.cmp(&a.created));
+    snapshots
+}
+
+fn same_files(snapshot: &Path, files: &[(String, PathBuf)]) -> bool {
+    files
+        .iter()
+        .all(|(file, path)| std::fs::read(snapshot.join(file)).ok() == std::fs::read(path).ok())
+}
+
+/// Copy the current config files into a new snapshot, unless they match the latest one.
+pub fn take() -> anyhow::Result<()> {
+    let files = user_files();
+    if !files.iter().any(|(_, path)| path.exists()) {
+        return Ok(());
+    }
+
+    let snapshots = list();
+    if let Some(latest) = snapshots.first()
+        && same_files(&latest.path, &files)
+    {
+        return Ok(());
+    }
+
+    let path = dir().join(Local::now().format(TIMESTAMP_FORMAT).to_string());
+    std::fs::create_dir_all(&path)?;
+    for (file, source) in &files {
+        if source.exists() {
+            std::fs::copy(source, path.join(file))?;
+        }
+    }
+
+    // `list` was taken before the new snapshot, so keep one less.
+    for old in snapshots.iter().skip(MAX_SNAPSHOTS - 1) {
+        _ = std::fs::remove_dir_all(&old.path);
+    }
+    Ok(())
+}
+
+/// Replace the config files with the ones from `snapshot` and apply them.
+///
+/// The current files are snapshotted first, so a restore can be undone.
+pub fn restore(snapshot: &Snapshot, cx: &mut App) -> anyhow::Result<()> {
+    take()?;
+
+    for (file, target) in user_files() {
+        let source = snapshot.path.join(&file);
+        if source.exists() {
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::copy(source, target)?;
+        } else if target.exists() {
+            std::fs::remove_file(target)?;
+        }
+    }
+
+    settings::reload(cx);
+    themes::reload_state(cx);
+    Ok(())
+}
+
+fn open_restore_dialog(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    _ = window.update(cx, |_, window, cx| {
+        window.open_modal(cx, |modal, _, cx| {
+            let snapshots = list();
+            let muted = cx.theme().muted_foreground;
+
+            let content = if snapshots.is_empty() {
+                v_flex().child(
+                    div()
+                        .text_color(muted)
+                        .child("No previous configurations saved yet."),
+                )
+            } else {
+                v_flex()
+                    .gap_2()
+                    .children(snapshots.into_iter().enumerate().map(|(ix, snapshot)| {
+                        let created = format::date_time(&snapshot.created);
+                        h_flex().justify_between().child(created).child(
+                            Button::new(("restore-snapshot", ix))
+                                .small()
+                                .outline()
+                                .label("Restore")
+                                .on_click(move |_, window, cx| {
+                                    let toast = match restore(&snapshot, cx) {
+                                        // `restore` saved the replaced configuration first.
+                                        Ok(()) => match list().into_iter().next() {
+                                            Some(previous) => Toast::new(
+                                                NotificationType::Success,
+                                                "Restored the previous configuration.",
+                                            )
+                                            .action("Undo", move |_, cx| {
+                                                if let Err(err) = restore(&previous, cx) {
+                                                    tracing::error!(
+                                                        "Failed to undo the restore: {}",
+                                                        err
+                                                    );
+                                                }
+                                            }),
+                                            None => Toast::new(
+                                                NotificationType::Success,
+                                                "Restored the previous configuration.",
+                                            ),
+                                        },
+                                        Err(err) => Toast::new(
+                                            NotificationType::Error,
+                                            format!("Failed to restore configuration: {}", err),
+                                        ),
+                                    };
+                                    window.close_modal(cx);
+                                    toast.show(window, cx);
+                                }),
+                        )
+                    }))
+            };
+
+            modal
+                .title("Restore Previous Configuration")
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(muted)
+                        .mb_2()
+                        .child("A snapshot of the settings and state is saved on every start."),
+                )
+                .child(content)
+        });
+    });
+}
+
+pub fn init(cx: &mut App) {
+    if let Err(err) = take() {
+        tracing::error!("Failed to take config snapshot: {}", err);
+    }
+
+    cx.on_action(|_: &RestoreConfiguration, cx| open_restore_dialog(cx));
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use gpui::{Action, App, Global, SharedString, actions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::Settings, themes::get_data_dir};
+
+actions!(sounds, [ToggleMute]);
+
+/// Select the sound pack by id, `None` for the first one.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = sounds, no_json)]
+pub struct SelectSoundPack(pub Option<SharedString>);
+
+/// The file types a sound can be.
+const EXTENSIONS: [&str; 2] = ["ogg", "wav"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SoundSettings {
+    /// Play sounds on app events, off by default.
+    pub enabled: bool,
+    /// Silence the sounds for now, without losing the other settings.
+    pub muted: bool,
+    /// From 0 to 1.
+    pub volume: f32,
+    /// The id of the sound pack, the first one when unset.
+    pub pack: Option<String>,
+    /// A chime for new notifications.
+    pub notification: bool,
+    /// A sound for errors.
+    pub error: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            muted: false,
+            volume: 0.75,
+            pack: None,
+            notification: true,
+            error: true,
+        }
+    }
+}
+
+impl SoundSettings {
+    fn plays(&self, event: SoundEvent) -> bool {
+        self.enabled
+            && !self.muted
+            && match event {
+                SoundEvent::Notification => self.notification,
+                SoundEvent::Error => self.error,
+            }
+    }
+}
+
+/// What a sound is played for, and the file name of its sound in a pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SoundEvent {
+    Notification,
+    Error,
+}
+
+impl SoundEvent {
+    const ALL: [Self; 2] = [Self::Notification, Self::Error];
+
+    fn file_stem(self) -> &'static str {
+        match self {
+            Self::Notification => "notification",
+            Self::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SoundPackManifest {
+    name: Option<String>,
+}
+
+/// A directory of `<event>.ogg` or `<event>.wav` files under `get_data_dir()/sounds/<id>`,
+/// e.g. `notification.ogg`, with an optional `pack.json` to give it a display name.
+#[derive(Debug, Clone)]
+pub struct SoundPack {
+    pub id: SharedString,
+    pub name: SharedString,
+    sounds: BTreeMap<SoundEvent, PathBuf>,
+}
+
+/// Plays the sounds of the active sound pack on app events.
+///
+/// The audio device is only opened with the first sound, on a thread of its own.
+#[derive(Default)]
+pub struct Sounds {
+    packs: Vec<SoundPack>,
+    player: Option<mpsc::Sender<(PathBuf, f32)>>,
+}
+
+impl Global for Sounds {}
+
+impl Sounds {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn dir() -> PathBuf {
+        get_data_dir().join("sounds")
+    }
+
+    pub fn packs(&self) -> &[SoundPack] {
+        &self.packs
+    }
+
+    fn load_packs() -> Vec<SoundPack> {
+        let Ok(entries) = std::fs::read_dir(Self::dir()) else {
+            return vec![];
+        };
+
+        let mut packs = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let id = entry.file_name().to_str()?.to_string();
+                let manifest = std::fs::read_to_string(path.join("pack.json"))
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<SoundPackManifest>(&json).ok())
+                    .unwrap_or_default();
+                let sounds = SoundEvent::ALL
+                    .into_iter()
+                    .filter_map(|event| {
+                        let file = EXTENSIONS
+                            .iter()
+                            .map(|ext| path.join(format!("{}.{}", event.file_stem(), ext)))
+                            .find(|file| file.is_file())?;
+                        Some((event, file))
+                    })
+                    .collect();
+
+                Some(SoundPack {
+                    name: manifest.name.unwrap_or_else(|| id.clone()).into(),
+                    id: id.into(),
+                    sounds,
+                })
+            })
+            .collect::<Vec<_>>();
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        packs
+    }
+
+    fn active_pack<'a>(&'a self, cx: &App) -> Option<&'a SoundPack> {
+        match Settings::global(cx).sounds.pack.as_deref() {
+            Some(id) => self.packs.iter().find(|pack| pack.id == id),
+            None => self.packs.first(),
+        }
+    }
+
+    /// Play the sound for `event`, when sounds are on and the active pack has one.
+    pub fn play(event: SoundEvent, cx: &mut App) {
+        let settings = &Settings::global(cx).sounds;
+        if !settings.plays(event) {
+            return;
+        }
+        let volume = settings.volume.clamp(0., 1.);
+        let Some(path) = Self::global(cx)
+            .active_pack(cx)
+            .and_then(|pack| pack.sounds.get(&event))
+            .cloned()
+        else {
+            return;
+        };
+
+        let this = cx.global_mut::<Self>();
+        let player = this.player.get_or_insert_with(spawn_player);
+        if player.send((path, volume)).is_err() {
+            // The player thread is gone, e.g. without an audio device, start over next time.
+            this.player = None;
+        }
+    }
+}
+
+/// A thread holding the audio output, which plays the sounds sent to it.
+fn spawn_player() -> mpsc::Sender<(PathBuf, f32)> {
+    let (tx, rx) = mpsc::channel::<(PathBuf, f32)>();
+    let spawned = std::thread::Builder::new()
+        .name("sounds".into())
+        .spawn(move || {
+            let (_stream, handle) = match rodio::OutputStream::try_default() {
+                Ok(output) => output,
+                Err(err) => {
+                    tracing::warn!("No audio output for sounds: {}", err);
+                    return;
+                }
+            };
+            for (path, volume) in rx {
+                if let Err(err) = play_file(&handle, &path, volume) {
+                    tracing::warn!("Failed to play {}: {}", path.display(), err);
+                }
+            }
+        });
+    if let Err(err) = spawned {
+        tracing::error!("Failed to start the sound player: {}", err);
+    }
+    tx
+}
+
+fn play_file(handle: &rodio::OutputStreamHandle, path: &Path, volume: f32) -> anyhow::Result<()> {
+    let sink = rodio::Sink::try_new(handle)?;
+    sink.set_volume(volume);
+    sink.append(rodio::Decoder::new(BufReader::new(File::open(path)?))?);
+    sink.detach();
+    Ok(())
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Sounds {
+        packs: Sounds::load_packs(),
+        player: None,
+    });
+
+    cx.on_action(|select: &SelectSoundPack, cx| {
+        let pack = select.0.as_ref().map(|id| id.to_string());
+        Settings::update(cx, |settings, _| settings.sounds.pack = pack);
+        // A sample of the pack just picked.
+        Sounds::play(SoundEvent::Notification, cx);
+    });
+    cx.on_action(|_: &ToggleMute, cx| {
+        Settings::update(cx, |settings, _| {
+            settings.sounds.muted = !settings.sounds.muted
+        });
+    });
+}
@@ -0,0 +1,221 @@
+use std::{collections::HashSet, ops::Range, path::PathBuf, sync::Arc};
+
+use gpui::{Action, App, Global, Hsla, SharedString};
+use gpui_component::ActiveTheme as _;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spellbook::Dictionary;
+
+use crate::{settings::Settings, themes::get_data_dir};
+
+/// The words kept from the suggestion menu, one per line.
+const PERSONAL_FILE: &str = "personal.dic";
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SpellCheckSettings {
+    /// Underline misspelled words in the text fields that opt into spell-checking.
+    pub enabled: bool,
+    /// The dictionary to check with, `<language>.aff` and `<language>.dic` in the
+    /// `dictionaries` data dir, e.g. `en_US`.
+    pub language: String,
+}
+
+impl Default for SpellCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            language: "en_US".to_string(),
+        }
+    }
+}
+
+/// Replace the misspelled word at `start..end` of the focused text field.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = spell_check, no_json)]
+pub struct ReplaceWord {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: SharedString,
+}
+
+/// Stop flagging a word, in every text field.
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = spell_check, no_json)]
+pub struct AddToDictionary(pub SharedString);
+
+/// Checks words with the hunspell dictionary of [`SpellCheckSettings::language`].
+///
+/// Dictionaries aren't bundled, put e.g. `en_US.aff` and `en_US.dic` from LibreOffice or
+/// the distribution's `hunspell-en-us` package into [`SpellCheck::dir`].
+#[derive(Default)]
+pub struct SpellCheck {
+    /// The language of `dictionary`, or of the one being loaded.
+    language: Option<String>,
+    dictionary: Option<Arc<Dictionary>>,
+    personal: HashSet<String>,
+}
+
+impl Global for SpellCheck {}
+
+impl SpellCheck {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn dir() -> PathBuf {
+        get_data_dir().join("dictionaries")
+    }
+
+    /// Whether spell-checking is on and its dictionary is loaded.
+    pub fn is_active(cx: &App) -> bool {
+        Settings::global(cx).spell_check.enabled && Self::global(cx).dictionary.is_some()
+    }
+
+    /// The color of the squiggles under misspelled words.
+    pub fn squiggle_color(cx: &App) -> Hsla {
+        Settings::global(cx)
+            .appearance
+            .squiggle_color
+            .as_deref()
+            .and_then(|hex| Hsla::parse_hex(hex).ok())
+            .unwrap_or(cx.theme().danger)
+    }
+
+    fn check(&self, word: &str) -> bool {
+        let Some(dictionary) = &self.dictionary else {
+            return true;
+        };
+        self.personal.contains(word) || dictionary.check(word)
+    }
+
+    /// The byte ranges of the misspelled words in `text`, none while inactive.
+    pub fn misspelled(text: &str, cx: &App) -> Vec<Range<usize>> {
+        if !Self::is_active(cx) {
+            return vec![];
+        }
+        let this = Self::global(cx);
+        words(text)
+            .into_iter()
+            .filter(|range| !this.check(&text[range.clone()]))
+            .collect()
+    }
+
+    /// Corrections for a misspelled `word`, the most likely first.
+    pub fn suggest(word: &str, cx: &App) -> Vec<String> {
+        let Some(dictionary) = &Self::global(cx).dictionary else {
+            return vec![];
+        };
+        let mut suggestions = vec![];
+        dictionary.suggest(word, &mut suggestions);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    fn add_word(word: &str, cx: &mut App) {
+        let mut words = read_personal();
+        words.insert(word.to_string());
+        let mut lines = words.iter().cloned().collect::<Vec<_>>();
+        lines.sort();
+        if let Err(err) = std::fs::create_dir_all(Self::dir())
+            .and_then(|_| std::fs::write(Self::dir().join(PERSONAL_FILE), lines.join("\n")))
+        {
+            tracing::error!("Failed to save the personal dictionary: {}", err);
+        }
+        cx.global_mut::<Self>().personal = words;
+        cx.refresh_windows();
+    }
+
+    /// Load the dictionary of the settings in the background, or drop it when turned off.
+    fn reload(cx: &mut App) {
+        let settings = Settings::global(cx).spell_check.clone();
+        if !settings.enabled {
+            if cx.global::<Self>().language.is_some() {
+                cx.set_global(Self::default());
+                cx.refresh_windows();
+            }
+            return;
+        }
+        if cx.global::<Self>().language.as_ref() == Some(&settings.language) {
+            return;
+        }
+
+        let language = settings.language;
+        cx.global_mut::<Self>().language = Some(language.clone());
+        let load = cx.background_spawn({
+            let language = language.clone();
+            async move { load_dictionary(&language) }
+        });
+        cx.spawn(async move |cx| {
+            let dictionary = load.await;
+            _ = cx.update(|cx| {
+                // The language changed again while loading.
+                if cx.global::<Self>().language.as_ref() != Some(&language) {
+                    return;
+                }
+                match dictionary {
+                    Ok(dictionary) => {
+                        tracing::info!("Loaded the {} dictionary", language);
+                        cx.set_global(Self {
+                            language: Some(language),
+                            dictionary: Some(Arc::new(dictionary)),
+                            personal: read_personal(),
+                        });
+                    }
+                    Err(err) => {
+                        tracing::warn!("No spell-checking, failed to load {}: {}", language, err)
+                    }
+                }
+                cx.refresh_windows();
+            });
+        })
+        .detach();
+    }
+}
+
+fn load_dictionary(language: &str) -> anyhow::Result<Dictionary> {
+    let dir = SpellCheck::dir();
+    let aff = std::fs::read_to_string(dir.join(format!("{}.aff", language)))?;
+    let dic = std::fs::read_to_string(dir.join(format!("{}.dic", language)))?;
+    Dictionary::new(&aff, &dic).map_err(|err| anyhow::anyhow!("{}", err))
+}
+
+fn read_personal() -> HashSet<String> {
+    std::fs::read_to_string(SpellCheck::dir().join(PERSONAL_FILE))
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The byte ranges of the words in `text`, skipping the ones with digits like `v2`.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut has_digit = false;
+    for (ix, ch) in text.char_indices().chain([(text.len(), ' ')]) {
+        if ch.is_alphanumeric() || (ch == '\'' && start.is_some()) {
+            start.get_or_insert(ix);
+            has_digit |= ch.is_numeric();
+            continue;
+        }
+        if let Some(start) = start.take() {
+            let word = text[start..ix].trim_end_matches('\'');
+            if !has_digit && word.chars().count() > 1 {
+                words.push(start..start + word.len());
+            }
+        }
+        has_digit = false;
+    }
+    words
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(SpellCheck::default());
+    SpellCheck::reload(cx);
+    cx.observe_global::<Settings>(SpellCheck::reload).detach();
+    cx.on_action(|action: &AddToDictionary, cx| SpellCheck::add_word(&action.0, cx));
+}
@@ -0,0 +1,781 @@
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, KeyBinding, KeyContext, ParentElement as _, Pixels,
+    Render, SharedString, Size, StatefulInteractiveElement as _, Styled as _, Subscription, Window,
+    actions, canvas, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    resizable::{ResizablePanelEvent, ResizableState, h_resizable, resizable_panel, v_resizable},
+    v_flex,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    StoryContainer, drag_drop::DragDropExt as _, input_settings::InputSettings, popout::Popouts,
+    scopes::ShortcutScope, session::Session,
+};
+
+actions!(
+    split,
+    [
+        SplitRight,
+        SplitDown,
+        CloseSplit,
+        FocusNextSplit,
+        FocusPreviousSplit,
+        GoBack,
+        GoForward
+    ]
+);
+
+const CONTEXT: &str = "SplitView";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitAxis {
+    /// Side by side.
+    Horizontal,
+    /// Stacked.
+    Vertical,
+}
+
+/// The split arrangement of the center area, as stored in the session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SplitLayout {
+    Pane {
+        /// The name of the story shown in the pane.
+        story: Option<String>,
+    },
+    Split {
+        axis: SplitAxis,
+        children: Vec<SplitLayout>,
+        /// The share of each child, summing up to 1.
+        ratios: Vec<f32>,
+    },
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self::Pane { story: None }
+    }
+}
+
+/// A story dragged by its title, shown in the pane it's dropped on.
+#[derive(Clone)]
+pub struct DraggedStory(pub Entity<StoryContainer>);
+
+struct Pane {
+    id: usize,
+    story: Option<Entity<StoryContainer>>,
+    /// The stories shown in the pane before, the last one most recent.
+    back: Vec<Entity<StoryContainer>>,
+    /// The stories gone back from, the last one most recent.
+    forward: Vec<Entity<StoryContainer>>,
+    focus_handle: FocusHandle,
+}
+
+struct Split {
+    id: usize,
+    axis: SplitAxis,
+    children: Vec<SplitNode>,
+    /// Used for the initial sizes, until the resizable state has measured the panels.
+    ratios: Vec<f32>,
+    state: Entity<ResizableState>,
+    _subscription: Subscription,
+}
+
+enum SplitNode {
+    Pane(Pane),
+    Split(Split),
+}
+
+impl SplitNode {
+    fn panes<'a>(&'a self, panes: &mut Vec<&'a Pane>) {
+        match self {
+            Self::Pane(pane) => panes.push(pane),
+            Self::Split(split) => split.children.iter().for_each(|child| child.panes(panes)),
+        }
+    }
+
+    fn pane_mut(&mut self, id: usize) -> Option<&mut Pane> {
+        match self {
+            Self::Pane(pane) => (pane.id == id).then_some(pane),
+            Self::Split(split) => split
+                .children
+                .iter_mut()
+                .find_map(|child| child.pane_mut(id)),
+        }
+    }
+
+    fn is_pane(&self, id: usize) -> bool {
+        matches!(self, Self::Pane(pane) if pane.id == id)
+    }
+
+    /// Put `new` next to the pane `target`, wrapping the pane into `split` unless its parent
+    /// already splits along the same axis. Gives `new` and `split` back if `target` isn't here.
+    fn insert(&mut self, target: usize, new: Pane, split: Split) -> Result<(), (Pane, Split)> {
+        if self.is_pane(target) {
+            let pane = std::mem::replace(self, Self::Split(split));
+            if let Self::Split(split) = self {
+                split.children = vec![pane, Self::Pane(new)];
+                split.ratios = vec![0.5, 0.5];
+            }
+            return Ok(());
+        }
+
+        let Self::Split(parent) = self else {
+            return Err((new, split));
+        };
+        if let Some(ix) = parent
+            .children
+            .iter()
+            .position(|child| child.is_pane(target))
+            && parent.axis == split.axis
+        {
+            parent.children.insert(ix + 1, Self::Pane(new));
+            parent.ratios = vec![1. / parent.children.len() as f32; parent.children.len()];
+            return Ok(());
+        }
+
+        let (mut new, mut split) = (new, split);
+        for child in &mut parent.children {
+            match child.insert(target, new, split) {
+                Ok(()) => return Ok(()),
+                Err(back) => (new, split) = back,
+            }
+        }
+        Err((new, split))
+    }
+
+    /// Remove the pane `target`, collapsing splits that are left with a single child.
+    fn remove(&mut self, target: usize) -> bool {
+        let Self::Split(split) = self else {
+            return false;
+        };
+
+        let removed = match split
+            .children
+            .iter()
+            .position(|child| child.is_pane(target))
+        {
+            Some(ix) => {
+                split.children.remove(ix);
+                split.ratios = vec![1. / split.children.len() as f32; split.children.len()];
+                true
+            }
+            None => split.children.iter_mut().any(|child| child.remove(target)),
+        };
+        if split.children.len() == 1
+            && let Some(only) = split.children.pop()
+        {
+            *self = only;
+        }
+        removed
+    }
+
+    fn layout(&self, cx: &App) -> SplitLayout {
+        match self {
+            Self::Pane(pane) => SplitLayout::Pane {
+                story: pane
+                    .story
+                    .as_ref()
+                    .map(|story| story.read(cx).name.to_string()),
+            },
+            Self::Split(split) => {
+                let sizes = split.state.read(cx).sizes();
+                let total = sizes.iter().map(|size| size.as_f32()).sum::<f32>();
+                let ratios = if sizes.len() == split.children.len() && total > 0. {
+                    sizes.iter().map(|size| size.as_f32() / total).collect()
+                } else {
+                    split.ratios.clone()
+                };
+                SplitLayout::Split {
+                    axis: split.axis,
+                    children: split
+                        .children
+                        .iter()
+                        .map(|child| child.layout(cx))
+                        .collect(),
+                    ratios,
+                }
+            }
+        }
+    }
+}
+
+/// The center area of the gallery, split into panes that each show a story.
+///
+/// The arrangement and the pane sizes are kept in the [`Session`].
+pub struct SplitView {
+    root: SplitNode,
+    focused: usize,
+    next_id: usize,
+    focus_handle: FocusHandle,
+    /// The size of the view, measured once it's laid out, to size the panels from the ratios.
+    size: Option<Size<Pixels>>,
+}
+
+impl SplitView {
+    /// Restore `layout`, looking up the stories by name in `stories`.
+    pub fn new(
+        layout: &SplitLayout,
+        stories: &[Entity<StoryContainer>],
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            root: SplitNode::Pane(Pane {
+                id: 0,
+                story: None,
+                back: vec![],
+                forward: vec![],
+                focus_handle: cx.focus_handle(),
+            }),
+            focused: 0,
+            next_id: 1,
+            focus_handle: cx.focus_handle(),
+            size: None,
+        };
+        this.root = this.build(layout, stories, cx);
+        if let Some(id) = this.panes().first().map(|pane| pane.id) {
+            this.focused = id;
+        }
+        this
+    }
+
+    pub fn view(
+        stories: &[Entity<StoryContainer>],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let session = Session::global(cx);
+        let layout = session.split.clone().unwrap_or_default();
+        let focused_pane = session.focused_pane;
+        let view = cx.new(|cx| {
+            let mut this = Self::new(&layout, stories, cx);
+            if let Some(id) = focused_pane.and_then(|ix| Some(this.panes().get(ix)?.id)) {
+                this.focused = id;
+            }
+            this
+        });
+
+        // The keyboard continues in the pane it was in.
+        let this = view.read(cx);
+        if let Some(pane) = this
+            .panes()
+            .into_iter()
+            .find(|pane| pane.id == this.focused)
+        {
+            window.focus(&pane.focus_handle);
+        }
+        view
+    }
+
+    fn build(
+        &mut self,
+        layout: &SplitLayout,
+        stories: &[Entity<StoryContainer>],
+        cx: &mut Context<Self>,
+    ) -> SplitNode {
+        match layout {
+            SplitLayout::Pane { story } => {
+                let story = story.as_ref().and_then(|name| {
+                    stories
+                        .iter()
+                        .find(|story| story.read(cx).name.as_ref() == name)
+                        .cloned()
+                });
+                SplitNode::Pane(self.new_pane(story, cx))
+            }
+            SplitLayout::Split {
+                axis,
+                children,
+                ratios,
+            } => {
+                let mut split = self.new_split(*axis, cx);
+                split.children = children
+                    .iter()
+                    .map(|child| self.build(child, stories, cx))
+                    .collect();
+                split.ratios = ratios.clone();
+                SplitNode::Split(split)
+            }
+        }
+    }
+
+    fn new_pane(&mut self, story: Option<Entity<StoryContainer>>, cx: &mut App) -> Pane {
+        self.next_id += 1;
+        Pane {
+            id: self.next_id,
+            story,
+            back: vec![],
+            forward: vec![],
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn new_split(&mut self, axis: SplitAxis, cx: &mut Context<Self>) -> Split {
+        self.next_id += 1;
+        let state = ResizableState::new(cx);
+        let _subscription = cx.subscribe(&state, |this, _, _: &ResizablePanelEvent, cx| {
+            this.save(cx);
+        });
+        Split {
+            id: self.next_id,
+            axis,
+            children: vec![],
+            ratios: vec![],
+            state,
+            _subscription,
+        }
+    }
+
+    fn panes(&self) -> Vec<&Pane> {
+        let mut panes = vec![];
+        self.root.panes(&mut panes);
+        panes
+    }
+
+    pub fn layout(&self, cx: &App) -> SplitLayout {
+        self.root.layout(cx)
+    }
+
+    /// Replace the arrangement, e.g. from a layout preset.
+    pub fn set_layout(
+        &mut self,
+        layout: &SplitLayout,
+        stories: &[Entity<StoryContainer>],
+        cx: &mut Context<Self>,
+    ) {
+        self.root = self.build(layout, stories, cx);
+        if let Some(id) = self.panes().first().map(|pane| pane.id) {
+            self.focused = id;
+        }
+        self.save(cx);
+        cx.notify();
+    }
+
+    /// The story in the focused pane.
+    pub fn active_story(&self) -> Option<&Entity<StoryContainer>> {
+        self.panes()
+            .into_iter()
+            .find(|pane| pane.id == self.focused)?
+            .story
+            .as_ref()
+    }
+
+    /// Show `story` in the focused pane, after the one it showed in its history.
+    pub fn set_story(&mut self, story: Entity<StoryContainer>, cx: &mut Context<Self>) {
+        if let Some(pane) = self.root.pane_mut(self.focused) {
+            if pane.story.as_ref() == Some(&story) {
+                return;
+            }
+            pane.back.extend(pane.story.replace(story));
+            pane.forward.clear();
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    /// Show `story` in the pane `id` it was dropped on, and focus that pane.
+    fn drop_story(
+        &mut self,
+        id: usize,
+        story: Entity<StoryContainer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(focus_handle) = self.root.pane_mut(id).map(|pane| pane.focus_handle.clone())
+        else {
+            return;
+        };
+        self.focused = id;
+        window.focus(&focus_handle);
+        self.set_story(story, cx);
+        // The focus moved, even when the pane already showed the story.
+        self.save(cx);
+        cx.notify();
+    }
+
+    fn go_back(&mut self, _: &GoBack, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(pane) = self.root.pane_mut(self.focused)
+            && let Some(story) = pane.back.pop()
+        {
+            pane.forward.extend(pane.story.replace(story));
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    fn go_forward(&mut self, _: &GoForward, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(pane) = self.root.pane_mut(self.focused)
+            && let Some(story) = pane.forward.pop()
+        {
+            pane.back.extend(pane.story.replace(story));
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    fn split(&mut self, axis: SplitAxis, window: &mut Window, cx: &mut Context<Self>) {
+        let pane = self.new_pane(None, cx);
+        let (id, focus_handle) = (pane.id, pane.focus_handle.clone());
+        let split = self.new_split(axis, cx);
+        if self.root.insert(self.focused, pane, split).is_ok() {
+            self.focused = id;
+            window.focus(&focus_handle);
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    fn split_right(&mut self, _: &SplitRight, window: &mut Window, cx: &mut Context<Self>) {
+        self.split(SplitAxis::Horizontal, window, cx);
+    }
+
+    fn split_down(&mut self, _: &SplitDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.split(SplitAxis::Vertical, window, cx);
+    }
+
+    fn close_split(&mut self, _: &CloseSplit, window: &mut Window, cx: &mut Context<Self>) {
+        let panes = self.panes();
+        let Some(ix) = panes.iter().position(|pane| pane.id == self.focused) else {
+            return;
+        };
+        // Focus the pane before, or the one after when closing the first.
+        let Some(next) = panes
+            .get(ix.saturating_sub(1))
+            .filter(|_| ix > 0)
+            .or(panes.get(1))
+        else {
+            return;
+        };
+        let (next, focus_handle) = (next.id, next.focus_handle.clone());
+
+        if self.root.remove(self.focused) {
+            self.focused = next;
+            window.focus(&focus_handle);
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    fn focus_offset(&mut self, offset: isize, window: &mut Window, cx: &mut Context<Self>) {
+        let panes = self.panes();
+        let Some(ix) = panes.iter().position(|pane| pane.id == self.focused) else {
+            return;
+        };
+        let next = panes[(ix as isize + offset).rem_euclid(panes.len() as isize) as usize];
+        let (next, focus_handle) = (next.id, next.focus_handle.clone());
+        self.focused = next;
+        window.focus(&focus_handle);
+        self.save(cx);
+        cx.notify();
+    }
+
+    fn focus_next(&mut self, _: &FocusNextSplit, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_offset(1, window, cx);
+    }
+
+    fn focus_previous(
+        &mut self,
+        _: &FocusPreviousSplit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.focus_offset(-1, window, cx);
+    }
+
+    fn save(&self, cx: &mut App) {
+        let layout = self.layout(cx);
+        let focused_pane = self.panes().iter().position(|pane| pane.id == self.focused);
+        Session::update(cx, |session| {
+            session.split = Some(layout);
+            session.focused_pane = focused_pane;
+        });
+    }
+
+    fn set_size(&mut self, size: Size<Pixels>, cx: &mut Context<Self>) {
+        if self.size != Some(size) {
+            self.size = Some(size);
+            cx.notify();
+        }
+    }
+
+    /// Render `node` in an area of `size`, the panels of a split sized from its ratios.
+    fn render_node(
+        &self,
+        node: &SplitNode,
+        size: Size<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let split = match node {
+            SplitNode::Pane(pane) => return self.render_pane(pane, cx),
+            SplitNode::Split(split) => split,
+        };
+
+        let panels = split.children.iter().enumerate().map(|(ix, child)| {
+            let ratio = split
+                .ratios
+                .get(ix)
+                .copied()
+                .unwrap_or(1. / split.children.len() as f32);
+            let child_size = match split.axis {
+                SplitAxis::Horizontal => Size {
+                    width: size.width * ratio,
+                    ..size
+                },
+                SplitAxis::Vertical => Size {
+                    height: size.height * ratio,
+                    ..size
+                },
+            };
+            resizable_panel()
+                .size(match split.axis {
+                    SplitAxis::Horizontal => child_size.width,
+                    SplitAxis::Vertical => child_size.height,
+                })
+                .size_range(px(120.)..Pixels::MAX)
+                .child(self.render_node(child, child_size, cx))
+        });
+
+        let id = SharedString::from(format!("split-{}", split.id));
+        match split.axis {
+            SplitAxis::Horizontal => h_resizable(id, split.state.clone())
+                .children(panels.collect::<Vec<_>>())
+                .into_any_element(),
+            SplitAxis::Vertical => v_resizable(id, split.state.clone())
+                .children(panels.collect::<Vec<_>>())
+                .into_any_element(),
+        }
+    }
+
+    fn render_pane(&self, pane: &Pane, cx: &mut Context<Self>) -> AnyElement {
+        let id = pane.id;
+        let focus_handle = pane.focus_handle.clone();
+        let highlight = id == self.focused && self.panes().len() > 1;
+        let story_name = pane.story.as_ref().map(|story| story.read(cx).name.clone());
+        let popped_out = story_name
+            .as_ref()
+            .is_some_and(|name| Popouts::is_popped_out(name, cx));
+
+        div()
+            .id(("split-pane", id))
+            .track_focus(&pane.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .border_1()
+            .border_color(if highlight {
+                cx.theme().ring
+            } else {
+                cx.theme().transparent
+            })
+            .capture_any_mouse_down(cx.listener(move |this, _, _, cx| {
+                if this.focused != id {
+                    this.focused = id;
+                    this.save(cx);
+                    cx.notify();
+                }
+            }))
+            .on_mouse_move(cx.listener(move |this, _, window, cx| {
+                if this.focused != id && InputSettings::global(cx).focus_follows_mouse {
+                    this.focused = id;
+                    window.focus(&focus_handle);
+                    this.save(cx);
+                    cx.notify();
+                }
+            }))
+            .drop_target(
+                cx,
+                cx.listener(move |this, dragged: &DraggedStory, window, cx| {
+                    this.drop_story(id, dragged.0.clone(), window, cx)
+                }),
+            )
+            .when(pane.story.is_none(), |this| {
+                this.child(
+                    v_flex()
+                        .size_full()
+                        .items_center()
+                        .justify_center()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("Pick a story from the sidebar, or drag one here by its title."),
+                )
+            })
+            .when_some(story_name.filter(|_| popped_out), |this, story_name| {
+                this.child(
+                    v_flex()
+                        .size_full()
+                        .gap_3()
+                        .items_center()
+                        .justify_center()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("{} is open in its own window.", story_name))
+                        .child(
+                            Button::new("dock")
+                                .small()
+                                .outline()
+                                .label("Dock")
+                                .on_click(move |_, _, cx| Popouts::dock(&story_name, cx)),
+                        ),
+                )
+            })
+            .when_some(pane.story.clone().filter(|_| !popped_out), |this, story| {
+                this.child(story)
+            })
+            .into_any_element()
+    }
+}
+
+impl Focusable for SplitView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SplitView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entity = cx.entity();
+        let mut key_context = KeyContext::default();
+        key_context.add(CONTEXT);
+        key_context.add(ShortcutScope::Panel.context());
+
+        div()
+            .key_context(key_context)
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::split_right))
+            .on_action(cx.listener(Self::split_down))
+            .on_action(cx.listener(Self::close_split))
+            .on_action(cx.listener(Self::focus_next))
+            .on_action(cx.listener(Self::focus_previous))
+            .on_action(cx.listener(Self::go_back))
+            .on_action(cx.listener(Self::go_forward))
+            .relative()
+            .child(
+                canvas(
+                    move |bounds, _, cx| {
+                        entity.update(cx, |this, cx| this.set_size(bounds.size, cx))
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            // The panels are sized once the size of the view is known, on the next frame.
+            .when_some(self.size, |this, size| {
+                this.child(self.render_node(&self.root, size, cx))
+            })
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-\\", SplitRight, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-\\", SplitRight, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-\\", SplitDown, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-\\", SplitDown, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-shift-w", CloseSplit, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("ctrl-shift-w", CloseSplit, Some(CONTEXT)),
+        KeyBinding::new("alt-]", FocusNextSplit, Some(CONTEXT)),
+        KeyBinding::new("alt-[", FocusPreviousSplit, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-[", GoBack, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("alt-left", GoBack, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-]", GoForward, Some(CONTEXT)),
+        #[cfg(not(target_os = "macos"))]
+        KeyBinding::new("alt-right", GoForward, Some(CONTEXT)),
+    ]);
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{App, AppContext as _, Subscription, TestAppContext};
+    use gpui_component::resizable::ResizableState;
+
+    use crate::split::{Pane, Split, SplitAxis, SplitNode};
+
+    fn pane(id: usize, cx: &mut App) -> Pane {
+        Pane {
+            id,
+            story: None,
+            back: vec![],
+            forward: vec![],
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn split(id: usize, axis: SplitAxis, cx: &mut App) -> Split {
+        Split {
+            id,
+            axis,
+            children: vec![],
+            ratios: vec![],
+            state: ResizableState::new(cx),
+            _subscription: Subscription::new(|| {}),
+        }
+    }
+
+    /// The pane ids of `node`, with the axis of each split.
+    fn shape(node: &SplitNode) -> String {
+        match node {
+            SplitNode::Pane(pane) => pane.id.to_string(),
+            SplitNode::Split(split) => format!(
+                "{:?}({})",
+                split.axis,
+                split
+                    .children
+                    .iter()
+                    .map(shape)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        }
+    }
+
+    #[gpui::test]
+    fn test_insert_remove(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let mut root = SplitNode::Pane(pane(1, cx));
+
+            let (new, wrap) = (pane(2, cx), split(3, SplitAxis::Horizontal, cx));
+            assert!(root.insert(1, new, wrap).is_ok());
+            assert_eq!(shape(&root), "Horizontal(1 2)");
+
+            // Along the same axis, the pane goes next to the target in its parent.
+            let (new, wrap) = (pane(4, cx), split(5, SplitAxis::Horizontal, cx));
+            assert!(root.insert(1, new, wrap).is_ok());
+            assert_eq!(shape(&root), "Horizontal(1 4 2)");
+            let SplitNode::Split(parent) = &root else {
+                panic!("expected a split");
+            };
+            assert_eq!(parent.ratios, vec![1. / 3.; 3]);
+
+            // Across it, the target is wrapped into a new split.
+            let (new, wrap) = (pane(6, cx), split(7, SplitAxis::Vertical, cx));
+            assert!(root.insert(2, new, wrap).is_ok());
+            assert_eq!(shape(&root), "Horizontal(1 4 Vertical(2 6))");
+
+            let (new, wrap) = (pane(8, cx), split(9, SplitAxis::Vertical, cx));
+            let (new, wrap) = root.insert(42, new, wrap).unwrap_err();
+            assert_eq!((new.id, wrap.id), (8, 9));
+
+            assert!(root.remove(6));
+            assert_eq!(shape(&root), "Horizontal(1 4 2)");
+            assert!(!root.remove(6));
+            assert!(root.remove(1));
+            assert!(root.remove(4));
+            assert_eq!(shape(&root), "2");
+            assert!(!root.remove(2));
+        });
+    }
+}
@@ -0,0 +1,47 @@
+use gpui::App;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    deep_links::{self, SCHEME},
+    kiosk,
+    settings::Settings,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct StartupSettings {
+    /// Run in order once the app is up, each either a link (`hello-gp://layouts/Focus`) or
+    /// the name of an action (`telemetry::ShowTelemetry`).
+    ///
+    /// Lightweight automation for what's done on every start, without writing a script.
+    pub actions: Vec<String>,
+    /// Show what's new after an update.
+    pub whats_new: bool,
+}
+
+impl Default for StartupSettings {
+    fn default() -> Self {
+        Self {
+            actions: vec![],
+            whats_new: true,
+        }
+    }
+}
+
+/// Run the startup actions, after the first window was opened.
+pub fn run(cx: &mut App) {
+    for entry in Settings::global(cx).startup.actions.clone() {
+        let entry = entry.trim();
+        if entry.starts_with(&format!("{}://", SCHEME)) {
+            deep_links::open(entry, cx);
+            continue;
+        }
+
+        match cx.build_action(entry, None) {
+            Ok(action) if kiosk::allows(action.as_ref(), cx) => deep_links::dispatch(action, cx),
+            Ok(_) => tracing::warn!("{} isn't allowed in kiosk mode", entry),
+            Err(err) => tracing::error!("Invalid startup action {}: {}", entry, err),
+        }
+    }
+}
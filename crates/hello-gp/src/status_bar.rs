@@ -0,0 +1,180 @@
+use gpui::{
+    AppContext as _, Context, Corner, Entity, IntoElement, ParentElement as _, Render, Styled as _,
+    Subscription, Window, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    indicator::Indicator,
+    popover::Popover,
+};
+
+use crate::{
+    extensions::{ExtensionRegistry, RunExtensionCommand},
+    icons::Icons,
+    persistence::{self, Persistence},
+    power,
+    tasks::{TaskList, TaskScheduler},
+    tooltips::{RichTooltip, WithTooltip},
+};
+
+/// The bar at the bottom of the window, showing background task progress
+/// and the items contributed by extensions.
+pub struct AppStatusBar {
+    task_list: Entity<TaskList>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl AppStatusBar {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let task_list = cx.new(|cx| TaskList::new(window, cx));
+        let _subscriptions = vec![
+            cx.observe(&TaskScheduler::global(cx), |_, _, cx| cx.notify()),
+            cx.observe(&ExtensionRegistry::global(cx), |_, _, cx| cx.notify()),
+            cx.observe(&Persistence::global(cx), |_, _, cx| cx.notify()),
+            // The spinner stops while the window is in the background.
+            cx.observe_window_activation(window, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            task_list,
+            _subscriptions,
+        }
+    }
+
+    fn render_tasks(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let scheduler = TaskScheduler::global(cx);
+        let scheduler = scheduler.read(cx);
+        let running = scheduler.running_tasks().collect::<Vec<_>>();
+
+        let label = match running.as_slice() {
+            [] => None,
+            [task] => Some(match task.progress {
+                Some(progress) => format!("{} ({:.0}%)", task.title, progress * 100.),
+                None => task.title.to_string(),
+            }),
+            tasks => Some(format!("{} tasks running", tasks.len())),
+        };
+        let task_list = self.task_list.clone();
+        let tooltip = RichTooltip::new("Background Tasks").body(
+            label
+                .clone()
+                .unwrap_or_else(|| "Nothing running.".to_string()),
+        );
+
+        WithTooltip::new(
+            "status-bar-tasks-tooltip",
+            tooltip,
+            Popover::new("status-bar-tasks")
+                .anchor(Corner::BottomRight)
+                .trigger(
+                    Button::new("status-bar-tasks")
+                        .xsmall()
+                        .ghost()
+                        .when_some(label.clone(), |this, label| this.label(label))
+                        .when(label.is_none(), |this| {
+                            this.icon(Icons::get(IconName::Inbox, cx))
+                        }),
+                )
+                .content(move |_, _| task_list.clone()),
+        )
+    }
+
+    /// A hint that changes are waiting for the next write, see [`Persistence`].
+    fn render_unsaved(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let persistence = Persistence::global(cx);
+        let persistence = persistence.read(cx);
+        if !persistence.has_pending() {
+            return None;
+        }
+        let pending = persistence.pending().collect::<Vec<_>>().join(", ");
+
+        Some(WithTooltip::new(
+            "status-bar-unsaved-tooltip",
+            RichTooltip::new("Unsaved Changes")
+                .body(format!("Not yet saved: {}. Click to save now.", pending)),
+            Button::new("status-bar-unsaved")
+                .xsmall()
+                .ghost()
+                .label("Unsaved changes")
+                .on_click(|_, _, cx| persistence::flush(cx)),
+        ))
+    }
+
+    fn render_extension_items(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let registry = ExtensionRegistry::global(cx);
+        let items = registry
+            .read(cx)
+            .status_bar_items()
+            .map(|(ext, item)| (ext.id.clone(), ext.name.clone(), item.clone()))
+            .collect::<Vec<_>>();
+
+        h_flex()
+            .flex_1()
+            .gap_1()
+            .children(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, (extension, name, item))| {
+                        let button = Button::new(("extension-status-item", ix))
+                            .xsmall()
+                            .ghost()
+                            .label(item.text.clone())
+                            .when_some(item.command.clone(), |this, command| {
+                                this.on_click(move |_, window, cx| {
+                                    window.dispatch_action(
+                                        Box::new(RunExtensionCommand {
+                                            extension: extension.clone(),
+                                            command: command.clone(),
+                                        }),
+                                        cx,
+                                    );
+                                })
+                            });
+                        match item.tooltip.clone() {
+                            Some(tooltip) => WithTooltip::new(
+                                ("extension-status-item-tooltip", ix),
+                                RichTooltip::new(tooltip).body(format!("From {}", name)),
+                                button,
+                            )
+                            .into_any_element(),
+                            None => button.into_any_element(),
+                        }
+                    }),
+            )
+    }
+}
+
+impl Render for AppStatusBar {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_running = TaskScheduler::global(cx)
+            .read(cx)
+            .running_tasks()
+            .next()
+            .is_some();
+        let animate = power::animations_enabled(window, cx);
+
+        h_flex()
+            .h(px(24.))
+            .px_2()
+            .gap_2()
+            .justify_between()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().title_bar)
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(self.render_extension_items(cx))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(has_running && animate, |this| {
+                        this.child(Indicator::new().xsmall())
+                    })
+                    .children(self.render_unsaved(cx))
+                    .child(div().child(self.render_tasks(cx))),
+            )
+    }
+}
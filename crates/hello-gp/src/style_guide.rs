@@ -0,0 +1,284 @@
+use gpui::{
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, Hsla, InteractiveElement as _,
+    IntoElement, ParentElement as _, Render, SharedString, StatefulInteractiveElement as _,
+    Styled as _, Window, actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, IconName, Sizable as _, ThemeRegistry,
+    alert::Alert,
+    badge::Badge,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex,
+    input::InputState,
+    popup_menu::PopupMenuExt as _,
+    progress::Progress,
+    radio::Radio,
+    switch::Switch,
+    tag::Tag,
+    v_flex,
+};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    section,
+    text_field::TextField,
+    themes::{SwitchTheme, ToggleThemeMode},
+};
+
+actions!(themes, [ShowStyleGuide]);
+
+const LIST_ITEMS: [&str; 4] = ["Inbox", "Drafts", "Sent", "Archive"];
+
+/// Every kind of widget on one page, under the active theme, to see a theme change everywhere
+/// at once.
+pub struct StyleGuide {
+    focus_handle: FocusHandle,
+    input: Entity<InputState>,
+    checked: bool,
+    selected: usize,
+}
+
+impl StyleGuide {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Type something..."));
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            input,
+            checked: true,
+            selected: 0,
+        }
+    }
+
+    fn swatch(name: &'static str, color: Hsla, cx: &App) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .items_center()
+            .child(
+                div()
+                    .size_10()
+                    .rounded(cx.theme().radius)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(color),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(name),
+            )
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let active = cx.theme().theme_name().clone();
+        let themes = ThemeRegistry::global(cx)
+            .sorted_themes()
+            .iter()
+            .map(|theme| theme.name.clone())
+            .collect::<Vec<SharedString>>();
+
+        h_flex()
+            .justify_between()
+            .child(
+                v_flex()
+                    .child(div().text_xl().font_semibold().child("Style Guide"))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{}, {} mode", active, cx.theme().mode.name())),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("theme")
+                            .small()
+                            .outline()
+                            .label("Theme")
+                            .icon(IconName::Palette)
+                            .popup_menu(move |menu, _, _| {
+                                themes.iter().fold(menu, |menu, theme| {
+                                    menu.menu_with_check(
+                                        theme.clone(),
+                                        *theme == active,
+                                        Box::new(SwitchTheme(theme.clone())),
+                                    )
+                                })
+                            }),
+                    )
+                    .child(
+                        Button::new("toggle-mode")
+                            .small()
+                            .outline()
+                            .label("Toggle Dark Mode")
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(ToggleThemeMode), cx)
+                            }),
+                    ),
+            )
+    }
+}
+
+impl Focusable for StyleGuide {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for StyleGuide {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors;
+        let hover_bg = cx.theme().accent;
+
+        v_flex()
+            .id("style-guide")
+            .size_full()
+            .p_4()
+            .gap_4()
+            .overflow_y_scroll()
+            .child(self.render_header(cx))
+            .child(
+                section("Colors")
+                    .child(Self::swatch("background", colors.background, cx))
+                    .child(Self::swatch("foreground", colors.foreground, cx))
+                    .child(Self::swatch("primary", colors.primary, cx))
+                    .child(Self::swatch("secondary", colors.secondary, cx))
+                    .child(Self::swatch("muted", colors.muted, cx))
+                    .child(Self::swatch("accent", colors.accent, cx))
+                    .child(Self::swatch("border", colors.border, cx))
+                    .child(Self::swatch("danger", colors.danger, cx))
+                    .child(Self::swatch("warning", colors.warning, cx))
+                    .child(Self::swatch("success", colors.success, cx))
+                    .child(Self::swatch("info", colors.info, cx)),
+            )
+            .child(
+                section("Buttons")
+                    .child(Button::new("primary").primary().label("Primary"))
+                    .child(Button::new("secondary").label("Secondary"))
+                    .child(Button::new("outline").outline().label("Outline"))
+                    .child(Button::new("ghost").ghost().label("Ghost"))
+                    .child(Button::new("danger").danger().label("Danger"))
+                    .child(Button::new("link").link().label("Link"))
+                    .child(
+                        Button::new("disabled")
+                            .primary()
+                            .label("Disabled")
+                            .disabled(true),
+                    ),
+            )
+            .child(
+                section("Inputs")
+                    .child(
+                        div()
+                            .w(px(240.))
+                            .child(TextField::new("input", &self.input).spell_check()),
+                    )
+                    .child(
+                        Checkbox::new("checkbox")
+                            .label("Checkbox")
+                            .checked(self.checked)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.checked = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Switch::new("switch")
+                            .label("Switch")
+                            .checked(self.checked)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.checked = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .child(Radio::new("radio").label("Radio").checked(self.checked)),
+            )
+            .child(
+                section("Lists and Menus")
+                    .child(
+                        v_flex()
+                            .w(px(240.))
+                            .p_1()
+                            .rounded(cx.theme().radius)
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .children(LIST_ITEMS.iter().enumerate().map(|(ix, item)| {
+                                div()
+                                    .id(("list-item", ix))
+                                    .px_2()
+                                    .py_1()
+                                    .rounded(cx.theme().radius)
+                                    .cursor_role(CursorRole::Pointer, cx)
+                                    .when(ix == self.selected, |this| {
+                                        this.bg(cx.theme().list_active)
+                                    })
+                                    .hover(|this| this.bg(hover_bg))
+                                    .child(*item)
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.selected = ix;
+                                        cx.notify();
+                                    }))
+                            })),
+                    )
+                    .child(Button::new("menu").outline().label("Open Menu").popup_menu(
+                        |menu, _, _| {
+                            menu.menu("Toggle Dark Mode", Box::new(ToggleThemeMode))
+                                .separator()
+                                .menu_with_check("Checked item", true, Box::new(ToggleThemeMode))
+                                .menu_with_disabled(
+                                    "Disabled item",
+                                    Box::new(ToggleThemeMode),
+                                    true,
+                                )
+                        },
+                    )),
+            )
+            .child(
+                section("Feedback")
+                    .child(
+                        v_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(Alert::info("info", "Something to know."))
+                            .child(Alert::success("success", "Something went well."))
+                            .child(Alert::warning("warning", "Something to look at."))
+                            .child(Alert::error("error", "Something went wrong.")),
+                    )
+                    .child(div().w(px(240.)).child(Progress::new().value(60.)))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Tag::primary().child("Primary"))
+                            .child(Tag::secondary().child("Secondary"))
+                            .child(Tag::success().child("Success"))
+                            .child(Tag::danger().child("Danger")),
+                    )
+                    .child(
+                        Badge::new()
+                            .count(3)
+                            .child(Button::new("badge").icon(IconName::Bell)),
+                    ),
+            )
+    }
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Style Guide",
+        Some(size(px(960.), px(800.))),
+        |window, cx| StyleGuide::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ShowStyleGuide, cx| open_window(cx));
+}
@@ -0,0 +1,194 @@
+use gpui::{App, Global};
+
+use crate::{notifications::NotificationCenter, tasks::TaskScheduler};
+
+/// The badge and progress shown on the app icon in the dock or taskbar.
+///
+/// | Platform | Badge | Progress |
+/// |----------|-------|----------|
+/// | macOS    | dock tile label | as a percentage label, when there's no badge |
+/// | Windows  | - | taskbar button progress |
+/// | Linux    | Unity launcher API (Ubuntu dock, KDE, Plank) | Unity launcher API |
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Taskbar {
+    badge: Option<usize>,
+    progress: Option<f32>,
+}
+
+impl Global for Taskbar {}
+
+impl Taskbar {
+    pub fn global(cx: &App) -> &Self {
+        cx.global::<Self>()
+    }
+
+    pub fn badge(&self) -> Option<usize> {
+        self.badge
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.progress
+    }
+
+    /// Show `count` on the app icon, `None` (or zero) to hide it.
+    pub fn set_badge(count: Option<usize>, cx: &mut App) {
+        Self::update(cx, |this| this.badge = count.filter(|count| *count > 0));
+    }
+
+    /// Show a progress in `0.0..=1.0` on the app icon, `None` to hide it.
+    pub fn set_progress(progress: Option<f32>, cx: &mut App) {
+        Self::update(cx, |this| this.progress = progress.map(|p| p.clamp(0., 1.)));
+    }
+
+    fn update(cx: &mut App, f: impl FnOnce(&mut Self)) {
+        let mut state = *Self::global(cx);
+        f(&mut state);
+        if state != *Self::global(cx) {
+            platform::apply(&state, cx);
+            cx.set_global(state);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::collections::HashMap;
+
+    use gpui::App;
+    use zbus::zvariant::Value;
+
+    use super::Taskbar;
+    use crate::integration::APP_ID;
+
+    pub(super) fn apply(state: &Taskbar, cx: &mut App) {
+        let state = *state;
+        // The launcher entry is matched to the `.desktop` file of the app.
+        let app_uri = format!("application://{}.desktop", APP_ID);
+        cx.background_spawn(async move {
+            let mut properties: HashMap<&str, Value> = HashMap::new();
+            properties.insert("count", Value::I64(state.badge.unwrap_or(0) as i64));
+            properties.insert("count-visible", Value::Bool(state.badge.is_some()));
+            properties.insert("progress", Value::F64(state.progress.unwrap_or(0.) as f64));
+            properties.insert("progress-visible", Value::Bool(state.progress.is_some()));
+
+            let result = zbus::blocking::Connection::session().and_then(|connection| {
+                connection.emit_signal(
+                    None::<&str>,
+                    "/com/canonical/unity/launcherentry/1",
+                    "com.canonical.Unity.LauncherEntry",
+                    "Update",
+                    &(app_uri, properties),
+                )
+            });
+            if let Err(err) = result {
+                tracing::debug!("Failed to update the launcher entry: {}", err);
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use gpui::App;
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSApplication;
+    use objc2_foundation::NSString;
+
+    use super::Taskbar;
+
+    pub(super) fn apply(state: &Taskbar, _: &mut App) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        let label = match (state.badge, state.progress) {
+            (Some(count), _) => Some(count.to_string()),
+            (None, Some(progress)) => Some(format!("{:.0}%", progress * 100.)),
+            (None, None) => None,
+        };
+
+        let dock_tile = NSApplication::sharedApplication(mtm).dockTile();
+        let label = label.map(|label| NSString::from_str(&label));
+        dock_tile.setBadgeLabel(label.as_deref());
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use gpui::App;
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    use windows::Win32::{
+        Foundation::HWND,
+        System::Com::{CLSCTX_INPROC_SERVER, CoCreateInstance},
+        UI::Shell::{ITaskbarList3, TBPF_NOPROGRESS, TBPF_NORMAL, TaskbarList},
+    };
+
+    use super::Taskbar;
+
+    /// Taskbar buttons have no badge without an overlay icon, so only the progress is shown.
+    pub(super) fn apply(state: &Taskbar, cx: &mut App) {
+        let taskbar: ITaskbarList3 =
+            match unsafe { CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER) } {
+                Ok(taskbar) => taskbar,
+                Err(err) => {
+                    tracing::debug!("Failed to get the taskbar: {}", err);
+                    return;
+                }
+            };
+
+        for window in cx.windows() {
+            let hwnd = window.update(cx, |_, window, _| match window.window_handle() {
+                Ok(handle) => match handle.as_raw() {
+                    RawWindowHandle::Win32(handle) => Some(HWND(handle.hwnd.get() as _)),
+                    _ => None,
+                },
+                Err(_) => None,
+            });
+            let Ok(Some(hwnd)) = hwnd else {
+                continue;
+            };
+
+            // SAFETY: `hwnd` belongs to a window of this process that is still open.
+            unsafe {
+                _ = match state.progress {
+                    Some(progress) => taskbar.SetProgressState(hwnd, TBPF_NORMAL).and_then(|_| {
+                        taskbar.SetProgressValue(hwnd, (progress * 1000.) as u64, 1000)
+                    }),
+                    None => taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+    use gpui::App;
+
+    use super::Taskbar;
+
+    pub(super) fn apply(_: &Taskbar, _: &mut App) {}
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(Taskbar::default());
+
+    // The overall progress of the running tasks that report one.
+    cx.observe(&TaskScheduler::global(cx), |scheduler, cx| {
+        let progress = scheduler
+            .read(cx)
+            .running_tasks()
+            .filter_map(|task| task.progress)
+            .collect::<Vec<_>>();
+        let progress =
+            (!progress.is_empty()).then(|| progress.iter().sum::<f32>() / progress.len() as f32);
+        Taskbar::set_progress(progress, cx);
+    })
+    .detach();
+
+    cx.observe(&NotificationCenter::global(cx), |center, cx| {
+        let unread = center.read(cx).unread();
+        Taskbar::set_badge(Some(unread), cx);
+    })
+    .detach();
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+
+use gpui::{
+    App, AppContext as _, AsyncApp, Context, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, Global, IntoElement, ParentElement as _, Render, SharedString, Styled as _,
+    Subscription, Task, WeakEntity, Window, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme as _, IconName, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    progress::Progress,
+    v_flex,
+};
+
+use crate::{power::FramePacer, quit::QuitGuards};
+
+/// How many finished tasks are kept around for the task list.
+const MAX_FINISHED_TASKS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(pub(crate) usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed(SharedString),
+    Cancelled,
+}
+
+impl TaskStatus {
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: TaskId,
+    pub title: SharedString,
+    /// Progress in `0.0..=1.0`, `None` for indeterminate tasks.
+    pub progress: Option<f32>,
+    pub status: TaskStatus,
+    pub started_at: Instant,
+}
+
+/// Handle given to a running job to report progress and check for cancellation.
+#[derive(Clone)]
+pub struct TaskHandle {
+    id: TaskId,
+    scheduler: WeakEntity<TaskScheduler>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Jobs doing blocking work on the background executor should poll this and bail out.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_progress(&self, progress: f32, cx: &mut AsyncApp) {
+        let id = self.id;
+        _ = self.scheduler.update(cx, |this, cx| {
+            if let Some(task) = this.task_mut(id) {
+                task.progress = Some(progress.clamp(0., 1.));
+                // Jobs may report progress in a tight loop, don't redraw for every update.
+                this.pacer.notify(cx);
+            }
+        });
+    }
+}
+
+/// Runs long-running background jobs and keeps track of their progress.
+///
+/// Views observe the global scheduler entity to render progress, e.g. the status bar.
+pub struct TaskScheduler {
+    next_id: usize,
+    tasks: Vec<TaskInfo>,
+    running: HashMap<TaskId, (Task<()>, Arc<AtomicBool>)>,
+    pacer: FramePacer,
+}
+
+struct GlobalTaskScheduler(Entity<TaskScheduler>);
+
+impl Global for GlobalTaskScheduler {}
+
+impl TaskScheduler {
+    fn new() -> Self {
+        Self {
+            next_id: 0,
+            tasks: vec![],
+            running: HashMap::new(),
+            pacer: FramePacer::default(),
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalTaskScheduler>().0.clone()
+    }
+
+    /// Spawn a job, the job receives a [`TaskHandle`] to report progress.
+    pub fn spawn<F>(title: impl Into<SharedString>, cx: &mut App, f: F) -> TaskId
+    where
+        F: AsyncFnOnce(TaskHandle, &mut AsyncApp) -> anyhow::Result<()> + 'static,
+    {
+        let title = title.into();
+        Self::global(cx).update(cx, |this, cx| {
+            let id = TaskId(this.next_id);
+            this.next_id += 1;
+
+            let cancelled = Arc::new(AtomicBool::new(false));
+            let handle = TaskHandle {
+                id,
+                scheduler: cx.weak_entity(),
+                cancelled: cancelled.clone(),
+            };
+
+            tracing::info!("Start task: {}", title);
+            this.tasks.push(TaskInfo {
+                id,
+                title,
+                progress: None,
+                status: TaskStatus::Running,
+                started_at: Instant::now(),
+            });
+
+            let task = cx.spawn(async move |this, cx| {
+                let result = f(handle, cx).await;
+                _ = this.update(cx, |this, cx| {
+                    let status = match result {
+                        Ok(()) => TaskStatus::Completed,
+                        Err(err) => TaskStatus::Failed(err.to_string().into()),
+                    };
+                    this.finish(id, status, cx);
+                });
+            });
+            this.running.insert(id, (task, cancelled));
+            cx.notify();
+
+            id
+        })
+    }
+
+    /// Cancel a running task, dropping its future.
+    pub fn cancel(&mut self, id: TaskId, cx: &mut Context<Self>) {
+        if let Some((_, cancelled)) = self.running.get(&id) {
+            cancelled.store(true, Ordering::Relaxed);
+            self.finish(id, TaskStatus::Cancelled, cx);
+        }
+    }
+
+    pub fn tasks(&self) -> &[TaskInfo] {
+        &self.tasks
+    }
+
+    pub fn running_tasks(&self) -> impl Iterator<Item = &TaskInfo> {
+        self.tasks.iter().filter(|task| task.status.is_running())
+    }
+
+    pub fn clear_finished(&mut self, cx: &mut Context<Self>) {
+        self.tasks.retain(|task| task.status.is_running());
+        cx.notify();
+    }
+
+    fn task_mut(&mut self, id: TaskId) -> Option<&mut TaskInfo> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    fn finish(&mut self, id: TaskId, status: TaskStatus, cx: &mut Context<Self>) {
+        self.running.remove(&id);
+        if let Some(task) = self.task_mut(id) {
+            tracing::info!("Task {} finished: {:?}", task.title, status);
+            if status == TaskStatus::Completed {
+                task.progress = Some(1.);
+            }
+            task.status = status;
+        }
+
+        let finished = self.tasks.len() - self.running.len();
+        if finished > MAX_FINISHED_TASKS {
+            let mut excess = finished - MAX_FINISHED_TASKS;
+            self.tasks.retain(|task| {
+                if excess > 0 && !task.status.is_running() {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        cx.notify();
+    }
+}
+
+/// The task list shown in the status bar popover.
+pub struct TaskList {
+    focus_handle: FocusHandle,
+    scheduler: Entity<TaskScheduler>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TaskList {
+    pub fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        let scheduler = TaskScheduler::global(cx);
+        let _subscriptions = vec![cx.observe(&scheduler, |_, _, cx| cx.notify())];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            scheduler,
+            _subscriptions,
+        }
+    }
+
+    fn render_task(&self, task: &TaskInfo, cx: &mut Context<Self>) -> impl IntoElement {
+        let id = task.id;
+        let status: SharedString = match &task.status {
+            TaskStatus::Running => match task.progress {
+                Some(progress) => format!("{:.0}%", progress * 100.).into(),
+                None => "Running".into(),
+            },
+            TaskStatus::Completed => "Completed".into(),
+            TaskStatus::Failed(err) => format!("Failed: {}", err).into(),
+            TaskStatus::Cancelled => "Cancelled".into(),
+        };
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .child(div().text_sm().child(task.title.clone()))
+                    .when(task.status.is_running(), |this| {
+                        this.child(
+                            Button::new(("cancel-task", id.0))
+                                .xsmall()
+                                .ghost()
+                                .icon(IconName::Close)
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.scheduler.update(cx, |scheduler, cx| {
+                                        scheduler.cancel(id, cx);
+                                    });
+                                })),
+                        )
+                    }),
+            )
+            .when(task.status.is_running(), |this| {
+                this.child(Progress::new().value(task.progress.unwrap_or(0.) * 100.))
+            })
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(status),
+            )
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskList {}
+
+impl Focusable for TaskList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TaskList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tasks = self.scheduler.read(cx).tasks().to_vec();
+        let has_finished = tasks.iter().any(|task| !task.status.is_running());
+
+        v_flex()
+            .p_3()
+            .gap_3()
+            .w(px(320.))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(div().font_semibold().child("Tasks"))
+                    .when(has_finished, |this| {
+                        this.child(
+                            Button::new("clear-finished-tasks")
+                                .xsmall()
+                                .ghost()
+                                .label("Clear")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.scheduler.update(cx, |scheduler, cx| {
+                                        scheduler.clear_finished(cx);
+                                    });
+                                })),
+                        )
+                    }),
+            )
+            .when(tasks.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("No background tasks."),
+                )
+            })
+            .children(
+                tasks
+                    .iter()
+                    .rev()
+                    .map(|task| self.render_task(task, cx))
+                    .collect::<Vec<_>>(),
+            )
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let scheduler = cx.new(|_| TaskScheduler::new());
+    cx.set_global(GlobalTaskScheduler(scheduler));
+
+    QuitGuards::add(cx, |cx| {
+        let running = TaskScheduler::global(cx).read(cx).running_tasks().count();
+        match running {
+            0 => None,
+            1 => Some("A background task is running.".into()),
+            n => Some(format!("{} background tasks are running.", n).into()),
+        }
+    });
+}
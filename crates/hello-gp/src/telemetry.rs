@@ -0,0 +1,373 @@
+use std::{
+    collections::BTreeMap,
+    io::Write as _,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use gpui::{
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, Global, IntoElement,
+    ParentElement as _, Render, SharedString, Styled as _, Subscription, Task, Timer, Window,
+    actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    h_flex,
+    switch::Switch,
+    text::TextView,
+    v_flex,
+};
+use http_client::{
+    AsyncBody,
+    http::{Request, header},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{idle::IdleMonitor, net::Net, policy, settings::Settings, themes::get_data_dir};
+
+actions!(telemetry, [ShowTelemetry]);
+
+const QUEUE_FILE: &str = "queue.jsonl";
+const MAX_QUEUE_SIZE: usize = 500;
+const BATCH_SIZE: usize = 50;
+const UPLOAD_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    /// Upload the queued events, nothing leaves the machine unless this is enabled.
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+}
+
+/// An anonymous usage event, no user or machine identifiers are recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryEvent {
+    pub name: SharedString,
+    pub timestamp: u64,
+    pub app_version: SharedString,
+    pub os: SharedString,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, String>,
+}
+
+impl TelemetryEvent {
+    fn new(name: impl Into<SharedString>, properties: BTreeMap<String, String>) -> Self {
+        Self {
+            name: name.into(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            app_version: env!("CARGO_PKG_VERSION").into(),
+            os: std::env::consts::OS.into(),
+            properties,
+        }
+    }
+}
+
+/// Local-first telemetry queue.
+///
+/// Events are always written to `get_data_dir()/telemetry/queue.jsonl` so the user can inspect
+/// them, but they are only uploaded (in batches) when telemetry is enabled in the settings.
+pub struct Telemetry {
+    queue: Vec<TelemetryEvent>,
+    /// The sequence number of the first event of the queue, the others follow in order. Lets
+    /// an upload remove exactly what it sent, whatever was evicted or cleared meanwhile.
+    first_seq: u64,
+    /// Whether a batch is being uploaded, so the next one doesn't send it again.
+    uploading: bool,
+    _upload_task: Task<()>,
+}
+
+struct GlobalTelemetry(Entity<Telemetry>);
+
+impl Global for GlobalTelemetry {}
+
+impl Telemetry {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let _upload_task = cx.spawn(async move |this, cx| {
+            loop {
+                Timer::after(UPLOAD_INTERVAL).await;
+                let Ok(task) = this.update(cx, |this, cx| this.upload(cx)) else {
+                    break;
+                };
+                task.await;
+            }
+        });
+
+        Self {
+            queue: Self::load(),
+            first_seq: 0,
+            uploading: false,
+            _upload_task,
+        }
+    }
+
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalTelemetry>().0.clone()
+    }
+
+    pub fn queue(&self) -> &[TelemetryEvent] {
+        &self.queue
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.first_seq += self.queue.len() as u64;
+        self.queue.clear();
+        self.save();
+        cx.notify();
+    }
+
+    fn dir() -> PathBuf {
+        get_data_dir().join("telemetry")
+    }
+
+    fn load() -> Vec<TelemetryEvent> {
+        std::fs::read_to_string(Self::dir().join(QUEUE_FILE))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    fn save(&self) {
+        let lines = self
+            .queue
+            .iter()
+            .filter_map(|event| serde_json::to_string(event).ok())
+            .collect::<Vec<_>>();
+        let result = std::fs::create_dir_all(Self::dir())
+            .and_then(|_| std::fs::write(Self::dir().join(QUEUE_FILE), lines.join("\n")));
+        if let Err(err) = result {
+            tracing::warn!("Failed to write telemetry queue: {}", err);
+        }
+    }
+
+    /// Add `event` at the end of the queue file, each line starts with a newline as the file
+    /// written by [`Self::save`] doesn't end with one.
+    fn append(event: &TelemetryEvent) -> anyhow::Result<()> {
+        let line = serde_json::to_string(event)?;
+        std::fs::create_dir_all(Self::dir())?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::dir().join(QUEUE_FILE))?;
+        write!(file, "\n{}", line)?;
+        Ok(())
+    }
+
+    fn push(&mut self, event: TelemetryEvent, cx: &mut Context<Self>) {
+        // Only an eviction rewrites the file, events are otherwise appended one by one.
+        if let Err(err) = Self::append(&event) {
+            tracing::warn!("Failed to write telemetry queue: {}", err);
+        }
+        self.queue.push(event);
+        if self.queue.len() > MAX_QUEUE_SIZE {
+            let excess = self.queue.len() - MAX_QUEUE_SIZE;
+            self.queue.drain(..excess);
+            self.first_seq += excess as u64;
+            self.save();
+        }
+        cx.notify();
+    }
+
+    /// Remove the events before the sequence number `end` that are still queued, the rest of
+    /// an uploaded batch was evicted or cleared while it was being sent.
+    fn remove_sent(&mut self, end: u64) {
+        let sent = (end.saturating_sub(self.first_seq) as usize).min(self.queue.len());
+        self.queue.drain(..sent);
+        self.first_seq += sent as u64;
+        self.save();
+    }
+
+    fn upload(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let settings = Settings::global(cx).telemetry.clone();
+        let endpoint = match settings.endpoint {
+            Some(endpoint) if settings.enabled && !self.queue.is_empty() => endpoint,
+            _ => return Task::ready(()),
+        };
+        // Nothing new is recorded while idle, the queue waits for the user to be back. Being
+        // offline doesn't stop it, the upload is what tells whether the network is back.
+        if self.uploading || IdleMonitor::is_idle(cx) {
+            return Task::ready(());
+        }
+        self.uploading = true;
+
+        let batch = self
+            .queue
+            .iter()
+            .take(BATCH_SIZE)
+            .cloned()
+            .collect::<Vec<_>>();
+        let sent_end = self.first_seq + batch.len() as u64;
+        let client = Net::client(cx);
+        cx.spawn(async move |this, cx| {
+            let result = async {
+                let body = serde_json::to_string(&batch)?;
+                let request = Request::post(endpoint.as_str())
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(AsyncBody::from(body))?;
+                let response = client.send(request).await;
+                Net::set_online(response.is_ok(), cx);
+                let response = response?;
+                anyhow::ensure!(response.status().is_success(), "{}", response.status());
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+
+            _ = this.update(cx, |this, cx| {
+                this.uploading = false;
+                match result {
+                    Ok(()) => {
+                        tracing::info!("Uploaded {} telemetry events", batch.len());
+                        this.remove_sent(sent_end);
+                        cx.notify();
+                    }
+                    Err(err) => tracing::warn!("Failed to upload telemetry: {}", err),
+                }
+            });
+        })
+    }
+}
+
+/// Record an anonymous usage event.
+pub fn record<I, K, V>(name: impl Into<SharedString>, properties: I, cx: &mut App)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    let properties = properties
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect();
+    let event = TelemetryEvent::new(name, properties);
+    Telemetry::global(cx).update(cx, |this, cx| this.push(event, cx));
+}
+
+/// Shows exactly what would be sent, and lets the user opt in or out.
+pub struct TelemetryView {
+    focus_handle: FocusHandle,
+    telemetry: Entity<Telemetry>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TelemetryView {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(_: &mut Window, cx: &mut Context<Self>) -> Self {
+        let telemetry = Telemetry::global(cx);
+        let _subscriptions = vec![cx.observe(&telemetry, |_, _, cx| cx.notify())];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            telemetry,
+            _subscriptions,
+        }
+    }
+}
+
+impl Focusable for TelemetryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TelemetryView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let enabled = Settings::global(cx).telemetry.enabled;
+        let locked = policy::is_locked("telemetry.enabled");
+        let queue = self.telemetry.read(cx).queue();
+        let json = serde_json::to_string_pretty(queue).unwrap_or_default();
+        let count = queue.len();
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Switch::new("telemetry-enabled")
+                            .checked(enabled)
+                            .label("Send anonymous usage data")
+                            .disabled(locked)
+                            .when(locked, |this| this.tooltip(policy::LOCKED_TOOLTIP))
+                            .on_click(|checked, _, cx| {
+                                let checked = *checked;
+                                Settings::update(cx, |settings, _| {
+                                    settings.telemetry.enabled = checked;
+                                });
+                                cx.refresh_windows();
+                            }),
+                    )
+                    .child(
+                        Button::new("clear-telemetry")
+                            .small()
+                            .outline()
+                            .label("Clear Queue")
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.telemetry
+                                    .update(cx, |telemetry, cx| telemetry.clear(cx));
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!(
+                        "{} events queued locally. This is exactly what would be uploaded.",
+                        count
+                    )),
+            )
+            .child(
+                TextView::markdown(
+                    "telemetry-queue",
+                    format!("```json\n{}\n```", json),
+                    window,
+                    cx,
+                )
+                .flex_1()
+                .scrollable()
+                .selectable(),
+            )
+    }
+}
+
+/// Append a crash event straight to the queue file, the app state can't be trusted in a panic.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Into the log file too, for the recovery window to point at.
+        tracing::error!("{}", info);
+        let _ = Telemetry::append(&TelemetryEvent::new("crash", BTreeMap::new()));
+        default_hook(info);
+    }));
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Telemetry",
+        Some(size(px(720.), px(560.))),
+        |window, cx| TelemetryView::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    install_panic_hook();
+
+    let telemetry = cx.new(Telemetry::new);
+    cx.set_global(GlobalTelemetry(telemetry));
+
+    cx.on_action(|_: &ShowTelemetry, cx| open_window(cx));
+
+    record("app_started", [("arch", std::env::consts::ARCH)], cx);
+}
@@ -0,0 +1,282 @@
+use std::ops::Range;
+
+use gpui::{
+    App, Bounds, ElementId, Entity, EntityInputHandler as _, Focusable as _,
+    InteractiveElement as _, IntoElement, MouseButton, ParentElement as _, Pixels, Refineable as _,
+    RenderOnce, StyleRefinement, Styled, UnderlineStyle, Window, canvas, div, point,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    Sizable, Size,
+    context_menu::ContextMenuExt as _,
+    input::{Copy, Cut, InputState, Paste, SelectAll, TextInput},
+    popup_menu::PopupMenu,
+};
+
+use crate::{
+    input_settings,
+    spell_check::{AddToDictionary, ReplaceWord, SpellCheck},
+};
+
+/// Byte offsets of `text` to the UTF-16 offsets of the input handler, and back.
+fn to_utf16(text: &str, offset: usize) -> usize {
+    text[..offset].encode_utf16().count()
+}
+
+fn from_utf16(text: &str, offset: usize) -> usize {
+    let mut utf16 = 0;
+    for (ix, ch) in text.char_indices() {
+        if utf16 >= offset {
+            return ix;
+        }
+        utf16 += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// The horizontal padding of a [`TextInput`] of `size`, where its text starts.
+fn text_inset(size: Size) -> Pixels {
+    match size {
+        Size::XSmall => px(4.),
+        Size::Small => px(8.),
+        Size::Large => px(16.),
+        _ => px(12.),
+    }
+}
+
+/// Wavy underlines under the `misspelled` ranges of `text`.
+///
+/// They're placed by shaping the text the way the input does, so they're left out once the
+/// text is wider than the input and scrolls.
+fn paint_squiggles(
+    bounds: Bounds<Pixels>,
+    text: &str,
+    misspelled: &[Range<usize>],
+    size: Size,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let style = window.text_style();
+    let font_size = style.font_size.to_pixels(window.rem_size());
+    let line = window.text_system().shape_line(
+        text.to_string().into(),
+        font_size,
+        &[style.to_run(text.len())],
+        None,
+    );
+    let inset = text_inset(size);
+    if line.width > bounds.size.width - inset * 2. {
+        return;
+    }
+
+    let underline = UnderlineStyle {
+        thickness: px(1.),
+        color: Some(SpellCheck::squiggle_color(cx)),
+        wavy: true,
+    };
+    let y = bounds.center().y + window.line_height() / 2. - px(2.);
+    for range in misspelled {
+        let start = line.x_for_index(range.start);
+        let end = line.x_for_index(range.end);
+        window.paint_underline(
+            point(bounds.left() + inset + start, y),
+            end - start,
+            &underline,
+        );
+    }
+}
+
+/// The text input of the shell: the command palette, the settings search and the dialogs.
+///
+/// It's a [`TextInput`], so it takes part in IME composition (the marked text of a CJK input
+/// method is shown underlined until it's committed), draws the caret and the selection with the
+/// `caret` and `selection` tokens of the theme, which [`crate::AppearanceSettings`] may
+/// override, and has the clipboard shortcuts. On top, a right click opens a menu with Cut,
+/// Copy, Paste and Select All.
+///
+/// With [`TextField::spell_check`], misspelled words are underlined while [`SpellCheck`] is on,
+/// and the menu suggests corrections for the one at the cursor.
+///
+/// ```ignore
+/// let input = cx.new(|cx| InputState::new(window, cx).placeholder("Name"));
+/// TextField::new("name", &input).cleanable()
+/// ```
+#[derive(IntoElement)]
+pub struct TextField {
+    id: ElementId,
+    state: Entity<InputState>,
+    style: StyleRefinement,
+    size: Size,
+    cleanable: bool,
+    disabled: bool,
+    spell_check: bool,
+}
+
+impl TextField {
+    pub fn new(id: impl Into<ElementId>, state: &Entity<InputState>) -> Self {
+        Self {
+            id: id.into(),
+            state: state.clone(),
+            style: StyleRefinement::default(),
+            size: Size::default(),
+            cleanable: false,
+            disabled: false,
+            spell_check: false,
+        }
+    }
+
+    /// Show a button that clears the text.
+    pub fn cleanable(mut self) -> Self {
+        self.cleanable = true;
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Opt into spell-checking, for prose rather than names, paths or passphrases.
+    pub fn spell_check(mut self) -> Self {
+        self.spell_check = true;
+        self
+    }
+}
+
+/// The spelling items of the menu for the misspelled word at the cursor, if any.
+fn spelling_menu(
+    menu: PopupMenu,
+    state: &Entity<InputState>,
+    window: &mut Window,
+    cx: &mut App,
+) -> PopupMenu {
+    let text = state.read(cx).value().to_string();
+    let Some(selection) =
+        state.update(cx, |state, cx| state.selected_text_range(false, window, cx))
+    else {
+        return menu;
+    };
+    let cursor = from_utf16(&text, selection.range.end);
+    let Some(range) = SpellCheck::misspelled(&text, cx)
+        .into_iter()
+        .find(|range| range.start <= cursor && cursor <= range.end)
+    else {
+        return menu;
+    };
+
+    let word = text[range.clone()].to_string();
+    let suggestions = SpellCheck::suggest(&word, cx);
+    let menu = if suggestions.is_empty() {
+        menu.menu_with_disabled(
+            "No Suggestions",
+            Box::new(AddToDictionary(word.clone().into())),
+            true,
+        )
+    } else {
+        suggestions.into_iter().fold(menu, |menu, suggestion| {
+            menu.menu(
+                suggestion.clone(),
+                Box::new(ReplaceWord {
+                    start: range.start,
+                    end: range.end,
+                    replacement: suggestion.into(),
+                }),
+            )
+        })
+    };
+    menu.menu(
+        format!("Add \"{}\" to Dictionary", word),
+        Box::new(AddToDictionary(word.into())),
+    )
+    .separator()
+}
+
+impl Styled for TextField {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl Sizable for TextField {
+    fn with_size(mut self, size: impl Into<Size>) -> Self {
+        self.size = size.into();
+        self
+    }
+}
+
+impl RenderOnce for TextField {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let focus_handle = self.state.read(cx).focus_handle(cx);
+        let text = self.state.read(cx).value().to_string();
+        let misspelled = if self.spell_check && !self.disabled {
+            SpellCheck::misspelled(&text, cx)
+        } else {
+            vec![]
+        };
+        let spell_check = !misspelled.is_empty();
+        let middle_click_paste = !self.disabled && input_settings::middle_click_paste(cx);
+
+        let mut input = TextInput::new(&self.state)
+            .w_full()
+            .with_size(self.size)
+            .disabled(self.disabled);
+        if self.cleanable {
+            input = input.cleanable();
+        }
+        let mut field = div().id(self.id).relative().w_full();
+        field.style().refine(&self.style);
+
+        let size = self.size;
+        let state = self.state.clone();
+        let disabled = self.disabled;
+        field
+            .child(input)
+            .when(spell_check, |this| {
+                this.child(
+                    canvas(
+                        |_, _, _| {},
+                        move |bounds, _, window, cx| {
+                            paint_squiggles(bounds, &text, &misspelled, size, window, cx)
+                        },
+                    )
+                    .absolute()
+                    .size_full(),
+                )
+            })
+            .when(middle_click_paste, |this| {
+                let state = self.state.clone();
+                this.on_mouse_down(MouseButton::Middle, move |_, window, cx| {
+                    input_settings::paste_primary(&state, window, cx)
+                })
+            })
+            .on_action({
+                let state = self.state.clone();
+                move |action: &ReplaceWord, window, cx| {
+                    state.update(cx, |state, cx| {
+                        let text = state.value().to_string();
+                        if !text.is_char_boundary(action.start)
+                            || !text.is_char_boundary(action.end)
+                        {
+                            return;
+                        }
+                        let range = to_utf16(&text, action.start)..to_utf16(&text, action.end);
+                        state.replace_text_in_range(Some(range), &action.replacement, window, cx);
+                    })
+                }
+            })
+            // The menu takes the focus while it's open, its actions go to the input.
+            .context_menu(move |menu, window, cx| {
+                let menu = menu.action_context(focus_handle.clone());
+                let menu = if spell_check {
+                    spelling_menu(menu, &state, window, cx)
+                } else {
+                    menu
+                };
+                menu.menu_with_disabled("Cut", Box::new(Cut), disabled)
+                    .menu("Copy", Box::new(Copy))
+                    .menu_with_disabled("Paste", Box::new(Paste), disabled)
+                    .separator()
+                    .menu("Select All", Box::new(SelectAll))
+            })
+    }
+}
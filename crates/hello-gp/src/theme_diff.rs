@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use gpui::{
+    App, AppContext as _, Context, Entity, FocusHandle, Focusable, Hsla, InteractiveElement as _,
+    IntoElement, ParentElement as _, Render, SharedString, StatefulInteractiveElement as _,
+    Styled as _, Subscription, Window, actions, div, prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, Colorize as _, IndexPath, ThemeConfig, ThemeRegistry,
+    dropdown::{Dropdown, DropdownEvent, DropdownState},
+    h_flex,
+    switch::Switch,
+    v_flex,
+};
+
+actions!(themes, [CompareThemes]);
+
+/// Flatten a theme config into `token -> value`, e.g. `colors.background -> #ffffff`.
+fn tokens(config: &ThemeConfig) -> BTreeMap<String, String> {
+    fn flatten(prefix: &str, value: &serde_json::Value, tokens: &mut BTreeMap<String, String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten(&key, value, tokens);
+                }
+            }
+            serde_json::Value::Null => {}
+            serde_json::Value::String(value) => {
+                tokens.insert(prefix.to_string(), value.clone());
+            }
+            value => {
+                tokens.insert(prefix.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let mut tokens = BTreeMap::new();
+    if let Ok(value) = serde_json::to_value(config) {
+        flatten("", &value, &mut tokens);
+    }
+    tokens.remove("name");
+    tokens
+}
+
+/// Shows two themes side by side, token by token, highlighting the values that differ.
+pub struct ThemeDiff {
+    focus_handle: FocusHandle,
+    left: Entity<DropdownState<Vec<SharedString>>>,
+    right: Entity<DropdownState<Vec<SharedString>>>,
+    changed_only: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ThemeDiff {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let names = ThemeRegistry::global(cx)
+            .sorted_themes()
+            .iter()
+            .map(|theme| theme.name.clone())
+            .collect::<Vec<_>>();
+        let active = names
+            .iter()
+            .position(|name| name == cx.theme().theme_name())
+            .unwrap_or_default();
+        let other = if names.len() > 1 {
+            (active + 1) % names.len()
+        } else {
+            active
+        };
+
+        let left = cx.new(|cx| {
+            DropdownState::new(
+                names.clone(),
+                Some(IndexPath::default().row(active)),
+                window,
+                cx,
+            )
+        });
+        let right = cx
+            .new(|cx| DropdownState::new(names, Some(IndexPath::default().row(other)), window, cx));
+        let _subscriptions = vec![
+            cx.subscribe_in(&left, window, Self::on_dropdown_event),
+            cx.subscribe_in(&right, window, Self::on_dropdown_event),
+        ];
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            left,
+            right,
+            changed_only: true,
+            _subscriptions,
+        }
+    }
+
+    fn on_dropdown_event(
+        &mut self,
+        _: &Entity<DropdownState<Vec<SharedString>>>,
+        _: &DropdownEvent<Vec<SharedString>>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.notify();
+    }
+
+    fn selected_tokens(
+        state: &Entity<DropdownState<Vec<SharedString>>>,
+        cx: &App,
+    ) -> BTreeMap<String, String> {
+        state
+            .read(cx)
+            .selected_value()
+            .and_then(|name| ThemeRegistry::global(cx).themes().get(name).cloned())
+            .map(|config| tokens(&config))
+            .unwrap_or_default()
+    }
+
+    fn render_value(value: Option<&String>, cx: &App) -> impl IntoElement {
+        let color = value.and_then(|value| Hsla::parse_hex(value).ok());
+
+        h_flex()
+            .flex_1()
+            .gap_2()
+            .when_some(color, |this, color| {
+                this.child(
+                    div()
+                        .size_4()
+                        .rounded(px(2.))
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .bg(color),
+                )
+            })
+            .child(
+                div()
+                    .when(value.is_none(), |this| {
+                        this.text_color(cx.theme().muted_foreground)
+                    })
+                    .child(value.cloned().unwrap_or_else(|| "-".into())),
+            )
+    }
+}
+
+impl Focusable for ThemeDiff {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ThemeDiff {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let left = Self::selected_tokens(&self.left, cx);
+        let right = Self::selected_tokens(&self.right, cx);
+        let keys = left.keys().chain(right.keys()).collect::<BTreeSet<_>>();
+        let changed_count = keys
+            .iter()
+            .filter(|key| left.get(**key) != right.get(**key))
+            .count();
+
+        let rows = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (a, b) = (left.get(key), right.get(key));
+                let changed = a != b;
+                if self.changed_only && !changed {
+                    return None;
+                }
+
+                Some(
+                    h_flex()
+                        .gap_4()
+                        .px_2()
+                        .py_1()
+                        .text_sm()
+                        .rounded(cx.theme().radius)
+                        .when(changed, |this| this.bg(cx.theme().warning.opacity(0.15)))
+                        .child(div().w(px(260.)).child(key.clone()))
+                        .child(Self::render_value(a, cx))
+                        .child(Self::render_value(b, cx)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(
+                        div().w(px(260.)).child(
+                            Switch::new("changed-only")
+                                .checked(self.changed_only)
+                                .label("Changed only")
+                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                    this.changed_only = *checked;
+                                    cx.notify();
+                                })),
+                        ),
+                    )
+                    .child(div().flex_1().child(Dropdown::new(&self.left)))
+                    .child(div().flex_1().child(Dropdown::new(&self.right))),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("{} tokens differ", changed_count)),
+            )
+            .child(
+                v_flex()
+                    .id("theme-diff-tokens")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(rows),
+            )
+    }
+}
+
+pub fn open_window(cx: &mut App) {
+    crate::create_new_window_with_size(
+        "Compare Themes",
+        Some(size(px(1000.), px(700.))),
+        |window, cx| ThemeDiff::view(window, cx),
+        cx,
+    );
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &CompareThemes, cx| open_window(cx));
+}
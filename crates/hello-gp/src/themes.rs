@@ -1,12 +1,35 @@
-use std::path::PathBuf;
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use directories::ProjectDirs;
-use gpui::{Action, App, SharedString};
-use gpui_component::{scroll::ScrollbarShow, ActiveTheme, Theme, ThemeMode, ThemeRegistry};
+use gpui::{Action, App, Global, SharedString, actions};
+use gpui_component::{
+    ActiveTheme, ContextModal as _, Theme, ThemeConfig, ThemeMode, ThemeRegistry,
+    notification::Notification, scroll::ScrollbarShow,
+};
 use lazy_static::lazy_static;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-const STATE_FILE: &str = "state.json";
+use crate::{
+    config,
+    dialogs::{self, DialogOptions},
+    events::{self, ThemeChanged},
+    history::{self, ChangeSource, History},
+    persistence::Persistence,
+    power::Visibility,
+    recovery, safe_mode,
+    settings::Settings,
+    telemetry,
+};
+
+pub(crate) const STATE_NAME: &str = "state";
+/// The extension of theme files to install, e.g. downloaded or shared by someone.
+pub const THEME_FILE_EXTENSION: &str = "hgptheme";
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
     pub static ref DATA_FOLDER: Option<PathBuf> =
@@ -43,9 +66,13 @@ pub fn get_data_dir() -> PathBuf {
     };
     directory
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct State {
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct State {
+    /// Name of the active theme.
+    #[schemars(with = "String")]
     theme: SharedString,
+    /// When to show scrollbars: `scrolling`, `hover` or `always`.
+    #[schemars(with = "Option<String>")]
     scrollbar_show: Option<ScrollbarShow>,
 }
 
@@ -58,23 +85,353 @@ impl Default for State {
     }
 }
 
+fn state_path() -> PathBuf {
+    config::path(&get_config_dir(), STATE_NAME)
+}
+
+fn load_state() -> anyhow::Result<State> {
+    config::load(&get_config_dir(), STATE_NAME)
+}
+
+/// Re-read the state file and apply it, e.g. after it was replaced on disk.
+pub(crate) fn reload_state(cx: &mut App) {
+    let state = load_state().unwrap_or_else(|err| {
+        tracing::error!("Failed to parse {}: {}", state_path().display(), err);
+        State::default()
+    });
+    let old = (cx.theme().theme_name().clone(), cx.theme().mode);
+    if let Some(theme) = ThemeRegistry::global(cx)
+        .themes()
+        .get(&state.theme)
+        .cloned()
+    {
+        Theme::global_mut(cx).apply_config(&theme);
+    }
+    if let Some(scrollbar_show) = state.scrollbar_show {
+        Theme::global_mut(cx).scrollbar_show = scrollbar_show;
+    }
+    history::with_source(ChangeSource::File, cx, |cx| record_theme(old, cx));
+    cx.refresh_windows();
+}
+
+/// `HELLO_GP_THEME` and `HELLO_GP_THEME_MODE`, overriding the saved theme for this run.
+///
+/// Handy for screenshots, demos and trying themes from scripts: the override isn't written
+/// to the state file, until a theme is picked in the app.
+#[derive(Default)]
+struct EnvOverride {
+    theme: Option<SharedString>,
+    /// Switches to the default theme of the mode, so it's ignored along with a `theme`.
+    mode: Option<ThemeMode>,
+    active: bool,
+}
+
+impl Global for EnvOverride {}
+
+impl EnvOverride {
+    fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(format!("{}_{}", PROJECT_NAME.clone(), name))
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let theme = var("THEME").map(SharedString::from);
+        let mode = var("THEME_MODE").and_then(|mode| match mode.to_lowercase().as_str() {
+            "light" => Some(ThemeMode::Light),
+            "dark" => Some(ThemeMode::Dark),
+            _ => {
+                tracing::warn!("Unknown theme mode {}, expected light or dark", mode);
+                None
+            }
+        });
+
+        let active = theme.is_some() || mode.is_some();
+        Self {
+            mode: mode.filter(|_| theme.is_none()),
+            theme,
+            active,
+        }
+    }
+
+    /// Stop overriding once the user picks a theme, so their choice is saved again.
+    fn clear(cx: &mut App) {
+        cx.global_mut::<Self>().active = false;
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ThemeFile {
+    pub(crate) themes: Vec<ThemeConfig>,
+    /// The theme to start with on a fresh install, keyed by OS (`macos`, `windows`, `linux`).
+    #[serde(default)]
+    pub(crate) defaults: BTreeMap<String, SharedString>,
+}
+
+/// Parse the text of a theme file.
+pub(crate) fn parse_theme_file(json: &str) -> serde_json::Result<ThemeFile> {
+    serde_json::from_str(json)
+}
+
+/// Install the themes of a `.hgptheme` file into the themes dir, e.g. when it's opened from
+/// the file manager. The theme registry picks them up from there.
+pub fn install_theme_file(path: &Path, cx: &mut App) {
+    let result = std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| {
+            let file = parse_theme_file(&json)?;
+            let dir = get_data_dir().join("themes");
+            std::fs::create_dir_all(&dir)?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            std::fs::write(dir.join(format!("{}.json", name)), json)?;
+            Ok(file
+                .themes
+                .iter()
+                .map(|theme| theme.name.to_string())
+                .collect::<Vec<_>>())
+        });
+
+    let notification = match result {
+        Ok(names) => {
+            tracing::info!("Installed the themes {:?} from {}", names, path.display());
+            Notification::success(format!("Installed {}.", names.join(", ")))
+        }
+        Err(err) => {
+            tracing::error!("Failed to install {}: {}", path.display(), err);
+            Notification::error(format!("Failed to install the theme: {}", err))
+        }
+    };
+    push_notification(notification, cx);
+}
+
+/// The theme file in `dir` that declares the theme `name`.
+fn find_theme_file(dir: &Path, name: &SharedString) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .find(|path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .and_then(|json| parse_theme_file(&json).ok())
+                .is_some_and(|file| file.themes.iter().any(|theme| &theme.name == name))
+        })
+}
+
+fn push_notification(notification: Notification, cx: &mut App) {
+    if let Some(window) = cx.active_window() {
+        _ = window.update(cx, |_, window, cx| {
+            window.push_notification(notification, cx)
+        });
+    }
+}
+
+/// Pick a theme file to install.
+fn import_theme(cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    let Ok(picked) = window.update(cx, |_, window, cx| {
+        let options = DialogOptions::new("themes", "Import Theme")
+            .extensions(&[THEME_FILE_EXTENSION, "json"]);
+        dialogs::open_file(options, window, cx)
+    }) else {
+        return;
+    };
+    cx.spawn(async move |cx| {
+        let result = picked.await;
+        _ = cx.update(|cx| match result {
+            Ok(Some(path)) => install_theme_file(&path, cx),
+            Ok(None) => {}
+            Err(err) => push_notification(
+                Notification::error(format!("Failed to install the theme: {}", err)),
+                cx,
+            ),
+        });
+    })
+    .detach();
+}
+
+/// Save the file of the active theme somewhere to share it, as a `.hgptheme` file.
+fn export_theme(cx: &mut App) {
+    let name = cx.theme().theme_name().clone();
+    let Some(source) = find_theme_file(&get_data_dir().join("themes"), &name) else {
+        push_notification(
+            Notification::warning(format!("{} is built in, there's no file to export.", name)),
+            cx,
+        );
+        return;
+    };
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    let Ok(picked) = window.update(cx, |_, window, cx| {
+        let options =
+            DialogOptions::new("themes", "Export Theme").extensions(&[THEME_FILE_EXTENSION]);
+        let file_name = format!("{}.{}", name, THEME_FILE_EXTENSION);
+        dialogs::save_file(options, file_name, window, cx)
+    }) else {
+        return;
+    };
+    cx.spawn(async move |cx| {
+        let target = match picked.await {
+            Ok(Some(target)) => target,
+            Ok(None) => return,
+            Err(err) => {
+                let notification =
+                    Notification::error(format!("Failed to export the theme: {}", err));
+                _ = cx.update(|cx| push_notification(notification, cx));
+                return;
+            }
+        };
+        let notification = match std::fs::copy(&source, &target) {
+            Ok(_) => {
+                tracing::info!("Exported the theme {} to {}", name, target.display());
+                Notification::success(format!("Exported {}.", name))
+            }
+            Err(err) => {
+                tracing::error!("Failed to export {} to {}: {}", name, target.display(), err);
+                Notification::error(format!("Failed to export the theme: {}", err))
+            }
+        };
+        _ = cx.update(|cx| push_notification(notification, cx));
+    })
+    .detach();
+}
+
+/// The first per-OS default declared by the theme files, in file name order.
+fn platform_default_theme(dir: &Path) -> Option<SharedString> {
+    let mut paths = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths.into_iter().find_map(|path| {
+        let json = std::fs::read_to_string(path).ok()?;
+        let mut file = parse_theme_file(&json).ok()?;
+        file.defaults.remove(std::env::consts::OS)
+    })
+}
+
+/// A theme file in the themes dir that failed to parse.
+#[derive(Debug, Clone)]
+pub(crate) struct ThemeError {
+    pub(crate) path: PathBuf,
+    pub(crate) message: String,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+#[derive(Default)]
+struct ThemeDiagnostics {
+    /// The file the active theme was last loaded from.
+    active_file: Option<PathBuf>,
+    error: Option<ThemeError>,
+}
+
+impl Global for ThemeDiagnostics {}
+
+/// The error of the active theme's file, shown as a banner until the file is fixed.
+pub(crate) fn theme_error(cx: &App) -> Option<&ThemeError> {
+    cx.try_global::<ThemeDiagnostics>()?.error.as_ref()
+}
+
+/// Parse the theme files ourselves, since the registry only logs (and skips) broken ones.
+fn check_theme_files(dir: &Path, theme_name: &SharedString, cx: &mut App) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let diagnostics = cx.global_mut::<ThemeDiagnostics>();
+    diagnostics.error = None;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let json = std::fs::read_to_string(&path).unwrap_or_default();
+        let parsed = parse_theme_file(&json);
+        // A file that doesn't parse can't be searched for the theme, it's the active theme's
+        // when it was before, or when it names the theme somewhere, e.g. broken at startup.
+        let is_active = match &parsed {
+            Ok(file) => file.themes.iter().any(|theme| &theme.name == theme_name),
+            Err(_) => {
+                diagnostics.active_file.as_ref() == Some(&path)
+                    || serde_json::to_string(theme_name.as_ref())
+                        .is_ok_and(|name| json.contains(&name))
+            }
+        };
+        if is_active {
+            diagnostics.active_file = Some(path.clone());
+        }
+        if let Err(err) = parsed {
+            tracing::error!("Failed to parse theme {}: {}", path.display(), err);
+            if is_active {
+                diagnostics.error = Some(ThemeError {
+                    path,
+                    message: err.to_string(),
+                    line: err.line(),
+                    column: err.column(),
+                });
+            }
+        }
+    }
+}
+
 pub fn init(cx: &mut App) {
     // Load last theme state
     let config_dir = get_config_dir();
     let data_dir = get_data_dir();
-    let config_path = config_dir.join(STATE_FILE);
     let theme_path = data_dir.join("themes");
-    let json = std::fs::read_to_string(config_path).unwrap_or(String::default());
     tracing::info!("Load themes...");
-    let state = serde_json::from_str::<State>(&json).unwrap_or_default();
-    if let Err(err) = ThemeRegistry::watch_dir(PathBuf::from(theme_path), cx, move |cx| {
-        if let Some(theme) = ThemeRegistry::global(cx)
-            .themes()
-            .get(&state.theme)
-            .cloned()
-        {
+    let is_fresh_install = config::find(&config_dir, STATE_NAME).is_none();
+    let mut state = load_state().unwrap_or_else(|err| {
+        recovery::recover_corrupted(&state_path(), err, reload_state, cx);
+        State::default()
+    });
+    if is_fresh_install && let Some(theme) = platform_default_theme(&theme_path) {
+        tracing::info!(
+            "Use the default theme for {}: {}",
+            std::env::consts::OS,
+            theme
+        );
+        state.theme = theme;
+    }
+    let env_override = EnvOverride::from_env();
+    if let Some(theme) = &env_override.theme {
+        tracing::info!("Use the theme from the environment: {}", theme);
+        state.theme = theme.clone();
+    }
+    let env_mode = Cell::new(env_override.mode);
+    cx.set_global(env_override);
+    cx.set_global(ThemeDiagnostics::default());
+    // The saved theme on the first load, then whichever theme is active when a file changes.
+    let initial_theme = Cell::new(Some(state.theme.clone()));
+    let watch_path = theme_path.clone();
+    let apply_themes = Rc::new(move |cx: &mut App| {
+        let theme_name = initial_theme
+            .take()
+            .unwrap_or_else(|| cx.theme().theme_name().clone());
+        check_theme_files(&watch_path, &theme_name, cx);
+        if let Some(theme) = ThemeRegistry::global(cx).themes().get(&theme_name).cloned() {
             Theme::global_mut(cx).apply_config(&theme);
         }
+        if let Some(mode) = env_mode.take() {
+            Theme::change(mode, None, cx);
+        }
+        cx.refresh_windows();
+    });
+    if safe_mode::enabled() {
+        // Stay on the built-in theme, a broken user theme may be why the app crashed.
+        tracing::info!("Safe mode, the user themes are not loaded");
+    } else if let Err(err) = ThemeRegistry::watch_dir(PathBuf::from(theme_path), cx, move |cx| {
+        // Edits made while every window is hidden are applied once one shows up again.
+        let apply_themes = apply_themes.clone();
+        Visibility::when_visible("themes", move |cx| apply_themes(cx), cx);
     }) {
         tracing::error!("Failed to watch themes directory: {}", err);
     }
@@ -84,34 +441,76 @@ pub fn init(cx: &mut App) {
     }
     cx.refresh_windows();
 
-    cx.observe_global::<Theme>(move |cx| {
+    cx.observe_global::<Theme>(|cx| {
+        let event = ThemeChanged {
+            name: cx.theme().theme_name().clone(),
+            mode: cx.theme().mode,
+        };
+        events::publish(event, cx);
+    })
+    .detach();
+
+    events::subscribe::<ThemeChanged>(cx, |event, cx| {
+        // The saved theme is kept for the next normal start.
+        if cx.global::<EnvOverride>().active || safe_mode::enabled() {
+            return;
+        }
         let state = State {
-            theme: cx.theme().theme_name().clone(),
+            theme: event.name.clone(),
             scrollbar_show: Some(cx.theme().scrollbar_show),
         };
-        let config_path = config_dir.join(STATE_FILE);
 
-        if let Ok(json) = serde_json::to_string_pretty(&state) {
-            // Ignore write errors - if STATE_FILE doesn't exist or can't be written, do nothing
-            let _ = std::fs::write(config_path, json);
-        }
+        Persistence::schedule(STATE_NAME, state, cx);
     })
     .detach();
 
-    cx.on_action(|switch: &SwitchTheme, cx| {
-        let theme_name = switch.0.clone();
-        if let Some(theme_config) = ThemeRegistry::global(cx).themes().get(&theme_name).cloned() {
-            Theme::global_mut(cx).apply_config(&theme_config);
-        }
-        cx.refresh_windows();
-    });
-    cx.on_action(|switch: &SwitchThemeMode, cx| {
-        let mode = switch.0;
-        Theme::change(mode, None, cx);
-        cx.refresh_windows();
+    cx.on_action(|_: &ImportTheme, cx| import_theme(cx));
+    cx.on_action(|_: &ExportTheme, cx| export_theme(cx));
+    cx.on_action(|switch: &SwitchTheme, cx| switch_theme(switch.0.clone(), cx));
+    cx.on_action(|switch: &SwitchThemeMode, cx| switch_mode(switch.0, cx));
+    cx.on_action(|_: &ToggleThemeMode, cx| {
+        let mode = if cx.theme().mode.is_dark() {
+            ThemeMode::Light
+        } else {
+            ThemeMode::Dark
+        };
+        switch_mode(mode, cx);
     });
 }
 
+pub(crate) fn switch_theme(theme_name: SharedString, cx: &mut App) {
+    EnvOverride::clear(cx);
+    if let Some(theme_config) = ThemeRegistry::global(cx).themes().get(&theme_name).cloned() {
+        let old = (cx.theme().theme_name().clone(), cx.theme().mode);
+        Theme::global_mut(cx).apply_config(&theme_config);
+        telemetry::record("theme_switched", [("theme", theme_name.to_string())], cx);
+        record_theme(old, cx);
+    }
+    cx.refresh_windows();
+}
+
+pub(crate) fn switch_mode(mode: ThemeMode, cx: &mut App) {
+    EnvOverride::clear(cx);
+    // Picking a mode stops following the system.
+    if Settings::global(cx).appearance.follow_system {
+        Settings::update(cx, |settings, _| settings.appearance.follow_system = false);
+    }
+    let old = (cx.theme().theme_name().clone(), cx.theme().mode);
+    Theme::change(mode, None, cx);
+    telemetry::record("theme_mode_switched", [("mode", mode.name())], cx);
+    record_theme(old, cx);
+    cx.refresh_windows();
+}
+
+/// Log the switch from the `old` theme and mode to the active ones.
+fn record_theme(old: (SharedString, ThemeMode), cx: &mut App) {
+    let new = (cx.theme().theme_name().clone(), cx.theme().mode);
+    let source = History::source(cx);
+    history::record_theme((&old.0, old.1), (&new.0, new.1), source, cx);
+}
+
+actions!(themes, [ToggleThemeMode, ImportTheme, ExportTheme]);
+
 #[derive(Action, Clone, PartialEq)]
 #[action(namespace = themes, no_json)]
 pub(crate) struct SwitchTheme(pub(crate) SharedString);
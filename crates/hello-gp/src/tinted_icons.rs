@@ -0,0 +1,236 @@
+//! SVG icons colored by the theme, for icons of more than one color.
+//!
+//! Monochrome icons are tinted by the text color already, like [`Icon`](gpui_component::Icon).
+//! An icon here fills its shapes with the colors of the theme instead, by name:
+//!
+//! ```svg
+//! <rect width="24" height="24" rx="6" fill="var(--primary)"/>
+//! <path d="M7 12h10" stroke="currentColor"/>
+//! ```
+//!
+//! `currentColor` is the color of the icon, the foreground unless it's given one.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::anyhow;
+use gpui::{
+    App, Global, Hsla, IntoElement, ParentElement as _, Refineable as _, RenderImage, RenderOnce,
+    SharedString, Size, StyleRefinement, Styled, Window, div, img, px,
+};
+use gpui_component::{ActiveTheme as _, Colorize as _};
+use image::{Frame, RgbaImage};
+use resvg::{tiny_skia, usvg};
+
+use crate::{
+    events::{self, ThemeChanged},
+    icons::Icons,
+    images,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RasterKey {
+    path: SharedString,
+    color: String,
+    size: (u32, u32),
+}
+
+/// The sources of the icons, and their rasterizations per color and size.
+///
+/// The rasterizations are dropped when the theme changes, the colors of the tokens with it.
+#[derive(Default)]
+pub struct TintedIcons {
+    /// `None` for an icon that's missing, or isn't valid SVG.
+    sources: HashMap<SharedString, Option<Arc<str>>>,
+    rasters: HashMap<RasterKey, Option<Arc<RenderImage>>>,
+}
+
+impl Global for TintedIcons {}
+
+impl TintedIcons {
+    fn source(path: &SharedString, cx: &mut App) -> Option<Arc<str>> {
+        if let Some(source) = cx.global::<Self>().sources.get(path) {
+            return source.clone();
+        }
+        let source = match cx.asset_source().load(path) {
+            Ok(Some(bytes)) => String::from_utf8(bytes.into_owned()).ok().map(Arc::from),
+            Ok(None) => None,
+            Err(err) => {
+                tracing::warn!("Missing icon {}: {}", path, err);
+                None
+            }
+        };
+        cx.global_mut::<Self>()
+            .sources
+            .insert(path.clone(), source.clone());
+        source
+    }
+
+    fn raster(
+        path: &SharedString,
+        color: Hsla,
+        size: Size<u32>,
+        cx: &mut App,
+    ) -> Option<Arc<RenderImage>> {
+        let key = RasterKey {
+            path: path.clone(),
+            color: color.to_hex(),
+            size: (size.width, size.height),
+        };
+        if let Some(raster) = cx.global::<Self>().rasters.get(&key) {
+            return raster.clone();
+        }
+        let raster = Self::source(path, cx).and_then(|source| {
+            rasterize(&tint(&source, color, cx), size)
+                .inspect_err(|err| tracing::warn!("Failed to render the icon {}: {}", path, err))
+                .ok()
+        });
+        cx.global_mut::<Self>().rasters.insert(key, raster.clone());
+        raster
+    }
+}
+
+/// The theme color of a token, e.g. `muted-foreground`.
+fn token_color(token: &str, cx: &App) -> Option<Hsla> {
+    let theme = cx.theme();
+    Some(match token {
+        "background" => theme.background,
+        "foreground" => theme.foreground,
+        "muted" => theme.muted,
+        "muted-foreground" => theme.muted_foreground,
+        "primary" => theme.primary,
+        "primary-foreground" => theme.primary_foreground,
+        "secondary" => theme.secondary,
+        "accent" => theme.accent,
+        "accent-foreground" => theme.accent_foreground,
+        "border" => theme.border,
+        "danger" => theme.danger,
+        "warning" => theme.warning,
+        "success" => theme.success,
+        "info" => theme.info,
+        _ => return None,
+    })
+}
+
+/// Replace the `var(--token)`s and `currentColor` of an SVG by colors.
+fn tint(svg: &str, color: Hsla, cx: &App) -> String {
+    const VAR: &str = "var(--";
+
+    let mut tinted = String::with_capacity(svg.len());
+    let mut rest = svg;
+    while let Some(start) = rest.find(VAR) {
+        let after = &rest[start + VAR.len()..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        tinted.push_str(&rest[..start]);
+        match token_color(&after[..end], cx) {
+            Some(color) => tinted.push_str(&color.to_hex()),
+            // Left for the SVG renderer to ignore.
+            None => tinted.push_str(&rest[start..start + VAR.len() + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+    tinted.push_str(rest);
+    tinted.replace("currentColor", &color.to_hex())
+}
+
+/// Render an SVG to fit `size`, centered.
+fn rasterize(svg: &str, size: Size<u32>) -> anyhow::Result<Arc<RenderImage>> {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default())?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width.max(1), size.height.max(1))
+        .ok_or_else(|| anyhow!("invalid icon size {:?}", size))?;
+    let (width, height) = (pixmap.width() as f32, pixmap.height() as f32);
+    let scale = (width / tree.size().width()).min(height / tree.size().height());
+    let transform = tiny_skia::Transform::from_scale(scale, scale).post_translate(
+        (width - tree.size().width() * scale) / 2.,
+        (height - tree.size().height() * scale) / 2.,
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // GPUI expects BGRA.
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let mut buffer = pixmap.take();
+    for pixel in buffer.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let buffer =
+        RgbaImage::from_raw(width, height, buffer).ok_or_else(|| anyhow!("invalid icon buffer"))?;
+    Ok(Arc::new(RenderImage::new(vec![Frame::new(buffer)])))
+}
+
+/// An icon from the active icon pack or the built-in icons, filled with the colors of the
+/// theme and rendered at the size it's shown at.
+///
+/// A missing icon shows a question mark in its place.
+#[derive(IntoElement)]
+pub struct TintedIcon {
+    name: SharedString,
+    color: Option<Hsla>,
+    style: StyleRefinement,
+}
+
+impl TintedIcon {
+    /// The icon `name`, e.g. `"bell"` for `bell.svg`, 16px square unless sized.
+    pub fn new(name: impl Into<SharedString>) -> Self {
+        let mut style = StyleRefinement::default();
+        style.size.width = Some(px(16.).into());
+        style.size.height = Some(px(16.).into());
+        Self {
+            name: name.into(),
+            color: None,
+            style,
+        }
+    }
+
+    /// The color of `currentColor`.
+    pub fn color(mut self, color: Hsla) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Styled for TintedIcon {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for TintedIcon {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let color = self.color.unwrap_or(cx.theme().foreground);
+        let path = Icons::asset_path(&self.name, cx);
+        let raster = images::physical_size(&self.style, window)
+            .and_then(|size| TintedIcons::raster(&path, color, size, cx));
+
+        match raster {
+            Some(raster) => {
+                let mut icon = img(raster);
+                icon.style().refine(&self.style);
+                icon.into_any_element()
+            }
+            None => {
+                let mut fallback = div()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .rounded(px(3.))
+                    .border_1()
+                    .border_color(color.opacity(0.5))
+                    .text_color(color)
+                    .text_xs()
+                    .child("?");
+                fallback.style().refine(&self.style);
+                fallback.into_any_element()
+            }
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(TintedIcons::default());
+
+    events::subscribe::<ThemeChanged>(cx, |_, cx| {
+        cx.global_mut::<TintedIcons>().rasters.clear();
+    })
+    .detach();
+}
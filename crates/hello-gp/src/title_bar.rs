@@ -6,19 +6,29 @@ use gpui::{
     Window, div, px,
 };
 use gpui_component::{
-    ActiveTheme as _, ContextModal as _, IconName, PixelsExt, Sizable as _, Theme, TitleBar,
+    ActiveTheme as _, ContextModal as _, IconName, PixelsExt, Sizable as _, Theme, ThemeMode,
+    TitleBar,
     badge::Badge,
     button::{Button, ButtonVariants as _},
     menu::AppMenuBar,
+    popover::Popover,
     popup_menu::PopupMenuExt as _,
     scroll::ScrollbarShow,
 };
 
-use crate::{SelectFont, SelectRadius, SelectScrollbarShow, app_menus};
+use crate::{
+    SelectFont, SelectRadius, SelectScrollbarShow, app_menus,
+    icons::Icons,
+    notifications::{NotificationCenter, NotificationList},
+    themes::ToggleThemeMode,
+    tooltips::{RichTooltip, WithTooltip},
+    window_frame,
+};
 
 pub struct AppTitleBar {
     app_menu_bar: Entity<AppMenuBar>,
     font_size_selector: Entity<FontSizeSelector>,
+    notification_list: Entity<NotificationList>,
     child: Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>,
     _subscriptions: Vec<Subscription>,
 }
@@ -33,12 +43,16 @@ impl AppTitleBar {
 
         let font_size_selector = cx.new(|cx| FontSizeSelector::new(window, cx));
         let app_menu_bar = AppMenuBar::new(window, cx);
+        let notification_list = cx.new(|cx| NotificationList::new(window, cx));
+        let _subscriptions =
+            vec![cx.observe(&NotificationCenter::global(cx), |_, _, cx| cx.notify())];
 
         Self {
             app_menu_bar,
             font_size_selector,
+            notification_list,
             child: Rc::new(|_, _| div().into_any_element()),
-            _subscriptions: vec![],
+            _subscriptions,
         }
     }
 
@@ -54,40 +68,92 @@ impl AppTitleBar {
 
 impl Render for AppTitleBar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let notifications_count = window.notifications(cx).len();
+        let notification_center = NotificationCenter::global(cx);
+        let unread = notification_center.read(cx).unread();
+        let notification_list = self.notification_list.clone();
 
-        TitleBar::new()
-            // left side
-            .child(div().flex().items_center().child(self.app_menu_bar.clone()))
+        // The window manager's menu on a right click, as on the title bars it draws.
+        div()
+            .flex_shrink_0()
+            .on_mouse_down(MouseButton::Right, |event, window, _| {
+                window_frame::show_window_menu(event.position, window)
+            })
             .child(
-                div()
-                    .flex()
-                    .items_center()
-                    .justify_end()
-                    .px_2()
-                    .gap_2()
-                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
-                    .child((self.child.clone())(window, cx))
-                    .child(self.font_size_selector.clone())
-                    .child(
-                        Button::new("github")
-                            .icon(IconName::GitHub)
-                            .small()
-                            .ghost()
-                            .on_click(|_, _, cx| {
-                                cx.open_url("https://github.com/longbridge/gpui-component")
-                            }),
-                    )
+                TitleBar::new()
+                    // left side
+                    .child(div().flex().items_center().child(self.app_menu_bar.clone()))
                     .child(
-                        div().relative().child(
-                            Badge::new().count(notifications_count).max(99).child(
-                                Button::new("bell")
+                        div()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .px_2()
+                            .gap_2()
+                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                            .child((self.child.clone())(window, cx))
+                            .child(WithTooltip::new(
+                                "theme-mode-tooltip",
+                                RichTooltip::new(match cx.theme().mode {
+                                    ThemeMode::Light => "Switch to Dark Mode",
+                                    ThemeMode::Dark => "Switch to Light Mode",
+                                })
+                                .body("Stops following the light or dark mode of the system.")
+                                .action(&ToggleThemeMode),
+                                Button::new("theme-mode")
                                     .small()
                                     .ghost()
-                                    .compact()
-                                    .icon(IconName::Bell),
-                            ),
-                        ),
+                                    .icon(Icons::get(
+                                        match cx.theme().mode {
+                                            ThemeMode::Light => IconName::Moon,
+                                            ThemeMode::Dark => IconName::Sun,
+                                        },
+                                        cx,
+                                    ))
+                                    .on_click(|_, window, cx| {
+                                        window.dispatch_action(Box::new(ToggleThemeMode), cx)
+                                    }),
+                            ))
+                            .child(WithTooltip::new(
+                                "appearance-tooltip",
+                                RichTooltip::new("Appearance")
+                                    .body("Font size, border radius and scrollbars."),
+                                self.font_size_selector.clone(),
+                            ))
+                            .child(
+                                Button::new("github")
+                                    .icon(Icons::get(IconName::GitHub, cx))
+                                    .small()
+                                    .ghost()
+                                    .on_click(|_, _, cx| {
+                                        cx.open_url("https://github.com/longbridge/gpui-component")
+                                    }),
+                            )
+                            .child(WithTooltip::new(
+                                "notifications-tooltip",
+                                RichTooltip::new("Notifications").body(match unread {
+                                    0 => "Nothing new.".to_string(),
+                                    1 => "1 unread notification.".to_string(),
+                                    unread => format!("{} unread notifications.", unread),
+                                }),
+                                div().relative().child(
+                                    Badge::new().count(unread).max(99).child(
+                                        Popover::new("notification-center")
+                                            .anchor(Corner::TopRight)
+                                            .trigger(
+                                                Button::new("bell")
+                                                    .small()
+                                                    .ghost()
+                                                    .compact()
+                                                    .icon(Icons::get(IconName::Bell, cx)),
+                                            )
+                                            .content(move |_, cx| {
+                                                notification_center
+                                                    .update(cx, |center, cx| center.mark_read(cx));
+                                                notification_list.clone()
+                                            }),
+                                    ),
+                                ),
+                            )),
                     ),
             )
     }
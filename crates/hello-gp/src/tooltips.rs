@@ -0,0 +1,144 @@
+//! Tooltips with a title, a line of detail and the shortcut of what the element does, shown on
+//! hover after a delay, and right away when the element has keyboard focus.
+
+use std::rc::Rc;
+
+use gpui::{
+    Action, AnyElement, AnyView, App, AppContext as _, ElementId, InteractiveElement as _,
+    IntoElement, ParentElement as _, Render, RenderOnce, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Window, anchored, deferred, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme as _, Kbd, h_flex, v_flex};
+
+use crate::dismiss::Dismiss;
+
+/// The content of a tooltip, colored by the `popover` tokens of the theme.
+#[derive(Clone)]
+pub struct RichTooltip {
+    title: SharedString,
+    body: Option<SharedString>,
+    action: Option<Rc<dyn Action>>,
+}
+
+impl RichTooltip {
+    pub fn new(title: impl Into<SharedString>) -> Self {
+        Self {
+            title: title.into(),
+            body: None,
+            action: None,
+        }
+    }
+
+    pub fn body(mut self, body: impl Into<SharedString>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Show the key binding of `action`, when it has one.
+    pub fn action(mut self, action: &dyn Action) -> Self {
+        self.action = Some(Rc::from(action.boxed_clone()));
+        self
+    }
+
+    /// Build the view for [`StatefulInteractiveElement::tooltip`].
+    pub fn build(self, _: &mut Window, cx: &mut App) -> AnyView {
+        cx.new(|_| TooltipView(self)).into()
+    }
+
+    fn render_content(&self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let binding = self
+            .action
+            .as_ref()
+            .and_then(|action| Kbd::binding_for_action(action.as_ref(), None, window));
+
+        v_flex()
+            .max_w(px(320.))
+            .gap_1()
+            .py_1p5()
+            .px_2()
+            .rounded(cx.theme().radius)
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().popover)
+            .text_color(cx.theme().popover_foreground)
+            .shadow_md()
+            .child(
+                h_flex()
+                    .gap_3()
+                    .justify_between()
+                    .text_sm()
+                    .font_semibold()
+                    .child(self.title.clone())
+                    .children(binding),
+            )
+            .when_some(self.body.clone(), |this, body| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(body),
+                )
+            })
+    }
+}
+
+struct TooltipView(RichTooltip);
+
+impl Render for TooltipView {
+    fn render(&mut self, window: &mut Window, cx: &mut gpui::Context<Self>) -> impl IntoElement {
+        // Clear of the pointer.
+        div().pl_2().pt_2().child(self.0.render_content(window, cx))
+    }
+}
+
+/// An element with a [`RichTooltip`], which can take keyboard focus to show it.
+#[derive(IntoElement)]
+pub struct WithTooltip {
+    id: ElementId,
+    tooltip: RichTooltip,
+    child: AnyElement,
+}
+
+impl WithTooltip {
+    pub fn new(id: impl Into<ElementId>, tooltip: RichTooltip, child: impl IntoElement) -> Self {
+        Self {
+            id: id.into(),
+            tooltip,
+            child: child.into_any_element(),
+        }
+    }
+}
+
+impl RenderOnce for WithTooltip {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let focus_handle = window
+            .use_keyed_state(self.id.clone(), cx, |_, cx| {
+                cx.focus_handle().tab_stop(true)
+            })
+            .read(cx)
+            .clone();
+        // Focused by the keyboard, a click focuses what's inside instead.
+        let focused = focus_handle.is_focused(window);
+        let tooltip = self.tooltip.clone();
+
+        div()
+            .id(self.id)
+            .track_focus(&focus_handle)
+            .relative()
+            .tooltip(move |window, cx| tooltip.clone().build(window, cx))
+            .child(self.child)
+            .when(focused, |this| {
+                // The topmost surface, escape hides it first by moving the focus off it.
+                let this = this.on_action(|_: &Dismiss, window, _| window.blur());
+                let content = self.tooltip.render_content(window, cx);
+                this.child(
+                    div().absolute().top_full().left_0().child(deferred(
+                        anchored()
+                            .snap_to_window()
+                            .child(div().mt_1().child(content)),
+                    )),
+                )
+            })
+    }
+}
@@ -0,0 +1,579 @@
+//! A tree of nodes whose children load when they're first expanded, with the expanded nodes
+//! remembered per tree id across restarts.
+
+use std::collections::{BTreeSet, HashMap};
+
+use gpui::{
+    App, ClickEvent, Context, EventEmitter, FocusHandle, Focusable, InteractiveElement as _,
+    IntoElement, KeyBinding, ParentElement as _, Render, ScrollHandle, SharedString,
+    StatefulInteractiveElement as _, Styled as _, Subscription, Task, Window, actions, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme as _, Icon, IconName, Sizable as _, h_flex, v_flex};
+
+use crate::{
+    cursor::{CursorExt as _, CursorRole},
+    dismiss::{DismissLayer, DismissStack},
+    session::Session,
+    skeletons::{Loadable, Shimmer},
+};
+
+actions!(
+    tree_view,
+    [
+        SelectPrev,
+        SelectNext,
+        ExtendPrev,
+        ExtendNext,
+        Collapse,
+        Expand,
+        ToggleSelected,
+        Confirm
+    ]
+);
+
+const CONTEXT: &str = "TreeView";
+/// How far each level is indented.
+const INDENT: f32 = 16.;
+
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    /// Identifies the node in the saved state of the tree, keep it stable, e.g. a path.
+    pub id: SharedString,
+    pub label: SharedString,
+    pub icon: Option<IconName>,
+    /// Whether the node can be expanded, before its children are loaded.
+    pub has_children: bool,
+}
+
+impl TreeNode {
+    pub fn new(id: impl Into<SharedString>, label: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            icon: None,
+            has_children: false,
+        }
+    }
+
+    pub fn icon(mut self, icon: IconName) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_children(mut self) -> Self {
+        self.has_children = true;
+        self
+    }
+}
+
+/// The nodes of a [`TreeView`].
+pub trait TreeDelegate: 'static {
+    fn roots(&mut self, cx: &mut App) -> Vec<TreeNode>;
+
+    /// The children of `node`, asked for when it's first expanded, e.g. reading a directory in
+    /// the background.
+    fn children(&mut self, node: &TreeNode, cx: &mut App) -> Task<anyhow::Result<Vec<TreeNode>>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeViewEvent {
+    /// The selected nodes changed, the node under the cursor is the last.
+    Select(Vec<SharedString>),
+    /// Enter, or a double click on a node without children.
+    Confirm(SharedString),
+}
+
+/// A row of the tree as shown, the nodes of the expanded nodes in order.
+enum TreeRow {
+    Node(TreeNode, usize),
+    Loading(SharedString, usize),
+    Failed(SharedString, usize),
+}
+
+/// Nodes in a tree, expanded and collapsed by click or the arrow keys, and selected with
+/// cmd/ctrl or shift to pick more than one.
+pub struct TreeView<D: TreeDelegate> {
+    id: SharedString,
+    focus_handle: FocusHandle,
+    delegate: D,
+    roots: Vec<TreeNode>,
+    children: HashMap<SharedString, Loadable<Vec<TreeNode>>>,
+    expanded: BTreeSet<SharedString>,
+    selected: BTreeSet<SharedString>,
+    /// The node the keyboard moves from, and shift selects from.
+    cursor: Option<SharedString>,
+    anchor: Option<SharedString>,
+    scroll_handle: ScrollHandle,
+    _dismiss: Subscription,
+}
+
+impl<D: TreeDelegate> EventEmitter<TreeViewEvent> for TreeView<D> {}
+
+impl<D: TreeDelegate> TreeView<D> {
+    /// `id` identifies the tree in the session, keep it stable.
+    pub fn new(
+        id: impl Into<SharedString>,
+        mut delegate: D,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let id = id.into();
+        let expanded = Session::global(cx)
+            .trees
+            .get(id.as_ref())
+            .map(|state| state.expanded.iter().cloned().map(Into::into).collect())
+            .unwrap_or_default();
+        let roots = delegate.roots(cx);
+        let view = cx.weak_entity();
+        let _dismiss =
+            DismissStack::register(DismissLayer::Selection, window, cx, move |window, cx| {
+                view.update(cx, |this, cx| this.clear_selection(window, cx))
+                    .unwrap_or(false)
+            });
+
+        let mut this = Self {
+            id,
+            focus_handle: cx.focus_handle(),
+            delegate,
+            roots,
+            children: HashMap::new(),
+            expanded,
+            selected: BTreeSet::new(),
+            cursor: None,
+            anchor: None,
+            scroll_handle: ScrollHandle::new(),
+            _dismiss,
+        };
+        this.load_expanded(cx);
+        this
+    }
+
+    pub fn delegate(&self) -> &D {
+        &self.delegate
+    }
+
+    /// Ask the delegate for the nodes again, e.g. after the files changed.
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        self.roots = self.delegate.roots(cx);
+        self.children.clear();
+        self.load_expanded(cx);
+        cx.notify();
+    }
+
+    pub fn selected(&self) -> impl Iterator<Item = &SharedString> {
+        self.selected.iter()
+    }
+
+    /// Load the children of the expanded nodes in view, that aren't loaded yet.
+    fn load_expanded(&mut self, cx: &mut Context<Self>) {
+        let pending = self
+            .rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                TreeRow::Node(node, _)
+                    if node.has_children
+                        && self.expanded.contains(&node.id)
+                        && !self.children.contains_key(&node.id) =>
+                {
+                    Some(node)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for node in pending {
+            self.load_children(node, cx);
+        }
+    }
+
+    fn load_children(&mut self, node: TreeNode, cx: &mut Context<Self>) {
+        self.children.insert(node.id.clone(), Loadable::Loading);
+        let task = self.delegate.children(&node, cx);
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                this.children.insert(node.id, result.into());
+                // Expanded descendants from the last run load in turn.
+                this.load_expanded(cx);
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
+    /// The rows of the nodes in view, in order.
+    fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        for node in &self.roots {
+            self.push_rows(node, 0, &mut rows);
+        }
+        rows
+    }
+
+    fn push_rows(&self, node: &TreeNode, depth: usize, rows: &mut Vec<TreeRow>) {
+        rows.push(TreeRow::Node(node.clone(), depth));
+        if !node.has_children || !self.expanded.contains(&node.id) {
+            return;
+        }
+        match self.children.get(&node.id) {
+            Some(Loadable::Loaded(children)) => {
+                for child in children {
+                    self.push_rows(child, depth + 1, rows);
+                }
+            }
+            Some(Loadable::Failed(err)) => rows.push(TreeRow::Failed(err.clone(), depth + 1)),
+            Some(Loadable::Loading) | None => {
+                rows.push(TreeRow::Loading(node.id.clone(), depth + 1))
+            }
+        }
+    }
+
+    /// The nodes in view with their depth, in order.
+    fn visible_nodes(&self) -> Vec<(TreeNode, usize)> {
+        self.rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                TreeRow::Node(node, depth) => Some((node, depth)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn set_expanded(&mut self, node: &TreeNode, expanded: bool, cx: &mut Context<Self>) {
+        if !node.has_children || self.expanded.contains(&node.id) == expanded {
+            return;
+        }
+        if expanded {
+            self.expanded.insert(node.id.clone());
+            if !self.children.contains_key(&node.id) {
+                self.load_children(node.clone(), cx);
+            }
+        } else {
+            self.expanded.remove(&node.id);
+        }
+
+        let id = self.id.to_string();
+        let expanded = self.expanded.iter().map(|id| id.to_string()).collect();
+        Session::update(cx, |session| {
+            session.trees.entry(id).or_default().expanded = expanded
+        });
+        cx.notify();
+    }
+
+    fn move_cursor(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        if let Some(ix) = self
+            .rows()
+            .iter()
+            .position(|row| matches!(row, TreeRow::Node(node, _) if node.id == id))
+        {
+            self.scroll_handle.scroll_to_item(ix);
+        }
+        self.cursor = Some(id);
+        self.emit_selection(cx);
+    }
+
+    fn emit_selection(&mut self, cx: &mut Context<Self>) {
+        let mut selected = self
+            .selected
+            .iter()
+            .filter(|id| Some(*id) != self.cursor.as_ref())
+            .cloned()
+            .collect::<Vec<_>>();
+        selected.extend(
+            self.cursor
+                .clone()
+                .filter(|cursor| self.selected.contains(cursor)),
+        );
+        cx.emit(TreeViewEvent::Select(selected));
+        cx.notify();
+    }
+
+    /// Clear the selection by escape, while the tree is focused. Whether there was one.
+    fn clear_selection(&mut self, window: &Window, cx: &mut Context<Self>) -> bool {
+        if self.selected.is_empty() || !self.focus_handle.contains_focused(window, cx) {
+            return false;
+        }
+        self.selected.clear();
+        self.anchor = None;
+        self.emit_selection(cx);
+        true
+    }
+
+    /// Select only `id`.
+    pub fn select(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        self.selected = BTreeSet::from([id.clone()]);
+        self.anchor = Some(id.clone());
+        self.move_cursor(id, cx);
+    }
+
+    /// Select the nodes in view from the anchor to `id`.
+    fn select_range(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        let nodes = self.visible_nodes();
+        let position = |id: &SharedString| nodes.iter().position(|(node, _)| &node.id == id);
+        let (Some(from), Some(to)) = (self.anchor.as_ref().and_then(position), position(&id))
+        else {
+            return self.select(id, cx);
+        };
+        self.selected = nodes[from.min(to)..=from.max(to)]
+            .iter()
+            .map(|(node, _)| node.id.clone())
+            .collect();
+        self.move_cursor(id, cx);
+    }
+
+    fn toggle_selected_id(&mut self, id: SharedString, cx: &mut Context<Self>) {
+        if !self.selected.remove(&id) {
+            self.selected.insert(id.clone());
+        }
+        self.anchor = Some(id.clone());
+        self.move_cursor(id, cx);
+    }
+
+    /// The node `delta` nodes away from the cursor, the first or last one without a cursor.
+    fn step(&self, delta: isize) -> Option<(TreeNode, usize)> {
+        let nodes = self.visible_nodes();
+        if nodes.is_empty() {
+            return None;
+        }
+        let ix = match self
+            .cursor
+            .as_ref()
+            .and_then(|cursor| nodes.iter().position(|(node, _)| &node.id == cursor))
+        {
+            Some(ix) => (ix as isize + delta).clamp(0, nodes.len() as isize - 1) as usize,
+            None if delta < 0 => nodes.len() - 1,
+            None => 0,
+        };
+        nodes.into_iter().nth(ix)
+    }
+
+    fn cursor_node(&self) -> Option<(TreeNode, usize)> {
+        let cursor = self.cursor.as_ref()?;
+        self.visible_nodes()
+            .into_iter()
+            .find(|(node, _)| &node.id == cursor)
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((node, _)) = self.step(-1) {
+            self.select(node.id, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((node, _)) = self.step(1) {
+            self.select(node.id, cx);
+        }
+    }
+
+    fn extend_prev(&mut self, _: &ExtendPrev, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((node, _)) = self.step(-1) {
+            self.select_range(node.id, cx);
+        }
+    }
+
+    fn extend_next(&mut self, _: &ExtendNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some((node, _)) = self.step(1) {
+            self.select_range(node.id, cx);
+        }
+    }
+
+    /// Collapse the node under the cursor, or move to its parent.
+    fn collapse(&mut self, _: &Collapse, _: &mut Window, cx: &mut Context<Self>) {
+        let Some((node, depth)) = self.cursor_node() else {
+            return;
+        };
+        if self.expanded.contains(&node.id) {
+            self.set_expanded(&node, false, cx);
+        } else if depth > 0 {
+            let nodes = self.visible_nodes();
+            let ix = nodes.iter().position(|(n, _)| n.id == node.id).unwrap_or(0);
+            if let Some((parent, _)) = nodes[..ix].iter().rev().find(|(_, d)| *d < depth) {
+                self.select(parent.id.clone(), cx);
+            }
+        }
+    }
+
+    /// Expand the node under the cursor, or move to its first child.
+    fn expand(&mut self, _: &Expand, _: &mut Window, cx: &mut Context<Self>) {
+        let Some((node, _)) = self.cursor_node() else {
+            return;
+        };
+        if !self.expanded.contains(&node.id) {
+            self.set_expanded(&node, true, cx);
+        } else if let Some((child, _)) = self.step(1)
+            && child.id != node.id
+        {
+            self.select(child.id, cx);
+        }
+    }
+
+    fn toggle_selected(&mut self, _: &ToggleSelected, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cursor) = self.cursor.clone() {
+            self.toggle_selected_id(cursor, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(cursor) = self.cursor.clone() {
+            cx.emit(TreeViewEvent::Confirm(cursor));
+        }
+    }
+
+    fn on_click_node(
+        &mut self,
+        node: &TreeNode,
+        event: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle);
+        let modifiers = event.modifiers();
+        if modifiers.shift {
+            self.select_range(node.id.clone(), cx);
+        } else if modifiers.secondary() {
+            self.toggle_selected_id(node.id.clone(), cx);
+        } else {
+            self.select(node.id.clone(), cx);
+            if event.click_count() >= 2 {
+                if node.has_children {
+                    let expanded = self.expanded.contains(&node.id);
+                    self.set_expanded(node, !expanded, cx);
+                } else {
+                    cx.emit(TreeViewEvent::Confirm(node.id.clone()));
+                }
+            }
+        }
+    }
+
+    fn render_row(
+        &self,
+        ix: usize,
+        row: TreeRow,
+        focused: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (depth, content) = match row {
+            TreeRow::Node(node, depth) => {
+                let selected = self.selected.contains(&node.id);
+                let at_cursor = self.cursor.as_ref() == Some(&node.id);
+                let expanded = self.expanded.contains(&node.id);
+                let disclosure = node.has_children.then(|| {
+                    div()
+                        .id(("tree-disclosure", ix))
+                        .child(
+                            Icon::new(if expanded {
+                                IconName::ChevronDown
+                            } else {
+                                IconName::ChevronRight
+                            })
+                            .xsmall(),
+                        )
+                        .on_click(cx.listener({
+                            let node = node.clone();
+                            move |this, _, _, cx| {
+                                cx.stop_propagation();
+                                this.set_expanded(&node, !expanded, cx);
+                            }
+                        }))
+                });
+
+                let content = h_flex()
+                    .id(("tree-node", ix))
+                    .flex_1()
+                    .h(px(26.))
+                    .gap_1()
+                    .px_1()
+                    .rounded(cx.theme().radius)
+                    .cursor_role(CursorRole::Pointer, cx)
+                    .text_sm()
+                    .when(selected, |this| this.bg(cx.theme().accent))
+                    .when(at_cursor && focused, |this| {
+                        this.border_1().border_color(cx.theme().ring)
+                    })
+                    .child(div().w(px(INDENT)).flex_shrink_0().children(disclosure))
+                    .when_some(node.icon.clone(), |this, icon| {
+                        this.child(Icon::new(icon).small())
+                    })
+                    .child(node.label.clone())
+                    .on_click(cx.listener(move |this, event, window, cx| {
+                        this.on_click_node(&node, event, window, cx)
+                    }))
+                    .into_any_element();
+                (depth, content)
+            }
+            TreeRow::Loading(id, depth) => (
+                depth,
+                h_flex()
+                    .h(px(26.))
+                    .pl(px(INDENT))
+                    .child(
+                        Shimmer::new(SharedString::from(format!("tree-loading-{}", id)))
+                            .h(px(12.))
+                            .w(px(120.)),
+                    )
+                    .into_any_element(),
+            ),
+            TreeRow::Failed(err, depth) => (
+                depth,
+                div()
+                    .h(px(26.))
+                    .pl(px(INDENT))
+                    .text_xs()
+                    .text_color(cx.theme().danger)
+                    .child(err)
+                    .into_any_element(),
+            ),
+        };
+
+        h_flex().pl(px(INDENT * depth as f32)).child(content)
+    }
+}
+
+impl<D: TreeDelegate> Focusable for TreeView<D> {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<D: TreeDelegate> Render for TreeView<D> {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focused = self.focus_handle.is_focused(window);
+        let rows = self
+            .rows()
+            .into_iter()
+            .enumerate()
+            .map(|(ix, row)| self.render_row(ix, row, focused, cx).into_any_element())
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id(self.id.clone())
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::extend_prev))
+            .on_action(cx.listener(Self::extend_next))
+            .on_action(cx.listener(Self::collapse))
+            .on_action(cx.listener(Self::expand))
+            .on_action(cx.listener(Self::toggle_selected))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .children(rows)
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("shift-up", ExtendPrev, Some(CONTEXT)),
+        KeyBinding::new("shift-down", ExtendNext, Some(CONTEXT)),
+        KeyBinding::new("left", Collapse, Some(CONTEXT)),
+        KeyBinding::new("right", Expand, Some(CONTEXT)),
+        KeyBinding::new("space", ToggleSelected, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm, Some(CONTEXT)),
+    ]);
+}
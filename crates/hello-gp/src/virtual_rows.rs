@@ -0,0 +1,320 @@
+//! A list that only renders the rows in view, for collections of thousands of rows.
+
+use std::{ops::Range, rc::Rc};
+
+use gpui::{
+    AnyElement, App, ClickEvent, Context, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, KeyBinding, ParentElement as _, Pixels, Render,
+    ScrollStrategy, Size, StatefulInteractiveElement as _, Styled as _, Window, actions, div,
+    prelude::FluentBuilder as _, px, size,
+};
+use gpui_component::{
+    ActiveTheme as _, VirtualListScrollHandle,
+    scroll::{Scrollbar, ScrollbarState},
+    v_virtual_list,
+};
+
+actions!(
+    virtual_rows,
+    [
+        SelectPrev,
+        SelectNext,
+        SelectPageUp,
+        SelectPageDown,
+        SelectFirst,
+        SelectLast,
+        Confirm
+    ]
+);
+
+const CONTEXT: &str = "VirtualRows";
+
+/// The rows of a [`VirtualRows`].
+pub trait RowsDelegate: 'static {
+    fn rows_count(&self, cx: &App) -> usize;
+
+    fn row_height(&self, _ix: usize, _cx: &App) -> Pixels {
+        px(28.)
+    }
+
+    /// Whether the row heads the rows after it, up to the next header.
+    ///
+    /// The header of the rows in view sticks to the top of the list, and headers are skipped
+    /// when selecting.
+    fn is_header(&self, _ix: usize, _cx: &App) -> bool {
+        false
+    }
+
+    fn render_row(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> AnyElement;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirtualRowsEvent {
+    Select(usize),
+    /// Enter, or a double click on a row.
+    Confirm(usize),
+}
+
+/// Renders only the rows of its delegate in view, with a selection moved by the keyboard.
+///
+/// Call [`VirtualRows::refresh`] after the rows of the delegate change.
+pub struct VirtualRows<D: RowsDelegate> {
+    focus_handle: FocusHandle,
+    delegate: D,
+    row_sizes: Rc<Vec<Size<Pixels>>>,
+    scroll_handle: VirtualListScrollHandle,
+    scroll_state: ScrollbarState,
+    selected: Option<usize>,
+    visible_range: Range<usize>,
+}
+
+impl<D: RowsDelegate> EventEmitter<VirtualRowsEvent> for VirtualRows<D> {}
+
+impl<D: RowsDelegate> VirtualRows<D> {
+    pub fn new(delegate: D, _: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            delegate,
+            row_sizes: Rc::default(),
+            scroll_handle: VirtualListScrollHandle::new(),
+            scroll_state: ScrollbarState::default(),
+            selected: None,
+            visible_range: 0..0,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    pub fn delegate(&self) -> &D {
+        &self.delegate
+    }
+
+    pub fn delegate_mut(&mut self) -> &mut D {
+        &mut self.delegate
+    }
+
+    /// Pick up the rows of the delegate after they changed.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        let count = self.delegate.rows_count(cx);
+        self.row_sizes = Rc::new(
+            (0..count)
+                .map(|ix| size(px(1.), self.delegate.row_height(ix, cx)))
+                .collect(),
+        );
+        if self.selected.is_some_and(|ix| ix >= count) {
+            self.selected = None;
+        }
+        cx.notify();
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select a row and scroll it into view, `None` to clear the selection.
+    pub fn set_selected_index(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected = ix;
+        if let Some(ix) = ix {
+            self.scroll_into_view(ix);
+            cx.emit(VirtualRowsEvent::Select(ix));
+        }
+        cx.notify();
+    }
+
+    fn scroll_into_view(&self, ix: usize) {
+        // The first row in view may be under the sticky header.
+        if ix <= self.visible_range.start || ix + 1 >= self.visible_range.end {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+    }
+
+    /// The header of the rows in view, which sticks to the top.
+    fn sticky_header(&self, cx: &App) -> Option<usize> {
+        if self.visible_range.is_empty() {
+            return None;
+        }
+        (0..=self.visible_range.start)
+            .rev()
+            .find(|ix| self.delegate.is_header(*ix, cx))
+    }
+
+    /// The selectable row `delta` rows away from the selection, the other way around past the
+    /// headers at either end.
+    fn step(&self, delta: isize, cx: &App) -> Option<usize> {
+        let count = self.row_sizes.len();
+        if count == 0 {
+            return None;
+        }
+        // Without a selection, down starts at the first row and up at the last.
+        let from = match self.selected {
+            Some(ix) => ix as isize,
+            None if delta < 0 => count as isize,
+            None => -1,
+        };
+        let target = (from + delta).clamp(0, count as isize - 1) as usize;
+        let selectable = |ix: &usize| !self.delegate.is_header(*ix, cx);
+        let (forward, backward) = (
+            (target..count).find(selectable),
+            (0..=target).rev().find(selectable),
+        );
+        if delta < 0 {
+            backward.or(forward)
+        } else {
+            forward.or(backward)
+        }
+    }
+
+    fn select_step(&mut self, delta: isize, cx: &mut Context<Self>) {
+        if let Some(ix) = self.step(delta, cx) {
+            self.set_selected_index(Some(ix), cx);
+        }
+    }
+
+    fn page(&self) -> isize {
+        self.visible_range.len().saturating_sub(1).max(1) as isize
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(-1, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(1, cx);
+    }
+
+    fn select_page_up(&mut self, _: &SelectPageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(-self.page(), cx);
+    }
+
+    fn select_page_down(&mut self, _: &SelectPageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(self.page(), cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(isize::MIN / 2, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_step(isize::MAX / 2, cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected {
+            cx.emit(VirtualRowsEvent::Confirm(ix));
+        }
+    }
+
+    fn render_rows(
+        &mut self,
+        visible_range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        if visible_range.start != self.visible_range.start {
+            // The sticky header follows on the next frame.
+            cx.notify();
+        }
+        self.visible_range = visible_range.clone();
+
+        visible_range
+            .map(|ix| {
+                let header = self.delegate.is_header(ix, cx);
+                let selected = self.selected == Some(ix);
+                let row = self.delegate.render_row(ix, selected, window, cx);
+                div()
+                    .id(("virtual-row", ix))
+                    .w_full()
+                    .h(self.row_sizes[ix].height)
+                    .child(row)
+                    .when(!header, |this| {
+                        this.on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                            window.focus(&this.focus_handle);
+                            this.set_selected_index(Some(ix), cx);
+                            if event.click_count() >= 2 {
+                                cx.emit(VirtualRowsEvent::Confirm(ix));
+                            }
+                        }))
+                    })
+                    .into_any_element()
+            })
+            .collect()
+    }
+}
+
+impl<D: RowsDelegate> Focusable for VirtualRows<D> {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl<D: RowsDelegate> Render for VirtualRows<D> {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let sticky_header = self.sticky_header(cx).map(|ix| {
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .h(self.row_sizes[ix].height)
+                .bg(cx.theme().background)
+                .child(self.delegate.render_row(ix, false, window, cx))
+        });
+
+        div()
+            .id("virtual-rows")
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_page_up))
+            .on_action(cx.listener(Self::select_page_down))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .relative()
+            .size_full()
+            .child(
+                v_virtual_list(
+                    cx.entity().clone(),
+                    "virtual-rows-list",
+                    self.row_sizes.clone(),
+                    Self::render_rows,
+                )
+                .track_scroll(&self.scroll_handle)
+                .size_full(),
+            )
+            .children(sticky_header)
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .right_0()
+                    .bottom_0()
+                    .child(Scrollbar::vertical(&self.scroll_state, &self.scroll_handle)),
+            )
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", SelectPrev, Some(CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("pageup", SelectPageUp, Some(CONTEXT)),
+        KeyBinding::new("pagedown", SelectPageDown, Some(CONTEXT)),
+        KeyBinding::new("home", SelectFirst, Some(CONTEXT)),
+        KeyBinding::new("end", SelectLast, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-up", SelectFirst, Some(CONTEXT)),
+        #[cfg(target_os = "macos")]
+        KeyBinding::new("cmd-down", SelectLast, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm, Some(CONTEXT)),
+    ]);
+}
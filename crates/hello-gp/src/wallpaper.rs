@@ -0,0 +1,170 @@
+use std::{path::PathBuf, sync::Arc};
+
+use gpui::{
+    App, AppContext as _, Context, Entity, Global, Hsla, RenderImage, SharedString, Size, Task,
+};
+use gpui_component::{ActiveTheme as _, Colorize as _};
+use serde::Deserialize;
+
+use crate::{
+    events::{self, ThemeChanged},
+    images::{ImageRequest, Images},
+    renderer,
+    themes::get_data_dir,
+};
+
+/// A background image declared by a theme, next to its colors:
+///
+/// ```json
+/// { "name": "Forest", "mode": "dark", "background": { "image": "forest.jpg", "blur": 8, "tint": "#00000080" } }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WallpaperSpec {
+    /// A path relative to the themes dir, or an `http(s)` URL (cached by
+    /// [`Net`](crate::net::Net)).
+    pub image: String,
+    /// Gaussian blur sigma, in image pixels.
+    #[serde(default)]
+    pub blur: f32,
+    /// A color drawn over the image, usually translucent, as `#rrggbbaa`.
+    #[serde(default)]
+    pub tint: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ThemeFileBackgrounds {
+    themes: Vec<ThemeBackground>,
+}
+
+#[derive(Deserialize)]
+struct ThemeBackground {
+    name: SharedString,
+    #[serde(default)]
+    background: Option<WallpaperSpec>,
+}
+
+struct GlobalWallpaper(Entity<Wallpaper>);
+
+impl Global for GlobalWallpaper {}
+
+/// The background image of the active theme, decoded and downscaled off the main thread.
+pub struct Wallpaper {
+    spec: Option<WallpaperSpec>,
+    image: Option<Arc<RenderImage>>,
+    load_task: Option<Task<()>>,
+}
+
+impl Wallpaper {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalWallpaper>().0.clone()
+    }
+
+    pub fn image(&self) -> Option<Arc<RenderImage>> {
+        self.image.clone()
+    }
+
+    pub fn tint(&self) -> Option<Hsla> {
+        Hsla::parse_hex(self.spec.as_ref()?.tint.as_deref()?).ok()
+    }
+
+    fn themes_dir() -> PathBuf {
+        get_data_dir().join("themes")
+    }
+
+    fn find_spec(theme: &SharedString) -> Option<WallpaperSpec> {
+        let entries = std::fs::read_dir(Self::themes_dir()).ok()?;
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .find_map(|path| {
+                let json = std::fs::read_to_string(path).ok()?;
+                let file = serde_json::from_str::<ThemeFileBackgrounds>(&json).ok()?;
+                file.themes
+                    .into_iter()
+                    .find(|entry| &entry.name == theme)?
+                    .background
+            })
+    }
+
+    /// The size to downscale to, so a huge image doesn't stay in memory at full resolution.
+    fn target_size(cx: &App) -> Size<u32> {
+        let size = cx
+            .displays()
+            .iter()
+            .map(|display| display.bounds().size)
+            .max_by(|a, b| {
+                let area = |size: &Size<gpui::Pixels>| size.width.as_f32() * size.height.as_f32();
+                area(a).total_cmp(&area(b))
+            })
+            .unwrap_or(gpui::size(gpui::px(1920.), gpui::px(1080.)));
+        // Display bounds are logical pixels, leave room for a 2x scale factor.
+        gpui::size(
+            (size.width.as_f32() * 2.) as u32,
+            (size.height.as_f32() * 2.) as u32,
+        )
+    }
+
+    fn update_theme(&mut self, theme: SharedString, cx: &mut Context<Self>) {
+        let spec = Self::find_spec(&theme).filter(|_| !renderer::reduced_effects());
+        // Already loaded, or loading.
+        if spec == self.spec && (spec.is_none() || self.image.is_some() || self.load_task.is_some())
+        {
+            return;
+        }
+
+        self.spec = spec.clone();
+        self.image = None;
+        self.load_task = None;
+        cx.notify();
+
+        let Some(spec) = spec else {
+            return;
+        };
+
+        let source = if spec.image.starts_with("http://") || spec.image.starts_with("https://") {
+            spec.image.clone()
+        } else {
+            Self::themes_dir()
+                .join(&spec.image)
+                .to_string_lossy()
+                .to_string()
+        };
+        let request = ImageRequest::new(source)
+            .max_size(Self::target_size(cx))
+            .blur(spec.blur);
+        let image = Images::load(request, cx);
+
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let image = image.await;
+            _ = this.update(cx, |this, cx| {
+                match image {
+                    Ok(image) => this.image = Some(image),
+                    Err(err) => tracing::error!("Failed to load wallpaper {}: {}", spec.image, err),
+                }
+                cx.notify();
+            });
+        }));
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let wallpaper = cx.new(|cx| {
+        let mut this = Wallpaper {
+            spec: None,
+            image: None,
+            load_task: None,
+        };
+        this.update_theme(cx.theme().theme_name().clone(), cx);
+        this
+    });
+    cx.set_global(GlobalWallpaper(wallpaper.clone()));
+
+    // Also fires when the theme file is edited, `update_theme` skips reloading an unchanged spec.
+    events::subscribe::<ThemeChanged>(cx, move |event, cx| {
+        wallpaper.update(cx, |wallpaper, cx| {
+            wallpaper.update_theme(event.name.clone(), cx)
+        });
+    })
+    .detach();
+}
@@ -0,0 +1,140 @@
+use std::{
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use gpui::{App, Timer};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// How often the main thread checks in.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(500);
+/// How often the watchdog looks at the last heartbeat.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct WatchdogSettings {
+    /// Seconds the main thread may go without handling events before it counts as stuck.
+    /// `0` turns the watchdog off.
+    pub threshold: u64,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        Self { threshold: 10 }
+    }
+}
+
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+/// Milliseconds since [`START`] of the last heartbeat of the main thread.
+static HEARTBEAT: AtomicU64 = AtomicU64::new(0);
+/// The threshold from the settings, in seconds.
+static THRESHOLD: AtomicU64 = AtomicU64::new(0);
+static STALLS: AtomicUsize = AtomicUsize::new(0);
+/// Milliseconds of the longest stall so far.
+static LONGEST_STALL: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> u64 {
+    START.elapsed().as_millis() as u64
+}
+
+/// How often the main thread was stuck for longer than the threshold, since the app started.
+pub fn stalls() -> usize {
+    STALLS.load(Ordering::Relaxed)
+}
+
+/// The longest time the main thread was stuck.
+pub fn longest_stall() -> Duration {
+    Duration::from_millis(LONGEST_STALL.load(Ordering::Relaxed))
+}
+
+/// What the main thread is blocked on, as far as the OS tells.
+#[cfg(target_os = "linux")]
+fn capture_main_thread() -> Option<String> {
+    // The main thread has the id of the process.
+    let task = format!("/proc/self/task/{}", std::process::id());
+    let wchan = std::fs::read_to_string(format!("{}/wchan", task)).ok()?;
+    let syscall = std::fs::read_to_string(format!("{}/syscall", task)).unwrap_or_default();
+    let stack = std::fs::read_to_string(format!("{}/stack", task)).unwrap_or_default();
+    Some(format!(
+        "waiting in {} (syscall {})\n{}",
+        wchan.trim(),
+        syscall.trim(),
+        stack.trim()
+    ))
+}
+
+/// Other platforms don't expose the state of another thread without a debugger.
+#[cfg(not(target_os = "linux"))]
+fn capture_main_thread() -> Option<String> {
+    None
+}
+
+fn watch() {
+    let mut stalled_since = None;
+    loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        let threshold = THRESHOLD.load(Ordering::Relaxed) * 1000;
+        let last_beat = HEARTBEAT.load(Ordering::Relaxed);
+        let stalled_for = now().saturating_sub(last_beat);
+
+        if threshold > 0 && stalled_for > threshold {
+            if stalled_since != Some(last_beat) {
+                stalled_since = Some(last_beat);
+                STALLS.fetch_add(1, Ordering::Relaxed);
+                tracing::error!(
+                    "The main thread hasn't handled events for {:.1}s",
+                    stalled_for as f64 / 1000.
+                );
+                match capture_main_thread() {
+                    Some(capture) => tracing::error!("Main thread: {}", capture),
+                    None => tracing::error!("No stack capture of the main thread on this platform"),
+                }
+            }
+            LONGEST_STALL.fetch_max(stalled_for, Ordering::Relaxed);
+        } else if let Some(since) = stalled_since.take() {
+            tracing::warn!(
+                "The main thread is responsive again after {:.1}s",
+                last_beat.saturating_sub(since) as f64 / 1000.
+            );
+        }
+    }
+}
+
+/// Notice when the main thread is stuck, e.g. in a deadlock or a long blocking call, and log
+/// where it's stuck. The stalls are counted in the diagnostics.
+pub fn init(cx: &mut App) {
+    let sync_threshold = |cx: &mut App| {
+        let threshold = Settings::global(cx).watchdog.threshold;
+        THRESHOLD.store(threshold, Ordering::Relaxed);
+    };
+    sync_threshold(cx);
+    cx.observe_global::<Settings>(sync_threshold).detach();
+
+    HEARTBEAT.store(now(), Ordering::Relaxed);
+    cx.spawn(async move |cx| {
+        loop {
+            Timer::after(HEARTBEAT_INTERVAL).await;
+            if cx
+                .update(|_| HEARTBEAT.store(now(), Ordering::Relaxed))
+                .is_err()
+            {
+                break;
+            }
+        }
+    })
+    .detach();
+
+    if let Err(err) = std::thread::Builder::new()
+        .name("watchdog".into())
+        .spawn(watch)
+    {
+        tracing::error!("Failed to start the watchdog: {}", err);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{App, Global, Timer};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+/// How long a burst of changes may go quiet before it's delivered, editors tend to
+/// write a file in a few steps (truncate, write, rename).
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Identifies a registration, to [`FileWatcher::unwatch`] it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchId(usize);
+
+type ChangeHandler = Rc<dyn Fn(&[PathBuf], &mut App)>;
+
+struct Registration {
+    id: WatchId,
+    paths: Vec<PathBuf>,
+    handler: ChangeHandler,
+}
+
+impl Registration {
+    /// The changed paths that are (or are inside) one of the watched paths.
+    fn matching(&self, changed: &HashSet<PathBuf>) -> Vec<PathBuf> {
+        let mut matching = changed
+            .iter()
+            .filter(|path| self.paths.iter().any(|watched| path.starts_with(watched)))
+            .cloned()
+            .collect::<Vec<_>>();
+        matching.sort();
+        matching
+    }
+}
+
+/// Watches files and dirs for any module, delivering debounced changes on the main thread.
+///
+/// A missing file is picked up once it's created, but dirs have to exist to be watched
+/// recursively (a missing one is taken for a file).
+///
+/// ```ignore
+/// FileWatcher::watch([get_config_dir().join("scripts")], cx, |paths, cx| {
+///     tracing::info!("{} scripts changed", paths.len());
+/// });
+/// ```
+pub struct FileWatcher {
+    watcher: Option<RecommendedWatcher>,
+    /// The paths given to the OS watcher, a file's parent dir when it's missing.
+    watched: HashSet<PathBuf>,
+    registrations: Vec<Registration>,
+    next_id: usize,
+}
+
+impl Global for FileWatcher {}
+
+impl FileWatcher {
+    /// Call `on_change` with the changed paths whenever one of `paths` changes.
+    pub fn watch(
+        paths: impl IntoIterator<Item = PathBuf>,
+        cx: &mut App,
+        on_change: impl Fn(&[PathBuf], &mut App) + 'static,
+    ) -> WatchId {
+        let this = cx.global_mut::<Self>();
+        let id = WatchId(this.next_id);
+        this.next_id += 1;
+
+        let paths = paths.into_iter().collect::<Vec<_>>();
+        for path in &paths {
+            this.watch_path(path);
+        }
+        this.registrations.push(Registration {
+            id,
+            paths,
+            handler: Rc::new(on_change),
+        });
+        id
+    }
+
+    /// Stop delivering changes to the registration, the paths stay watched by the OS.
+    pub fn unwatch(id: WatchId, cx: &mut App) {
+        cx.global_mut::<Self>()
+            .registrations
+            .retain(|registration| registration.id != id);
+    }
+
+    fn watch_path(&mut self, path: &Path) {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return;
+        };
+        // A file may be replaced rather than written (atomic saves), so watch its dir.
+        let (target, mode) = if path.is_dir() {
+            (path, RecursiveMode::Recursive)
+        } else {
+            match path.parent() {
+                Some(parent) => (parent, RecursiveMode::NonRecursive),
+                None => return,
+            }
+        };
+        if self.watched.contains(target) {
+            return;
+        }
+
+        if let Err(err) = std::fs::create_dir_all(target) {
+            tracing::warn!("Failed to create {}: {}", target.display(), err);
+        }
+        match watcher.watch(target, mode) {
+            Ok(()) => _ = self.watched.insert(target.to_path_buf()),
+            Err(err) => tracing::error!("Failed to watch {}: {}", target.display(), err),
+        }
+    }
+
+    fn dispatch(changed: HashSet<PathBuf>, cx: &mut App) {
+        let handlers = cx
+            .global::<Self>()
+            .registrations
+            .iter()
+            .filter_map(|registration| {
+                let matching = registration.matching(&changed);
+                (!matching.is_empty()).then(|| (registration.handler.clone(), matching))
+            })
+            .collect::<Vec<_>>();
+
+        for (handler, paths) in handlers {
+            handler(&paths, cx);
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let (tx, mut rx) = mpsc::unbounded::<Vec<PathBuf>>();
+    let watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+            Ok(event) => _ = tx.unbounded_send(event.paths),
+            Err(err) => tracing::warn!("File watcher error: {}", err),
+        })
+        .inspect_err(|err| tracing::error!("Failed to start the file watcher: {}", err))
+        .ok();
+
+    cx.set_global(FileWatcher {
+        watcher,
+        watched: HashSet::default(),
+        registrations: vec![],
+        next_id: 0,
+    });
+
+    cx.spawn(async move |cx| {
+        while let Some(paths) = rx.next().await {
+            let mut changed = paths.into_iter().collect::<HashSet<_>>();
+            loop {
+                Timer::after(DEBOUNCE).await;
+                let mut quiet = true;
+                while let Ok(Some(paths)) = rx.try_next() {
+                    changed.extend(paths);
+                    quiet = false;
+                }
+                if quiet {
+                    break;
+                }
+            }
+
+            if cx.update(|cx| FileWatcher::dispatch(changed, cx)).is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+}
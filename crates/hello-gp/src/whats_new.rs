@@ -0,0 +1,155 @@
+use gpui::{App, ParentElement as _, SharedString, Styled as _, actions, px};
+use gpui_component::{
+    ContextModal as _, Sizable as _,
+    button::{Button, ButtonVariants as _},
+    checkbox::Checkbox,
+    h_flex, v_flex,
+};
+
+use crate::{confetti, kiosk, markdown::Markdown, session::Session, settings::Settings};
+
+actions!(help, [ShowWhatsNew]);
+
+const CHANGELOG: &str = include_str!("../CHANGELOG.md");
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+type Version = (u64, u64, u64);
+
+/// `0.3.0`, `v0.3` or `0.3.0-beta.1`, ignoring what comes after the patch version.
+fn parse_version(version: &str) -> Option<Version> {
+    let mut parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map(|part| part.parse::<u64>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+/// The `## <version>` sections of the changelog, e.g. `## 0.3.0` or `## [0.3.0] - 2025-06-01`,
+/// with the text below their heading.
+fn sections(changelog: &str) -> Vec<(Version, String)> {
+    let mut sections = vec![];
+    let mut current: Option<(Option<Version>, Vec<&str>)> = None;
+    for line in changelog.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            sections.extend(current.take());
+            let version = heading
+                .split_whitespace()
+                .next()
+                .map(|version| version.trim_matches(['[', ']']))
+                .and_then(parse_version);
+            current = Some((version, vec![]));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+    sections.extend(current);
+
+    sections
+        .into_iter()
+        .filter_map(|(version, body)| Some((version?, body.join("\n").trim().to_string())))
+        .collect()
+}
+
+/// The changelog of the versions after `since` up to the running one, newest first.
+fn notes(since: Option<Version>) -> String {
+    let Some(current) = parse_version(VERSION) else {
+        return String::new();
+    };
+    let mut sections = sections(CHANGELOG)
+        .into_iter()
+        .filter(|(version, _)| *version <= current && since.is_none_or(|since| *version > since))
+        .collect::<Vec<_>>();
+    sections.sort_by(|(a, _), (b, _)| b.cmp(a));
+    sections
+        .into_iter()
+        .map(|((major, minor, patch), body)| {
+            format!("## {}.{}.{}\n\n{}", major, minor, patch, body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn open_dialog(title: String, notes: String, cx: &mut App) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    let notes = SharedString::from(notes);
+
+    _ = window.update(cx, |_, window, cx| {
+        window.open_modal(cx, move |modal, _, cx| {
+            let show_after_updates = Settings::global(cx).startup.whats_new;
+            modal.title(title.clone()).width(px(560.)).child(
+                v_flex()
+                    .gap_3()
+                    .child(
+                        Markdown::new("whats-new", notes.clone())
+                            .scrollable()
+                            .h(px(400.)),
+                    )
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(
+                                Checkbox::new("show-whats-new")
+                                    .label("Show after updates")
+                                    .checked(show_after_updates)
+                                    .on_click(|checked: &bool, _, cx| {
+                                        let checked = *checked;
+                                        Settings::update(cx, |settings, _| {
+                                            settings.startup.whats_new = checked
+                                        });
+                                    }),
+                            )
+                            .child(
+                                Button::new("close-whats-new")
+                                    .small()
+                                    .primary()
+                                    .label("Close")
+                                    .on_click(|_, window, cx| window.close_modal(cx)),
+                            ),
+                    ),
+            )
+        });
+    });
+}
+
+/// Show what changed since the version that ran last, once, after the first window opened.
+pub fn show_if_updated(cx: &mut App) {
+    let last = Session::global(cx).last_version.clone();
+    if last.as_deref() == Some(VERSION) {
+        return;
+    }
+    Session::update(cx, |session| {
+        session.last_version = Some(VERSION.to_string())
+    });
+
+    // A fresh install has nothing to compare with, and a downgrade nothing new.
+    let Some(last) = last else {
+        return;
+    };
+    let Some(since) = parse_version(&last) else {
+        return;
+    };
+    if kiosk::enabled() || !Settings::global(cx).startup.whats_new {
+        return;
+    }
+    if parse_version(VERSION).is_none_or(|current| current <= since) {
+        return;
+    }
+    let notes = notes(Some(since));
+    if notes.is_empty() {
+        return;
+    }
+    tracing::info!("Updated from {} to {}", last, VERSION);
+    open_dialog(format!("What's New in {}", VERSION), notes, cx);
+    confetti::celebrate(cx);
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &ShowWhatsNew, cx| open_dialog("What's New".to_string(), notes(None), cx));
+}
@@ -0,0 +1,161 @@
+//! Resizing and managing windows that draw their own decorations on Linux: the edges and
+//! corners resize them, and the keyboard shortcuts of the window manager work, so they behave
+//! like the windows it decorates.
+
+use gpui::{
+    AnyElement, App, CursorStyle, Decorations, InteractiveElement as _, IntoElement, KeyBinding,
+    MouseButton, Pixels, Point, ResizeEdge, Styled as _, Tiling, Window, actions, div, point, px,
+};
+
+use crate::macos::with_active_window;
+
+actions!(window, [Maximize, RestoreOrMinimize, ShowWindowMenu]);
+
+/// How far in from the edges of the window it resizes from.
+const RESIZE_ZONE: Pixels = px(6.);
+/// How far along the edges the corners resize from.
+const CORNER_SIZE: Pixels = px(16.);
+
+/// The zones over the edges and corners of the window it resizes from, when it draws its own
+/// decorations, to lay over its content.
+///
+/// None along the sides the compositor tiled, all of them when maximized.
+pub fn resize_handles(window: &Window) -> Vec<AnyElement> {
+    match window.window_decorations() {
+        Decorations::Client { tiling } if !window.is_fullscreen() => edge_handles(tiling),
+        _ => vec![],
+    }
+}
+
+fn edge_handles(tiling: Tiling) -> Vec<AnyElement> {
+    let handle = |edge: ResizeEdge, cursor: CursorStyle| {
+        div()
+            .absolute()
+            .cursor(cursor)
+            .on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                cx.stop_propagation();
+                window.start_window_resize(edge);
+            })
+    };
+    let mut handles = vec![];
+    if !tiling.top {
+        handles.push(
+            handle(ResizeEdge::Top, CursorStyle::ResizeUpDown)
+                .top_0()
+                .left(CORNER_SIZE)
+                .right(CORNER_SIZE)
+                .h(RESIZE_ZONE),
+        );
+    }
+    if !tiling.bottom {
+        handles.push(
+            handle(ResizeEdge::Bottom, CursorStyle::ResizeUpDown)
+                .bottom_0()
+                .left(CORNER_SIZE)
+                .right(CORNER_SIZE)
+                .h(RESIZE_ZONE),
+        );
+    }
+    if !tiling.left {
+        handles.push(
+            handle(ResizeEdge::Left, CursorStyle::ResizeLeftRight)
+                .left_0()
+                .top(CORNER_SIZE)
+                .bottom(CORNER_SIZE)
+                .w(RESIZE_ZONE),
+        );
+    }
+    if !tiling.right {
+        handles.push(
+            handle(ResizeEdge::Right, CursorStyle::ResizeLeftRight)
+                .right_0()
+                .top(CORNER_SIZE)
+                .bottom(CORNER_SIZE)
+                .w(RESIZE_ZONE),
+        );
+    }
+    if !tiling.top && !tiling.left {
+        handles.push(
+            handle(ResizeEdge::TopLeft, CursorStyle::ResizeUpLeftDownRight)
+                .top_0()
+                .left_0()
+                .size(CORNER_SIZE),
+        );
+    }
+    if !tiling.top && !tiling.right {
+        handles.push(
+            handle(ResizeEdge::TopRight, CursorStyle::ResizeUpRightDownLeft)
+                .top_0()
+                .right_0()
+                .size(CORNER_SIZE),
+        );
+    }
+    if !tiling.bottom && !tiling.left {
+        handles.push(
+            handle(ResizeEdge::BottomLeft, CursorStyle::ResizeUpRightDownLeft)
+                .bottom_0()
+                .left_0()
+                .size(CORNER_SIZE),
+        );
+    }
+    if !tiling.bottom && !tiling.right {
+        handles.push(
+            handle(ResizeEdge::BottomRight, CursorStyle::ResizeUpLeftDownRight)
+                .bottom_0()
+                .right_0()
+                .size(CORNER_SIZE),
+        );
+    }
+    handles
+        .into_iter()
+        .map(IntoElement::into_any_element)
+        .collect()
+}
+
+/// Whether the window draws its own decorations.
+pub fn is_client_decorated(window: &Window) -> bool {
+    matches!(window.window_decorations(), Decorations::Client { .. })
+}
+
+/// The menu of the window manager (move, resize, always on top...), at `position` in the
+/// window, as for a right click on the title bar.
+pub fn show_window_menu(position: Point<Pixels>, window: &mut Window) {
+    if is_client_decorated(window) {
+        window.show_window_menu(position);
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.on_action(|_: &Maximize, cx| {
+        with_active_window(cx, |window| {
+            if !window.is_maximized() {
+                window.zoom_window();
+            }
+        })
+    });
+    cx.on_action(|_: &RestoreOrMinimize, cx| {
+        with_active_window(cx, |window| {
+            if window.is_maximized() {
+                window.zoom_window();
+            } else {
+                window.minimize_window();
+            }
+        })
+    });
+    cx.on_action(|_: &ShowWindowMenu, cx| {
+        with_active_window(cx, |window| {
+            show_window_menu(point(CORNER_SIZE, CORNER_SIZE), window)
+        })
+    });
+
+    // What GNOME and KDE bind, for the window managers that don't grab them themselves.
+    // Windows snaps its windows natively, the title bar is a drag area of the system there.
+    cx.bind_keys([
+        #[cfg(target_os = "linux")]
+        KeyBinding::new("super-up", Maximize, None),
+        #[cfg(target_os = "linux")]
+        KeyBinding::new("super-down", RestoreOrMinimize, None),
+        #[cfg(target_os = "linux")]
+        KeyBinding::new("alt-space", ShowWindowMenu, None),
+    ]);
+}
@@ -47,14 +47,31 @@ impl ReqwestClient {
     }
 
     pub fn proxy_and_user_agent(proxy: Option<Url>, user_agent: &str) -> anyhow::Result<Self> {
-        let user_agent = HeaderValue::from_str(user_agent)?;
+        Self::with_options(ClientOptions {
+            proxy,
+            user_agent: Some(user_agent.to_string()),
+            ..Default::default()
+        })
+    }
+
+    pub fn with_options(options: ClientOptions) -> anyhow::Result<Self> {
+        let user_agent = options
+            .user_agent
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()?;
 
         let mut map = HeaderMap::new();
-        map.insert(http::header::USER_AGENT, user_agent.clone());
+        if let Some(user_agent) = &user_agent {
+            map.insert(http::header::USER_AGENT, user_agent.clone());
+        }
         let mut client = Self::builder().default_headers(map);
         let client_has_proxy;
 
-        if let Some(proxy) = proxy.as_ref().and_then(|proxy_url| {
+        if options.no_proxy {
+            client = client.no_proxy();
+            client_has_proxy = false;
+        } else if let Some(proxy) = options.proxy.as_ref().and_then(|proxy_url| {
             reqwest::Proxy::all(proxy_url.clone())
                 .inspect_err(|e| {
                     log::error!(
@@ -72,16 +89,42 @@ impl ReqwestClient {
             client_has_proxy = false;
         };
 
-        let client = client
-            .use_preconfigured_tls(http_client_tls::tls_config())
-            .build()?;
-        let mut client: ReqwestClient = client.into();
-        client.proxy = client_has_proxy.then_some(proxy).flatten();
-        client.user_agent = Some(user_agent);
+        let client = if options.root_certificates.is_empty() {
+            client.use_preconfigured_tls(http_client_tls::tls_config())
+        } else {
+            // The platform verifier can't take extra roots on every platform, so these use
+            // the roots bundled with reqwest plus the given ones.
+            for pem in &options.root_certificates {
+                let certificates = reqwest::Certificate::from_pem_bundle(pem)?;
+                if certificates.is_empty() {
+                    return Err(anyhow!("no PEM encoded certificates found"));
+                }
+                for certificate in certificates {
+                    client = client.add_root_certificate(certificate);
+                }
+            }
+            client
+        };
+
+        let mut client: ReqwestClient = client.build()?.into();
+        client.proxy = client_has_proxy.then_some(options.proxy).flatten();
+        client.user_agent = user_agent;
         Ok(client)
     }
 }
 
+/// How [`ReqwestClient::with_options`] sets up the client.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    pub user_agent: Option<String>,
+    /// The proxy for all requests, hosts in the `NO_PROXY` env var still connect directly.
+    pub proxy: Option<Url>,
+    /// Connect directly, ignoring `proxy` and the proxy env vars.
+    pub no_proxy: bool,
+    /// Extra CA certificates to trust, PEM encoded, e.g. of a corporate TLS proxy.
+    pub root_certificates: Vec<Vec<u8>>,
+}
+
 impl From<reqwest::Client> for ReqwestClient {
     fn from(client: reqwest::Client) -> Self {
         let handle = tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
@@ -300,7 +343,7 @@ impl http_client::HttpClient for ReqwestClient {
 mod tests {
     use http_client::{HttpClient, Url};
 
-    use crate::ReqwestClient;
+    use crate::{ClientOptions, ReqwestClient};
 
     #[test]
     fn test_proxy_uri() {
@@ -341,4 +384,25 @@ mod tests {
             "An invalid proxy URL should add no proxy to the client!"
         )
     }
+
+    #[test]
+    fn test_no_proxy() {
+        let proxy = Url::parse("http://localhost:10809").unwrap();
+        let client = ReqwestClient::with_options(ClientOptions {
+            proxy: Some(proxy),
+            no_proxy: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(client.proxy(), None);
+    }
+
+    #[test]
+    fn test_invalid_root_certificate() {
+        let result = ReqwestClient::with_options(ClientOptions {
+            root_certificates: vec![b"not a certificate".to_vec()],
+            ..Default::default()
+        });
+        assert!(result.is_err());
+    }
 }